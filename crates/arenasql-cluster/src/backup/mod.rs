@@ -0,0 +1,509 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use anyhow::{anyhow, bail, Context, Result};
+use arenasql::chrono::{DateTime, NaiveDateTime};
+use arenasql::rocks::{BackupEngine, BackupEngineInfo, RestoreOptions};
+use arenasql::storage::rocks::RocksStorage;
+
+use crate::schema::ClusterManifest;
+use crate::server::backup::{
+  backup_rocks_storage, open_backup_engine, BACKUP_TRASH_DIR,
+};
+
+/// Options to locate the databases and their backups. The backups of
+/// each database are in `<backup_dir>/<database>` and the backups of the
+/// dropped databases are in `<backup_dir>/trash/<database>.<unix
+/// timestamp in ms>`. Pass `<backup_dir>/trash` as the backup dir to
+/// restore a dropped database
+#[derive(clap::Args, Debug, Clone)]
+pub struct BackupLocation {
+  /// Path to the cluster config file
+  #[arg(long)]
+  pub config: String,
+
+  /// Backup directory to use instead of the one in the config
+  #[arg(long)]
+  pub backup_dir: Option<String>,
+}
+
+/// Back up databases to the backup directory. The cluster shouldn't be
+/// running since the database can't be opened while it's in use
+#[derive(clap::Parser, Debug, Clone)]
+pub struct CreateBackup {
+  #[command(flatten)]
+  pub location: BackupLocation,
+
+  /// Name of the database to back up; all the databases are backed up
+  /// if it's not passed
+  #[arg(long)]
+  pub catalog: Option<String>,
+}
+
+/// List the backups of the databases
+#[derive(clap::Parser, Debug, Clone)]
+pub struct ListBackups {
+  #[command(flatten)]
+  pub location: BackupLocation,
+
+  /// Name of the database to list the backups of; backups of all the
+  /// databases are listed if it's not passed
+  #[arg(long)]
+  pub catalog: Option<String>,
+}
+
+/// Restore a database from a backup. The cluster shouldn't be running
+#[derive(clap::Parser, Debug, Clone)]
+pub struct RestoreBackup {
+  #[command(flatten)]
+  pub location: BackupLocation,
+
+  /// Name of the database to restore
+  #[arg(long)]
+  pub catalog: String,
+
+  /// Id of the backup to restore
+  #[arg(long, conflicts_with = "timestamp")]
+  pub backup_id: Option<u32>,
+
+  /// Restore the latest backup taken at or before this time. Either a
+  /// unix timestamp in seconds or a RFC 3339 date time
+  #[arg(long)]
+  pub timestamp: Option<String>,
+
+  /// Restore even if the database exists. The existing database is
+  /// moved to `trash/<database>.<unix timestamp in ms>` in the cluster's
+  /// data directory so that it isn't opened or backed up by the cluster
+  #[arg(long, default_value_t = false)]
+  pub force: bool,
+}
+
+/// Verify the integrity of the backups
+#[derive(clap::Parser, Debug, Clone)]
+pub struct VerifyBackups {
+  #[command(flatten)]
+  pub location: BackupLocation,
+
+  /// Name of the database to verify the backups of; backups of all the
+  /// databases are verified if it's not passed
+  #[arg(long)]
+  pub catalog: Option<String>,
+
+  /// Id of the backup to verify; all the backups are verified if it's
+  /// not passed
+  #[arg(long, requires = "catalog")]
+  pub backup_id: Option<u32>,
+}
+
+/// Export a database to a directory that can be copied to another
+/// machine. The exported directory has the same layout as the backup
+/// directory, so the database can be restored from it by passing it
+/// as `--backup-dir` to `restore`
+#[derive(clap::Parser, Debug, Clone)]
+pub struct ExportCatalog {
+  /// Path to the cluster config file
+  #[arg(long)]
+  pub config: String,
+
+  /// Name of the database to export
+  #[arg(long)]
+  pub catalog: String,
+
+  /// Directory to export the database to
+  #[arg(long)]
+  pub output: String,
+}
+
+impl BackupLocation {
+  fn load(&self) -> Result<(ClusterManifest, PathBuf)> {
+    let manifest = load_manifest(&self.config)?;
+    let backup_dir = self
+      .backup_dir
+      .clone()
+      .or_else(|| manifest.backup_dir.clone())
+      .map(PathBuf::from)
+      .ok_or_else(|| {
+        anyhow!("Backup dir isn't set in the config; pass --backup-dir")
+      })?;
+    Ok((manifest, backup_dir))
+  }
+}
+
+impl CreateBackup {
+  pub async fn execute(self) -> Result<()> {
+    let (manifest, backup_dir) = self.location.load()?;
+    let catalogs = match self.catalog {
+      Some(catalog) => vec![catalog],
+      None => list_dirs(&catalogs_dir(&manifest))?,
+    };
+    for catalog in catalogs {
+      let rocks = open_catalog(&manifest, &catalog)?;
+      let backup = backup_rocks_storage(
+        &backup_dir,
+        &rocks,
+        &catalog,
+        manifest.backups_to_keep,
+      )
+      .with_context(|| format!("Error backing up database \"{}\"", catalog))?;
+      println!(
+        "{}: created backup {} ({} bytes)",
+        catalog, backup.backup_id, backup.size
+      );
+    }
+    Ok(())
+  }
+}
+
+impl ListBackups {
+  pub async fn execute(self) -> Result<()> {
+    let (_, backup_dir) = self.location.load()?;
+    let catalogs = match self.catalog {
+      Some(catalog) => vec![catalog],
+      None => list_catalog_backups(&backup_dir)?,
+    };
+    println!(
+      "{:<24} {:>10} {:<26} {:>14} {:>8}",
+      "database", "backup id", "time", "size (bytes)", "files"
+    );
+    for catalog in catalogs {
+      let engine = open_catalog_backups(&backup_dir, &catalog)?;
+      for backup in sorted_backups(&engine) {
+        println!(
+          "{:<24} {:>10} {:<26} {:>14} {:>8}",
+          catalog,
+          backup.backup_id,
+          format_timestamp(backup.timestamp),
+          backup.size,
+          backup.num_files
+        );
+      }
+    }
+    Ok(())
+  }
+}
+
+impl RestoreBackup {
+  pub async fn execute(self) -> Result<()> {
+    let (manifest, backup_dir) = self.location.load()?;
+    let mut engine = open_catalog_backups(&backup_dir, &self.catalog)?;
+    let backups = sorted_backups(&engine);
+    let backup = match (self.backup_id, &self.timestamp) {
+      (Some(backup_id), _) => backups
+        .iter()
+        .find(|backup| backup.backup_id == backup_id)
+        .ok_or_else(|| anyhow!("Backup {} doesn't exist", backup_id))?,
+      (None, Some(timestamp)) => {
+        let timestamp = parse_timestamp(timestamp)?;
+        backups
+          .iter()
+          .rev()
+          .find(|backup| backup.timestamp <= timestamp)
+          .ok_or_else(|| {
+            anyhow!(
+              "No backup was taken at or before {}",
+              format_timestamp(timestamp)
+            )
+          })?
+      }
+      (None, None) => backups
+        .last()
+        .ok_or_else(|| anyhow!("No backups of \"{}\"", self.catalog))?,
+    };
+
+    let db_dir = catalogs_dir(&manifest).join(&self.catalog);
+    if db_dir.exists() {
+      if !self.force {
+        bail!(
+          "Database \"{}\" already exists; pass --force to replace it",
+          self.catalog
+        );
+      }
+      // Opening the database fails if a running cluster has it open;
+      // replacing it then would lose the writes of the cluster
+      let rocks = RocksStorage::new(db_dir.clone()).map_err(|e| {
+        anyhow!(
+          "Database \"{}\" can't be replaced since it's in use; the \
+            cluster is running: {:?}",
+          self.catalog,
+          e
+        )
+      })?;
+      drop(rocks);
+      let trash_dir = trash_dir(&manifest);
+      fs::create_dir_all(&trash_dir)
+        .context(format!("Failed to create dir: {:?}", trash_dir))?;
+      let old_dir =
+        trash_dir.join(format!("{}.{}", self.catalog, now_millis()));
+      fs::rename(&db_dir, &old_dir).context(format!(
+        "Failed to move existing database to {:?}",
+        old_dir
+      ))?;
+      println!("Moved existing database to {:?}", old_dir);
+    }
+
+    engine
+      .restore_from_backup(
+        &db_dir,
+        db_dir.join("wal"),
+        &RestoreOptions::default(),
+        backup.backup_id,
+      )
+      .with_context(|| {
+        format!("Error restoring backup {}", backup.backup_id)
+      })?;
+    println!(
+      "Restored database \"{}\" from backup {} taken at {}",
+      self.catalog,
+      backup.backup_id,
+      format_timestamp(backup.timestamp)
+    );
+    Ok(())
+  }
+}
+
+impl VerifyBackups {
+  pub async fn execute(self) -> Result<()> {
+    let (_, backup_dir) = self.location.load()?;
+    let catalogs = match self.catalog {
+      Some(catalog) => vec![catalog],
+      None => list_catalog_backups(&backup_dir)?,
+    };
+    let mut num_failed = 0;
+    for catalog in catalogs {
+      let engine = open_catalog_backups(&backup_dir, &catalog)?;
+      let backup_ids = match self.backup_id {
+        Some(backup_id) => vec![backup_id],
+        None => sorted_backups(&engine)
+          .iter()
+          .map(|backup| backup.backup_id)
+          .collect(),
+      };
+      for backup_id in backup_ids {
+        match engine.verify_backup(backup_id) {
+          Ok(_) => println!("{}: backup {} OK", catalog, backup_id),
+          Err(err) => {
+            num_failed += 1;
+            println!("{}: backup {} FAILED: {}", catalog, backup_id, err);
+          }
+        }
+      }
+    }
+    if num_failed > 0 {
+      bail!("{} backup(s) failed verification", num_failed);
+    }
+    Ok(())
+  }
+}
+
+impl ExportCatalog {
+  pub async fn execute(self) -> Result<()> {
+    let manifest = load_manifest(&self.config)?;
+    let output = PathBuf::from(&self.output);
+    if output.join(&self.catalog).exists() {
+      bail!("Export already exists: {:?}", output.join(&self.catalog));
+    }
+    let rocks = open_catalog(&manifest, &self.catalog)?;
+    let backup = backup_rocks_storage(&output, &rocks, &self.catalog, 1)
+      .with_context(|| format!("Error exporting \"{}\"", self.catalog))?;
+    println!(
+      "Exported database \"{}\" to {:?} ({} bytes)",
+      self.catalog,
+      output.join(&self.catalog),
+      backup.size
+    );
+    Ok(())
+  }
+}
+
+fn load_manifest(config: &str) -> Result<ClusterManifest> {
+  let manifest = fs::read_to_string(Path::new(config))
+    .context("Error reading cluster manifest")?;
+  Ok(toml::from_str(&manifest)?)
+}
+
+fn catalogs_dir(manifest: &ClusterManifest) -> PathBuf {
+  PathBuf::from(&manifest.catalogs_dir).join("catalogs")
+}
+
+/// Directory to move the replaced databases to. It's outside the
+/// catalogs dir since every dir in there is treated as a database
+fn trash_dir(manifest: &ClusterManifest) -> PathBuf {
+  PathBuf::from(&manifest.catalogs_dir).join("trash")
+}
+
+/// Opens the database; fails if it's being used by a running cluster
+fn open_catalog(
+  manifest: &ClusterManifest,
+  catalog: &str,
+) -> Result<RocksStorage> {
+  let db_dir = catalogs_dir(manifest).join(catalog);
+  if !db_dir.exists() {
+    bail!("Database \"{}\" doesn't exist", catalog);
+  }
+  RocksStorage::new(db_dir).map_err(|e| {
+    anyhow!(
+      "Error opening database \"{}\"; make sure the cluster isn't \
+        running: {:?}",
+      catalog,
+      e
+    )
+  })
+}
+
+fn open_catalog_backups(
+  backup_dir: &Path,
+  catalog: &str,
+) -> Result<BackupEngine> {
+  let catalog_backup_dir = backup_dir.join(catalog);
+  if !catalog_backup_dir.exists() {
+    bail!("No backups of \"{}\" in {:?}", catalog, backup_dir);
+  }
+  Ok(open_backup_engine(&catalog_backup_dir)?)
+}
+
+/// Returns the backups from the oldest to the latest
+fn sorted_backups(engine: &BackupEngine) -> Vec<BackupEngineInfo> {
+  let mut backups = engine.get_backup_info();
+  backups.sort_by_key(|backup| backup.backup_id);
+  backups
+}
+
+/// Returns the databases that have backups in the backup dir
+fn list_catalog_backups(backup_dir: &Path) -> Result<Vec<String>> {
+  let mut catalogs = list_dirs(backup_dir)?;
+  catalogs.retain(|catalog| catalog != BACKUP_TRASH_DIR);
+  Ok(catalogs)
+}
+
+fn list_dirs(dir: &Path) -> Result<Vec<String>> {
+  let mut dirs = fs::read_dir(dir)
+    .context(format!("Failed to read dir: {:?}", dir))?
+    .filter_map(|entry| entry.ok())
+    .filter(|entry| entry.path().is_dir())
+    .filter_map(|entry| entry.file_name().to_str().map(|n| n.to_owned()))
+    .collect::<Vec<String>>();
+  dirs.sort();
+  Ok(dirs)
+}
+
+/// Parses unix timestamp in seconds or RFC 3339 date time
+fn parse_timestamp(timestamp: &str) -> Result<i64> {
+  match timestamp.parse::<i64>() {
+    Ok(seconds) => Ok(seconds),
+    Err(_) => Ok(
+      DateTime::parse_from_rfc3339(timestamp)
+        .context("Timestamp should be unix seconds or RFC 3339 date time")?
+        .timestamp(),
+    ),
+  }
+}
+
+fn format_timestamp(seconds: i64) -> String {
+  NaiveDateTime::from_timestamp_opt(seconds, 0)
+    .map(|time| format!("{}Z", time.format("%Y-%m-%dT%H:%M:%S")))
+    .unwrap_or_else(|| seconds.to_string())
+}
+
+fn now_millis() -> u128 {
+  SystemTime::now()
+    .duration_since(UNIX_EPOCH)
+    .unwrap()
+    .as_millis()
+}
+
+#[cfg(test)]
+mod tests {
+  use std::fs;
+
+  use arenasql::storage::rocks::RocksStorage;
+  use arenasql::storage::{KeyValueGroup, KeyValueStoreProvider};
+  use tempdir::TempDir;
+
+  use super::{
+    catalogs_dir, list_dirs, load_manifest, open_catalog, trash_dir,
+    BackupLocation, CreateBackup, RestoreBackup,
+  };
+  use crate::schema::ClusterManifestBuilder;
+
+  fn write_manifest(dir: &TempDir) -> BackupLocation {
+    let manifest = ClusterManifestBuilder::default()
+      .catalogs_dir(dir.path().to_str().unwrap().to_owned())
+      .backup_dir(Some(dir.path().join("backups").to_str().unwrap().into()))
+      .build()
+      .unwrap();
+    let config = dir.path().join("config.toml");
+    fs::write(&config, toml::to_string(&manifest).unwrap()).unwrap();
+    BackupLocation {
+      config: config.to_str().unwrap().to_owned(),
+      backup_dir: None,
+    }
+  }
+
+  fn put(rocks: &RocksStorage, key: &[u8], value: &[u8]) {
+    let kv = rocks.new_transaction().unwrap();
+    kv.put(KeyValueGroup::Rows, key, value).unwrap();
+    kv.commit().unwrap();
+  }
+
+  fn get(rocks: &RocksStorage, key: &[u8]) -> Option<Vec<u8>> {
+    let kv = rocks.new_transaction().unwrap();
+    kv.get(KeyValueGroup::Rows, key).unwrap()
+  }
+
+  fn restore(location: &BackupLocation, force: bool) -> RestoreBackup {
+    RestoreBackup {
+      location: location.clone(),
+      catalog: "db1".to_owned(),
+      backup_id: None,
+      timestamp: None,
+      force,
+    }
+  }
+
+  #[tokio::test(flavor = "multi_thread")]
+  async fn backup_test_backup_and_restore() {
+    let dir = TempDir::new("arenasql").unwrap();
+    let location = write_manifest(&dir);
+    let manifest = load_manifest(&location.config).unwrap();
+    fs::create_dir_all(catalogs_dir(&manifest)).unwrap();
+
+    let rocks = RocksStorage::new(catalogs_dir(&manifest).join("db1")).unwrap();
+    put(&rocks, b"key1", b"value1");
+    drop(rocks);
+
+    CreateBackup {
+      location: location.clone(),
+      catalog: Some("db1".to_owned()),
+    }
+    .execute()
+    .await
+    .unwrap();
+
+    let rocks = open_catalog(&manifest, "db1").unwrap();
+    put(&rocks, b"key2", b"value2");
+
+    // Shouldn't replace the database while it's open
+    assert!(restore(&location, true).execute().await.is_err());
+    assert!(!trash_dir(&manifest).exists());
+    drop(rocks);
+
+    assert!(restore(&location, false).execute().await.is_err());
+    restore(&location, true).execute().await.unwrap();
+
+    // The replaced database shouldn't be in the catalogs dir
+    assert_eq!(list_dirs(&catalogs_dir(&manifest)).unwrap(), vec!["db1"]);
+    let trash = list_dirs(&trash_dir(&manifest)).unwrap();
+    assert_eq!(trash.len(), 1);
+    assert!(trash[0].starts_with("db1."));
+
+    let rocks = open_catalog(&manifest, "db1").unwrap();
+    assert_eq!(get(&rocks, b"key1"), Some(b"value1".to_vec()));
+    assert_eq!(get(&rocks, b"key2"), None);
+    drop(rocks);
+
+    let replaced =
+      RocksStorage::new(trash_dir(&manifest).join(&trash[0])).unwrap();
+    assert_eq!(get(&replaced, b"key2"), Some(b"value2".to_vec()));
+  }
+}
@@ -11,6 +11,7 @@ use arenasql::execution::{
   convert_literals_to_columnar_values, convert_sql_params_to_df_expr,
   replace_placeholders_with_values, AdvisoryLocks, CustomExecutionPlan,
   ExecutionPlanResponse, Privilege, SessionContext, Transaction,
+  TransactionHandle,
 };
 use arenasql::runtime::RuntimeEnv;
 use arenasql::schema::{
@@ -292,6 +293,39 @@ impl CustomExecutionPlan for ListCatalogUserCredentials {
   }
 }
 
+/// Updates the catalog of the users of the renamed catalog so that they
+/// can login to the catalog using its new name. The handle must be of
+/// the catalog that's being renamed and have access to the system schema
+pub(crate) fn rename_catalog_users(
+  handle: &TransactionHandle,
+  catalog: &str,
+  new_catalog: &str,
+) -> Result<()> {
+  let Some(users_table) = handle.get_table(SYSTEM_SCHEMA_NAME, "users") else {
+    return Ok(());
+  };
+  let storage = handle.lock(true)?;
+  let cols = vec![0];
+  let mut rows_iter = HeapIterator::new(&storage, &users_table, &cols);
+  let existing_users =
+    scan_catalog_users(&mut rows_iter, &handle.serializer())?;
+
+  for (row_id, mut user) in existing_users {
+    if user.catalog != catalog {
+      continue;
+    }
+    user.catalog = new_catalog.to_owned();
+    storage.insert_row(
+      &users_table,
+      &row_id,
+      &vec![OwnedSerializedCell::Blob(
+        handle.serializer().serialize(&user)?.into(),
+      )],
+    )?;
+  }
+  Ok(())
+}
+
 #[tracing::instrument(skip(transaction), level = "TRACE")]
 fn create_admin_session_context_for_catalog(
   transaction: &Transaction,
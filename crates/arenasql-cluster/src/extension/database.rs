@@ -0,0 +1,153 @@
+use std::pin::Pin;
+use std::sync::Arc;
+
+use arenasql::ast::statement::{normalize_ident, DatabaseStatement};
+use arenasql::datafusion::{
+  DatafusionField as Field, LogicalPlan, Schema, SchemaRef, TaskContext,
+};
+use arenasql::execution::{CustomExecutionPlan, Transaction};
+use arenasql::schema::DataFrame;
+use arenasql::sqlparser::ast::Statement;
+use arenasql::{Error, Result};
+use futures::Stream;
+
+use crate::server::storage::ClusterStorageFactory;
+
+/// Max length of the database name; same as Postgres' NAMEDATALEN - 1
+const MAX_DATABASE_NAME_LENGTH: usize = 63;
+
+#[derive(Debug, Clone)]
+enum Command {
+  Create { name: String, if_not_exists: bool },
+  Drop { name: String, if_exists: bool },
+  Rename { name: String, new_name: String },
+}
+
+/// Execution plan of `CREATE DATABASE`, `DROP DATABASE` and
+/// `ALTER DATABASE ... RENAME TO`. Databases are stored as directories
+/// under the cluster's `catalogs` dir; see [`ClusterStorageFactory`]
+#[derive(Clone)]
+pub struct ManageDatabase {
+  storage: Arc<ClusterStorageFactory>,
+  command: Command,
+}
+
+impl ManageDatabase {
+  pub fn try_new(
+    transaction: &Transaction,
+    stmt: &Statement,
+  ) -> Result<Option<Self>> {
+    let command = match stmt {
+      Statement::CreateDatabase {
+        db_name,
+        if_not_exists,
+        ..
+      } => {
+        let [name] = db_name.0.as_slice() else {
+          return Err(Error::InvalidQuery(format!(
+            "invalid database name \"{}\"",
+            db_name
+          )));
+        };
+        Command::Create {
+          name: normalize_ident(name),
+          if_not_exists: *if_not_exists,
+        }
+      }
+      _ => match DatabaseStatement::from_statement(stmt) {
+        Some(DatabaseStatement::Drop { name, if_exists }) => {
+          Command::Drop { name, if_exists }
+        }
+        Some(DatabaseStatement::Rename { name, new_name }) => {
+          Command::Rename { name, new_name }
+        }
+        None => return Ok(None),
+      },
+    };
+
+    match &command {
+      Command::Create { name, .. } | Command::Drop { name, .. } => {
+        validate_database_name(name)?;
+      }
+      Command::Rename { name, new_name } => {
+        validate_database_name(name)?;
+        validate_database_name(new_name)?;
+      }
+    }
+
+    let storage = transaction
+      .session_state()
+      .read()
+      .borrow::<Arc<ClusterStorageFactory>>()
+      .clone();
+    Ok(Some(Self { storage, command }))
+  }
+}
+
+impl CustomExecutionPlan for ManageDatabase {
+  fn schema(&self) -> SchemaRef {
+    SchemaRef::new(Schema::new(Vec::<Field>::new()))
+  }
+
+  fn execute(
+    &self,
+    _partition: usize,
+    _context: Arc<TaskContext>,
+    _exprs: Vec<arenasql::datafusion::Expr>,
+    _inputs: Vec<LogicalPlan>,
+  ) -> Result<Pin<Box<dyn Stream<Item = Result<DataFrame>> + Send>>> {
+    let storage = self.storage.clone();
+    let command = self.command.clone();
+    let query = async move {
+      // Opening, closing and deleting the databases blocks
+      tokio::task::spawn_blocking(move || execute_command(&storage, command))
+        .await
+        .map_err(|e| Error::InternalError(e.to_string()))??;
+      Ok(DataFrame::empty())
+    };
+    Ok(Box::pin(futures::stream::once(query)))
+  }
+}
+
+fn execute_command(
+  storage: &ClusterStorageFactory,
+  command: Command,
+) -> Result<()> {
+  match command {
+    Command::Create {
+      name,
+      if_not_exists,
+    } => {
+      if !storage.create_catalog(&name)? && !if_not_exists {
+        return Err(Error::DatabaseAlreadyExists(name));
+      }
+    }
+    Command::Drop { name, if_exists } => {
+      if !storage.drop_catalog(&name)? && !if_exists {
+        return Err(Error::DatabaseDoesntExist(name));
+      }
+    }
+    Command::Rename { name, new_name } => {
+      storage.rename_catalog(&name, &new_name)?;
+    }
+  }
+  Ok(())
+}
+
+/// Since the databases are stored in directories named after them, only
+/// allow names that are safe to use as directory names
+fn validate_database_name(name: &str) -> Result<()> {
+  let is_valid = !name.is_empty()
+    && name.len() <= MAX_DATABASE_NAME_LENGTH
+    && !name.starts_with('-')
+    && name
+      .chars()
+      .all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-');
+  match is_valid {
+    true => Ok(()),
+    false => Err(Error::InvalidQuery(format!(
+      "invalid database name \"{}\"",
+      name
+    ))),
+  }
+}
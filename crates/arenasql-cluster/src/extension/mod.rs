@@ -10,15 +10,20 @@ use arenasql::Result;
 use once_cell::sync::Lazy;
 
 use self::catalog_users::SetCatalogUserCredentials;
+use self::database::ManageDatabase;
 use crate::extension::catalog_users::ListCatalogUserCredentials;
 
 pub(crate) mod catalog_users;
+pub(crate) mod database;
 
 /// Returns a custom admin execution plan extension
 pub fn admin_exetension(
   transaction: &Transaction,
   stmt: &Statement,
 ) -> Result<Option<Arc<dyn CustomExecutionPlan>>> {
+  if let Some(plan) = ManageDatabase::try_new(transaction, stmt)? {
+    return Ok(Some(Arc::new(plan)));
+  }
   match stmt {
     Statement::Execute { name, parameters } => {
       let extension = SCALAR_EXTENSIONS.get(&name.value).map(|f| f.clone());
@@ -1,4 +1,5 @@
 mod auth;
+mod backup;
 mod error;
 mod extension;
 mod init;
@@ -12,6 +13,9 @@ use std::sync::atomic::AtomicBool;
 use std::sync::Arc;
 
 use anyhow::{Context, Result};
+use backup::{
+  CreateBackup, ExportCatalog, ListBackups, RestoreBackup, VerifyBackups,
+};
 use clap::Parser;
 use init::InitCluster;
 use signal_hook::consts::TERM_SIGNALS;
@@ -51,6 +55,21 @@ enum Commands {
 
   /// Start Arena DB cluster server
   Start(ClusterOptions),
+
+  /// Back up databases to the backup directory
+  Backup(CreateBackup),
+
+  /// List the backups of the databases
+  ListBackups(ListBackups),
+
+  /// Restore a database from a backup
+  Restore(RestoreBackup),
+
+  /// Verify the integrity of the backups
+  Verify(VerifyBackups),
+
+  /// Export a database to a portable directory
+  Export(ExportCatalog),
 }
 
 fn main() {
@@ -86,6 +105,11 @@ fn main() {
       match args.command {
         Commands::Init(cmd) => cmd.execute().await,
         Commands::Start(cmd) => cmd.execute(shutdown_rx).await,
+        Commands::Backup(cmd) => cmd.execute().await,
+        Commands::ListBackups(cmd) => cmd.execute().await,
+        Commands::Restore(cmd) => cmd.execute().await,
+        Commands::Verify(cmd) => cmd.execute().await,
+        Commands::Export(cmd) => cmd.execute().await,
       }
     });
 
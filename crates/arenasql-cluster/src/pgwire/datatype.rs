@@ -3,7 +3,7 @@ use std::str::FromStr;
 use arenasql::datafusion::{DatafusionDataType, DatafusionField};
 use arenasql::pgwire::api::results::{FieldFormat, FieldInfo};
 use arenasql::pgwire::api::Type;
-use arenasql::schema::DataType as ArenaDataType;
+use arenasql::schema::{array_pg_type, DataType as ArenaDataType};
 
 pub fn to_field_info(
   field: &DatafusionField,
@@ -25,11 +25,14 @@ pub fn derive_pg_type(
   // If there's metadata, use it, else derive default type
   // Metadata will be set if the type came from table schema
   match arena_data_type {
-    Some(ty) => ArenaDataType::from_str(ty).unwrap().pg_type(),
+    // The element type of arrays isn't part of the metadata, so derive
+    // it from the list field
+    Some(ty) if ty != "ARRAY" => ArenaDataType::from_str(ty).unwrap().pg_type(),
     // The following is to derive fields that aren't associated with
     // a table, for sth like scalar value
     _ => match data_type {
       DatafusionDataType::Boolean => Type::BOOL,
+      DatafusionDataType::Int16 => Type::INT2,
       DatafusionDataType::Int32 => Type::INT4,
       DatafusionDataType::UInt32 => Type::INT4,
       DatafusionDataType::Int64 => Type::INT8,
@@ -37,9 +40,18 @@ pub fn derive_pg_type(
       DatafusionDataType::Float32 => Type::FLOAT4,
       DatafusionDataType::Float64 => Type::FLOAT8,
       DatafusionDataType::Utf8 => Type::TEXT,
+      DatafusionDataType::Decimal128(_, _) => Type::NUMERIC,
       DatafusionDataType::Decimal256(_, _) => Type::JSONB,
-      DatafusionDataType::Timestamp(_, _) => Type::TIMESTAMP,
-      DatafusionDataType::List(_) => Type::FLOAT4_ARRAY,
+      DatafusionDataType::Timestamp(_, None) => Type::TIMESTAMP,
+      DatafusionDataType::Timestamp(_, Some(_)) => Type::TIMESTAMPTZ,
+      DatafusionDataType::Date32 => Type::DATE,
+      DatafusionDataType::Time64(_) => Type::TIME,
+      DatafusionDataType::Interval(_) => Type::INTERVAL,
+      DatafusionDataType::List(field) => array_pg_type(&derive_pg_type(
+        field.data_type(),
+        field.metadata().get("TYPE"),
+      ))
+      .unwrap_or(Type::FLOAT4_ARRAY),
       DatafusionDataType::Null => Type::VOID,
       DatafusionDataType::Binary | DatafusionDataType::LargeBinary => {
         Type::BYTEA
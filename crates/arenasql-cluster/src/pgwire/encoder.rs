@@ -2,20 +2,29 @@ use std::io::Write;
 use std::sync::Arc;
 
 use arenasql::arrow::{
-  as_binary_array, as_null_array, as_primitive_array, as_string_array,
-  TimeUnit, TimestampNanosecondType,
+  as_binary_array, as_boolean_array, as_null_array, as_primitive_array,
+  as_string_array, ArrayRef, Date32Type, Decimal128Type, Float32Type,
+  Float64Type, Int16Type, Int32Type, Int64Type, IntervalMonthDayNanoType,
+  IntervalUnit, Time64NanosecondType, TimeUnit, TimestampNanosecondType,
 };
 use arenasql::bytes::BufMut;
+use arenasql::chrono::{DateTime, NaiveDate, NaiveTime, Utc};
 use arenasql::datafusion::DatafusionDataType;
 use arenasql::pgwire::api::results::DataRowEncoder;
 use arenasql::pgwire::api::Type;
 use arenasql::pgwire::error::{PgWireError, PgWireResult};
 use arenasql::pgwire::types::ToSqlText;
-use arenasql::postgres_types::{IsNull, ToSql};
+use arenasql::postgres_types::{IsNull, Kind, ToSql};
+use arenasql::schema::VECTOR_PG_TYPE;
 use arenasql::{arrow, bytes, postgres_types};
 use arrow::Array;
 
 use crate::error::ArenaClusterError;
+use crate::pgwire::types::{PgArray, PgInterval, PgNumeric, PgUuid};
+use crate::pgwire::vector::PgVector;
+
+/// Number of days from 0001-01-01 to 1970-01-01
+const UNIX_EPOCH_DAYS_FROM_CE: i32 = 719_163;
 
 #[macro_export]
 macro_rules! encode_all_fields {
@@ -83,17 +92,37 @@ pub fn encode_column_array(
         .iter()
         .zip(encoders)
         .map(|(value, encoder)| {
-          encoder.encode_field(&value.and_then(|v| {
-            match arenasql::chrono::DateTime::from_timestamp(
-              v / 1_000_000_000,
-              (v % 1_000_000_000) as u32,
-            ) {
-              Some(parsed) => Some(parsed),
-              None => {
-                eprintln!("Error parsing timestamp [{}]", v);
-                None
-              }
-            }
+          encoder.encode_field(&value.and_then(to_datetime))
+        })
+        .collect()
+    }
+    DatafusionDataType::Date32 => as_primitive_array::<Date32Type>(array)
+      .iter()
+      .zip(encoders)
+      .map(|(value, encoder)| encoder.encode_field(&value.and_then(to_date)))
+      .collect(),
+    DatafusionDataType::Time64(TimeUnit::Nanosecond) => {
+      as_primitive_array::<Time64NanosecondType>(array)
+        .iter()
+        .zip(encoders)
+        .map(|(value, encoder)| encoder.encode_field(&value.and_then(to_time)))
+        .collect()
+    }
+    DatafusionDataType::Interval(IntervalUnit::MonthDayNano) => {
+      as_primitive_array::<IntervalMonthDayNanoType>(array)
+        .iter()
+        .zip(encoders)
+        .map(|(value, encoder)| encoder.encode_field(&value.map(to_interval)))
+        .collect()
+    }
+    DatafusionDataType::Decimal128(_, scale) => {
+      as_primitive_array::<Decimal128Type>(array)
+        .iter()
+        .zip(encoders)
+        .map(|(value, encoder)| {
+          encoder.encode_field(&value.map(|value| PgNumeric {
+            value,
+            scale: *scale,
           }))
         })
         .collect()
@@ -123,6 +152,11 @@ pub fn encode_column_array(
           encoder.encode_field(&value.map(|v| SerializedJson(&v.as_bytes())))
         })
         .collect(),
+      Type::UUID => as_string_array(array)
+        .iter()
+        .zip(encoders)
+        .map(|(value, encoder)| encoder.encode_field(&value.map(PgUuid)))
+        .collect(),
       _ => encode_all_fields!(arrow::StringArray, array, encoders),
     },
     DatafusionDataType::List(field) => match pg_type.clone() {
@@ -142,13 +176,41 @@ pub fn encode_column_array(
           encoder.encode_field(&float_arr)
         })
         .collect(),
-      ty => {
-        unimplemented!(
+      ty if ty == *VECTOR_PG_TYPE => array
+        .as_any()
+        .downcast_ref::<arrow::ListArray>()
+        .unwrap()
+        .iter()
+        .zip(encoders)
+        .map(|(arr, encoder)| {
+          let vector = arr.map(|array| {
+            PgVector(
+              as_primitive_array::<arrow::Float32Type>(&array)
+                .values()
+                .to_vec(),
+            )
+          });
+          encoder.encode_field(&vector)
+        })
+        .collect(),
+      ty => match ty.kind() {
+        Kind::Array(member) => array
+          .as_any()
+          .downcast_ref::<arrow::ListArray>()
+          .unwrap()
+          .iter()
+          .zip(encoders)
+          .map(|(values, encoder)| match values {
+            Some(values) => encode_array(encoder, &values, member),
+            None => encoder.encode_field(&None::<i64>),
+          })
+          .collect(),
+        _ => unimplemented!(
           "Converting List[{:?}] to {:?} not implemented",
           field,
           ty
-        )
-      }
+        ),
+      },
     },
     DatafusionDataType::Null => (0..as_null_array(&array).len())
       .zip(encoders)
@@ -160,6 +222,129 @@ pub fn encode_column_array(
   }
 }
 
+/// Encodes the elements of a single array value; `member` is the pg type
+/// of the elements
+fn encode_array(
+  encoder: &mut DataRowEncoder,
+  values: &ArrayRef,
+  member: &Type,
+) -> PgWireResult<()> {
+  match values.data_type() {
+    DatafusionDataType::Boolean => {
+      encoder.encode_field(&PgArray(as_boolean_array(values).iter().collect()))
+    }
+    DatafusionDataType::Int16 => encoder.encode_field(&PgArray(
+      as_primitive_array::<Int16Type>(values).iter().collect(),
+    )),
+    DatafusionDataType::Int32 => encoder.encode_field(&PgArray(
+      as_primitive_array::<Int32Type>(values).iter().collect(),
+    )),
+    DatafusionDataType::Int64 => encoder.encode_field(&PgArray(
+      as_primitive_array::<Int64Type>(values).iter().collect(),
+    )),
+    DatafusionDataType::Float32 => encoder.encode_field(&PgArray(
+      as_primitive_array::<Float32Type>(values).iter().collect(),
+    )),
+    DatafusionDataType::Float64 => encoder.encode_field(&PgArray(
+      as_primitive_array::<Float64Type>(values).iter().collect(),
+    )),
+    DatafusionDataType::Binary => encoder.encode_field(&PgArray(
+      as_binary_array(values)
+        .expect("Unable to downcast to binary array")
+        .iter()
+        .collect(),
+    )),
+    DatafusionDataType::Timestamp(TimeUnit::Nanosecond, _) => encoder
+      .encode_field(&PgArray(
+        as_primitive_array::<TimestampNanosecondType>(values)
+          .iter()
+          .map(|v| v.and_then(to_datetime))
+          .collect(),
+      )),
+    DatafusionDataType::Date32 => encoder.encode_field(&PgArray(
+      as_primitive_array::<Date32Type>(values)
+        .iter()
+        .map(|v| v.and_then(to_date))
+        .collect(),
+    )),
+    DatafusionDataType::Time64(TimeUnit::Nanosecond) => {
+      encoder.encode_field(&PgArray(
+        as_primitive_array::<Time64NanosecondType>(values)
+          .iter()
+          .map(|v| v.and_then(to_time))
+          .collect(),
+      ))
+    }
+    DatafusionDataType::Interval(IntervalUnit::MonthDayNano) => encoder
+      .encode_field(&PgArray(
+        as_primitive_array::<IntervalMonthDayNanoType>(values)
+          .iter()
+          .map(|v| v.map(to_interval))
+          .collect(),
+      )),
+    DatafusionDataType::Decimal128(_, scale) => encoder.encode_field(&PgArray(
+      as_primitive_array::<Decimal128Type>(values)
+        .iter()
+        .map(|v| {
+          v.map(|value| PgNumeric {
+            value,
+            scale: *scale,
+          })
+        })
+        .collect(),
+    )),
+    DatafusionDataType::Utf8 => {
+      let values = as_string_array(values).iter();
+      match *member {
+        Type::UUID => encoder
+          .encode_field(&PgArray(values.map(|v| v.map(PgUuid)).collect())),
+        Type::JSONB => encoder.encode_field(&PgArray(
+          values
+            .map(|v| v.map(|v| SerializedJson(v.as_bytes())))
+            .collect(),
+        )),
+        _ => encoder.encode_field(&PgArray(values.collect())),
+      }
+    }
+    dt => Err(PgWireError::ApiError(Box::new(
+      ArenaClusterError::UnsupportedDataType(format!("{} array", dt)),
+    ))),
+  }
+}
+
+fn to_datetime(nanos: i64) -> Option<DateTime<Utc>> {
+  match DateTime::from_timestamp(
+    nanos / 1_000_000_000,
+    (nanos % 1_000_000_000) as u32,
+  ) {
+    Some(parsed) => Some(parsed),
+    None => {
+      eprintln!("Error parsing timestamp [{}]", nanos);
+      None
+    }
+  }
+}
+
+fn to_date(days: i32) -> Option<NaiveDate> {
+  NaiveDate::from_num_days_from_ce_opt(days + UNIX_EPOCH_DAYS_FROM_CE)
+}
+
+fn to_time(nanos: i64) -> Option<NaiveTime> {
+  NaiveTime::from_num_seconds_from_midnight_opt(
+    (nanos / 1_000_000_000) as u32,
+    (nanos % 1_000_000_000) as u32,
+  )
+}
+
+fn to_interval(value: i128) -> PgInterval {
+  let (months, days, nanos) = IntervalMonthDayNanoType::to_parts(value);
+  PgInterval {
+    months,
+    days,
+    nanos,
+  }
+}
+
 /// Json that's already serialized to bytes
 #[derive(Debug)]
 struct SerializedJson<'a>(&'a [u8]);
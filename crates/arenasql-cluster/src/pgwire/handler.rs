@@ -4,7 +4,7 @@ use std::sync::Arc;
 use arenasql::arrow::{Float32Type, ListArray};
 use arenasql::bytes::Bytes;
 use arenasql::chrono::TimeZone;
-use arenasql::chrono::{Duration, Utc};
+use arenasql::chrono::{Duration, NaiveDate, Utc};
 use arenasql::datafusion::{LogicalPlan, ScalarValue};
 use arenasql::pgwire::api::portal::{Format, Portal};
 use arenasql::pgwire::api::query::{
@@ -22,7 +22,7 @@ use arenasql::pgwire::messages::extendedquery::{
 };
 use arenasql::pgwire::messages::PgWireBackendMessage;
 use arenasql::postgres_types::FromSql;
-use arenasql::schema::CTID_COLUMN;
+use arenasql::schema::{CTID_COLUMN, VECTOR_PG_TYPE};
 use arenasql::{pgwire, Error};
 use async_trait::async_trait;
 use futures::{Sink, SinkExt};
@@ -33,6 +33,7 @@ use super::portal::ArenaPortalState;
 use super::{ArenaQuery, ArenaQueryParser};
 use crate::auth::AuthHeader;
 use crate::pgwire::datatype;
+use crate::pgwire::vector::PgVector;
 use crate::server::ArenaSqlCluster;
 
 #[async_trait]
@@ -434,7 +435,37 @@ fn convert_bytes_to_scalar_value(
         ),
       )));
     }
-    Type::TIMESTAMP => {
+    Type::UUID => ScalarValue::Utf8(
+      bytes
+        .map(|by| match is_binary_format {
+          false => std::str::from_utf8(by).map(|s| s.to_owned()).ok(),
+          true => uuid::Uuid::from_slice(by).map(|u| u.to_string()).ok(),
+        })
+        .map(|uuid| uuid.ok_or_else(|| invalid_param_err(index)))
+        .transpose()?,
+    ),
+    Type::DATE => ScalarValue::Date32(
+      bytes
+        .map(|by| match is_binary_format {
+          false => NaiveDate::parse_from_str(
+            &parse_from_text::<String>(index, by)?,
+            "%Y-%m-%d",
+          )
+          .map_err(|_| invalid_param_err(index))
+          .map(|date| {
+            (date - NaiveDate::from_ymd_opt(1970, 1, 1).unwrap()).num_days()
+              as i32
+          }),
+          // Binary date is the number of days since 2000-01-01
+          true => by
+            .as_bytes()
+            .try_into()
+            .map_err(|_| invalid_param_err(index))
+            .map(|v| i32::from_be_bytes(v) + DAYS_FROM_UNIX_EPOCH_TO_Y2K),
+        })
+        .transpose()?,
+    ),
+    Type::TIMESTAMP | Type::TIMESTAMPTZ => {
       return Ok(ScalarValue::TimestampNanosecond(
         bytes
           .map(|by| match is_binary_format {
@@ -452,9 +483,25 @@ fn convert_bytes_to_scalar_value(
             let datetime = base_date + duration;
             datetime.timestamp_micros() * 1000
           }),
-        None,
+        match *r#type {
+          Type::TIMESTAMPTZ => Some("+00:00".into()),
+          _ => None,
+        },
       ));
     }
+    ref ty if *ty == *VECTOR_PG_TYPE => {
+      return Ok(ScalarValue::List(Arc::new(
+        ListArray::from_iter_primitive::<Float32Type, _, _>(
+          bytes
+            .map(|b| {
+              let vector = PgVector::decode(b, is_binary_format)?;
+              Ok::<_, Error>(vec![Some(vector.0.into_iter().map(|v| Some(v)))])
+            })
+            .transpose()?
+            .unwrap_or_default(),
+        ),
+      )));
+    }
     _ => {
       unimplemented!("Converting bytes to ScalarValue for type {:?}", r#type)
     }
@@ -463,6 +510,9 @@ fn convert_bytes_to_scalar_value(
   Ok(scalar)
 }
 
+/// Number of days from 1970-01-01 to 2000-01-01
+const DAYS_FROM_UNIX_EPOCH_TO_Y2K: i32 = 10_957;
+
 fn parse_from_text<T: std::str::FromStr>(
   index: usize,
   bytes: &Bytes,
@@ -7,5 +7,7 @@ pub(crate) mod datatype;
 pub(crate) mod encoder;
 pub(crate) mod rowconverter;
 pub(crate) mod statement;
+pub(crate) mod types;
+pub(crate) mod vector;
 
 pub use parser::{ArenaQuery, ArenaQueryParser};
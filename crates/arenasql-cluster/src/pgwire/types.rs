@@ -0,0 +1,456 @@
+use arenasql::bytes::{BufMut, BytesMut};
+use arenasql::pgwire::api::Type;
+use arenasql::pgwire::types::ToSqlText;
+use arenasql::postgres_types::{IsNull, Kind, ToSql};
+
+/// UUID that's passed around as a string in datafusion
+///
+/// Text format: `a0eebc99-9c0b-4ef8-bb6d-6bb9bd380a11`
+/// Binary format: 16 bytes
+#[derive(Debug)]
+pub(crate) struct PgUuid<'a>(pub &'a str);
+
+impl<'a> ToSqlText for PgUuid<'a> {
+  fn to_sql_text(
+    &self,
+    _ty: &Type,
+    out: &mut BytesMut,
+  ) -> Result<IsNull, Box<dyn std::error::Error + Sync + Send>>
+  where
+    Self: Sized,
+  {
+    out.put_slice(self.0.as_bytes());
+    Ok(IsNull::No)
+  }
+}
+
+impl<'a> ToSql for PgUuid<'a> {
+  fn to_sql(
+    &self,
+    _ty: &Type,
+    out: &mut BytesMut,
+  ) -> Result<IsNull, Box<dyn std::error::Error + Sync + Send>>
+  where
+    Self: Sized,
+  {
+    out.put_slice(uuid::Uuid::parse_str(self.0)?.as_bytes());
+    Ok(IsNull::No)
+  }
+
+  fn to_sql_checked(
+    &self,
+    ty: &Type,
+    out: &mut BytesMut,
+  ) -> Result<IsNull, Box<dyn std::error::Error + Sync + Send>> {
+    self.to_sql(ty, out)
+  }
+
+  fn accepts(_ty: &Type) -> bool
+  where
+    Self: Sized,
+  {
+    true
+  }
+}
+
+/// Decimal value with the given scale
+///
+/// Text format: `-123.450`
+/// Binary format: number of digits (i16), weight of the first digit (i16),
+/// sign (u16), display scale (u16) and the base 10000 digits (i16), all in
+/// network byte order
+#[derive(Debug)]
+pub(crate) struct PgNumeric {
+  pub value: i128,
+  pub scale: i8,
+}
+
+impl PgNumeric {
+  /// Returns the integer and fractional digits of the absolute value
+  fn digits(&self) -> (String, String) {
+    let mut digits = self.value.unsigned_abs().to_string();
+    if self.scale < 0 {
+      digits.push_str(&"0".repeat(self.scale.unsigned_abs() as usize));
+    }
+    let scale = self.scale.max(0) as usize;
+    let digits = format!("{:0>width$}", digits, width = scale + 1);
+    let (integer, fraction) = digits.split_at(digits.len() - scale);
+    (integer.to_owned(), fraction.to_owned())
+  }
+}
+
+impl ToSqlText for PgNumeric {
+  fn to_sql_text(
+    &self,
+    _ty: &Type,
+    out: &mut BytesMut,
+  ) -> Result<IsNull, Box<dyn std::error::Error + Sync + Send>>
+  where
+    Self: Sized,
+  {
+    let (integer, fraction) = self.digits();
+    if self.value < 0 {
+      out.put_u8(b'-');
+    }
+    out.put_slice(integer.as_bytes());
+    if !fraction.is_empty() {
+      out.put_u8(b'.');
+      out.put_slice(fraction.as_bytes());
+    }
+    Ok(IsNull::No)
+  }
+}
+
+impl ToSql for PgNumeric {
+  fn to_sql(
+    &self,
+    _ty: &Type,
+    out: &mut BytesMut,
+  ) -> Result<IsNull, Box<dyn std::error::Error + Sync + Send>>
+  where
+    Self: Sized,
+  {
+    let (integer, fraction) = self.digits();
+    // Pad the digits so that they can be split into groups of 4
+    let integer =
+      format!("{:0>width$}", integer, width = (integer.len() + 3) / 4 * 4);
+    let fraction = format!(
+      "{:0<width$}",
+      fraction,
+      width = (fraction.len() + 3) / 4 * 4
+    );
+
+    let mut weight = (integer.len() / 4) as i16 - 1;
+    let mut groups = integer
+      .as_bytes()
+      .chunks(4)
+      .chain(fraction.as_bytes().chunks(4))
+      .map(|group| std::str::from_utf8(group).unwrap().parse::<i16>())
+      .collect::<Result<Vec<i16>, _>>()?;
+    // Leading and trailing zeros aren't sent
+    while groups.first() == Some(&0) {
+      groups.remove(0);
+      weight -= 1;
+    }
+    while groups.last() == Some(&0) {
+      groups.pop();
+    }
+    if groups.is_empty() {
+      weight = 0;
+    }
+
+    out.put_i16(groups.len().try_into()?);
+    out.put_i16(weight);
+    out.put_u16(if self.value < 0 { 0x4000 } else { 0 });
+    out.put_u16(self.scale.max(0) as u16);
+    groups.iter().for_each(|group| out.put_i16(*group));
+    Ok(IsNull::No)
+  }
+
+  fn to_sql_checked(
+    &self,
+    ty: &Type,
+    out: &mut BytesMut,
+  ) -> Result<IsNull, Box<dyn std::error::Error + Sync + Send>> {
+    self.to_sql(ty, out)
+  }
+
+  fn accepts(_ty: &Type) -> bool
+  where
+    Self: Sized,
+  {
+    true
+  }
+}
+
+/// Interval with months, days and nanoseconds
+///
+/// Text format: `1 year 2 mons 3 days 04:05:06.5`
+/// Binary format: microseconds (i64), days (i32) and months (i32), all in
+/// network byte order
+#[derive(Debug)]
+pub(crate) struct PgInterval {
+  pub months: i32,
+  pub days: i32,
+  pub nanos: i64,
+}
+
+impl ToSqlText for PgInterval {
+  fn to_sql_text(
+    &self,
+    _ty: &Type,
+    out: &mut BytesMut,
+  ) -> Result<IsNull, Box<dyn std::error::Error + Sync + Send>>
+  where
+    Self: Sized,
+  {
+    let plural = |value: i32, unit: &str, units: &str| match value {
+      0 => None,
+      1 => Some(format!("{} {}", value, unit)),
+      _ => Some(format!("{} {}", value, units)),
+    };
+    let mut parts = [
+      plural(self.months / 12, "year", "years"),
+      plural(self.months % 12, "mon", "mons"),
+      plural(self.days, "day", "days"),
+    ]
+    .into_iter()
+    .flatten()
+    .collect::<Vec<String>>();
+
+    if self.nanos != 0 || parts.is_empty() {
+      let micros = (self.nanos / 1000).unsigned_abs();
+      let mut time = format!(
+        "{}{:02}:{:02}:{:02}",
+        if self.nanos < 0 { "-" } else { "" },
+        micros / 3_600_000_000,
+        micros / 60_000_000 % 60,
+        micros / 1_000_000 % 60
+      );
+      if micros % 1_000_000 > 0 {
+        let fraction = format!("{:06}", micros % 1_000_000);
+        time.push('.');
+        time.push_str(fraction.trim_end_matches('0'));
+      }
+      parts.push(time);
+    }
+    out.put_slice(parts.join(" ").as_bytes());
+    Ok(IsNull::No)
+  }
+}
+
+impl ToSql for PgInterval {
+  fn to_sql(
+    &self,
+    _ty: &Type,
+    out: &mut BytesMut,
+  ) -> Result<IsNull, Box<dyn std::error::Error + Sync + Send>>
+  where
+    Self: Sized,
+  {
+    out.put_i64(self.nanos / 1000);
+    out.put_i32(self.days);
+    out.put_i32(self.months);
+    Ok(IsNull::No)
+  }
+
+  fn to_sql_checked(
+    &self,
+    ty: &Type,
+    out: &mut BytesMut,
+  ) -> Result<IsNull, Box<dyn std::error::Error + Sync + Send>> {
+    self.to_sql(ty, out)
+  }
+
+  fn accepts(_ty: &Type) -> bool
+  where
+    Self: Sized,
+  {
+    true
+  }
+}
+
+/// One dimensional array whose elements are encoded using the member
+/// type of the array type
+///
+/// Text format: `{a,"b c",NULL}`
+/// Binary format: same as Postgres arrays
+#[derive(Debug)]
+pub(crate) struct PgArray<T>(pub Vec<Option<T>>);
+
+impl<T: ToSqlText + ToSql> ToSqlText for PgArray<T> {
+  fn to_sql_text(
+    &self,
+    ty: &Type,
+    out: &mut BytesMut,
+  ) -> Result<IsNull, Box<dyn std::error::Error + Sync + Send>>
+  where
+    Self: Sized,
+  {
+    let member = match ty.kind() {
+      Kind::Array(member) => member,
+      _ => ty,
+    };
+    out.put_u8(b'{');
+    for (index, element) in self.0.iter().enumerate() {
+      if index > 0 {
+        out.put_u8(b',');
+      }
+      let mut text = BytesMut::new();
+      let is_null = match element {
+        Some(element) => element.to_sql_text(member, &mut text)?,
+        None => IsNull::Yes,
+      };
+      if let IsNull::Yes = is_null {
+        out.put_slice(b"NULL");
+        continue;
+      }
+
+      let needs_quotes = text.is_empty()
+        || text.eq_ignore_ascii_case(b"NULL")
+        || text.iter().any(|c| {
+          matches!(c, b'{' | b'}' | b',' | b'"' | b'\\')
+            || c.is_ascii_whitespace()
+        });
+      if !needs_quotes {
+        out.put_slice(&text);
+        continue;
+      }
+      out.put_u8(b'"');
+      for c in text.iter() {
+        if matches!(c, b'"' | b'\\') {
+          out.put_u8(b'\\');
+        }
+        out.put_u8(*c);
+      }
+      out.put_u8(b'"');
+    }
+    out.put_u8(b'}');
+    Ok(IsNull::No)
+  }
+}
+
+impl<T: ToSqlText + ToSql> ToSql for PgArray<T> {
+  fn to_sql(
+    &self,
+    ty: &Type,
+    out: &mut BytesMut,
+  ) -> Result<IsNull, Box<dyn std::error::Error + Sync + Send>>
+  where
+    Self: Sized,
+  {
+    self.0.to_sql(ty, out)
+  }
+
+  fn to_sql_checked(
+    &self,
+    ty: &Type,
+    out: &mut BytesMut,
+  ) -> Result<IsNull, Box<dyn std::error::Error + Sync + Send>> {
+    self.to_sql(ty, out)
+  }
+
+  fn accepts(_ty: &Type) -> bool
+  where
+    Self: Sized,
+  {
+    true
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use arenasql::bytes::BytesMut;
+  use arenasql::pgwire::api::Type;
+  use arenasql::pgwire::types::ToSqlText;
+  use arenasql::postgres_types::ToSql;
+
+  use super::{PgArray, PgInterval, PgNumeric, PgUuid};
+
+  #[test]
+  fn test_pguuid_wire_format() {
+    let uuid = PgUuid("a0eebc99-9c0b-4ef8-bb6d-6bb9bd380a11");
+
+    let mut binary = BytesMut::new();
+    uuid.to_sql(&Type::UUID, &mut binary).unwrap();
+    assert_eq!(binary.len(), 16);
+    assert_eq!(&binary[..4], &[0xa0, 0xee, 0xbc, 0x99]);
+
+    assert!(PgUuid("invalid")
+      .to_sql(&Type::UUID, &mut BytesMut::new())
+      .is_err());
+  }
+
+  #[test]
+  fn test_pgnumeric_wire_format() {
+    let numeric = PgNumeric {
+      value: -12345678,
+      scale: 3,
+    };
+
+    let mut text = BytesMut::new();
+    numeric.to_sql_text(&Type::NUMERIC, &mut text).unwrap();
+    assert_eq!(&text[..], b"-12345.678");
+
+    let mut binary = BytesMut::new();
+    numeric.to_sql(&Type::NUMERIC, &mut binary).unwrap();
+    // ndigits = 3, weight = 1, sign = negative, dscale = 3,
+    // digits = [1, 2345, 6780]
+    assert_eq!(
+      &binary[..],
+      &[0, 3, 0, 1, 0x40, 0, 0, 3, 0, 1, 0x09, 0x29, 0x1a, 0x7c]
+    );
+
+    let mut text = BytesMut::new();
+    PgNumeric {
+      value: 12,
+      scale: 4,
+    }
+    .to_sql_text(&Type::NUMERIC, &mut text)
+    .unwrap();
+    assert_eq!(&text[..], b"0.0012");
+
+    let mut binary = BytesMut::new();
+    PgNumeric {
+      value: 12,
+      scale: 4,
+    }
+    .to_sql(&Type::NUMERIC, &mut binary)
+    .unwrap();
+    assert_eq!(&binary[..], &[0, 1, 0xff, 0xff, 0, 0, 0, 4, 0, 12]);
+  }
+
+  #[test]
+  fn test_pginterval_wire_format() {
+    let interval = PgInterval {
+      months: 14,
+      days: 3,
+      nanos: 14_706_500_000_000,
+    };
+
+    let mut text = BytesMut::new();
+    interval.to_sql_text(&Type::INTERVAL, &mut text).unwrap();
+    assert_eq!(&text[..], b"1 year 2 mons 3 days 04:05:06.5");
+
+    let mut binary = BytesMut::new();
+    interval.to_sql(&Type::INTERVAL, &mut binary).unwrap();
+    assert_eq!(&binary[..8], &14_706_500_000i64.to_be_bytes());
+    assert_eq!(&binary[8..12], &3i32.to_be_bytes());
+    assert_eq!(&binary[12..], &14i32.to_be_bytes());
+
+    let mut text = BytesMut::new();
+    PgInterval {
+      months: 0,
+      days: 0,
+      nanos: 0,
+    }
+    .to_sql_text(&Type::INTERVAL, &mut text)
+    .unwrap();
+    assert_eq!(&text[..], b"00:00:00");
+  }
+
+  #[test]
+  fn test_pgarray_wire_format() {
+    let array = PgArray(vec![Some("a"), Some("b c"), None, Some("d\"e")]);
+
+    let mut text = BytesMut::new();
+    array.to_sql_text(&Type::TEXT_ARRAY, &mut text).unwrap();
+    assert_eq!(&text[..], br#"{a,"b c",NULL,"d\"e"}"#);
+
+    let mut text = BytesMut::new();
+    PgArray(vec![Some(1i64), Some(2)])
+      .to_sql_text(&Type::INT8_ARRAY, &mut text)
+      .unwrap();
+    assert_eq!(&text[..], b"{1,2}");
+
+    let mut binary = BytesMut::new();
+    PgArray(vec![Some(1i32), None])
+      .to_sql(&Type::INT4_ARRAY, &mut binary)
+      .unwrap();
+    // ndim = 1, has nulls = 1, element oid = 23, length = 2, lower bound = 1
+    assert_eq!(
+      &binary[..20],
+      &[0, 0, 0, 1, 0, 0, 0, 1, 0, 0, 0, 23, 0, 0, 0, 2, 0, 0, 0, 1]
+    );
+  }
+}
@@ -0,0 +1,124 @@
+use arenasql::bytes::{Buf, BufMut, BytesMut};
+use arenasql::pgwire::api::Type;
+use arenasql::pgwire::types::ToSqlText;
+use arenasql::postgres_types::{IsNull, ToSql};
+use arenasql::Error;
+
+/// Vector encoded in pgvector's wire format
+///
+/// Text format: `[1,2,3]`
+/// Binary format: dimension (u16), unused (u16) and the values (f32),
+/// all in network byte order
+#[derive(Debug)]
+pub(crate) struct PgVector(pub Vec<f32>);
+
+impl PgVector {
+  pub(crate) fn decode(
+    bytes: &[u8],
+    is_binary_format: bool,
+  ) -> Result<Self, Error> {
+    let invalid_vector =
+      || Error::InvalidParameter("Invalid vector".to_owned());
+    match is_binary_format {
+      false => std::str::from_utf8(bytes)
+        .ok()
+        .and_then(|text| serde_json::from_str::<Vec<f32>>(text).ok())
+        .map(Self)
+        .ok_or_else(invalid_vector),
+      true => {
+        let mut bytes = bytes;
+        if bytes.len() < 4 {
+          return Err(invalid_vector());
+        }
+        let dim = bytes.get_u16() as usize;
+        // unused
+        bytes.get_u16();
+        if bytes.len() != dim * 4 {
+          return Err(invalid_vector());
+        }
+        Ok(Self((0..dim).map(|_| bytes.get_f32()).collect()))
+      }
+    }
+  }
+}
+
+impl ToSqlText for PgVector {
+  fn to_sql_text(
+    &self,
+    _ty: &Type,
+    out: &mut BytesMut,
+  ) -> Result<IsNull, Box<dyn std::error::Error + Sync + Send>>
+  where
+    Self: Sized,
+  {
+    out.put_u8(b'[');
+    for (i, v) in self.0.iter().enumerate() {
+      if i > 0 {
+        out.put_u8(b',');
+      }
+      out.put_slice(v.to_string().as_bytes());
+    }
+    out.put_u8(b']');
+    Ok(IsNull::No)
+  }
+}
+
+impl ToSql for PgVector {
+  fn to_sql(
+    &self,
+    _ty: &Type,
+    out: &mut BytesMut,
+  ) -> Result<IsNull, Box<dyn std::error::Error + Sync + Send>>
+  where
+    Self: Sized,
+  {
+    out.put_u16(self.0.len().try_into()?);
+    out.put_u16(0);
+    self.0.iter().for_each(|v| out.put_f32(*v));
+    Ok(IsNull::No)
+  }
+
+  fn to_sql_checked(
+    &self,
+    ty: &Type,
+    out: &mut BytesMut,
+  ) -> Result<IsNull, Box<dyn std::error::Error + Sync + Send>> {
+    self.to_sql(ty, out)
+  }
+
+  fn accepts(_ty: &Type) -> bool
+  where
+    Self: Sized,
+  {
+    true
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use arenasql::bytes::BytesMut;
+  use arenasql::pgwire::types::ToSqlText;
+  use arenasql::postgres_types::ToSql;
+  use arenasql::schema::VECTOR_PG_TYPE;
+
+  use super::PgVector;
+
+  #[test]
+  fn test_pgvector_wire_format() {
+    let vector = PgVector(vec![1.0, -2.5, 0.125]);
+
+    let mut text = BytesMut::new();
+    vector.to_sql_text(&VECTOR_PG_TYPE, &mut text).unwrap();
+    assert_eq!(&text[..], b"[1,-2.5,0.125]");
+    assert_eq!(PgVector::decode(&text, false).unwrap().0, vector.0);
+
+    let mut binary = BytesMut::new();
+    vector.to_sql(&VECTOR_PG_TYPE, &mut binary).unwrap();
+    assert_eq!(&binary[..4], &[0, 3, 0, 0]);
+    assert_eq!(&binary[4..8], &1.0f32.to_be_bytes());
+    assert_eq!(PgVector::decode(&binary, true).unwrap().0, vector.0);
+
+    assert!(PgVector::decode(&binary[..7], true).is_err());
+    assert!(PgVector::decode(b"{1,2}", false).is_err());
+  }
+}
@@ -33,11 +33,46 @@ pub struct ClusterManifest {
   #[builder(default)]
   pub backup_dir: Option<String>,
 
+  /// Interval between the periodic backups in seconds
+  /// Backups aren't scheduled if it's zero
+  #[builder(default = "default_backup_interval_secs()")]
+  #[serde(default = "default_backup_interval_secs")]
+  pub backup_interval_secs: u64,
+
+  /// Number of most recent backups to keep per database
+  #[builder(default = "default_backups_to_keep()")]
+  #[serde(default = "default_backups_to_keep")]
+  pub backups_to_keep: usize,
+
   /// Directory to put a checkpoint of the databases to
   /// When cluster is terminated, all the databases that were opened will
   /// be checkpointed to that directory
   #[builder(default)]
   pub checkpoint_dir: Option<String>,
+
+  /// Path to the PEM encoded TLS certificate chain
+  /// If set with `tls_key`, clients can connect using TLS
+  #[builder(default)]
+  pub tls_cert: Option<String>,
+
+  /// Path to the PEM encoded private key of the TLS certificate
+  #[builder(default)]
+  pub tls_key: Option<String>,
+
+  /// If set, connections from non-loopback addresses that don't use TLS
+  /// are rejected
+  #[builder(default)]
+  #[serde(default)]
+  pub require_tls: bool,
+}
+
+fn default_backup_interval_secs() -> u64 {
+  // 1 hour
+  60 * 60
+}
+
+fn default_backups_to_keep() -> usize {
+  24
 }
 
 impl ClusterManifest {
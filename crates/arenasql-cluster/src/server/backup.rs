@@ -0,0 +1,143 @@
+use std::path::Path;
+use std::time::{Duration, Instant, SystemTime};
+
+use arenasql::execution::factory::StorageFactory;
+use arenasql::rocks::{BackupEngine, BackupEngineOptions, Env};
+use arenasql::storage::rocks::RocksStorage;
+use tracing::{info, warn};
+
+use crate::error::{ArenaClusterError, ArenaClusterResult};
+
+/// Dir in the backup dir that the backups of the dropped catalogs are
+/// moved to
+pub(crate) const BACKUP_TRASH_DIR: &str = "trash";
+
+/// Status of the periodic backups of a catalog
+#[derive(Debug, Clone, Default)]
+pub struct BackupStatus {
+  /// Time when the last backup was attempted
+  pub last_attempt_at: Option<SystemTime>,
+  /// Last successful backup
+  pub last_backup: Option<CatalogBackup>,
+  /// Error of the last backup if it failed
+  pub last_error: Option<String>,
+}
+
+impl BackupStatus {
+  /// Logs the status of the backups of the catalog
+  pub fn report(&self, catalog_name: &str) {
+    match (&self.last_error, &self.last_backup) {
+      (Some(err), _) => warn!(
+        "Last backup of catalog \"{}\" at {:?} failed: {}",
+        catalog_name, self.last_attempt_at, err
+      ),
+      (None, Some(backup)) => info!(
+        "Catalog \"{}\": last backup = {}, size = {} bytes, \
+          time taken = {}ms, backups = {}",
+        catalog_name,
+        backup.backup_id,
+        backup.size,
+        backup.duration.as_millis(),
+        backup.num_backups
+      ),
+      (None, None) => {}
+    }
+  }
+}
+
+#[derive(Debug, Clone)]
+pub struct CatalogBackup {
+  pub backup_id: u32,
+  /// Size of the backup in bytes including the files that are shared
+  /// with the older backups
+  pub size: u64,
+  /// Time taken to create the backup
+  pub duration: Duration,
+  /// Number of backups of the catalog in the backup directory
+  pub num_backups: usize,
+}
+
+/// Backs up the catalog using [`backup_rocks_storage`].
+/// Returns `None` if the catalog isn't stored in RocksDB
+pub(super) fn backup_catalog(
+  backup_dir: &Path,
+  storage: &StorageFactory,
+  catalog_name: &str,
+  backups_to_keep: usize,
+) -> ArenaClusterResult<Option<CatalogBackup>> {
+  match storage
+    .kv_provider()
+    .as_any()
+    .downcast_ref::<RocksStorage>()
+  {
+    Some(rocks) => Ok(Some(backup_rocks_storage(
+      backup_dir,
+      rocks,
+      catalog_name,
+      backups_to_keep,
+    )?)),
+    None => Ok(None),
+  }
+}
+
+/// Backs up the catalog to `<backup_dir>/<catalog>` and purges the old
+/// backups so that only `backups_to_keep` most recent backups remain.
+/// Since all the backups of the catalog are in the same directory, the
+/// SST files that were already backed up are shared with the previous
+/// backups, so only the new files are copied
+pub(crate) fn backup_rocks_storage(
+  backup_dir: &Path,
+  rocks: &RocksStorage,
+  catalog_name: &str,
+  backups_to_keep: usize,
+) -> ArenaClusterResult<CatalogBackup> {
+  let catalog_backup_dir = backup_dir.join(catalog_name);
+  if !catalog_backup_dir.exists() {
+    std::fs::create_dir_all(&catalog_backup_dir)?;
+  }
+
+  info!(
+    "Backing up catalog \"{}\" to {:?}",
+    catalog_name, catalog_backup_dir
+  );
+  let started_at = Instant::now();
+
+  let mut engine = open_backup_engine(&catalog_backup_dir)?;
+  // Flush the memtables so that the backup doesn't depend on the WAL
+  engine.create_new_backup_flush(rocks.db(), true)?;
+  engine.purge_old_backups(backups_to_keep.max(1))?;
+
+  let backups = engine.get_backup_info();
+  let backup = backups
+    .iter()
+    .max_by_key(|backup| backup.backup_id)
+    .map(|backup| CatalogBackup {
+      backup_id: backup.backup_id,
+      size: backup.size,
+      duration: started_at.elapsed(),
+      num_backups: backups.len(),
+    })
+    .ok_or_else(|| {
+      ArenaClusterError::ArenaSqlError(arenasql::Error::InternalError(format!(
+        "Backup of catalog \"{}\" not found after it was created",
+        catalog_name
+      )))
+    })?;
+
+  info!(
+    "Catalog \"{}\" backup {} saved, time taken = {}ms",
+    catalog_name,
+    backup.backup_id,
+    backup.duration.as_millis(),
+  );
+  Ok(backup)
+}
+
+/// Opens the backup engine of the backups in the given directory
+pub(crate) fn open_backup_engine(
+  dir: &Path,
+) -> ArenaClusterResult<BackupEngine> {
+  let backup_opts = BackupEngineOptions::new(dir.to_str().unwrap())?;
+  let env = Env::new()?;
+  Ok(BackupEngine::open(&backup_opts, &env)?)
+}
@@ -1,5 +1,6 @@
 use std::path::PathBuf;
 use std::sync::Arc;
+use std::time::Duration;
 
 use anyhow::{Context, Result};
 use arenasql::execution::{
@@ -53,6 +54,8 @@ impl ArenaSqlCluster {
     let mut storage_options = StorageOption::default();
     storage_options
       .set_backup_dir(backup_dir)
+      .set_backup_interval(Duration::from_secs(manifest.backup_interval_secs))
+      .set_backups_to_keep(manifest.backups_to_keep)
       .set_checkpoint_dir(checkpoint_dir)
       .set_cache_size_mb(Some(manifest.cache_size_mb))
       .set_root_dir(catalogs_dir.into());
@@ -210,7 +213,6 @@ impl ArenaSqlCluster {
           vec![
             SYSTEM_SCHEMA_NAME.to_owned(),
             DEFAULT_SCHEMA_NAME.to_owned(),
-            "information_schema".to_owned(),
           ],
           vec![Arc::new(admin_exetension)],
         ),
@@ -228,7 +230,6 @@ impl ArenaSqlCluster {
         df_runtime: Default::default(),
         catalog: catalog.into(),
         schemas: Arc::new(schemas),
-        enable_information_schema: is_admin_user,
         storage_factory,
         catalog_list_provider,
         execution_plan_extensions: Arc::new(extensions),
@@ -30,9 +30,11 @@ impl ArenaSqlCluster {
 
     match stmt_type {
       // TODO: drop future/stream when connection drops?
-      StatementType::Query | StatementType::Execute => {
-        Self::to_row_stream(response, field_format)
-      }
+      StatementType::Query
+      | StatementType::Execute
+      | StatementType::Explain => Self::to_row_stream(response, field_format),
+      // DML queries with `RETURNING` clause return the modified rows
+      _ if response.has_rows() => Self::to_row_stream(response, field_format),
       _ => {
         let tag = Tag::new(stmt_type.to_string());
         let tag = match response.get_modified_rows() {
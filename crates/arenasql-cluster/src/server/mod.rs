@@ -10,9 +10,11 @@ use arenasql::pgwire::tokio::process_socket;
 use tokio::net::TcpListener;
 use tokio::sync::oneshot;
 
+pub(crate) mod backup;
 pub(crate) mod cluster;
 mod execution;
 pub(crate) mod storage;
+mod tls;
 
 use crate::pgwire::auth::ArenaSqlClusterAuthenticator;
 use crate::schema::ClusterManifest;
@@ -43,8 +45,11 @@ impl ClusterOptions {
     let manifest = std::fs::read_to_string(Path::new(&self.config))
       .context("Error reading cluster manifest")?;
     let manifest: ClusterManifest = toml::from_str(&manifest)?;
+    let tls_acceptor = tls::create_tls_acceptor(&manifest)?;
+    let require_tls = manifest.require_tls;
 
     let cluster = Arc::new(ArenaSqlCluster::load(manifest)?);
+    cluster.storage.start_backup_scheduler();
     let processor = Arc::new(StatelessMakeHandler::new(cluster.clone()));
     let authenticator = ArenaSqlClusterAuthenticator::new(cluster.clone());
 
@@ -59,10 +64,11 @@ impl ClusterOptions {
       TcpListener::bind(addr).await.context("TCP binding error")?;
 
     tracing::info!(
-      "Listening to {}:{} [process id = {}]",
+      "Listening to {}:{} [process id = {}, tls = {}]",
       host,
       port,
-      process::id()
+      process::id(),
+      tls_acceptor.is_some()
     );
 
     loop {
@@ -71,13 +77,19 @@ impl ClusterOptions {
           break;
         },
         socket = listener.accept() => {
-          let incoming_socket = socket?;
+          let mut incoming_socket = socket?;
           let authenticator_ref = authenticator.make();
           let processor_ref = processor.make();
+          let tls_acceptor = tls_acceptor.clone();
           tokio::spawn(async move {
+            if !tls::check_tls_requirement(&mut incoming_socket.0, require_tls)
+              .await
+            {
+              return Ok(());
+            }
             process_socket(
               incoming_socket.0,
-              None,
+              tls_acceptor,
               authenticator_ref,
               processor_ref.clone(),
               processor_ref,
@@ -1,7 +1,7 @@
 use std::fs::{self, read_dir};
 use std::path::PathBuf;
 use std::sync::Arc;
-use std::time::{Instant, SystemTime, UNIX_EPOCH};
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
 
 use arenasql::execution::factory::{StorageFactory, StorageFactoryBuilder};
 use arenasql::rocks::{BackupEngine, BackupEngineOptions, Env};
@@ -9,16 +9,19 @@ use arenasql::storage::rocks::{self, RocksStorage};
 use arenasql::storage::{
   KeyValueStoreProvider, MemoryKeyValueStoreProvider, Serializer,
 };
-use arenasql::Result;
+use arenasql::{Error, Result};
 use dashmap::DashMap;
 use futures::future::join_all;
 use getset::{Getters, Setters};
 use parking_lot::Mutex;
 use tokio::task::JoinHandle;
+use tokio::time::MissedTickBehavior;
 use tracing::info;
 
-use crate::error::ArenaClusterResult;
-use crate::schema::SYSTEM_CATALOG_NAME;
+use super::backup::{self, BackupStatus};
+use crate::error::{ArenaClusterError, ArenaClusterResult};
+use crate::extension::catalog_users::rename_catalog_users;
+use crate::schema::{SYSTEM_CATALOG_NAME, SYSTEM_SCHEMA_NAME};
 
 #[derive(Getters)]
 pub struct ClusterStorageFactory {
@@ -26,6 +29,8 @@ pub struct ClusterStorageFactory {
   options: StorageOption,
   storage_lock: Arc<Mutex<()>>,
   storages: DashMap<String, Arc<StorageFactory>>,
+  backup_status: DashMap<String, BackupStatus>,
+  backup_scheduler: Mutex<Option<JoinHandle<()>>>,
 }
 
 #[derive(Debug, Default, Getters, Setters)]
@@ -38,6 +43,14 @@ pub struct StorageOption {
   /// backed up to that directory periodically
   backup_dir: Option<PathBuf>,
 
+  /// Interval between the periodic backups
+  /// Backups aren't scheduled if it's zero
+  backup_interval: Duration,
+
+  /// Number of most recent backups to keep per database
+  /// Older backups are purged after each backup
+  backups_to_keep: usize,
+
   /// Directory to put a checkpoint of the databases to
   /// When cluster is terminated, all the databases that were opened will
   /// be checkpointed to that directory
@@ -60,9 +73,197 @@ impl ClusterStorageFactory {
       options,
       storage_lock: Arc::new(Mutex::new(())),
       storages: DashMap::new(),
+      backup_status: DashMap::new(),
+      backup_scheduler: Mutex::new(None),
+    }
+  }
+
+  /// Restores the catalog from its latest checkpoint or its latest
+  /// periodic backup, whichever is newer. The checkpoint is only taken
+  /// when the cluster is shutdown cleanly, so the backups taken after it
+  /// are newer if the cluster crashed later. Returns `None` if the
+  /// catalog doesn't have any checkpoint or backup
+  fn restore_catalog(
+    &self,
+    db_name: &str,
+    db_dir: PathBuf,
+    cache: Option<rocks::Cache>,
+  ) -> Result<Option<RocksStorage>> {
+    let checkpoint = self.latest_checkpoint(db_name);
+    let backup = self.latest_backup(db_name)?;
+    let (source, restore_dir) = match (checkpoint, backup) {
+      (None, None) => return Ok(None),
+      (Some((dir, checkpoint_ms)), Some((_, backup_ms)))
+        if checkpoint_ms >= backup_ms =>
+      {
+        ("checkpoint", dir)
+      }
+      (Some((dir, _)), None) => ("checkpoint", dir),
+      (_, Some((dir, _))) => ("backup", dir),
+    };
+
+    info!(
+      "Restoring catalog \"{}\" from {} {:?}",
+      db_name, source, restore_dir
+    );
+    let start = Instant::now();
+    let db = RocksStorage::load_from_backup(
+      restore_dir.to_str().unwrap(),
+      db_dir,
+      cache,
+    )?;
+    info!(
+      "Time taken to restore catalog \"{}\" from {}: {}s",
+      db_name,
+      source,
+      start.elapsed().as_secs()
+    );
+    Ok(Some(db))
+  }
+
+  /// Returns the dir of the latest checkpoint of the catalog and the time
+  /// it was taken at in ms. The checkpoints are stored in the dirs named
+  /// by the time they were taken at
+  fn latest_checkpoint(&self, db_name: &str) -> Option<(PathBuf, u128)> {
+    let checkpoint_dir = self.options.checkpoint_dir.as_ref()?;
+    let catalog_checkpoint_dir = checkpoint_dir.join(db_name);
+    // The catalog doesn't have any checkpoint if its checkpoint dir
+    // doesn't exist
+    read_dir(&catalog_checkpoint_dir)
+      .into_iter()
+      .flatten()
+      .filter_map(|dir| {
+        let path = dir.ok()?.path();
+        let timestamp = path.file_name()?.to_str()?.parse::<u128>().ok()?;
+        Some((path, timestamp))
+      })
+      .max_by_key(|(_, timestamp)| *timestamp)
+  }
+
+  /// Returns the dir of the periodic backups of the catalog and the time
+  /// the latest backup was taken at in ms
+  fn latest_backup(&self, db_name: &str) -> Result<Option<(PathBuf, u128)>> {
+    let Some(backup_dir) = &self.options.backup_dir else {
+      return Ok(None);
+    };
+    let catalog_backup_dir = backup_dir.join(db_name);
+    if !catalog_backup_dir.exists() {
+      return Ok(None);
+    }
+    let backup_opts =
+      BackupEngineOptions::new(catalog_backup_dir.to_str().unwrap())?;
+    let engine = BackupEngine::open(&backup_opts, &Env::new()?)?;
+    let timestamp = engine
+      .get_backup_info()
+      .iter()
+      .map(|backup| backup.timestamp)
+      .max();
+    Ok(
+      timestamp
+        .map(|timestamp| (catalog_backup_dir, timestamp.max(0) as u128 * 1000)),
+    )
+  }
+
+  /// Starts backing up all the opened catalogs to the backup dir
+  /// periodically if the backup dir is set
+  pub fn start_backup_scheduler(self: &Arc<Self>) {
+    let interval = self.options.backup_interval;
+    if self.options.backup_dir.is_none() || interval.is_zero() {
+      return;
+    }
+    info!("Scheduling backups every {}s", interval.as_secs());
+    let factory = self.clone();
+    let handle = tokio::spawn(async move {
+      let mut interval = tokio::time::interval(interval);
+      interval.set_missed_tick_behavior(MissedTickBehavior::Delay);
+      // The first tick completes immediately; skip it so that the first
+      // backup is taken after the interval
+      interval.tick().await;
+      loop {
+        interval.tick().await;
+        factory.backup_catalogs().await;
+        for (catalog, status) in factory.backup_status() {
+          status.report(&catalog);
+        }
+      }
+    });
+    if let Some(previous) = self.backup_scheduler.lock().replace(handle) {
+      previous.abort();
     }
   }
 
+  /// Backs up all the opened catalogs to the backup dir and updates
+  /// their backup status. Errors are logged and reported in the status
+  /// so that a failing catalog doesn't stop the others from being
+  /// backed up
+  pub async fn backup_catalogs(&self) {
+    let Some(backup_dir) = self.options.backup_dir.clone() else {
+      return;
+    };
+    let backups_to_keep = self.options.backups_to_keep;
+    let (catalogs, backups): (Vec<String>, Vec<JoinHandle<_>>) = self
+      .storages
+      .iter()
+      .filter(|entry| entry.key() != SYSTEM_CATALOG_NAME)
+      .map(|entry| {
+        let (catalog, storage) = (entry.key().clone(), entry.value().clone());
+        let backup_dir = backup_dir.clone();
+        let backup = tokio::task::spawn_blocking({
+          let catalog = catalog.clone();
+          move || {
+            backup::backup_catalog(
+              &backup_dir,
+              storage.as_ref(),
+              &catalog,
+              backups_to_keep,
+            )
+          }
+        });
+        (catalog, backup)
+      })
+      .unzip();
+
+    for (catalog, backup) in catalogs.into_iter().zip(join_all(backups).await) {
+      // Record the error of the backup task too; e.g. if it panicked
+      let res = backup.unwrap_or_else(|err| {
+        Err(ArenaClusterError::ArenaSqlError(
+          arenasql::Error::InternalError(format!(
+            "Backup task failed: {}",
+            err
+          )),
+        ))
+      });
+      let mut status = self.backup_status.entry(catalog.clone()).or_default();
+      status.last_attempt_at = Some(SystemTime::now());
+      match res {
+        Ok(backup) => {
+          status.last_backup = backup.or(status.last_backup.take());
+          status.last_error = None;
+        }
+        Err(err) => {
+          tracing::error!(
+            "Error backing up catalog \"{}\": {:?}",
+            catalog,
+            err
+          );
+          status.last_error = Some(err.to_string());
+        }
+      }
+    }
+  }
+
+  /// Returns the status of the periodic backups of each catalog that was
+  /// backed up at least once
+  pub fn backup_status(&self) -> Vec<(String, BackupStatus)> {
+    let mut status: Vec<(String, BackupStatus)> = self
+      .backup_status
+      .iter()
+      .map(|entry| (entry.key().clone(), entry.value().clone()))
+      .collect();
+    status.sort_by(|a, b| a.0.cmp(&b.0));
+    status
+  }
+
   // Note: call this from different thread to avoid deadlock
   pub fn get_catalog(
     &self,
@@ -90,7 +291,7 @@ impl ClusterStorageFactory {
               .options
               .cache_size_mb
               .map(|size| rocks::Cache::new_lru_cache(size * 1024 * 1024));
-            let db_dir = self.options.root_dir.join("catalogs").join(db_name);
+            let db_dir = self.catalog_dir(db_name);
             tracing::trace!(
               "Using database dir [{:?}] for catalog = {:?}",
               db_dir.to_str(),
@@ -98,52 +299,7 @@ impl ClusterStorageFactory {
             );
 
             let rocks_storage = match db_dir.exists() {
-              false => {
-                if let Some(checkpoint_dir) = &self.options.checkpoint_dir {
-                  let catalog_checkpoint_dir = checkpoint_dir.join(db_name);
-                  let max_checkpoint = read_dir(&catalog_checkpoint_dir)
-                    .unwrap()
-                    .filter_map(|dir| {
-                      pathdiff::diff_paths(
-                        &dir.unwrap().path(),
-                        &catalog_checkpoint_dir,
-                      )
-                      .and_then(|p| {
-                        let timestamp_str = p.to_str();
-                        timestamp_str.and_then(|str| str.parse::<u64>().ok())
-                      })
-                    })
-                    .max();
-                  match max_checkpoint {
-                    Some(checkpoint_timestamp) => {
-                      info!(
-                        "Loading catalog \"{}\" from checkpoint: {:?}",
-                        db_name, checkpoint_timestamp
-                      );
-                      let start = Instant::now();
-                      let db = Some(RocksStorage::load_from_backup(
-                        catalog_checkpoint_dir
-                          .join(format!("{}", checkpoint_timestamp))
-                          .to_str()
-                          .unwrap(),
-                        db_dir,
-                        cache,
-                      )?);
-
-                      info!(
-                        "Time taken to load catalog \"{}\" from checkpoint: {}s",
-                        db_name,
-                        start.elapsed().as_secs()
-                      );
-
-                      db
-                    }
-                    _ => None,
-                  }
-                } else {
-                  None
-                }
-              }
+              false => self.restore_catalog(db_name, db_dir, cache)?,
               true => Some(RocksStorage::new_with_cache(db_dir, cache)?),
             };
 
@@ -170,7 +326,191 @@ impl ClusterStorageFactory {
     }
   }
 
+  /// Creates the directory of a new catalog. The catalog is opened when
+  /// it's first used. Returns false if the catalog already exists
+  pub fn create_catalog(&self, db_name: &str) -> Result<bool> {
+    if db_name == SYSTEM_CATALOG_NAME {
+      return Ok(false);
+    }
+    Self::check_catalog_name(db_name)?;
+    // This loads the catalog from checkpoint or backup if it was
+    // checkpointed or backed up, so check that first
+    if self.get_catalog(db_name)?.is_some() {
+      return Ok(false);
+    }
+    let _lock = self.storage_lock.lock();
+    let db_dir = self.catalog_dir(db_name);
+    if db_dir.exists() {
+      return Ok(false);
+    }
+    fs::create_dir_all(&db_dir)?;
+    info!("Created catalog \"{}\"", db_name);
+    Ok(true)
+  }
+
+  /// Closes the catalog and deletes its directory and checkpoints. The
+  /// catalog users are stored in the catalog itself so they are deleted
+  /// along with it. The backups are kept so that the catalog can be
+  /// restored; they are moved to `<backup_dir>/trash/<catalog>.<ms>`.
+  /// Fails if the catalog is being used.
+  /// Returns false if the catalog doesn't exist
+  pub fn drop_catalog(&self, db_name: &str) -> Result<bool> {
+    if db_name == SYSTEM_CATALOG_NAME {
+      return Err(Error::InvalidQuery(format!(
+        "cannot drop the system database \"{}\"",
+        db_name
+      )));
+    }
+    let _lock = self.storage_lock.lock();
+    let storage = self.close_catalog(db_name)?;
+    let dirs: Vec<PathBuf> = [
+      Some(self.catalog_dir(db_name)),
+      self
+        .options
+        .checkpoint_dir
+        .as_ref()
+        .map(|dir| dir.join(db_name)),
+    ]
+    .into_iter()
+    .flatten()
+    .filter(|dir| dir.exists())
+    .collect();
+    let backup_dir = self
+      .options
+      .backup_dir
+      .as_ref()
+      .map(|dir| (dir.join(db_name), dir.join(backup::BACKUP_TRASH_DIR)))
+      .filter(|(dir, _)| dir.exists());
+    if storage.is_none() && dirs.is_empty() && backup_dir.is_none() {
+      return Ok(false);
+    }
+    // Close the storage before deleting its files
+    drop(storage);
+    // Move the backups first so that nothing is deleted if it fails
+    if let Some((dir, trash_dir)) = backup_dir {
+      fs::create_dir_all(&trash_dir)?;
+      let timestamp = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap()
+        .as_millis();
+      let to = trash_dir.join(format!("{}.{}", db_name, timestamp));
+      fs::rename(&dir, &to)?;
+      info!("Moved backups of catalog \"{}\" to {:?}", db_name, to);
+    }
+    for dir in dirs {
+      fs::remove_dir_all(&dir)?;
+    }
+    self.backup_status.remove(db_name);
+    info!("Dropped catalog \"{}\"", db_name);
+    Ok(true)
+  }
+
+  /// Renames the catalog; moves its directory, checkpoints and backups
+  /// and the metadata that's keyed by the catalog name. Fails if the
+  /// catalog is being used
+  pub fn rename_catalog(&self, db_name: &str, new_name: &str) -> Result<()> {
+    if db_name == SYSTEM_CATALOG_NAME || new_name == SYSTEM_CATALOG_NAME {
+      return Err(Error::InvalidQuery(format!(
+        "cannot rename the system database \"{}\"",
+        SYSTEM_CATALOG_NAME
+      )));
+    }
+    Self::check_catalog_name(new_name)?;
+    // Open the catalog first since it might only exist in checkpoint or
+    // backup
+    if self.get_catalog(db_name)?.is_none() {
+      return Err(Error::DatabaseDoesntExist(db_name.to_owned()));
+    }
+    if self.get_catalog(new_name)?.is_some() {
+      return Err(Error::DatabaseAlreadyExists(new_name.to_owned()));
+    }
+
+    let _lock = self.storage_lock.lock();
+    let storage = self
+      .close_catalog(db_name)?
+      .ok_or_else(|| Error::DatabaseDoesntExist(db_name.to_owned()))?;
+    let res = Self::rename_catalog_metadata(&storage, db_name, new_name);
+    if let Err(err) = res {
+      self.storages.insert(db_name.to_owned(), storage);
+      return Err(err);
+    }
+    // Close the storage before moving its files
+    drop(storage);
+
+    let mut dirs =
+      vec![(self.catalog_dir(db_name), self.catalog_dir(new_name))];
+    for dir in [&self.options.checkpoint_dir, &self.options.backup_dir]
+      .into_iter()
+      .flatten()
+    {
+      dirs.push((dir.join(db_name), dir.join(new_name)));
+    }
+    for (from, to) in dirs {
+      if from.exists() {
+        fs::rename(&from, &to)?;
+      }
+    }
+    if let Some((_, status)) = self.backup_status.remove(db_name) {
+      self.backup_status.insert(new_name.to_owned(), status);
+    }
+    info!("Renamed catalog \"{}\" to \"{}\"", db_name, new_name);
+    Ok(())
+  }
+
+  /// Fails if the catalog name is reserved; the backups of a catalog are
+  /// stored in a dir with its name next to the trash dir
+  fn check_catalog_name(db_name: &str) -> Result<()> {
+    if db_name == backup::BACKUP_TRASH_DIR {
+      return Err(Error::InvalidQuery(format!(
+        "database name \"{}\" is reserved",
+        db_name
+      )));
+    }
+    Ok(())
+  }
+
+  fn rename_catalog_metadata(
+    storage: &StorageFactory,
+    db_name: &str,
+    new_name: &str,
+  ) -> Result<()> {
+    let handle = storage.create_new_transaction_handle(Arc::new(vec![
+      SYSTEM_SCHEMA_NAME.to_owned(),
+    ]))?;
+    // The users are stored in the catalog with the catalog name
+    rename_catalog_users(&handle, db_name, new_name)?;
+    let storage_handler = handle.lock(true)?;
+    storage_handler.rename_catalog(db_name, new_name)?;
+    drop(storage_handler);
+    handle.commit()
+  }
+
+  /// Removes the catalog from the opened catalogs so that its storage is
+  /// closed once the returned factory is dropped. Fails if the catalog
+  /// is being used by any session. This should be called while holding
+  /// the `storage_lock` so that the catalog isn't re-opened
+  fn close_catalog(
+    &self,
+    db_name: &str,
+  ) -> Result<Option<Arc<StorageFactory>>> {
+    let Some((_, storage)) = self.storages.remove(db_name) else {
+      return Ok(None);
+    };
+    if Arc::strong_count(&storage) > 1 {
+      self.storages.insert(db_name.to_owned(), storage);
+      return Err(Error::DatabaseInUse(db_name.to_owned()));
+    }
+    Ok(Some(storage))
+  }
+
+  fn catalog_dir(&self, db_name: &str) -> PathBuf {
+    self.options.root_dir.join("catalogs").join(db_name)
+  }
+
   pub async fn graceful_shutdown(&self) -> ArenaClusterResult<()> {
+    if let Some(scheduler) = self.backup_scheduler.lock().take() {
+      scheduler.abort();
+    }
     let timetamp = SystemTime::now();
     let storages: Vec<JoinHandle<()>> = self
       .storages
@@ -255,3 +595,205 @@ impl ClusterStorageFactory {
     Ok(())
   }
 }
+
+#[cfg(test)]
+mod tests {
+  use std::fs;
+  use std::sync::Arc;
+  use std::time::{Duration, SystemTime};
+
+  use arenasql::arrow::as_string_array;
+  use arenasql::datafusion::RecordBatch;
+  use arenasql::execution::{AdvisoryLocks, Privilege, SessionContext};
+  use arenasql::runtime::RuntimeEnv;
+  use tempdir::TempDir;
+
+  use super::{ClusterStorageFactory, StorageOption};
+  use crate::schema::ADMIN_USERNAME;
+  use crate::server::ArenaSqlCluster;
+
+  fn create_cluster_storage(dir: &TempDir) -> Arc<ClusterStorageFactory> {
+    let mut options = StorageOption::default();
+    options.set_root_dir(Arc::new(dir.path().join("catalogs")));
+    options.set_checkpoint_dir(Some(dir.path().join("checkpoints")));
+    options.set_backup_dir(Some(dir.path().join("backups")));
+    Arc::new(ClusterStorageFactory::new(options))
+  }
+
+  fn create_admin_session(
+    storage: &Arc<ClusterStorageFactory>,
+    catalog: &str,
+  ) -> SessionContext {
+    ArenaSqlCluster::create_session_context_using_cluster_storage(
+      storage.clone(),
+      Arc::new(RuntimeEnv::default()),
+      Arc::new(AdvisoryLocks::new()),
+      catalog,
+      ADMIN_USERNAME,
+      Privilege::SUPER_USER,
+    )
+    .unwrap()
+  }
+
+  /// Returns the (catalog, user) of the users of the given catalog
+  async fn list_catalog_users(
+    storage: &Arc<ClusterStorageFactory>,
+    catalog: &str,
+  ) -> Vec<(String, String)> {
+    let session = create_admin_session(storage, catalog);
+    let batches = session
+      .execute_sql(&format!("EXECUTE arena_list_catalog_users('{}')", catalog))
+      .await
+      .unwrap()
+      .pop()
+      .unwrap()
+      .collect_batches()
+      .await
+      .unwrap();
+    batches
+      .iter()
+      .flat_map(|batch| {
+        as_string_array(batch.column_by_name("catalog").unwrap())
+          .iter()
+          .zip(as_string_array(batch.column_by_name("user").unwrap()))
+          .map(|(c, u)| (c.unwrap().to_owned(), u.unwrap().to_owned()))
+          .collect::<Vec<(String, String)>>()
+      })
+      .collect()
+  }
+
+  async fn execute_sql(
+    storage: &Arc<ClusterStorageFactory>,
+    catalog: &str,
+    sql: &str,
+  ) -> Vec<RecordBatch> {
+    let session = create_admin_session(storage, catalog);
+    session
+      .execute_sql(sql)
+      .await
+      .unwrap()
+      .pop()
+      .unwrap()
+      .collect_batches()
+      .await
+      .unwrap()
+  }
+
+  async fn set_catalog_user(
+    storage: &Arc<ClusterStorageFactory>,
+    catalog: &str,
+    user: &str,
+  ) {
+    execute_sql(
+      storage,
+      catalog,
+      &format!(
+        "EXECUTE arena_set_catalog_user_credential('{}', '{}', 'password')",
+        catalog, user
+      ),
+    )
+    .await;
+  }
+
+  #[tokio::test(flavor = "multi_thread")]
+  async fn cluster_storage_test_create_catalog() {
+    let dir = TempDir::new("arenasql").unwrap();
+    let storage = create_cluster_storage(&dir);
+
+    assert!(storage.create_catalog("db1").unwrap());
+    assert!(storage.catalog_dir("db1").exists());
+    assert!(!storage.create_catalog("db1").unwrap());
+    assert!(storage.get_catalog("db1").unwrap().is_some());
+    assert!(!storage.create_catalog("postgres").unwrap());
+    assert!(storage.create_catalog("trash").is_err());
+  }
+
+  #[tokio::test(flavor = "multi_thread")]
+  async fn cluster_storage_test_drop_catalog() {
+    let dir = TempDir::new("arenasql").unwrap();
+    let storage = create_cluster_storage(&dir);
+
+    assert!(storage.create_catalog("db1").unwrap());
+    set_catalog_user(&storage, "db1", "user1").await;
+    storage.backup_catalogs().await;
+
+    assert!(storage.drop_catalog("db1").unwrap());
+    assert!(!storage.catalog_dir("db1").exists());
+    // The backups should be kept in the trash
+    let backup_dir = dir.path().join("backups");
+    assert!(!backup_dir.join("db1").exists());
+    let trash: Vec<String> = fs::read_dir(backup_dir.join("trash"))
+      .unwrap()
+      .map(|entry| entry.unwrap().file_name().into_string().unwrap())
+      .collect();
+    assert_eq!(trash.len(), 1);
+    assert!(trash[0].starts_with("db1."));
+    assert!(storage.get_catalog("db1").unwrap().is_none());
+    assert!(!storage.drop_catalog("db1").unwrap());
+    assert!(storage.drop_catalog("postgres").is_err());
+
+    // The users of the dropped catalog shouldn't be carried over to a new
+    // catalog with the same name
+    assert!(storage.create_catalog("db1").unwrap());
+    set_catalog_user(&storage, "db1", "user2").await;
+    assert_eq!(
+      list_catalog_users(&storage, "db1").await,
+      vec![("db1".to_owned(), "user2".to_owned())]
+    );
+  }
+
+  #[tokio::test(flavor = "multi_thread")]
+  async fn cluster_storage_test_rename_catalog() {
+    let dir = TempDir::new("arenasql").unwrap();
+    let storage = create_cluster_storage(&dir);
+
+    assert!(storage.create_catalog("db1").unwrap());
+    set_catalog_user(&storage, "db1", "user1").await;
+    assert!(storage.create_catalog("db3").unwrap());
+
+    storage.rename_catalog("db1", "db2").unwrap();
+    assert!(!storage.catalog_dir("db1").exists());
+    assert!(storage.catalog_dir("db2").exists());
+    assert!(storage.get_catalog("db1").unwrap().is_none());
+    assert_eq!(
+      list_catalog_users(&storage, "db2").await,
+      vec![("db2".to_owned(), "user1".to_owned())]
+    );
+
+    assert!(storage.rename_catalog("db1", "db4").is_err());
+    assert!(storage.rename_catalog("db2", "db3").is_err());
+    assert!(storage.rename_catalog("db2", "postgres").is_err());
+  }
+
+  #[tokio::test(flavor = "multi_thread")]
+  async fn cluster_storage_test_restore_backup_newer_than_checkpoint() {
+    let dir = TempDir::new("arenasql").unwrap();
+    let storage = create_cluster_storage(&dir);
+
+    assert!(storage.create_catalog("db1").unwrap());
+    execute_sql(&storage, "db1", "CREATE TABLE t (id INT)").await;
+    execute_sql(&storage, "db1", "INSERT INTO t VALUES (1)").await;
+
+    // Checkpoint taken when the cluster was shutdown a while ago
+    ClusterStorageFactory::checkpoint_catalog(
+      dir.path().join("checkpoints"),
+      &storage.get_catalog("db1").unwrap().unwrap(),
+      "db1",
+      &(SystemTime::now() - Duration::from_secs(60)),
+    )
+    .await
+    .unwrap();
+
+    execute_sql(&storage, "db1", "INSERT INTO t VALUES (2)").await;
+    storage.backup_catalogs().await;
+    assert!(storage.backup_status()[0].1.last_error.is_none());
+
+    // Lose the database dir as if the cluster crashed
+    drop(storage.close_catalog("db1").unwrap());
+    fs::remove_dir_all(storage.catalog_dir("db1")).unwrap();
+
+    let batches = execute_sql(&storage, "db1", "SELECT id FROM t").await;
+    let num_rows: usize = batches.iter().map(|b| b.num_rows()).sum();
+    assert_eq!(num_rows, 2);
+  }
+}
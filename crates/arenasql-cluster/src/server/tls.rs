@@ -0,0 +1,136 @@
+use std::fs::File;
+use std::io::BufReader;
+use std::sync::Arc;
+use std::time::Duration;
+
+use anyhow::{anyhow, bail, Context, Result};
+use tokio::io::AsyncWriteExt;
+use tokio::net::TcpStream;
+use tokio::time::{sleep, timeout};
+use tokio_rustls::rustls::ServerConfig;
+use tokio_rustls::TlsAcceptor;
+
+use crate::schema::ClusterManifest;
+
+/// Request code of the `SSLRequest` message
+const SSL_REQUEST_CODE: i32 = 80877103;
+
+/// Time to wait for the first message of the client when checking
+/// whether it's an `SSLRequest`
+const SSL_REQUEST_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Returns the TLS acceptor if the TLS certificate and key are set in
+/// the manifest
+pub(crate) fn create_tls_acceptor(
+  manifest: &ClusterManifest,
+) -> Result<Option<Arc<TlsAcceptor>>> {
+  let (cert_path, key_path) = match (&manifest.tls_cert, &manifest.tls_key) {
+    (Some(cert), Some(key)) => (cert, key),
+    (None, None) if manifest.require_tls => {
+      bail!("tls_cert and tls_key must be set to require TLS")
+    }
+    (None, None) => return Ok(None),
+    _ => bail!("Both tls_cert and tls_key must be set to enable TLS"),
+  };
+
+  let certs = rustls_pemfile::certs(&mut BufReader::new(
+    File::open(cert_path)
+      .context(format!("Failed to open TLS certificate: {:?}", cert_path))?,
+  ))
+  .collect::<Result<Vec<_>, _>>()
+  .context("Failed to parse TLS certificate")?;
+  let key = rustls_pemfile::private_key(&mut BufReader::new(
+    File::open(key_path)
+      .context(format!("Failed to open TLS key: {:?}", key_path))?,
+  ))
+  .context("Failed to parse TLS key")?
+  .ok_or_else(|| anyhow!("No private key found in {:?}", key_path))?;
+
+  let config = ServerConfig::builder()
+    .with_no_client_auth()
+    .with_single_cert(certs, key)
+    .context("Invalid TLS certificate or key")?;
+  Ok(Some(Arc::new(TlsAcceptor::from(Arc::new(config)))))
+}
+
+/// Returns true if the connection is allowed to continue. When TLS is
+/// required, connections from non-loopback addresses are rejected with an
+/// error unless the first message is an `SSLRequest`. The connection is
+/// closed if the client doesn't send the message in time
+pub(crate) async fn check_tls_requirement(
+  socket: &mut TcpStream,
+  require_tls: bool,
+) -> bool {
+  if !require_tls {
+    return true;
+  }
+  let is_local = socket
+    .peer_addr()
+    .map(|addr| addr.ip().is_loopback())
+    .unwrap_or(false);
+  if is_local {
+    return true;
+  }
+  match timeout(SSL_REQUEST_TIMEOUT, is_ssl_request(socket)).await {
+    Ok(true) => return true,
+    Ok(false) => {}
+    Err(_) => {
+      tracing::debug!(
+        "Closing connection from {:?} that didn't send a message in {}s",
+        socket.peer_addr().ok(),
+        SSL_REQUEST_TIMEOUT.as_secs()
+      );
+      let _ = socket.shutdown().await;
+      return false;
+    }
+  }
+
+  tracing::debug!(
+    "Rejecting non-TLS connection from {:?}",
+    socket.peer_addr().ok()
+  );
+  let _ = socket
+    .write_all(&fatal_error_message(
+      // invalid_authorization_specification
+      "28000",
+      "TLS is required to connect to the server",
+    ))
+    .await;
+  let _ = socket.shutdown().await;
+  false
+}
+
+/// Checks if the first message of the client is an `SSLRequest` without
+/// consuming it. `SSLRequest` is 8 bytes: the message length followed by
+/// the request code
+async fn is_ssl_request(socket: &TcpStream) -> bool {
+  let mut buf = [0u8; 8];
+  let mut read = 0;
+  // Peek until the whole message is received since the bytes might
+  // arrive separately
+  while read < buf.len() {
+    match socket.peek(&mut buf).await {
+      Ok(0) | Err(_) => return false,
+      Ok(n) if n == read => sleep(Duration::from_millis(10)).await,
+      Ok(n) => read = n,
+    }
+  }
+  i32::from_be_bytes(buf[0..4].try_into().unwrap()) == 8
+    && i32::from_be_bytes(buf[4..8].try_into().unwrap()) == SSL_REQUEST_CODE
+}
+
+/// Encodes an `ErrorResponse` message with FATAL severity
+fn fatal_error_message(code: &str, message: &str) -> Vec<u8> {
+  let mut fields = vec![];
+  for (field, value) in [(b'S', "FATAL"), (b'C', code), (b'M', message)] {
+    fields.push(field);
+    fields.extend_from_slice(value.as_bytes());
+    fields.push(0);
+  }
+  fields.push(0);
+
+  let mut msg = vec![b'E'];
+  msg.extend_from_slice(&((fields.len() + 4) as i32).to_be_bytes());
+  msg.extend_from_slice(&fields);
+  msg
+}
@@ -8,6 +8,8 @@ use arenasql::execution::TransactionHandle;
 use arenasql::{CatalogListProvider, CatalogProvider};
 use derive_builder::Builder;
 
+use super::pg_catalog::ClusterCatalogProvider;
+
 #[derive(Clone, Debug, Builder)]
 pub struct CatalogListOptions {
   cluster_dir: Arc<PathBuf>,
@@ -86,10 +88,13 @@ impl DatafusionCatalogList for DirectoryCatalogList {
 
   fn catalog(&self, name: &str) -> Option<Arc<dyn DatafusionCatalogProvider>> {
     if self.get_catalog_dir(&name).exists() {
-      Some(Arc::new(CatalogProvider {
-        catalog: name.into(),
-        schemas: self.schemas.clone(),
-        transaction: self.transaction.clone(),
+      Some(Arc::new(ClusterCatalogProvider {
+        inner: Arc::new(CatalogProvider {
+          catalog: name.into(),
+          schemas: self.schemas.clone(),
+          transaction: self.transaction.clone(),
+        }),
+        cluster_dir: self.options.cluster_dir.clone(),
       }))
     } else {
       None
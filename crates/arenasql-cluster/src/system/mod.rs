@@ -1,4 +1,5 @@
 mod catalog;
+mod pg_catalog;
 
 #[allow(unused)]
 pub use catalog::{
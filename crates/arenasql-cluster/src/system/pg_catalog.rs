@@ -0,0 +1,109 @@
+use std::any::Any;
+use std::fs::read_dir;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+use arenasql::datafusion::{
+  DatafusionCatalogProvider, DatafusionSchemaProvider, DatafusionTableProvider,
+  MemTable, RecordBatch,
+};
+use arenasql::PG_CATALOG_SCHEMA;
+use async_trait::async_trait;
+
+use crate::schema::SYSTEM_CATALOG_NAME;
+
+/// Catalog provider that replaces the `pg_database` table of the catalog's
+/// `pg_catalog` schema with the one that lists all the databases of the
+/// cluster
+pub struct ClusterCatalogProvider {
+  pub inner: Arc<dyn DatafusionCatalogProvider>,
+  pub cluster_dir: Arc<PathBuf>,
+}
+
+impl DatafusionCatalogProvider for ClusterCatalogProvider {
+  fn as_any(&self) -> &dyn Any {
+    self
+  }
+
+  fn schema(
+    &self,
+    schema_name: &str,
+  ) -> Option<Arc<dyn DatafusionSchemaProvider>> {
+    let schema = self.inner.schema(schema_name)?;
+    match schema_name {
+      PG_CATALOG_SCHEMA => Some(Arc::new(PgCatalogSchemaProvider {
+        inner: schema,
+        cluster_dir: self.cluster_dir.clone(),
+      })),
+      _ => Some(schema),
+    }
+  }
+
+  fn schema_names(&self) -> Vec<String> {
+    self.inner.schema_names()
+  }
+}
+
+/// `pg_catalog` schema of the catalog with `pg_database` derived from
+/// the state of the cluster
+pub struct PgCatalogSchemaProvider {
+  inner: Arc<dyn DatafusionSchemaProvider>,
+  cluster_dir: Arc<PathBuf>,
+}
+
+#[async_trait]
+impl DatafusionSchemaProvider for PgCatalogSchemaProvider {
+  fn as_any(&self) -> &dyn Any {
+    self
+  }
+
+  fn table_names(&self) -> Vec<String> {
+    self.inner.table_names()
+  }
+
+  async fn table(
+    &self,
+    name: &str,
+  ) -> Option<Arc<dyn DatafusionTableProvider>> {
+    if name != "pg_database" {
+      return self.inner.table(name).await;
+    }
+    match pg_database(&self.cluster_dir) {
+      Ok(batch) => MemTable::try_new(batch.schema(), vec![vec![batch]])
+        .map(|table| Arc::new(table) as Arc<dyn DatafusionTableProvider>)
+        .ok(),
+      Err(err) => {
+        tracing::error!("Error loading pg_catalog.{}: {:?}", name, err);
+        None
+      }
+    }
+  }
+
+  fn table_exist(&self, name: &str) -> bool {
+    self.inner.table_exist(name)
+  }
+}
+
+/// Returns the names of all the databases in the cluster; i.e. the
+/// directories in `<cluster_dir>/catalogs` and the system catalog
+fn list_databases(cluster_dir: &Path) -> std::io::Result<Vec<String>> {
+  let catalogs_dir = cluster_dir.join("catalogs");
+  let mut databases = vec![SYSTEM_CATALOG_NAME.to_owned()];
+  if catalogs_dir.exists() {
+    for entry in read_dir(&catalogs_dir)? {
+      let entry = entry?;
+      if entry.path().is_dir() {
+        if let Some(name) = entry.file_name().to_str() {
+          databases.push(name.to_owned());
+        }
+      }
+    }
+  }
+  databases.sort();
+  databases.dedup();
+  Ok(databases)
+}
+
+fn pg_database(cluster_dir: &Path) -> anyhow::Result<RecordBatch> {
+  Ok(arenasql::pg_database(&list_databases(cluster_dir)?)?)
+}
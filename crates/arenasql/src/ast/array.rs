@@ -0,0 +1,205 @@
+use std::ops::ControlFlow;
+
+use sqlparser::ast::{
+  visit_expressions_mut, Array, BinaryOperator, DataType, Expr, Function,
+  FunctionArg, FunctionArgExpr, Ident, ObjectName, Statement as SQLStatement,
+  UnaryOperator, Value,
+};
+use sqlparser::parser::ParserError;
+
+/// Rewrites Postgres array expressions to the ones that Datafusion can run:
+///  - `'{a,b}'::TEXT[]` to `ARRAY['a','b']::TEXT[]`
+///  - `tags && ARRAY['a']` to `array_has_any(tags, ARRAY['a'])`
+///  - `'a' = ANY(tags)` to `array_has(tags, 'a')` and `'a' <> ALL(tags)`
+///    to `NOT array_has(tags, 'a')`
+///
+/// `@>` and `<@` are supported by Datafusion
+pub(super) fn rewrite_array_expressions(
+  stmt: &mut SQLStatement,
+) -> Result<(), ParserError> {
+  let result = visit_expressions_mut(stmt, |expr| {
+    let rewritten = match expr {
+      Expr::Cast {
+        expr: value,
+        data_type: data_type @ DataType::Array(_),
+        format,
+      } => match value.as_ref() {
+        Expr::Value(Value::SingleQuotedString(array)) => {
+          parse_array_literal(array).map(|array| Expr::Cast {
+            expr: Box::new(array),
+            data_type: data_type.clone(),
+            format: format.clone(),
+          })
+        }
+        _ => return ControlFlow::Continue(()),
+      },
+      Expr::BinaryOp {
+        left,
+        op: BinaryOperator::PGOverlap,
+        right,
+      } => Ok(array_function(
+        "array_has_any",
+        left.as_ref().clone(),
+        right.as_ref().clone(),
+      )),
+      Expr::AnyOp {
+        left,
+        compare_op: BinaryOperator::Eq,
+        right,
+      } => Ok(array_function(
+        "array_has",
+        right.as_ref().clone(),
+        left.as_ref().clone(),
+      )),
+      Expr::AllOp {
+        left,
+        compare_op: BinaryOperator::NotEq,
+        right,
+      } => Ok(Expr::UnaryOp {
+        op: UnaryOperator::Not,
+        expr: Box::new(Expr::Nested(Box::new(array_function(
+          "array_has",
+          right.as_ref().clone(),
+          left.as_ref().clone(),
+        )))),
+      }),
+      Expr::AnyOp { compare_op, .. } | Expr::AllOp { compare_op, .. } => {
+        Err(ParserError::ParserError(format!(
+          "Operator \"{}\" is not supported with ANY/ALL yet",
+          compare_op
+        )))
+      }
+      _ => return ControlFlow::Continue(()),
+    };
+
+    match rewritten {
+      Ok(rewritten) => {
+        *expr = rewritten;
+        ControlFlow::Continue(())
+      }
+      Err(e) => ControlFlow::Break(e),
+    }
+  });
+
+  match result {
+    ControlFlow::Break(e) => Err(e),
+    ControlFlow::Continue(_) => Ok(()),
+  }
+}
+
+/// Parses the text representation of one dimensional Postgres array,
+/// i.e. `{a,"b c",NULL}`, to an array of strings
+fn parse_array_literal(array: &str) -> Result<Expr, ParserError> {
+  let elem = parse_array_elements(array)?
+    .into_iter()
+    .map(|value| match value {
+      Some(value) => Expr::Value(Value::SingleQuotedString(value)),
+      None => Expr::Value(Value::Null),
+    })
+    .collect();
+  Ok(Expr::Array(Array { elem, named: true }))
+}
+
+/// Returns the elements of the text representation of one dimensional
+/// Postgres array; `NULL` elements are returned as `None`
+pub(crate) fn parse_array_elements(
+  array: &str,
+) -> Result<Vec<Option<String>>, ParserError> {
+  let invalid_array = || {
+    ParserError::ParserError(format!("malformed array literal: \"{}\"", array))
+  };
+  let content = array
+    .trim()
+    .strip_prefix('{')
+    .and_then(|a| a.strip_suffix('}'))
+    .ok_or_else(invalid_array)?;
+
+  let mut elem = vec![];
+  let mut chars = content.chars().peekable();
+  while chars.peek().is_some() {
+    while chars.next_if(|c| c.is_whitespace()).is_some() {}
+    let mut value = String::new();
+    let quoted = chars.next_if_eq(&'"').is_some();
+    if quoted {
+      loop {
+        match chars.next().ok_or_else(invalid_array)? {
+          '"' => break,
+          '\\' => value.push(chars.next().ok_or_else(invalid_array)?),
+          c => value.push(c),
+        }
+      }
+      while chars.next_if(|c| c.is_whitespace()).is_some() {}
+    } else {
+      while let Some(c) = chars.next_if(|c| *c != ',') {
+        if c == '{' || c == '}' || c == '"' {
+          return Err(invalid_array());
+        }
+        value.push(c);
+      }
+      value = value.trim_end().to_owned();
+      if value.is_empty() {
+        return Err(invalid_array());
+      }
+    }
+
+    elem.push(match !quoted && value.eq_ignore_ascii_case("NULL") {
+      true => None,
+      false => Some(value),
+    });
+    match chars.next() {
+      Some(',') if chars.peek().is_none() => return Err(invalid_array()),
+      Some(',') | None => {}
+      Some(_) => return Err(invalid_array()),
+    }
+  }
+  Ok(elem)
+}
+
+fn array_function(name: &str, array: Expr, value: Expr) -> Expr {
+  Expr::Function(Function {
+    name: ObjectName(vec![Ident::new(name)]),
+    args: vec![
+      FunctionArg::Unnamed(FunctionArgExpr::Expr(array)),
+      FunctionArg::Unnamed(FunctionArgExpr::Expr(value)),
+    ],
+    filter: None,
+    null_treatment: None,
+    over: None,
+    distinct: false,
+    special: false,
+    order_by: vec![],
+  })
+}
+
+#[cfg(test)]
+mod tests {
+  use crate::ast::parse;
+
+  #[test]
+  fn test_array_expressions() {
+    for (sql, expected) in [
+      (
+        r#"SELECT '{a, "b,c",NULL, "d\"e"}'::TEXT[]"#,
+        r#"SELECT CAST(ARRAY['a', 'b,c', NULL, 'd"e'] AS TEXT[])"#,
+      ),
+      (
+        "SELECT id FROM t WHERE tags && ARRAY['a']",
+        "SELECT id FROM t WHERE array_has_any(tags, ARRAY['a'])",
+      ),
+      (
+        "SELECT id FROM t WHERE 'a' = ANY(tags)",
+        "SELECT id FROM t WHERE array_has(tags, 'a')",
+      ),
+      (
+        "SELECT id FROM t WHERE 'a' <> ALL(tags)",
+        "SELECT id FROM t WHERE NOT (array_has(tags, 'a'))",
+      ),
+    ] {
+      assert_eq!(parse(sql).unwrap()[0].to_string(), expected);
+    }
+
+    assert!(parse("SELECT '{a,{b}}'::TEXT[]").is_err());
+    assert!(parse("SELECT '{a,}'::TEXT[]").is_err());
+    assert!(parse("SELECT id FROM t WHERE 1 > ANY(ids)").is_err());
+  }
+}
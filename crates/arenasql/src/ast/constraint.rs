@@ -0,0 +1,33 @@
+use sqlparser::ast::{
+  ColumnOption, Statement as SQLStatement, TableConstraint,
+};
+
+use crate::Result;
+
+/// Removes the constraints from the `CREATE TABLE` query that Datafusion
+/// doesn't support. These constraints are read from the original query
+/// when the table is created, so they aren't lost
+pub fn remove_unsupported_constraints(stmt: &mut SQLStatement) -> Result<()> {
+  match stmt {
+    SQLStatement::CreateTable {
+      ref mut columns,
+      ref mut constraints,
+      ..
+    } => {
+      constraints.retain(|constraint| match constraint {
+        TableConstraint::ForeignKey { .. } | TableConstraint::Check { .. } => {
+          false
+        }
+        _ => true,
+      });
+      for col in columns {
+        col.options.retain(|opt| match opt.option {
+          ColumnOption::ForeignKey { .. } | ColumnOption::Check(_) => false,
+          _ => true,
+        });
+      }
+      Ok(())
+    }
+    _ => Ok(()),
+  }
+}
@@ -16,7 +16,10 @@ pub fn cast_unsupported_data_types(stmt: &mut SQLStatement) -> Result<()> {
       for col in columns {
         match &col.data_type {
           // Postgres JSONB, VECTOR, etc will be parsed as Custom data type
-          SQLDataType::Custom(_, _) => {
+          // and datafusion doesn't support UUID and arrays of those types
+          SQLDataType::Custom(_, _)
+          | SQLDataType::Uuid
+          | SQLDataType::Array(_) => {
             col.data_type =
               SQLDataType::Decimal(ExactNumberInfo::PrecisionAndScale(76, 1));
           }
@@ -0,0 +1,57 @@
+use std::ops::ControlFlow;
+
+use sqlparser::ast::{
+  visit_expressions_mut, Expr, Function, Ident, Statement as SQLStatement,
+};
+
+use crate::df::providers::PG_CATALOG_SCHEMA;
+
+/// Postgres functions that Datafusion supports under a different name
+const FUNCTION_ALIASES: &[(&str, &str)] = &[("gen_random_uuid", "uuid")];
+
+/// Renames the Postgres functions to the corresponding Datafusion functions
+/// and removes the `pg_catalog` schema from the function names since the
+/// functions aren't registered under a schema
+pub(super) fn rewrite_function_aliases(stmt: &mut SQLStatement) {
+  let _ = visit_expressions_mut(stmt, |expr| {
+    if let Expr::Function(Function { name, .. }) = expr {
+      if name.0.len() == 2
+        && name.0[0].value.eq_ignore_ascii_case(PG_CATALOG_SCHEMA)
+      {
+        name.0.remove(0);
+      }
+      if let [function_name] = name.0.as_mut_slice() {
+        if let Some((_, alias)) =
+          FUNCTION_ALIASES.iter().find(|(pg_name, _)| {
+            function_name.value.eq_ignore_ascii_case(pg_name)
+          })
+        {
+          *function_name = Ident::new(*alias);
+        }
+      }
+    }
+    ControlFlow::<()>::Continue(())
+  });
+}
+
+#[cfg(test)]
+mod tests {
+  use crate::ast::parse;
+
+  #[test]
+  fn test_function_aliases() {
+    assert_eq!(
+      parse("CREATE TABLE t (id TEXT DEFAULT gen_random_uuid())").unwrap()[0]
+        .to_string(),
+      "CREATE TABLE t (id TEXT DEFAULT uuid())"
+    );
+  }
+
+  #[test]
+  fn test_pg_catalog_functions() {
+    assert_eq!(
+      parse("SELECT pg_catalog.format_type(23, -1)").unwrap()[0].to_string(),
+      "SELECT format_type(23, -1)"
+    );
+  }
+}
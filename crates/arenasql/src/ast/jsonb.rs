@@ -0,0 +1,393 @@
+use std::ops::ControlFlow;
+
+use sqlparser::ast::{
+  visit_expressions_mut, BinaryOperator, DataType, Expr, Function, FunctionArg,
+  FunctionArgExpr, Ident, JsonOperator, ObjectName, SelectItem, SetExpr,
+  Statement as SQLStatement, Value,
+};
+use sqlparser::parser::ParserError;
+use sqlparser::tokenizer::{Token, TokenWithLocation};
+
+/// Postgres' JSONB key exists operators; mapped to `jsonb_exists`,
+/// `jsonb_exists_any` and `jsonb_exists_all` functions
+const KEY_EXISTS_OPERATOR: &str = "?";
+const ANY_KEY_EXISTS_OPERATOR: &str = "?|";
+const ALL_KEYS_EXIST_OPERATOR: &str = "?&";
+
+/// Postgres uses `$1` for placeholders, so `?` is tokenized as a
+/// placeholder only when it's used as the JSONB key exists operator.
+/// Replace `?`, `?|` and `?&` with `OPERATOR(<op>)` such that they are
+/// parsed as Postgres custom binary operators
+pub(super) fn replace_key_exists_operators(
+  tokens: Vec<TokenWithLocation>,
+) -> Vec<TokenWithLocation> {
+  let mut replaced = Vec::with_capacity(tokens.len());
+  let mut tokens = tokens.into_iter().peekable();
+  while let Some(token) = tokens.next() {
+    if token.token != Token::Placeholder(KEY_EXISTS_OPERATOR.to_owned()) {
+      replaced.push(token);
+      continue;
+    }
+    let operator = match tokens.peek().map(|t| &t.token) {
+      Some(Token::Pipe) => ANY_KEY_EXISTS_OPERATOR,
+      Some(Token::Ampersand) => ALL_KEYS_EXIST_OPERATOR,
+      _ => KEY_EXISTS_OPERATOR,
+    };
+    if operator != KEY_EXISTS_OPERATOR {
+      tokens.next();
+    }
+
+    let location = token.location;
+    replaced.extend(
+      [
+        Token::make_keyword("OPERATOR"),
+        Token::LParen,
+        Token::make_word(operator, None),
+        Token::RParen,
+      ]
+      .into_iter()
+      .map(|t| TokenWithLocation::new(t, location.line, location.column)),
+    );
+  }
+  replaced
+}
+
+/// Rewrites JSONB expressions to the functions that Datafusion can run:
+///  - `data -> 'key'` and `data ->> 'key'` to `jsonb_get(data, 'key')` and
+///    `jsonb_get_text(data, 'key')`; `#>` and `#>>` to `jsonb_get_path`
+///    and `jsonb_get_path_text`
+///  - `data @> '{"a":1}'` to `jsonb_contains(data, '{"a":1}')`
+///  - `data ? 'key'` to `jsonb_exists(data, 'key')`, and similarly `?|`
+///    and `?&` to `jsonb_exists_any` and `jsonb_exists_all`
+///  - `jsonb_array_elements(data)` to `unnest(jsonb_to_array(data))`
+///  - `'{"a":1}'::jsonb` and `jsonb '{"a":1}'` to `'{"a":1}'` since JSONB
+///    is a string in Datafusion
+///
+/// `@>` and `<@` are only rewritten if any of the operands is a JSONB
+/// literal or expression since they are also array operators
+pub(super) fn rewrite_jsonb_expressions(
+  stmt: &mut SQLStatement,
+) -> Result<(), ParserError> {
+  fix_json_operators_precedence(stmt);
+  name_array_elements_columns(stmt);
+
+  let result = visit_expressions_mut(stmt, |expr| {
+    let rewritten = match expr {
+      Expr::Cast {
+        expr: value,
+        data_type,
+        ..
+      } if is_jsonb_type(data_type) => value.as_ref().clone(),
+      Expr::TypedString { data_type, value } if is_jsonb_type(data_type) => {
+        Expr::Value(Value::SingleQuotedString(value.clone()))
+      }
+      Expr::JsonAccess {
+        left,
+        operator,
+        right,
+      } => {
+        let (left, right) = (left.as_ref().clone(), right.as_ref().clone());
+        match operator {
+          JsonOperator::Arrow => function("jsonb_get", vec![left, right]),
+          JsonOperator::LongArrow => {
+            function("jsonb_get_text", vec![left, right])
+          }
+          JsonOperator::HashArrow => {
+            function("jsonb_get_path", vec![left, right])
+          }
+          JsonOperator::HashLongArrow => {
+            function("jsonb_get_path_text", vec![left, right])
+          }
+          JsonOperator::AtArrow if is_jsonb(&left) || is_jsonb(&right) => {
+            function("jsonb_contains", vec![left, right])
+          }
+          JsonOperator::ArrowAt if is_jsonb(&left) || is_jsonb(&right) => {
+            function("jsonb_contains", vec![right, left])
+          }
+          _ => return ControlFlow::Continue(()),
+        }
+      }
+      Expr::BinaryOp {
+        left,
+        op: BinaryOperator::PGCustomBinaryOperator(op),
+        right,
+      } => {
+        let name = match op.join(".").as_str() {
+          KEY_EXISTS_OPERATOR => "jsonb_exists",
+          ANY_KEY_EXISTS_OPERATOR => "jsonb_exists_any",
+          ALL_KEYS_EXIST_OPERATOR => "jsonb_exists_all",
+          _ => return ControlFlow::Continue(()),
+        };
+        function(name, vec![left.as_ref().clone(), right.as_ref().clone()])
+      }
+      Expr::Function(Function { name, args, .. }) => {
+        let to_array = match name.to_string().to_lowercase().as_str() {
+          "jsonb_array_elements" => "jsonb_to_array",
+          "jsonb_array_elements_text" => "jsonb_to_text_array",
+          _ => return ControlFlow::Continue(()),
+        };
+        let json = match args.as_slice() {
+          [FunctionArg::Unnamed(FunctionArgExpr::Expr(json))] => json.clone(),
+          _ => {
+            return ControlFlow::Break(ParserError::ParserError(format!(
+              "{} expects a single JSONB argument",
+              name
+            )))
+          }
+        };
+        function("unnest", vec![function(to_array, vec![json])])
+      }
+      _ => return ControlFlow::Continue(()),
+    };
+    *expr = rewritten;
+    ControlFlow::Continue(())
+  });
+
+  match result {
+    ControlFlow::Break(e) => Err(e),
+    ControlFlow::Continue(_) => Ok(()),
+  }
+}
+
+/// sqlparser parses everything after a JSON operator as its right operand,
+/// i.e. `data ->> 'key' = 'x'` is parsed as `data ->> ('key' = 'x')`. So,
+/// move the JSON operator into the left most operand of the operators
+/// that bind looser than it
+fn fix_json_operators_precedence(stmt: &mut SQLStatement) {
+  // Note: expressions are visited bottom up, so the right operand is
+  // already fixed when visiting the JSON operator
+  let _ = visit_expressions_mut(stmt, |expr| {
+    if let Expr::JsonAccess {
+      left,
+      operator,
+      right,
+    } = expr
+    {
+      *expr = reassociate(
+        left.as_ref().clone(),
+        operator.clone(),
+        right.as_ref().clone(),
+      );
+    }
+    ControlFlow::<()>::Continue(())
+  });
+}
+
+fn reassociate(left: Expr, operator: JsonOperator, mut right: Expr) -> Expr {
+  match left_operand_mut(&mut right) {
+    Some(operand) => {
+      let inner = std::mem::replace(operand.as_mut(), Expr::Value(Value::Null));
+      **operand = reassociate(left, operator, inner);
+      right
+    }
+    None => Expr::JsonAccess {
+      left: Box::new(left),
+      operator,
+      right: Box::new(right),
+    },
+  }
+}
+
+/// Returns the left operand of the expressions whose operator has the same
+/// or lower precedence than the JSON operators
+fn left_operand_mut(expr: &mut Expr) -> Option<&mut Box<Expr>> {
+  match expr {
+    Expr::BinaryOp { left, .. }
+    | Expr::JsonAccess { left, .. }
+    | Expr::AnyOp { left, .. }
+    | Expr::AllOp { left, .. }
+    | Expr::IsDistinctFrom(left, _)
+    | Expr::IsNotDistinctFrom(left, _) => Some(left),
+    Expr::IsFalse(expr)
+    | Expr::IsNotFalse(expr)
+    | Expr::IsTrue(expr)
+    | Expr::IsNotTrue(expr)
+    | Expr::IsNull(expr)
+    | Expr::IsNotNull(expr)
+    | Expr::IsUnknown(expr)
+    | Expr::IsNotUnknown(expr)
+    | Expr::InList { expr, .. }
+    | Expr::InSubquery { expr, .. }
+    | Expr::InUnnest { expr, .. }
+    | Expr::Between { expr, .. }
+    | Expr::Like { expr, .. }
+    | Expr::ILike { expr, .. }
+    | Expr::SimilarTo { expr, .. } => Some(expr),
+    _ => None,
+  }
+}
+
+/// Names the column of `jsonb_array_elements` as `value` like Postgres
+/// if it's not aliased
+fn name_array_elements_columns(stmt: &mut SQLStatement) {
+  let SQLStatement::Query(query) = stmt else {
+    return;
+  };
+  let SetExpr::Select(select) = query.body.as_mut() else {
+    return;
+  };
+  for item in select.projection.iter_mut() {
+    if let SelectItem::UnnamedExpr(expr @ Expr::Function(_)) = item {
+      let Expr::Function(Function { name, .. }) = &expr else {
+        unreachable!()
+      };
+      let name = name.to_string().to_lowercase();
+      if name == "jsonb_array_elements" || name == "jsonb_array_elements_text" {
+        *item = SelectItem::ExprWithAlias {
+          expr: expr.clone(),
+          alias: Ident::new("value"),
+        };
+      }
+    }
+  }
+}
+
+fn is_jsonb_type(data_type: &DataType) -> bool {
+  match data_type {
+    DataType::JSON => true,
+    DataType::Custom(name, _) => {
+      name.0.len() == 1
+        && (name.0[0].value.eq_ignore_ascii_case("jsonb")
+          || name.0[0].value.eq_ignore_ascii_case("json"))
+    }
+    _ => false,
+  }
+}
+
+/// Returns true if the expression is a JSONB literal or the result of a
+/// JSONB function
+fn is_jsonb(expr: &Expr) -> bool {
+  match expr {
+    Expr::Value(Value::SingleQuotedString(_)) => true,
+    Expr::Function(Function { name, .. }) => {
+      name.to_string().to_lowercase().starts_with("jsonb_")
+    }
+    Expr::Nested(expr) => is_jsonb(expr),
+    _ => false,
+  }
+}
+
+fn function(name: &str, args: Vec<Expr>) -> Expr {
+  Expr::Function(Function {
+    name: ObjectName(vec![Ident::new(name)]),
+    args: args
+      .into_iter()
+      .map(|arg| FunctionArg::Unnamed(FunctionArgExpr::Expr(arg)))
+      .collect(),
+    filter: None,
+    null_treatment: None,
+    over: None,
+    distinct: false,
+    special: false,
+    order_by: vec![],
+  })
+}
+
+/// Returns the (column, path) of the JSON path expression that extracts
+/// the value as text, i.e. `data->'a'->>'b'` or `data#>>'{a,b}'`. This
+/// should be called after the JSONB expressions are rewritten
+pub fn get_json_path_expr(expr: &Expr) -> Option<(String, Vec<String>)> {
+  get_json_path(expr, true)
+}
+
+fn get_json_path(expr: &Expr, text: bool) -> Option<(String, Vec<String>)> {
+  let (name, args) = match expr {
+    Expr::Nested(expr) => return get_json_path(expr, text),
+    Expr::Identifier(column) if !text => {
+      return Some((column.value.clone(), vec![]))
+    }
+    Expr::Function(Function { name, args, .. }) => {
+      (name.to_string().to_lowercase(), args)
+    }
+    _ => return None,
+  };
+  let (get, get_path) = match text {
+    true => ("jsonb_get_text", "jsonb_get_path_text"),
+    false => ("jsonb_get", "jsonb_get_path"),
+  };
+  let args = args
+    .iter()
+    .map(|arg| match arg {
+      FunctionArg::Unnamed(FunctionArgExpr::Expr(arg)) => Some(arg),
+      _ => None,
+    })
+    .collect::<Option<Vec<&Expr>>>()?;
+  let (json, keys) = match args.as_slice() {
+    [json, Expr::Value(Value::SingleQuotedString(key))] if name == get => {
+      (*json, vec![key.clone()])
+    }
+    [json, Expr::Value(Value::SingleQuotedString(path))]
+      if name == get_path =>
+    {
+      let keys = super::array::parse_array_elements(path).ok()?;
+      (*json, keys.into_iter().collect::<Option<Vec<String>>>()?)
+    }
+    _ => return None,
+  };
+  let (column, mut path) = get_json_path(json, false)?;
+  path.extend(keys);
+  Some((column, path))
+}
+
+#[cfg(test)]
+mod tests {
+  use super::get_json_path_expr;
+  use crate::ast::parse;
+
+  #[test]
+  fn test_jsonb_expressions() {
+    for (sql, expected) in [
+      (
+        "SELECT data->'a'->>'b' FROM t WHERE data->>'kind' = 'x' AND id > 1",
+        "SELECT jsonb_get_text(jsonb_get(data, 'a'), 'b') FROM t \
+        WHERE jsonb_get_text(data, 'kind') = 'x' AND id > 1",
+      ),
+      (
+        "SELECT data#>'{a,b}', data#>>'{a,0}' FROM t",
+        "SELECT jsonb_get_path(data, '{a,b}'), \
+        jsonb_get_path_text(data, '{a,0}') FROM t",
+      ),
+      (
+        r#"SELECT id FROM t WHERE data @> '{"a":1}'::jsonb"#,
+        r#"SELECT id FROM t WHERE jsonb_contains(data, '{"a":1}')"#,
+      ),
+      (
+        "SELECT id FROM t WHERE tags @> ARRAY['a'] OR tags <@ ARRAY['b']",
+        "SELECT id FROM t WHERE tags @> ARRAY['a'] OR tags <@ ARRAY['b']",
+      ),
+      (
+        "SELECT id FROM t WHERE data ? 'a' AND data ?| ARRAY['b', 'c'] \
+        AND data ?& ARRAY['d']",
+        "SELECT id FROM t WHERE jsonb_exists(data, 'a') \
+        AND jsonb_exists_any(data, ARRAY['b', 'c']) \
+        AND jsonb_exists_all(data, ARRAY['d'])",
+      ),
+      (
+        "SELECT jsonb_array_elements(data->'tags') FROM t",
+        "SELECT unnest(jsonb_to_array(jsonb_get(data, 'tags'))) AS value \
+        FROM t",
+      ),
+    ] {
+      assert_eq!(parse(sql).unwrap()[0].to_string(), expected);
+    }
+  }
+
+  #[test]
+  fn test_get_json_path_expr() {
+    let stmt = parse("CREATE INDEX ON t ((data->'a'->>'b'), (data#>>'{c,0}'))")
+      .unwrap()
+      .pop()
+      .unwrap();
+    let sqlparser::ast::Statement::CreateIndex { columns, .. } = stmt else {
+      unreachable!()
+    };
+    assert_eq!(
+      get_json_path_expr(&columns[0].expr),
+      Some(("data".to_owned(), vec!["a".to_owned(), "b".to_owned()]))
+    );
+    assert_eq!(
+      get_json_path_expr(&columns[1].expr),
+      Some(("data".to_owned(), vec!["c".to_owned(), "0".to_owned()]))
+    );
+  }
+}
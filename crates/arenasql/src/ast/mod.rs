@@ -1,10 +1,18 @@
+mod array;
+mod constraint;
 mod datatype;
+mod function;
+mod jsonb;
+mod pgvector;
 
 pub mod statement;
+pub(crate) use array::parse_array_elements;
+pub use constraint::remove_unsupported_constraints;
 pub use datatype::cast_unsupported_data_types;
+pub use jsonb::get_json_path_expr;
 
 use sqlparser::ast::{
-  DataType, Expr, Ident, Statement as SQLStatement, StructField,
+  DataType, Expr, Ident, ObjectName, Statement as SQLStatement, StructField,
 };
 use sqlparser::dialect::{Dialect, PostgreSqlDialect};
 use sqlparser::keywords::Keyword;
@@ -25,7 +33,10 @@ pub fn parse(sql: &str) -> ArenasqlResult<Vec<SQLStatement>> {
 pub fn sanitize(statements: &mut Vec<SQLStatement>) -> ArenasqlResult<()> {
   statements
     .iter_mut()
-    .map(|stmt| cast_unsupported_data_types(stmt))
+    .map(|stmt| {
+      cast_unsupported_data_types(stmt)?;
+      remove_unsupported_constraints(stmt)
+    })
     .collect::<ArenasqlResult<()>>()
 }
 
@@ -54,7 +65,12 @@ impl<'a> ArenasqlParser<'a> {
     let tokens = Tokenizer::new(self.dialect, sql)
       .with_unescape(self.options.unescape)
       .tokenize_with_location()?;
-    Ok(self.with_tokens_with_locations(tokens))
+    let tokens = pgvector::replace_distance_operators(tokens);
+    Ok(
+      self.with_tokens_with_locations(jsonb::replace_key_exists_operators(
+        tokens,
+      )),
+    )
   }
 
   pub fn with_tokens_with_locations(
@@ -87,7 +103,16 @@ impl<'a> ArenasqlParser<'a> {
           .expected("end of statement", self.parser.peek_token());
       }
 
-      let mut statement = self.parser.parse_statement()?;
+      let mut statement = match self.parse_analyze()? {
+        Some(statement) => statement,
+        None => match self.parse_savepoint_statement()? {
+          Some(statement) => statement,
+          None => match self.parse_database_statement()? {
+            Some(statement) => statement,
+            None => self.parser.parse_statement()?,
+          },
+        },
+      };
       if let SQLStatement::CreateIndex {
         ref mut predicate, ..
       } = statement
@@ -112,9 +137,109 @@ impl<'a> ArenasqlParser<'a> {
           *predicate = Some(Expr::Struct { values, fields });
         }
       }
+      jsonb::rewrite_jsonb_expressions(&mut statement)?;
+      pgvector::rewrite_vector_expressions(&mut statement)?;
+      array::rewrite_array_expressions(&mut statement)?;
+      function::rewrite_function_aliases(&mut statement);
       stmts.push(statement);
       expecting_statement_delimiter = true;
     }
     Ok(stmts)
   }
+
+  /// Parses Postgres' `ANALYZE [VERBOSE] [table_name]` query since the
+  /// parser only supports Hive's `ANALYZE TABLE table_name`. The table
+  /// name is empty if all the tables should be analyzed
+  fn parse_analyze(&mut self) -> Result<Option<SQLStatement>, ParserError> {
+    if !is_keyword(&self.parser.peek_token().token, Keyword::ANALYZE)
+      || is_keyword(&self.parser.peek_nth_token(1).token, Keyword::TABLE)
+    {
+      return Ok(None);
+    }
+    self.parser.next_token();
+    self.parser.parse_keyword(Keyword::VERBOSE);
+    let table_name = match self.parser.peek_token().token {
+      Token::EOF | Token::SemiColon => ObjectName(vec![]),
+      _ => self.parser.parse_object_name()?,
+    };
+    Ok(Some(SQLStatement::Analyze {
+      table_name,
+      partitions: None,
+      for_columns: false,
+      columns: vec![],
+      cache_metadata: false,
+      noscan: false,
+      compute_statistics: false,
+    }))
+  }
+
+  /// Parses `RELEASE [SAVEPOINT] name` and `ROLLBACK [WORK | TRANSACTION]
+  /// TO [SAVEPOINT] name` since the parser doesn't support them. See
+  /// [`statement::savepoint_statement`] for the statements they are
+  /// parsed into
+  fn parse_savepoint_statement(
+    &mut self,
+  ) -> Result<Option<SQLStatement>, ParserError> {
+    let token = self.parser.peek_token().token;
+    let variable = if is_keyword(&token, Keyword::RELEASE) {
+      self.parser.next_token();
+      statement::RELEASE_SAVEPOINT
+    } else if is_keyword(&token, Keyword::ROLLBACK) {
+      let next_token = self.parser.peek_nth_token(1).token;
+      let to_index = match is_keyword(&next_token, Keyword::WORK)
+        || is_keyword(&next_token, Keyword::TRANSACTION)
+      {
+        true => 2,
+        false => 1,
+      };
+      if !is_keyword(&self.parser.peek_nth_token(to_index).token, Keyword::TO) {
+        return Ok(None);
+      }
+      for _ in 0..=to_index {
+        self.parser.next_token();
+      }
+      statement::ROLLBACK_TO_SAVEPOINT
+    } else {
+      return Ok(None);
+    };
+    self.parser.parse_keyword(Keyword::SAVEPOINT);
+    let name = self.parser.parse_identifier()?;
+    Ok(Some(statement::savepoint_statement(variable, name)))
+  }
+
+  /// Parses `DROP DATABASE [IF EXISTS] name` and `ALTER DATABASE name
+  /// RENAME TO new_name` since the parser doesn't support them. See
+  /// [`statement::DatabaseStatement`] for the statements they are
+  /// parsed into
+  fn parse_database_statement(
+    &mut self,
+  ) -> Result<Option<SQLStatement>, ParserError> {
+    let token = self.parser.peek_token().token;
+    if !is_keyword(&self.parser.peek_nth_token(1).token, Keyword::DATABASE) {
+      return Ok(None);
+    }
+    if is_keyword(&token, Keyword::DROP) {
+      self.parser.next_token();
+      self.parser.next_token();
+      let if_exists =
+        self.parser.parse_keywords(&[Keyword::IF, Keyword::EXISTS]);
+      let name = self.parser.parse_identifier()?;
+      Ok(Some(statement::drop_database_statement(name, if_exists)))
+    } else if is_keyword(&token, Keyword::ALTER) {
+      self.parser.next_token();
+      self.parser.next_token();
+      let name = self.parser.parse_identifier()?;
+      self
+        .parser
+        .expect_keywords(&[Keyword::RENAME, Keyword::TO])?;
+      let new_name = self.parser.parse_identifier()?;
+      Ok(Some(statement::rename_database_statement(name, new_name)))
+    } else {
+      Ok(None)
+    }
+  }
+}
+
+fn is_keyword(token: &Token, keyword: Keyword) -> bool {
+  matches!(token, Token::Word(word) if word.keyword == keyword)
 }
@@ -0,0 +1,230 @@
+use std::ops::ControlFlow;
+
+use sqlparser::ast::{
+  visit_expressions_mut, Array, BinaryOperator, DataType, Expr, Function,
+  FunctionArg, FunctionArgExpr, Ident, ObjectName, Statement as SQLStatement,
+  Value,
+};
+use sqlparser::parser::ParserError;
+use sqlparser::tokenizer::{Token, TokenWithLocation};
+
+/// pgvector's euclidean distance operator; mapped to `l2` function
+const L2_DISTANCE_OPERATOR: &str = "<->";
+/// pgvector's negative inner product operator; mapped to
+/// `negative_inner_product` function
+const NEGATIVE_INNER_PRODUCT_OPERATOR: &str = "<#>";
+/// pgvector's cosine distance operator; mapped to `cosine_distance` function
+const COSINE_DISTANCE_OPERATOR: &str = "<=>";
+
+/// sqlparser doesn't support pgvector's distance operators; `<->` and
+/// `<#>` are split into multiple tokens and `<=>` is tokenized as MySQL's
+/// spaceship operator. So, replace those tokens with `OPERATOR(<op>)`
+/// such that they are parsed as Postgres custom binary operators
+pub(super) fn replace_distance_operators(
+  tokens: Vec<TokenWithLocation>,
+) -> Vec<TokenWithLocation> {
+  let mut replaced = Vec::with_capacity(tokens.len());
+  let mut tokens = tokens.into_iter().peekable();
+  while let Some(token) = tokens.next() {
+    let operator = match (&token.token, tokens.peek().map(|t| &t.token)) {
+      (Token::Lt, Some(Token::Arrow)) => L2_DISTANCE_OPERATOR,
+      (Token::Lt, Some(Token::HashArrow)) => NEGATIVE_INNER_PRODUCT_OPERATOR,
+      (Token::Spaceship, _) => COSINE_DISTANCE_OPERATOR,
+      _ => {
+        replaced.push(token);
+        continue;
+      }
+    };
+    if token.token == Token::Lt {
+      tokens.next();
+    }
+
+    let location = token.location;
+    replaced.extend(
+      [
+        Token::make_keyword("OPERATOR"),
+        Token::LParen,
+        Token::make_word(operator, None),
+        Token::RParen,
+      ]
+      .into_iter()
+      .map(|t| TokenWithLocation::new(t, location.line, location.column)),
+    );
+  }
+  replaced
+}
+
+/// Rewrites pgvector expressions to the ones that Datafusion can run:
+///  - `'[1,2,3]'::vector` and `vector '[1,2,3]'` to array `[1,2,3]`
+///  - `column <-> '[1,2,3]'` to `l2(column, '[1,2,3]')`, and similarly
+///    `<#>` and `<=>` to `negative_inner_product` and `cosine_distance`
+pub(super) fn rewrite_vector_expressions(
+  stmt: &mut SQLStatement,
+) -> Result<(), ParserError> {
+  // Note: expressions are visited bottom up, so vector casts in the
+  // operands are already rewritten when visiting the operator
+  let result = visit_expressions_mut(stmt, |expr| {
+    let rewritten = match expr {
+      Expr::Cast {
+        expr: value,
+        data_type,
+        ..
+      } if is_vector_type(data_type) => match value.as_ref() {
+        Expr::Value(Value::SingleQuotedString(vector)) => {
+          parse_vector_literal(vector)
+        }
+        value => Ok(value.clone()),
+      },
+      Expr::TypedString { data_type, value } if is_vector_type(data_type) => {
+        parse_vector_literal(value)
+      }
+      Expr::BinaryOp {
+        left,
+        op: BinaryOperator::PGCustomBinaryOperator(op),
+        right,
+      } => {
+        let function = match op.join(".").as_str() {
+          L2_DISTANCE_OPERATOR => "l2",
+          NEGATIVE_INNER_PRODUCT_OPERATOR => "negative_inner_product",
+          COSINE_DISTANCE_OPERATOR => "cosine_distance",
+          _ => return ControlFlow::Continue(()),
+        };
+        // All distance functions are commutative but the vector column
+        // must be the first argument
+        let (column, query) = match is_vector_literal(left) {
+          true => (right.as_ref(), left.as_ref()),
+          false => (left.as_ref(), right.as_ref()),
+        };
+        Ok(distance_function(
+          function,
+          column.clone(),
+          to_json_string(query),
+        ))
+      }
+      _ => return ControlFlow::Continue(()),
+    };
+
+    match rewritten {
+      Ok(rewritten) => {
+        *expr = rewritten;
+        ControlFlow::Continue(())
+      }
+      Err(e) => ControlFlow::Break(e),
+    }
+  });
+
+  match result {
+    ControlFlow::Break(e) => Err(e),
+    ControlFlow::Continue(_) => Ok(()),
+  }
+}
+
+fn is_vector_type(data_type: &DataType) -> bool {
+  match data_type {
+    DataType::Custom(name, _) => {
+      name.0.len() == 1 && name.0[0].value.eq_ignore_ascii_case("vector")
+    }
+    _ => false,
+  }
+}
+
+/// Parses pgvector's text representation, i.e. `[1,2,3]`, to an array
+fn parse_vector_literal(vector: &str) -> Result<Expr, ParserError> {
+  let values = serde_json::from_str::<Vec<f32>>(vector).map_err(|_| {
+    ParserError::ParserError(format!(
+      "Invalid input syntax for type vector: \"{}\"",
+      vector
+    ))
+  })?;
+  Ok(Expr::Array(Array {
+    elem: values
+      .iter()
+      .map(|v| Expr::Value(Value::Number(v.to_string(), false)))
+      .collect(),
+    named: false,
+  }))
+}
+
+fn is_vector_literal(expr: &Expr) -> bool {
+  match expr {
+    Expr::Array(_) | Expr::Value(Value::SingleQuotedString(_)) => true,
+    _ => false,
+  }
+}
+
+/// Vector functions expect the query vector to be a JSON string, so convert
+/// the array literal back to string. Other expressions like placeholders are
+/// left as is.
+fn to_json_string(expr: &Expr) -> Expr {
+  match expr {
+    Expr::Array(Array { elem, .. }) => {
+      Expr::Value(Value::SingleQuotedString(format!(
+        "[{}]",
+        elem
+          .iter()
+          .map(|e| e.to_string())
+          .collect::<Vec<String>>()
+          .join(",")
+      )))
+    }
+    expr => expr.clone(),
+  }
+}
+
+fn distance_function(name: &str, column: Expr, query: Expr) -> Expr {
+  Expr::Function(Function {
+    name: ObjectName(vec![Ident::new(name)]),
+    args: vec![
+      FunctionArg::Unnamed(FunctionArgExpr::Expr(column)),
+      FunctionArg::Unnamed(FunctionArgExpr::Expr(query)),
+    ],
+    filter: None,
+    null_treatment: None,
+    over: None,
+    distinct: false,
+    special: false,
+    order_by: vec![],
+  })
+}
+
+#[cfg(test)]
+mod tests {
+  use crate::ast::parse;
+
+  #[test]
+  fn test_pgvector_operators() {
+    for (sql, expected) in [
+      (
+        "SELECT id FROM t ORDER BY embedding <-> '[1,2.5,-3]'::vector LIMIT 1",
+        "SELECT id FROM t ORDER BY l2(embedding, '[1,2.5,-3]') LIMIT 1",
+      ),
+      (
+        "SELECT embedding <#> '[1,2]' FROM t",
+        "SELECT negative_inner_product(embedding, '[1,2]') FROM t",
+      ),
+      (
+        "SELECT id FROM t WHERE '[1, 2]'::vector(2) <=> embedding < 0.5",
+        "SELECT id FROM t WHERE cosine_distance(embedding, '[1,2]') < 0.5",
+      ),
+      (
+        "SELECT id FROM t ORDER BY embedding <-> $1::vector",
+        "SELECT id FROM t ORDER BY l2(embedding, $1)",
+      ),
+      (
+        "INSERT INTO t VALUES ('id1', '[0.1,0.2]'::vector)",
+        "INSERT INTO t VALUES ('id1', [0.1, 0.2])",
+      ),
+      (
+        "SELECT a < -1, a <= b FROM t",
+        "SELECT a < -1, a <= b FROM t",
+      ),
+    ] {
+      assert_eq!(parse(sql).unwrap()[0].to_string(), expected);
+    }
+  }
+
+  #[test]
+  fn test_invalid_vector_literal() {
+    assert!(parse("SELECT 'abc'::vector").is_err());
+  }
+}
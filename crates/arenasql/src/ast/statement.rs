@@ -1,10 +1,47 @@
-use sqlparser::ast::Statement as SQLStatement;
+use sqlparser::ast::{
+  Expr, Ident, ObjectName, Statement as SQLStatement, Value,
+};
+
+/// Name of the variable of the `SET LOCAL` statement that `RELEASE
+/// [SAVEPOINT] name` is parsed into since the parser doesn't support it.
+/// The variable is an unquoted identifier in the parsed statement; users
+/// can only set it as a quoted identifier, which isn't treated as a
+/// savepoint statement
+pub const RELEASE_SAVEPOINT: &str = "arenasql.release_savepoint";
+
+/// Name of the variable of the `SET LOCAL` statement that `ROLLBACK TO
+/// [SAVEPOINT] name` is parsed into since the parser doesn't support it
+pub const ROLLBACK_TO_SAVEPOINT: &str = "arenasql.rollback_to_savepoint";
+
+/// Name of the `EXECUTE` statement that `DROP DATABASE` is parsed into
+/// since the parser doesn't support it. Like the savepoint variables,
+/// it's an unquoted identifier in the parsed statement, so `EXECUTE`
+/// statements typed by users with this name aren't database statements
+pub const DROP_DATABASE: &str = "arenasql.drop_database";
+
+/// Name of the `EXECUTE` statement that `ALTER DATABASE ... RENAME TO`
+/// is parsed into since the parser doesn't support it
+pub const RENAME_DATABASE: &str = "arenasql.rename_database";
+
+/// `DROP DATABASE` and `ALTER DATABASE` statements. Since the parser
+/// doesn't support them, they are parsed into `EXECUTE` statements with
+/// reserved names; use [`DatabaseStatement::from_statement`] to get them
+#[derive(Debug, Clone, PartialEq)]
+pub enum DatabaseStatement {
+  /// `DROP DATABASE [IF EXISTS] name`
+  Drop { name: String, if_exists: bool },
+  /// `ALTER DATABASE name RENAME TO new_name`
+  Rename { name: String, new_name: String },
+}
 
 #[derive(Debug, PartialEq)]
 pub enum StatementType {
   Begin,
   Commit,
   Rollback,
+  Savepoint,
+  ReleaseSavepoint,
+  RollbackToSavepoint,
   Set,
   Query,
   Insert,
@@ -14,6 +51,8 @@ pub enum StatementType {
   Update,
   Alter,
   Execute,
+  Explain,
+  Analyze,
 }
 
 impl From<&SQLStatement> for StatementType {
@@ -22,17 +61,37 @@ impl From<&SQLStatement> for StatementType {
       SQLStatement::StartTransaction { .. } => Self::Begin,
       SQLStatement::Commit { .. } => Self::Commit,
       SQLStatement::Rollback { .. } => Self::Rollback,
+      SQLStatement::Savepoint { .. } => Self::Savepoint,
+      SQLStatement::SetVariable { .. }
+        if get_savepoint_variable(stmt) == Some(RELEASE_SAVEPOINT) =>
+      {
+        Self::ReleaseSavepoint
+      }
+      SQLStatement::SetVariable { .. }
+        if get_savepoint_variable(stmt) == Some(ROLLBACK_TO_SAVEPOINT) =>
+      {
+        Self::RollbackToSavepoint
+      }
+      SQLStatement::Execute { .. } => {
+        match DatabaseStatement::from_statement(stmt) {
+          Some(DatabaseStatement::Drop { .. }) => Self::Drop,
+          Some(DatabaseStatement::Rename { .. }) => Self::Alter,
+          None => Self::Execute,
+        }
+      }
       SQLStatement::Query(_) => Self::Query,
       SQLStatement::Insert { .. } => Self::Insert,
       SQLStatement::CreateDatabase { .. }
       | SQLStatement::CreateTable { .. }
-      | SQLStatement::CreateIndex { .. } => Self::Create,
+      | SQLStatement::CreateIndex { .. }
+      | SQLStatement::CreateSequence { .. } => Self::Create,
       SQLStatement::Delete { .. } => Self::Delete,
       SQLStatement::Update { .. } => Self::Update,
       SQLStatement::AlterIndex { .. } | SQLStatement::AlterTable { .. } => {
         Self::Alter
       }
-      SQLStatement::Execute { .. } => Self::Execute,
+      SQLStatement::Explain { .. } => Self::Explain,
+      SQLStatement::Analyze { .. } => Self::Analyze,
       SQLStatement::Drop { .. } => Self::Drop,
       SQLStatement::SetTimeZone { .. } | SQLStatement::SetVariable { .. } => {
         Self::Set
@@ -49,6 +108,9 @@ impl StatementType {
       Self::Begin => "BEGIN",
       Self::Commit => "COMMIT",
       Self::Rollback => "ROLLBACK",
+      Self::Savepoint => "SAVEPOINT",
+      Self::ReleaseSavepoint => "RELEASE",
+      Self::RollbackToSavepoint => "ROLLBACK",
       Self::Set => "SET",
       Self::Query => "SELECT",
       Self::Insert => "INSERT",
@@ -58,6 +120,8 @@ impl StatementType {
       Self::Update => "UPDATE",
       Self::Alter => "ALTER",
       Self::Execute => "EXECUTE",
+      Self::Explain => "EXPLAIN",
+      Self::Analyze => "ANALYZE",
     }
   }
 
@@ -90,4 +154,123 @@ impl StatementType {
   pub fn is_rollback(&self) -> bool {
     *self == Self::Rollback
   }
+
+  /// Returns true for `SAVEPOINT`, `RELEASE` and `ROLLBACK TO`
+  #[inline]
+  pub fn is_savepoint(&self) -> bool {
+    matches!(
+      self,
+      Self::Savepoint | Self::ReleaseSavepoint | Self::RollbackToSavepoint
+    )
+  }
+}
+
+impl DatabaseStatement {
+  pub fn from_statement(stmt: &SQLStatement) -> Option<Self> {
+    let SQLStatement::Execute { name, parameters } = stmt else {
+      return None;
+    };
+    if name.quote_style.is_some() {
+      return None;
+    }
+    match (name.value.as_str(), parameters.as_slice()) {
+      (
+        DROP_DATABASE,
+        [Expr::Identifier(db), Expr::Value(Value::Boolean(if_exists))],
+      ) => Some(Self::Drop {
+        name: normalize_ident(db),
+        if_exists: *if_exists,
+      }),
+      (RENAME_DATABASE, [Expr::Identifier(db), Expr::Identifier(new_name)]) => {
+        Some(Self::Rename {
+          name: normalize_ident(db),
+          new_name: normalize_ident(new_name),
+        })
+      }
+      _ => None,
+    }
+  }
+}
+
+/// Returns the `EXECUTE` statement that `DROP DATABASE` is parsed into.
+/// The name of the statement is unquoted so that it's distinguished from
+/// the `EXECUTE` statements typed by users
+pub(crate) fn drop_database_statement(
+  name: Ident,
+  if_exists: bool,
+) -> SQLStatement {
+  SQLStatement::Execute {
+    name: Ident::new(DROP_DATABASE),
+    parameters: vec![
+      Expr::Identifier(name),
+      Expr::Value(Value::Boolean(if_exists)),
+    ],
+  }
+}
+
+/// Returns the `EXECUTE` statement that `ALTER DATABASE ... RENAME TO`
+/// is parsed into. The name of the statement is unquoted like the one of
+/// [`drop_database_statement`]
+pub(crate) fn rename_database_statement(
+  name: Ident,
+  new_name: Ident,
+) -> SQLStatement {
+  SQLStatement::Execute {
+    name: Ident::new(RENAME_DATABASE),
+    parameters: vec![Expr::Identifier(name), Expr::Identifier(new_name)],
+  }
+}
+
+/// Returns the name of the identifier the way Postgres does; i.e. the
+/// unquoted identifiers are case insensitive
+pub fn normalize_ident(ident: &Ident) -> String {
+  match ident.quote_style {
+    Some(_) => ident.value.clone(),
+    None => ident.value.to_lowercase(),
+  }
+}
+
+/// Returns the statement for `RELEASE [SAVEPOINT] name` or `ROLLBACK TO
+/// [SAVEPOINT] name` with the given variable
+pub(crate) fn savepoint_statement(variable: &str, name: Ident) -> SQLStatement {
+  SQLStatement::SetVariable {
+    local: true,
+    hivevar: false,
+    variable: ObjectName(vec![Ident::new(variable)]),
+    value: vec![Expr::Identifier(name)],
+  }
+}
+
+/// Returns the name of the savepoint if the statement is `SAVEPOINT`,
+/// `RELEASE` or `ROLLBACK TO`
+pub fn get_savepoint_name(stmt: &SQLStatement) -> Option<String> {
+  match stmt {
+    SQLStatement::Savepoint { name } => Some(normalize_ident(name)),
+    SQLStatement::SetVariable { value, .. }
+      if get_savepoint_variable(stmt).is_some() =>
+    {
+      match value.as_slice() {
+        [Expr::Identifier(name)] => Some(normalize_ident(name)),
+        _ => None,
+      }
+    }
+    _ => None,
+  }
+}
+
+fn get_savepoint_variable(stmt: &SQLStatement) -> Option<&'static str> {
+  match stmt {
+    SQLStatement::SetVariable {
+      local: true,
+      variable,
+      ..
+    } if variable.0.len() == 1 && variable.0[0].quote_style.is_none() => {
+      match variable.0[0].value.as_str() {
+        RELEASE_SAVEPOINT => Some(RELEASE_SAVEPOINT),
+        ROLLBACK_TO_SAVEPOINT => Some(ROLLBACK_TO_SAVEPOINT),
+        _ => None,
+      }
+    }
+    _ => None,
+  }
 }
@@ -1,7 +1,13 @@
 use std::fmt;
 use std::sync::Arc;
 
-use datafusion::arrow::datatypes::{Schema, SchemaRef};
+use datafusion::arrow::array::as_primitive_array;
+use datafusion::arrow::compute::{cast_with_options, CastOptions};
+use datafusion::arrow::datatypes::{
+  DataType as DfDataType, Field, Schema, SchemaRef, UInt64Type,
+};
+use datafusion::arrow::record_batch::{RecordBatch, RecordBatchOptions};
+use datafusion::error::DataFusionError;
 use datafusion::execution::TaskContext;
 use datafusion::logical_expr::{Expr, LogicalPlan};
 use datafusion::physical_plan::{DisplayAs, DisplayFormatType};
@@ -9,17 +15,25 @@ use derivative::Derivative;
 use derive_builder::Builder;
 use futures::StreamExt;
 use sqlparser::ast::{
-  AlterTableOperation, ColumnDef, Statement as SQLStatement,
+  AlterColumnOperation, AlterTableOperation, ColumnDef, ColumnOption, Ident,
+  Statement as SQLStatement,
 };
 
+use super::table_exprs::TableExprs;
 use crate::df::providers::{get_schema_provider, get_table_ref};
 use crate::error::Error;
+use crate::execution::iterators::HeapIterator;
 use crate::execution::{CustomExecutionPlan, Transaction};
 use crate::execution::{ExecutionPlanResponse, TransactionHandle};
-use crate::schema::{Column, ColumnProperty, DataFrame, DataType, Table};
+use crate::schema::{
+  get_default_expr, set_column_sequence, Column, ColumnProperty, Constraint,
+  DataFrame, DataType, OwnedSerializedCell, RowId, Sequence, Table, TableIndex,
+  CTID_COLUMN,
+};
+use crate::storage::StorageHandler;
 use crate::{bail, Result};
 
-/// Returns a custom execution plan extension to create index
+/// Returns a custom execution plan extension to alter table
 #[tracing::instrument(skip_all, fields(name = "alter_table"), level = "trace")]
 pub fn extension(
   transaction: &Transaction,
@@ -48,29 +62,30 @@ pub fn extension(
         bail!(Error::RelationDoesntExist(table_name));
       }
 
-      let columns = operations
+      operations
         .iter()
-        .map(|op| {
-          if let AlterTableOperation::AddColumn { column_def, .. } = op {
-            Ok(column_def.to_owned())
-          } else {
-            Err(Error::UnsupportedOperation(format!("{:?}", operations)))
-          }
+        .map(|op| match op {
+          AlterTableOperation::AddColumn { .. }
+          | AlterTableOperation::DropColumn { .. }
+          | AlterTableOperation::RenameColumn { .. }
+          | AlterTableOperation::RenameTable { .. }
+          | AlterTableOperation::AlterColumn { .. } => Ok(()),
+          _ => Err(Error::UnsupportedOperation(format!("ALTER TABLE {}", op))),
         })
-        .collect::<Result<Vec<ColumnDef>>>()?;
+        .collect::<Result<Vec<()>>>()?;
 
       let table = transaction
         .handle()
         .get_table(&table_ref.schema, &table_name)
         .unwrap();
       return Ok(Some(Arc::new(
-        AddColumnExecutionPlanBuilder::default()
+        AlterTableExecutionPlanBuilder::default()
           .transaction(transaction.handle().clone())
-          .add_column(AddColumn {
+          .alter_table(AlterTable {
             catalog: table_ref.catalog.as_ref().into(),
             schema: table_ref.schema.as_ref().into(),
             table,
-            columns: columns.into(), // operations: operations.to_owned().into(),
+            operations: operations.to_owned().into(),
           })
           .build()
           .unwrap(),
@@ -83,21 +98,21 @@ pub fn extension(
 
 #[derive(Builder, Derivative)]
 #[derivative(Debug)]
-pub struct AddColumnExecutionPlan {
+pub struct AlterTableExecutionPlan {
   #[derivative(Debug = "ignore")]
   transaction: TransactionHandle,
-  add_column: AddColumn,
+  alter_table: AlterTable,
 }
 
 #[derive(Debug, Clone)]
-pub struct AddColumn {
+pub struct AlterTable {
   catalog: Arc<str>,
   schema: Arc<str>,
   table: Arc<Table>,
-  columns: Arc<Vec<ColumnDef>>,
+  operations: Arc<Vec<AlterTableOperation>>,
 }
 
-impl DisplayAs for AddColumnExecutionPlan {
+impl DisplayAs for AlterTableExecutionPlan {
   fn fmt_as(
     &self,
     _t: DisplayFormatType,
@@ -107,7 +122,7 @@ impl DisplayAs for AddColumnExecutionPlan {
   }
 }
 
-impl CustomExecutionPlan for AddColumnExecutionPlan {
+impl CustomExecutionPlan for AlterTableExecutionPlan {
   fn schema(&self) -> SchemaRef {
     Arc::new(Schema::empty())
   }
@@ -115,42 +130,167 @@ impl CustomExecutionPlan for AddColumnExecutionPlan {
   fn execute(
     &self,
     _partition: usize,
-    _context: Arc<TaskContext>,
+    context: Arc<TaskContext>,
     _exprs: Vec<Expr>,
     _inputs: Vec<LogicalPlan>,
   ) -> crate::Result<ExecutionPlanResponse> {
-    let add_column = self.add_column.clone();
+    let alter_table = self.alter_table.clone();
     let transaction = self.transaction.clone();
     let stream = futures::stream::once(async move {
-      let AddColumn {
+      let AlterTable {
         catalog,
         schema,
         table,
-        columns,
-      } = add_column;
+        operations,
+      } = alter_table;
 
+      let old_name = table.name.clone();
       let mut table = table.as_ref().clone();
       let table_lock = transaction
         .acquire_table_schema_write_lock(schema.as_ref(), &table.name)
         .await?;
 
       let storage_handler = transaction.lock(true)?;
-      columns
-        .iter()
-        .map(|col| {
-          let new_column = Column {
-            id: table.columns.len() as u8,
-            name: col.name.value.clone(),
-            data_type: DataType::from_column_def(&col, None)?,
-            properties: ColumnProperty::DEFAULT,
-            default_value: None,
-          };
-          table.columns.push(new_column);
-          Ok(())
-        })
-        .collect::<Result<()>>()?;
-      storage_handler.put_table_schema(&catalog, &schema, &table)?;
-      transaction.hold_table_schema_lock(Arc::new(table), table_lock)?;
+      let mut added_columns = vec![];
+      let referencing_tables =
+        get_referencing_tables(&transaction, &schema, &table.name);
+      for op in operations.iter() {
+        match op {
+          AlterTableOperation::AddColumn {
+            if_not_exists,
+            column_def,
+            ..
+          } => {
+            if *if_not_exists
+              && table.find_column(&column_def.name.value).is_some()
+            {
+              continue;
+            }
+            if let Some(sequence) = add_column(&mut table, column_def)? {
+              storage_handler.create_sequence(&catalog, &schema, &sequence)?;
+            }
+            added_columns.push(table.columns.len() - 1);
+          }
+          AlterTableOperation::DropColumn {
+            column_name,
+            if_exists,
+            ..
+          } => {
+            let Some(column) = table.find_column(&column_name.value) else {
+              if *if_exists {
+                continue;
+              }
+              bail!(Error::ColumnDoesntExist(column_name.value.clone()));
+            };
+            let is_referenced = referencing_tables.iter().any(|t| {
+              t.foreign_keys().any(|fk| {
+                fk.foreign_table == table.name
+                  && fk.referred_columns.contains(&column)
+              })
+            });
+            if is_referenced {
+              bail!(Error::InvalidQuery(format!(
+                "cannot drop column {} of table {} because other objects \
+                depend on it",
+                column_name.value, table.name
+              )));
+            }
+            let old_table = table.clone();
+            let dropped_indexes = table.drop_column(column);
+            restore_dropped_index_vectors(
+              &storage_handler,
+              &old_table,
+              &table,
+              &dropped_indexes,
+            )?;
+            for index in dropped_indexes {
+              storage_handler.delete_index_rows(&index)?;
+            }
+          }
+          AlterTableOperation::RenameColumn {
+            old_column_name,
+            new_column_name,
+          } => {
+            let column = find_column(&table, old_column_name)?;
+            if table.find_column(&new_column_name.value).is_some() {
+              bail!(Error::ColumnAlreadyExists {
+                table: table.name.clone(),
+                column: new_column_name.value.clone(),
+              });
+            }
+            table.rename_column(column, &new_column_name.value)?;
+          }
+          AlterTableOperation::RenameTable { table_name } => {
+            let new_name = table_name.0.last().unwrap().value.clone();
+            if transaction.get_table(&schema, &new_name).is_some() {
+              bail!(Error::RelationAlreadyExists(new_name));
+            }
+            let previous_name =
+              std::mem::replace(&mut table.name, new_name.clone());
+            // Update self referencing foreign keys
+            rename_foreign_table(&mut table, &previous_name, &new_name);
+          }
+          AlterTableOperation::AlterColumn { column_name, op } => {
+            let column = find_column(&table, column_name)?;
+            alter_column(&storage_handler, &mut table, column, op)?;
+          }
+          // Unsupported operations are rejected when creating the plan
+          _ => unreachable!(),
+        }
+      }
+
+      // This also makes sure the default and check expressions are
+      // still valid
+      let table_exprs = TableExprs::new(&Arc::new(table.clone()), &context)?;
+      for column in added_columns {
+        if table.columns[column].default_expr.is_some() {
+          backfill_default(&storage_handler, &table, column, &table_exprs)?;
+        }
+      }
+
+      // Foreign keys that reference the table need to be updated
+      // if the table was renamed
+      if table.name != old_name {
+        for referencing_table in referencing_tables {
+          let mut referencing_table = referencing_table.as_ref().clone();
+          let lock = transaction
+            .acquire_table_schema_write_lock(
+              schema.as_ref(),
+              &referencing_table.name,
+            )
+            .await?;
+          rename_foreign_table(&mut referencing_table, &old_name, &table.name);
+          storage_handler.put_table_schema(
+            &catalog,
+            &schema,
+            &referencing_table,
+          )?;
+          transaction
+            .hold_table_schema_lock(Arc::new(referencing_table), lock)?;
+        }
+
+        let new_table_lock = transaction
+          .acquire_table_schema_write_lock(schema.as_ref(), &table.name)
+          .await?;
+        storage_handler.delete_table_schema(&catalog, &schema, &old_name)?;
+        storage_handler.put_table_schema(&catalog, &schema, &table)?;
+        for mut sequence in storage_handler
+          .get_all_sequences(&catalog, &schema)?
+          .into_iter()
+          .filter(|seq| seq.owned_by.as_ref() == Some(&old_name))
+        {
+          sequence.owned_by = Some(table.name.clone());
+          storage_handler.put_sequence(&catalog, &schema, &sequence)?;
+        }
+        let table = Arc::new(table);
+        // Replace the table locked by the old name too so that the old
+        // table isn't returned from the locked tables
+        transaction.hold_table_schema_lock(table.clone(), table_lock)?;
+        transaction.hold_table_schema_lock(table, new_table_lock)?;
+      } else {
+        storage_handler.put_table_schema(&catalog, &schema, &table)?;
+        transaction.hold_table_schema_lock(Arc::new(table), table_lock)?;
+      }
       Ok(DataFrame::empty())
     })
     .boxed();
@@ -158,3 +298,299 @@ impl CustomExecutionPlan for AddColumnExecutionPlan {
     Ok(Box::pin(stream))
   }
 }
+
+/// Adds the column to the table and returns the sequence of the column
+/// if it's a `SERIAL` or identity column
+fn add_column(
+  table: &mut Table,
+  column_def: &ColumnDef,
+) -> Result<Option<Sequence>> {
+  if table.find_column(&column_def.name.value).is_some() {
+    bail!(Error::ColumnAlreadyExists {
+      table: table.name.clone(),
+      column: column_def.name.value.clone(),
+    });
+  }
+  let default_expr = column_def
+    .options
+    .iter()
+    .find_map(|opt| match &opt.option {
+      ColumnOption::Default(expr) => Some(expr),
+      _ => None,
+    })
+    .map(get_default_expr)
+    .transpose()?;
+  let mut column = Column {
+    id: table.columns.len() as u8,
+    name: column_def.name.value.clone(),
+    data_type: DataType::from_column_def(&column_def, None)?,
+    properties: ColumnProperty::DEFAULT,
+    default_expr,
+  };
+  let sequence = set_column_sequence(&table.name, &mut column, column_def)?;
+  table.columns.push(column);
+  Ok(sequence)
+}
+
+fn alter_column(
+  storage: &StorageHandler,
+  table: &mut Table,
+  column: usize,
+  op: &AlterColumnOperation,
+) -> Result<()> {
+  match op {
+    AlterColumnOperation::SetNotNull => {
+      if scan_column(storage, table, column)?.column(0).null_count() > 0 {
+        bail!(Error::NullConstraintViolated {
+          table: table.name.clone(),
+          column: table.columns[column].name.clone(),
+        });
+      }
+      table.columns[column]
+        .properties
+        .insert(ColumnProperty::NOT_NULL);
+    }
+    AlterColumnOperation::DropNotNull => {
+      let is_primary_key = table.constraints.iter().any(|c| match c {
+        Constraint::PrimaryKey(columns) => columns.contains(&column),
+        _ => false,
+      });
+      if is_primary_key {
+        bail!(Error::InvalidQuery(format!(
+          r#"column "{}" is in a primary key"#,
+          table.columns[column].name
+        )));
+      }
+      table.columns[column]
+        .properties
+        .remove(ColumnProperty::NOT_NULL);
+    }
+    AlterColumnOperation::SetDefault { value } => {
+      table.columns[column].default_expr = Some(get_default_expr(value)?);
+    }
+    AlterColumnOperation::DropDefault => {
+      table.columns[column].default_expr = None;
+    }
+    AlterColumnOperation::SetDataType { data_type, using } => {
+      if using.is_some() {
+        bail!(Error::UnsupportedOperation(
+          "`USING` expression isn't supported when altering column type"
+            .to_owned()
+        ));
+      }
+      let data_type = DataType::from_column_def(
+        &ColumnDef {
+          name: Ident::new(&table.columns[column].name),
+          data_type: data_type.clone(),
+          collation: None,
+          options: vec![],
+        },
+        None,
+      )?;
+      alter_column_type(storage, table, column, data_type)?;
+    }
+  }
+  Ok(())
+}
+
+/// Changes the data type of the column and casts the values of the
+/// column in all the existing rows to the new data type
+fn alter_column_type(
+  storage: &StorageHandler,
+  table: &mut Table,
+  column: usize,
+  data_type: DataType,
+) -> Result<()> {
+  if table.columns[column].data_type == data_type {
+    return Ok(());
+  }
+  if table.foreign_keys().any(|fk| fk.columns.contains(&column)) {
+    bail!(Error::UnsupportedOperation(
+      "cannot alter type of a column used by a foreign key constraint"
+        .to_owned()
+    ));
+  }
+  if table.indexes.iter().any(|index| {
+    index.is_vector_index()
+      && (index.columns().contains(&column)
+        || index.namespace_column() == Some(column))
+  }) {
+    bail!(Error::UnsupportedOperation(
+      "cannot alter type of a column used by a vector index".to_owned()
+    ));
+  }
+
+  let old_table = table.clone();
+  table.columns[column].data_type = data_type;
+  let (df_data_type, _) = table.columns[column].data_type.to_df_datatype();
+
+  let batch = scan_column(storage, &old_table, column)?;
+  let values = cast_with_options(
+    batch.column(0),
+    &df_data_type,
+    &CastOptions {
+      safe: false,
+      ..Default::default()
+    },
+  )
+  .map_err(|_| {
+    Error::InvalidQuery(format!(
+      r#"column "{}" cannot be cast automatically to type {}"#,
+      table.columns[column].name, table.columns[column].data_type
+    ))
+  })?;
+  let cells = OwnedSerializedCell::column_array_to_vec(
+    &table.name,
+    &table.columns[column],
+    &values,
+  )?;
+
+  let indexes: Vec<_> = table
+    .indexes
+    .iter()
+    .filter(|index| index.columns().contains(&column))
+    .collect();
+  let row_ids = as_primitive_array::<UInt64Type>(batch.column(1));
+  for (row_id, cell) in row_ids.values().iter().zip(cells) {
+    let row_id = RowId::serialize_u64(*row_id);
+    let old_row = storage.get_row(&old_table, &row_id)?.unwrap();
+    let mut new_row = old_row.clone();
+    if new_row.len() <= column {
+      new_row.resize(column + 1, OwnedSerializedCell::Null);
+    }
+    new_row[column] = cell;
+    for index in &indexes {
+      storage.delete_row_from_index(index, &row_id, &old_row)?;
+      storage.add_row_to_index(table, index, &row_id, &new_row)?;
+    }
+    storage.insert_row(table, &row_id, &new_row)?;
+  }
+  Ok(())
+}
+
+/// Sets the value of the newly added column in all the existing rows
+/// to the default value of the column
+fn backfill_default(
+  storage: &StorageHandler,
+  table: &Table,
+  column: usize,
+  table_exprs: &TableExprs,
+) -> Result<()> {
+  let row_ids = scan_column(storage, table, column)?;
+  let row_ids = as_primitive_array::<UInt64Type>(row_ids.column(1));
+  let defaults = table_exprs.fill_defaults(
+    &RecordBatch::try_new_with_options(
+      Arc::new(Schema::empty()),
+      vec![],
+      &RecordBatchOptions::new().with_row_count(Some(row_ids.len())),
+    )
+    .map_err(|e| {
+      Error::DataFusionError(DataFusionError::ArrowError(e).into())
+    })?,
+  )?;
+  let col = &table.columns[column];
+  let cells = OwnedSerializedCell::column_array_to_vec(
+    &table.name,
+    col,
+    defaults.column_by_name(&col.name).unwrap(),
+  )?;
+
+  for (row_id, cell) in row_ids.values().iter().zip(cells) {
+    let row_id = RowId::serialize_u64(*row_id);
+    let mut row = storage.get_row(table, &row_id)?.unwrap();
+    row.resize(table.columns.len(), OwnedSerializedCell::Null);
+    row[column] = cell;
+    storage.insert_row(table, &row_id, &row)?;
+  }
+  Ok(())
+}
+
+/// Writes the vectors that are only stored in the dropped vector indexes
+/// back into the rows so that they aren't lost when the index rows are
+/// deleted; e.g. when the namespace column of the index is dropped
+fn restore_dropped_index_vectors(
+  storage: &StorageHandler,
+  old_table: &Table,
+  table: &Table,
+  dropped_indexes: &[TableIndex],
+) -> Result<()> {
+  let Some(vector_column) = dropped_indexes
+    .iter()
+    .filter(|index| !index.retains_vectors())
+    .map(|index| index.columns()[0])
+    .find(|column| !table.columns[*column].archived())
+  else {
+    return Ok(());
+  };
+
+  let batch = scan_column(storage, old_table, vector_column)?;
+  let row_ids = as_primitive_array::<UInt64Type>(batch.column(1));
+  for row_id in row_ids.values().iter() {
+    let row_id = RowId::serialize_u64(*row_id);
+    // The row read using the old table has all the stripped vectors
+    // restored and it's stripped again only for the remaining indexes
+    let row = storage.get_row(old_table, &row_id)?.unwrap();
+    storage.insert_row(table, &row_id, &row)?;
+  }
+  Ok(())
+}
+
+/// Returns the batch with the values of the column of all the rows in
+/// the first column and the row ids in the second column
+fn scan_column(
+  storage: &StorageHandler,
+  table: &Table,
+  column: usize,
+) -> Result<RecordBatch> {
+  let col = &table.columns[column];
+  let schema = Arc::new(Schema::new(vec![
+    col.to_field(table).with_nullable(true),
+    Field::new(CTID_COLUMN, DfDataType::UInt64, false),
+  ]));
+  let mut dataframe = DataFrame::with_capacity(
+    1_000,
+    vec![
+      (col.name.clone(), col.data_type.clone()),
+      (CTID_COLUMN.to_owned(), DataType::UInt64),
+    ],
+  );
+  HeapIterator::new(storage, table, &vec![column]).fill_into(&mut dataframe)?;
+  Ok(dataframe.to_record_batch(schema)?)
+}
+
+fn find_column(table: &Table, name: &Ident) -> Result<usize> {
+  table
+    .find_column(&name.value)
+    .ok_or_else(|| Error::ColumnDoesntExist(name.value.clone()))
+}
+
+/// Returns the tables in the schema that have foreign keys referencing
+/// the given table; the table itself isn't included
+fn get_referencing_tables(
+  transaction: &TransactionHandle,
+  schema: &str,
+  table_name: &str,
+) -> Vec<Arc<Table>> {
+  transaction
+    .table_names(schema)
+    .iter()
+    .filter(|name| name.as_str() != table_name)
+    .filter_map(|name| transaction.get_table(schema, name))
+    .filter(|table| {
+      table
+        .foreign_keys()
+        .any(|fk| fk.foreign_table == table_name)
+    })
+    .collect()
+}
+
+/// Updates the foreign keys of the table that reference the renamed table
+fn rename_foreign_table(table: &mut Table, old_name: &str, new_name: &str) {
+  table.constraints.iter_mut().for_each(|constraint| {
+    if let Constraint::ForeignKey(foreign_key) = constraint {
+      if foreign_key.foreign_table == old_name {
+        foreign_key.foreign_table = new_name.to_owned();
+      }
+    }
+  });
+}
@@ -0,0 +1,117 @@
+use std::fmt;
+use std::sync::Arc;
+
+use datafusion::arrow::datatypes::{Schema, SchemaRef};
+use datafusion::execution::TaskContext;
+use datafusion::logical_expr::{Expr, LogicalPlan};
+use datafusion::physical_plan::{DisplayAs, DisplayFormatType};
+use derivative::Derivative;
+use derive_builder::Builder;
+use futures::StreamExt;
+use sqlparser::ast::Statement as SQLStatement;
+
+use crate::df::providers::get_table_ref;
+use crate::error::Error;
+use crate::execution::{CustomExecutionPlan, Transaction};
+use crate::execution::{ExecutionPlanResponse, TransactionHandle};
+use crate::schema::{DataFrame, Table};
+use crate::Result;
+
+/// Returns a custom execution plan extension to collect the statistics
+/// of the tables. If the table name is empty, all the tables in the
+/// schemas of the session are analyzed
+#[tracing::instrument(skip_all, fields(name = "analyze"), level = "trace")]
+pub fn extension(
+  transaction: &Transaction,
+  stmt: &SQLStatement,
+) -> Result<Option<Arc<dyn CustomExecutionPlan>>> {
+  let SQLStatement::Analyze { table_name, .. } = stmt else {
+    return Ok(None);
+  };
+
+  let handle = transaction.handle();
+  let tables = match table_name.0.is_empty() {
+    true => transaction
+      .session_config()
+      .schemas
+      .iter()
+      .flat_map(|schema| {
+        handle
+          .table_names(schema)
+          .iter()
+          .filter_map(|name| handle.get_table(schema, name))
+          .collect::<Vec<Arc<Table>>>()
+      })
+      .collect(),
+    false => {
+      let state = transaction.datafusion_context().state();
+      let name = table_name.to_string();
+      let table_ref = get_table_ref(&state, &name);
+      let table = handle
+        .get_table(table_ref.schema.as_ref(), table_ref.table.as_ref())
+        .ok_or_else(|| {
+          Error::RelationDoesntExist(table_ref.table.as_ref().to_owned())
+        })?;
+      vec![table]
+    }
+  };
+
+  Ok(Some(Arc::new(
+    AnalyzeExecutionPlanBuilder::default()
+      .transaction(handle.clone())
+      .tables(tables)
+      .build()
+      .unwrap(),
+  )))
+}
+
+#[derive(Builder, Derivative)]
+#[derivative(Debug)]
+pub struct AnalyzeExecutionPlan {
+  #[derivative(Debug = "ignore")]
+  transaction: TransactionHandle,
+  tables: Vec<Arc<Table>>,
+}
+
+impl DisplayAs for AnalyzeExecutionPlan {
+  fn fmt_as(
+    &self,
+    _t: DisplayFormatType,
+    f: &mut fmt::Formatter,
+  ) -> fmt::Result {
+    write!(f, "{:?}", self)
+  }
+}
+
+impl CustomExecutionPlan for AnalyzeExecutionPlan {
+  fn schema(&self) -> SchemaRef {
+    Arc::new(Schema::empty())
+  }
+
+  fn execute(
+    &self,
+    _partition: usize,
+    _context: Arc<TaskContext>,
+    _exprs: Vec<Expr>,
+    _inputs: Vec<LogicalPlan>,
+  ) -> crate::Result<ExecutionPlanResponse> {
+    let tables = self.tables.clone();
+    let transaction = self.transaction.clone();
+    let stream = futures::stream::once(async move {
+      let storage_handler = transaction.lock(true)?;
+      for table in tables {
+        let statistics = storage_handler.compute_table_statistics(&table)?;
+        tracing::trace!(
+          "table = {:?}, row_count = {}",
+          table.name,
+          statistics.row_count
+        );
+        storage_handler.put_table_statistics(&table, &statistics)?;
+      }
+      Ok(DataFrame::empty())
+    })
+    .boxed();
+
+    Ok(Box::pin(stream))
+  }
+}
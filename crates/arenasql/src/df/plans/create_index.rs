@@ -12,11 +12,13 @@ use derive_builder::Builder;
 use futures::StreamExt;
 use sqlparser::ast::{Expr as SqlExpr, Statement as SQLStatement, Value};
 
+use crate::ast::get_json_path_expr;
 use crate::df::providers::{get_schema_provider, get_table_ref};
 use crate::execution::{CustomExecutionPlan, Transaction};
 use crate::execution::{ExecutionPlanResponse, TransactionHandle};
 use crate::schema::{
-  DataFrame, IndexProvider, OwnedRow, Table, TableIndex, VectorMetric,
+  DataFrame, DataType as ArenaDataType, IndexProvider, Table, TableIndex,
+  VectorMetric,
 };
 use crate::storage::{KeyValueGroup, StorageHandler};
 use crate::{bail, table_rows_prefix_key, Error, Result};
@@ -83,18 +85,32 @@ pub fn extension(
         .handle()
         .get_table(&table_ref.schema, &table_name)
         .unwrap();
-      let column_projection = columns
-        .to_vec()
-        .iter()
-        .map(|c| c.to_string())
-        .map(|col_name| {
-          table
-            .columns
-            .iter()
-            .position(|c| c.name == col_name)
-            .ok_or_else(|| Error::ColumnDoesntExist(col_name.to_owned()))
-        })
-        .collect::<crate::Result<Vec<usize>>>()?;
+      // Index on JSON path expression, i.e. `((data->>'kind'))`
+      let json_path = match columns.as_slice() {
+        [column] => get_json_path_expr(&column.expr),
+        _ => None,
+      };
+      let column_projection = match json_path {
+        Some((ref column, _)) => {
+          let column_idx = table
+            .find_column(column)
+            .ok_or_else(|| Error::ColumnDoesntExist(column.to_owned()))?;
+          if table.columns[column_idx].data_type != ArenaDataType::Jsonb {
+            bail!(invalid_query!("JSON path index must be on a JSONB column"));
+          }
+          vec![column_idx]
+        }
+        None => columns
+          .to_vec()
+          .iter()
+          .map(|c| c.to_string())
+          .map(|col_name| {
+            table
+              .find_column(&col_name)
+              .ok_or_else(|| Error::ColumnDoesntExist(col_name.to_owned()))
+          })
+          .collect::<crate::Result<Vec<usize>>>()?,
+      };
 
       let create_index = CreateIndex {
         name: name.as_ref().map(|n| n.to_string()),
@@ -102,6 +118,7 @@ pub fn extension(
         schema: table_ref.schema.as_ref().into(),
         table,
         columns: column_projection,
+        json_path: json_path.map(|(_, path)| path),
         unique: *unique,
         if_not_exists: *if_not_exists,
         using: using.as_ref().map(|using| using.value.clone()),
@@ -138,6 +155,8 @@ pub struct CreateIndex {
   pub table: Arc<Table>,
   /// Column projection on the table
   pub columns: Vec<usize>,
+  /// Path of the JSONB column if the index is on JSON path expression
+  pub json_path: Option<Vec<String>>,
   pub unique: bool,
   pub if_not_exists: bool,
   pub using: Option<String>,
@@ -182,6 +201,7 @@ impl CustomExecutionPlan for CreateIndexExecutionPlan {
         schema,
         table,
         columns,
+        json_path,
         unique,
         if_not_exists,
         using,
@@ -203,6 +223,9 @@ impl CustomExecutionPlan for CreateIndexExecutionPlan {
       }
 
       let index_provider = match using.as_ref().map(|s| s.as_str()) {
+        Some("hnsw") if json_path.is_some() => {
+          bail!(invalid_query!("hnsw index must be on a vector column"));
+        }
         Some("hnsw") => {
           let options = predicate
             .and_then(|p| match p {
@@ -225,14 +248,30 @@ impl CustomExecutionPlan for CreateIndexExecutionPlan {
             })
             .ok_or_else(|| invalid_query!("Invalid hnsw index params"))?;
 
+          let is_vector_column = match columns.as_slice() {
+            [column] => match table.columns[*column].data_type {
+              ArenaDataType::Vector { .. }
+              | ArenaDataType::QuantizedVector { .. } => true,
+              _ => false,
+            },
+            _ => false,
+          };
+          if !is_vector_column {
+            bail!(invalid_query!(
+              "hnsw index must have exactly one vector column"
+            ));
+          }
+
           let options = IndexOptions { options };
+          let m = options.get_required_number::<usize>("m")?;
+          if m < 2 {
+            bail!(invalid_query!("`m` param must be at least 2"));
+          }
           let namespace_column = options
             .get_string("namespace")?
             .map(|name| {
               table
-                .columns
-                .iter()
-                .position(|col| &col.name == name)
+                .find_column(name)
                 .ok_or_else(|| Error::ColumnDoesntExist(name.to_owned()))
             })
             .transpose()?;
@@ -242,16 +281,24 @@ impl CustomExecutionPlan for CreateIndexExecutionPlan {
               &options.get_required_string("metric")?,
             )
             .map_err(|_| invalid_query!("invalid `metric` param"))?,
-            m: options.get_required_number::<usize>("m")?,
+            m,
             ef_construction: options
               .get_required_number::<usize>("ef_construction")?,
             ef: options.get_required_number::<usize>("ef")?,
             dim: options.get_required_number::<usize>("dim")?,
-            retain_vectors: true,
+            retain_vectors: options.get_bool("retain_vectors")?.unwrap_or(true),
             namespace_column,
+            rerank: options.get_bool("rerank")?.unwrap_or(false),
           }
         }
-        _ => IndexProvider::BasicIndex { columns, unique },
+        _ => match json_path {
+          Some(path) => IndexProvider::JsonPathIndex {
+            columns,
+            path,
+            unique,
+          },
+          None => IndexProvider::BasicIndex { columns, unique },
+        },
       };
 
       let table_lock = transaction
@@ -287,11 +334,15 @@ fn backfill_index_data(
   let table_row_prefix = table_rows_prefix_key!(table.id);
   while let Some((row_key, row_bytes)) = rows_iter.get() {
     let row_id_bytes = &row_key[table_row_prefix.len()..];
-    let row = storage_handler
-      .serializer
-      .deserialize::<OwnedRow>(row_bytes)?;
+    let row =
+      storage_handler.deserialize_owned_row(table, row_id_bytes, row_bytes)?;
 
     storage_handler.add_row_to_index(table, &new_index, row_id_bytes, &row)?;
+    // Write the row again so that the vectors that are now stored in the
+    // index are removed from the row data
+    if !new_index.retains_vectors() {
+      storage_handler.insert_row(table, row_id_bytes, &row)?;
+    }
     rows_iter.next();
   }
   Ok(())
@@ -312,6 +363,16 @@ impl IndexOptions {
       .transpose()
   }
 
+  pub fn get_bool(&self, key: &str) -> Result<Option<bool>> {
+    self
+      .get(key)
+      .map(|value| match value {
+        Value::Boolean(b) => Ok(*b),
+        _ => Err(Error::InvalidQuery(format!("invalid param `{}`", key))),
+      })
+      .transpose()
+  }
+
   pub fn get_required_string(&self, key: &str) -> Result<&String> {
     match self.get_required(key)? {
       Value::SingleQuotedString(s) => Ok(s),
@@ -2,7 +2,6 @@ use std::any::Any;
 use std::fmt;
 use std::sync::Arc;
 
-use datafusion::arrow::array::UInt64Array;
 use datafusion::arrow::datatypes::{DataType, Field, Schema, SchemaRef};
 use datafusion::arrow::record_batch::RecordBatch;
 use datafusion::common::Statistics;
@@ -19,29 +18,39 @@ use derivative::Derivative;
 use derive_builder::Builder;
 use futures::{StreamExt, TryStreamExt};
 
+use super::foreign_keys::ForeignKeys;
+use super::returning;
+use super::table_exprs::ReturningExprs;
 use crate::execution::TransactionHandle;
 use crate::schema::{RowId, Table};
 use crate::utils::rowconverter;
-use crate::Error;
 
 #[derive(Builder, Derivative)]
 #[derivative(Debug)]
 pub struct DeleteRowsExecutionPlan {
+  schema_name: Arc<str>,
   table: Arc<Table>,
   pub(crate) scanner: Arc<dyn ExecutionPlan>,
   #[builder(setter(skip), default = "self.default_schema()")]
   schema: SchemaRef,
   #[derivative(Debug = "ignore")]
   transaction: TransactionHandle,
+  /// `RETURNING` clause of the query
+  #[builder(default)]
+  #[derivative(Debug = "ignore")]
+  returning: Option<Arc<ReturningExprs>>,
 }
 
 impl DeleteRowsExecutionPlanBuilder {
   fn default_schema(&self) -> SchemaRef {
-    Arc::new(Schema::new(vec![Field::new(
-      "count",
-      DataType::UInt64,
-      false,
-    )]))
+    match self.returning.clone().flatten() {
+      Some(returning) => returning.schema(),
+      None => Arc::new(Schema::new(vec![Field::new(
+        "count",
+        DataType::UInt64,
+        false,
+      )])),
+    }
   }
 }
 
@@ -69,48 +78,63 @@ impl ExecutionPlan for DeleteRowsExecutionPlan {
     let schema = self.schema();
     let table = self.table.clone();
 
+    let foreign_keys =
+      ForeignKeys::new(&self.transaction, &self.schema_name, &self.table);
     let transaction = self.transaction.clone();
     let table_scanner = self.scanner.clone();
+    let returning = self.returning.clone();
     let delete_fut = async move {
-      let stream = table_scanner.execute(partition, context)?;
-
-      let modified_rows_count: usize = stream
-        .map(move |maybe_batch| {
+      // Scan all the rows before modifying them so that the scan doesn't
+      // return the modified rows and its read lock is released
+      let batches = table_scanner
+        .execute(partition, context)?
+        .try_collect::<Vec<RecordBatch>>()
+        .await?;
+      transaction.wait_for_cancelled_scans().await;
+      let stream = futures::stream::iter(
+        batches.into_iter().map(Ok::<RecordBatch, DataFusionError>),
+      );
+
+      let returning_exprs = returning.clone();
+      let results = stream
+        .map(|maybe_batch| {
           let transaction = transaction.lock(true)?;
           maybe_batch.and_then(|batch| {
             let rows = rowconverter::convert_to_rows(&table, &batch, true)?;
             rows
               .iter()
               .map(|row| {
+                let row_id_bytes =
+                  RowId::serialize_u64(row[row.len() - 1].as_u64().unwrap());
                 for table_index in &table.indexes {
-                  transaction.delete_row_from_index(&table_index, row)?;
+                  transaction.delete_row_from_index(
+                    &table_index,
+                    &row_id_bytes,
+                    row,
+                  )?;
                 }
-                Ok(transaction.delete_row(
-                  &table,
-                  &RowId::serialize_u64(row[row.len() - 1].as_u64().unwrap()),
-                )?)
+                transaction.delete_row(&table, &row_id_bytes)?;
+                Ok(foreign_keys.on_delete(&transaction, &table, row)?)
               })
               .collect::<Result<Vec<()>>>()?;
-
-            Ok(batch.num_rows())
+            transaction
+              .update_table_row_count(&table, -(batch.num_rows() as i64))?;
+
+            let returned_rows = returning_exprs
+              .as_ref()
+              .map(|returning| returning.evaluate(&batch))
+              .transpose()?;
+            Ok((batch.num_rows(), returned_rows))
           })
         })
-        .try_collect::<Vec<usize>>()
-        .await?
-        .iter()
-        .sum();
-
-      Ok(
-        RecordBatch::try_new(
-          schema,
-          vec![Arc::new(UInt64Array::from(vec![
-            modified_rows_count as u64,
-          ]))],
-        )
-        .map_err(|e| {
-          Error::DataFusionError(DataFusionError::ArrowError(e).into())
-        })?,
-      )
+        .try_collect::<Vec<(usize, Option<RecordBatch>)>>()
+        .await?;
+      foreign_keys.check_no_action(&transaction.lock(true)?)?;
+
+      let modified_rows_count = results.iter().map(|(count, _)| count).sum();
+      let returned_rows = returning
+        .map(|_| results.into_iter().filter_map(|(_, rows)| rows).collect());
+      returning::create_dml_result(schema, modified_rows_count, returned_rows)
     };
 
     let stream = futures::stream::once(async move { delete_fut.await }).boxed();
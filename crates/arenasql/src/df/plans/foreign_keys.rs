@@ -0,0 +1,416 @@
+use std::sync::Arc;
+
+use parking_lot::Mutex;
+
+use crate::execution::TransactionHandle;
+use crate::schema::{
+  ForeignKey, OwnedRow, OwnedSerializedCell, ReferentialAction, RowTrait,
+  Table, TableIndex,
+};
+use crate::storage::StorageHandler;
+use crate::{Error, Result};
+
+/// Enforces the foreign key constraints of a table when its rows are
+/// inserted, updated or deleted
+pub(crate) struct ForeignKeys {
+  // Tables referenced by the table and the tables that reference the
+  // table directly or through the actions of the other referencing
+  // tables; this is empty if no foreign key involves the table so that
+  // the checks can be skipped
+  tables: Vec<Arc<Table>>,
+  // `NO ACTION` checks of the deleted or updated referenced rows; these
+  // are run at the end of the statement since the referencing rows might
+  // be deleted or updated later in the same statement
+  no_action_checks: Mutex<Vec<NoActionCheck>>,
+}
+
+struct NoActionCheck {
+  table: String,
+  // Unique index of the referenced columns
+  index: TableIndex,
+  referencing_table: Arc<Table>,
+  foreign_key: ForeignKey,
+  values: Vec<OwnedSerializedCell>,
+}
+
+impl ForeignKeys {
+  pub fn new(
+    transaction: &TransactionHandle,
+    schema: &str,
+    table: &Table,
+  ) -> Self {
+    let schema_tables: Vec<Arc<Table>> = transaction
+      .table_names(schema)
+      .iter()
+      .filter_map(|name| transaction.get_table(schema, name))
+      .collect();
+
+    let mut tables: Vec<Arc<Table>> = schema_tables
+      .iter()
+      .filter(|t| table.foreign_keys().any(|fk| fk.foreign_table == t.name))
+      .cloned()
+      .collect();
+    // The actions of the referencing tables can modify the tables that
+    // reference them; so, add those tables too
+    let mut referenced_tables = vec![table.name.clone()];
+    let mut visited = referenced_tables.clone();
+    while let Some(referenced_table) = referenced_tables.pop() {
+      for referencing_table in schema_tables.iter().filter(|t| {
+        t.foreign_keys()
+          .any(|fk| fk.foreign_table == referenced_table)
+      }) {
+        if !tables.iter().any(|t| t.name == referencing_table.name) {
+          tables.push(referencing_table.clone());
+        }
+        if !visited.contains(&referencing_table.name) {
+          visited.push(referencing_table.name.clone());
+          referenced_tables.push(referencing_table.name.clone());
+        }
+      }
+    }
+    Self {
+      tables,
+      no_action_checks: Mutex::new(vec![]),
+    }
+  }
+
+  /// Returns error if the rows referenced by the given row don't exist.
+  /// If the old row is given, only the foreign keys whose columns were
+  /// updated are checked.
+  ///
+  /// This should be called after the row is added to the indexes so
+  /// that rows can reference themselves
+  pub fn check_references(
+    &self,
+    storage: &StorageHandler,
+    table: &Table,
+    row: &OwnedRow,
+    old_row: Option<&OwnedRow>,
+  ) -> Result<()> {
+    if self.tables.is_empty() {
+      return Ok(());
+    }
+    for foreign_key in table.foreign_keys() {
+      let values = row.project(&foreign_key.columns);
+      // Note: references with NULL values aren't checked (MATCH SIMPLE)
+      if values.iter().any(|v| v.is_null()) {
+        continue;
+      }
+      if let Some(old_row) = old_row {
+        if old_row.project(&foreign_key.columns) == values {
+          continue;
+        }
+      }
+
+      let foreign_table = self.get_table(&foreign_key.foreign_table)?;
+      let index = find_index(foreign_table, foreign_key, true)?;
+      if storage.get_row_ids_from_index(index, &values)?.is_empty() {
+        return Err(Error::ForeignKeyConstraintViolated {
+          constraint: foreign_key.name.clone(),
+          table: table.name.clone(),
+          referencing_table: None,
+        });
+      }
+    }
+    Ok(())
+  }
+
+  /// Runs the `ON DELETE` actions of the foreign keys that reference
+  /// the deleted row. This should be called after the row is deleted.
+  /// The `NO ACTION` checks are deferred until [`Self::check_no_action`]
+  /// is called
+  pub fn on_delete(
+    &self,
+    storage: &StorageHandler,
+    table: &Table,
+    row: &OwnedRow,
+  ) -> Result<()> {
+    for (referencing_table, foreign_key) in self.referencing_foreign_keys(table)
+    {
+      let values = row.project(&foreign_key.referred_columns);
+      if values.iter().any(|v| v.is_null()) {
+        continue;
+      }
+      let referencing_rows = self.get_referencing_rows(
+        storage,
+        referencing_table,
+        foreign_key,
+        &values,
+      )?;
+      if referencing_rows.is_empty() {
+        continue;
+      }
+
+      match foreign_key.on_delete {
+        ReferentialAction::NoAction => self.add_no_action_check(
+          table,
+          referencing_table,
+          foreign_key,
+          values,
+        )?,
+        ReferentialAction::Restrict => {
+          return Err(Error::ForeignKeyConstraintViolated {
+            constraint: foreign_key.name.clone(),
+            table: table.name.clone(),
+            referencing_table: Some(referencing_table.name.clone()),
+          });
+        }
+        ReferentialAction::Cascade => {
+          for (row_id, referencing_row) in referencing_rows {
+            for table_index in &referencing_table.indexes {
+              storage.delete_row_from_index(
+                table_index,
+                &row_id,
+                &referencing_row,
+              )?;
+            }
+            storage.delete_row(referencing_table, &row_id)?;
+            self.on_delete(storage, referencing_table, &referencing_row)?;
+          }
+        }
+        ReferentialAction::SetNull => self.set_null(
+          storage,
+          referencing_table,
+          foreign_key,
+          referencing_rows,
+        )?,
+      }
+    }
+    Ok(())
+  }
+
+  /// Runs the `ON UPDATE` actions of the foreign keys that reference
+  /// the updated row. This should be called after the row is updated.
+  /// The `NO ACTION` checks are deferred until [`Self::check_no_action`]
+  /// is called
+  pub fn on_update(
+    &self,
+    storage: &StorageHandler,
+    table: &Table,
+    old_row: &OwnedRow,
+    new_row: &OwnedRow,
+  ) -> Result<()> {
+    for (referencing_table, foreign_key) in self.referencing_foreign_keys(table)
+    {
+      let old_values = old_row.project(&foreign_key.referred_columns);
+      let new_values = new_row.project(&foreign_key.referred_columns);
+      if old_values == new_values || old_values.iter().any(|v| v.is_null()) {
+        continue;
+      }
+      let referencing_rows = self.get_referencing_rows(
+        storage,
+        referencing_table,
+        foreign_key,
+        &old_values,
+      )?;
+      if referencing_rows.is_empty() {
+        continue;
+      }
+
+      match foreign_key.on_update {
+        ReferentialAction::NoAction => self.add_no_action_check(
+          table,
+          referencing_table,
+          foreign_key,
+          old_values,
+        )?,
+        ReferentialAction::Restrict => {
+          return Err(Error::ForeignKeyConstraintViolated {
+            constraint: foreign_key.name.clone(),
+            table: table.name.clone(),
+            referencing_table: Some(referencing_table.name.clone()),
+          });
+        }
+        ReferentialAction::Cascade => {
+          for (row_id, referencing_row) in referencing_rows {
+            let mut updated_row = referencing_row.clone();
+            foreign_key.columns.iter().zip(new_values.iter()).for_each(
+              |(col, value)| {
+                updated_row[*col] = (*value).clone();
+              },
+            );
+            self.update_row(
+              storage,
+              referencing_table,
+              &row_id,
+              &referencing_row,
+              &updated_row,
+            )?;
+          }
+        }
+        ReferentialAction::SetNull => self.set_null(
+          storage,
+          referencing_table,
+          foreign_key,
+          referencing_rows,
+        )?,
+      }
+    }
+    Ok(())
+  }
+
+  /// Returns error if any row references the rows that were deleted or
+  /// updated with `NO ACTION`. This should be called after all the rows
+  /// of the statement are modified so that the rows referencing each
+  /// other can be modified by the same statement
+  pub fn check_no_action(&self, storage: &StorageHandler) -> Result<()> {
+    let checks = std::mem::take(&mut *self.no_action_checks.lock());
+    for check in checks {
+      let values = check.values.iter().collect::<Vec<&OwnedSerializedCell>>();
+      // Another row might have the referenced values now
+      if !storage
+        .get_row_ids_from_index(&check.index, &values)?
+        .is_empty()
+      {
+        continue;
+      }
+      let referencing_rows = self.get_referencing_rows(
+        storage,
+        &check.referencing_table,
+        &check.foreign_key,
+        &values,
+      )?;
+      if !referencing_rows.is_empty() {
+        return Err(Error::ForeignKeyConstraintViolated {
+          constraint: check.foreign_key.name.clone(),
+          table: check.table,
+          referencing_table: Some(check.referencing_table.name.clone()),
+        });
+      }
+    }
+    Ok(())
+  }
+
+  fn add_no_action_check(
+    &self,
+    table: &Table,
+    referencing_table: &Arc<Table>,
+    foreign_key: &ForeignKey,
+    values: Vec<&OwnedSerializedCell>,
+  ) -> Result<()> {
+    let index = find_index(table, foreign_key, true)?;
+    self.no_action_checks.lock().push(NoActionCheck {
+      table: table.name.clone(),
+      index: index.clone(),
+      referencing_table: referencing_table.clone(),
+      foreign_key: foreign_key.clone(),
+      values: values.into_iter().cloned().collect(),
+    });
+    Ok(())
+  }
+
+  fn set_null(
+    &self,
+    storage: &StorageHandler,
+    table: &Table,
+    foreign_key: &ForeignKey,
+    rows: Vec<(Vec<u8>, OwnedRow)>,
+  ) -> Result<()> {
+    if let Some(col) = foreign_key
+      .columns
+      .iter()
+      .find(|col| !table.columns[**col].nullable())
+    {
+      return Err(Error::NullConstraintViolated {
+        table: table.name.clone(),
+        column: table.columns[*col].name.clone(),
+      });
+    }
+
+    for (row_id, row) in rows {
+      let mut updated_row = row.clone();
+      foreign_key.columns.iter().for_each(|col| {
+        updated_row[*col] = OwnedSerializedCell::Null;
+      });
+      self.update_row(storage, table, &row_id, &row, &updated_row)?;
+    }
+    Ok(())
+  }
+
+  fn update_row(
+    &self,
+    storage: &StorageHandler,
+    table: &Table,
+    row_id: &[u8],
+    old_row: &OwnedRow,
+    new_row: &OwnedRow,
+  ) -> Result<()> {
+    for table_index in &table.indexes {
+      storage.delete_row_from_index(table_index, row_id, old_row)?;
+      storage.add_row_to_index(table, table_index, row_id, new_row)?;
+    }
+    storage.delete_row(table, row_id)?;
+    storage.insert_row(table, row_id, new_row)?;
+    self.on_update(storage, table, old_row, new_row)
+  }
+
+  /// Returns the (row id, row) of the rows of the referencing table
+  /// that reference the given values
+  fn get_referencing_rows(
+    &self,
+    storage: &StorageHandler,
+    referencing_table: &Table,
+    foreign_key: &ForeignKey,
+    values: &[&OwnedSerializedCell],
+  ) -> Result<Vec<(Vec<u8>, OwnedRow)>> {
+    let index = find_index(referencing_table, foreign_key, false)?;
+    storage
+      .get_row_ids_from_index(index, values)?
+      .into_iter()
+      .filter_map(|row_id| {
+        storage
+          .get_row(referencing_table, &row_id)
+          .transpose()
+          .map(|row| row.map(|row| (row_id, row)))
+      })
+      .collect()
+  }
+
+  fn referencing_foreign_keys<'a>(
+    &'a self,
+    table: &'a Table,
+  ) -> impl Iterator<Item = (&'a Arc<Table>, &'a ForeignKey)> {
+    self.tables.iter().flat_map(move |referencing_table| {
+      referencing_table
+        .foreign_keys()
+        .filter(move |foreign_key| foreign_key.foreign_table == table.name)
+        .map(move |foreign_key| (referencing_table, foreign_key))
+    })
+  }
+
+  fn get_table(&self, name: &str) -> Result<&Arc<Table>> {
+    self
+      .tables
+      .iter()
+      .find(|table| table.name == name)
+      .ok_or_else(|| Error::RelationDoesntExist(name.to_owned()))
+  }
+}
+
+/// Returns the index of the referenced columns if `referenced` is true,
+/// else returns the index of the referencing columns
+fn find_index<'a>(
+  table: &'a Table,
+  foreign_key: &ForeignKey,
+  referenced: bool,
+) -> Result<&'a TableIndex> {
+  let columns = match referenced {
+    true => &foreign_key.referred_columns,
+    false => &foreign_key.columns,
+  };
+  table
+    .indexes
+    .iter()
+    .find(|index| {
+      !index.is_vector_index()
+        && index.json_path().is_none()
+        && index.columns() == columns
+        && (!referenced || index.is_unique())
+    })
+    .ok_or_else(|| {
+      Error::InternalError(format!(
+        "Index not found for foreign key \"{}\"",
+        foreign_key.name
+      ))
+    })
+}
@@ -0,0 +1,150 @@
+use std::sync::Arc;
+
+use datafusion::common::tree_node::{Transformed, TreeNode};
+use datafusion::error::Result;
+use datafusion::physical_expr::expressions::Column;
+use datafusion::physical_expr::split_conjunction;
+use datafusion::physical_plan::coalesce_batches::CoalesceBatchesExec;
+use datafusion::physical_plan::coalesce_partitions::CoalescePartitionsExec;
+use datafusion::physical_plan::filter::FilterExec;
+use datafusion::physical_plan::projection::ProjectionExec;
+use datafusion::physical_plan::repartition::RepartitionExec;
+use datafusion::physical_plan::sorts::sort::SortExec;
+use datafusion::physical_plan::ExecutionPlan;
+
+use super::scan_table::{OrderedIndexScan, TableScaner};
+use crate::execution::filter::IndexKeyRange;
+use crate::execution::iterators::IndexOrder;
+
+/// Rewrites the physical plan of `ORDER BY column LIMIT n` queries such
+/// that the first n rows are scanned in the order of the index of the
+/// column instead of sorting all the rows of the table.
+///
+/// The index can be used if the column is the first index column or if
+/// all the index columns before it are selected by `=` or `IN` filters.
+/// All the filters of the query must be applied by scanning the index,
+/// otherwise the first n rows in the index might not match the filters.
+pub fn use_index_order(
+  plan: Arc<dyn ExecutionPlan>,
+) -> Result<Arc<dyn ExecutionPlan>> {
+  plan.transform_down(&|plan| match with_index_order(&plan)? {
+    Some(new_plan) => Ok(Transformed::Yes(new_plan)),
+    None => Ok(Transformed::No(plan)),
+  })
+}
+
+fn with_index_order(
+  plan: &Arc<dyn ExecutionPlan>,
+) -> Result<Option<Arc<dyn ExecutionPlan>>> {
+  let Some(sort) = plan.as_any().downcast_ref::<SortExec>() else {
+    return Ok(None);
+  };
+  let (Some(limit), [sort_expr]) = (sort.fetch(), sort.expr()) else {
+    return Ok(None);
+  };
+  let Some(column) = sort_expr.expr.as_any().downcast_ref::<Column>() else {
+    return Ok(None);
+  };
+
+  // The sort isn't needed since the rows are scanned in order
+  push_down_index_order(
+    sort.input(),
+    column.index(),
+    &IndexOrder {
+      descending: sort_expr.options.descending,
+      nulls_first: sort_expr.options.nulls_first,
+      limit,
+    },
+    0,
+  )
+}
+
+/// Finds the table scanner that provides the column and sets the ordered
+/// index scan in it. Returns `None` if the table scanner can't be reached
+/// without changing the rows or if there's no index that can be scanned
+/// in the order of the column.
+///
+/// `filter_count` is the number of filter predicates applied on top of
+/// the plan so far.
+fn push_down_index_order(
+  plan: &Arc<dyn ExecutionPlan>,
+  column_index: usize,
+  order: &IndexOrder,
+  filter_count: usize,
+) -> Result<Option<Arc<dyn ExecutionPlan>>> {
+  let plan_any = plan.as_any();
+  if let Some(scanner) = plan_any.downcast_ref::<TableScaner>() {
+    // Since the filter pushdown is inexact, the filters of the scanner are
+    // also applied by the filter plans above it. So, if there are more
+    // filters above than in the scanner, some of those filters can't be
+    // pushed down and the first rows of the index might not match them
+    let filters = scanner.filters();
+    if filters.len() != filter_count || scanner.vector_search().is_some() {
+      return Ok(None);
+    }
+    let schema = plan.schema();
+    let column_name = schema.field(column_index).name();
+    let table = scanner.table();
+    let Some(column) = table.find_column(column_name) else {
+      return Ok(None);
+    };
+
+    return Ok(
+      table
+        .indexes
+        .iter()
+        .filter(|index| !index.is_vector_index() && index.json_path().is_none())
+        .find(|index| {
+          let key_range = IndexKeyRange::new(index, filters);
+          key_range.applied_filters == filters.len()
+            && index.columns().get(key_range.prefix_len) == Some(&column)
+        })
+        .map(|index| {
+          Arc::new(scanner.with_ordered_index_scan(OrderedIndexScan {
+            index: index.clone(),
+            order: order.clone(),
+          })) as Arc<dyn ExecutionPlan>
+        }),
+    );
+  }
+
+  let mut filter_count = filter_count;
+  let column_index =
+    if let Some(projection) = plan_any.downcast_ref::<ProjectionExec>() {
+      match projection.expr()[column_index]
+        .0
+        .as_any()
+        .downcast_ref::<Column>()
+      {
+        Some(column) => column.index(),
+        None => return Ok(None),
+      }
+    } else if let Some(filter) = plan_any.downcast_ref::<FilterExec>() {
+      // Filter doesn't change the schema
+      filter_count += split_conjunction(filter.predicate()).len();
+      column_index
+    } else if plan_any.is::<CoalesceBatchesExec>() {
+      column_index
+    } else if plan_any.is::<CoalescePartitionsExec>()
+      || plan_any.is::<RepartitionExec>()
+    {
+      // The scanner returns the ordered rows in a single partition, so
+      // repartitioning them would change the order
+      let children = plan.children();
+      let [input] = children.as_slice() else {
+        return Ok(None);
+      };
+      return push_down_index_order(input, column_index, order, filter_count);
+    } else {
+      return Ok(None);
+    };
+
+  let children = plan.children();
+  let [input] = children.as_slice() else {
+    return Ok(None);
+  };
+  match push_down_index_order(input, column_index, order, filter_count)? {
+    Some(input) => Ok(Some(plan.clone().with_new_children(vec![input])?)),
+    None => Ok(None),
+  }
+}
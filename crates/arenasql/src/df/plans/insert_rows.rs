@@ -1,33 +1,56 @@
 use std::any::Any;
+use std::collections::HashSet;
 use std::fmt::Formatter;
 use std::sync::Arc;
 
 use async_trait::async_trait;
-use datafusion::arrow::datatypes::SchemaRef;
+use datafusion::arrow::compute::concat_batches;
+use datafusion::arrow::datatypes::{Schema, SchemaRef};
+use datafusion::arrow::record_batch::RecordBatch;
+use datafusion::common::Statistics;
 use datafusion::error::Result;
 use datafusion::execution::context::SessionState;
 use datafusion::execution::TaskContext;
+use datafusion::physical_expr::PhysicalSortExpr;
 use datafusion::physical_plan::insert::DataSink;
 use datafusion::physical_plan::metrics::MetricsSet;
-use datafusion::physical_plan::{DisplayAs, DisplayFormatType};
+use datafusion::physical_plan::stream::RecordBatchStreamAdapter;
+use datafusion::physical_plan::{
+  DisplayAs, DisplayFormatType, ExecutionPlan, Partitioning,
+  SendableRecordBatchStream,
+};
 use derivative::Derivative;
-use futures::StreamExt;
-use sqlparser::ast::{Ident, Statement as SQLStatement};
+use futures::{StreamExt, TryStreamExt};
+use sqlparser::ast::{
+  ConflictTarget, Ident, OnConflict, OnConflictAction, OnInsert,
+  Statement as SQLStatement,
+};
 
+use super::foreign_keys::ForeignKeys;
+use super::returning;
+use super::table_exprs::{ConflictUpdateExprs, ReturningExprs, TableExprs};
 use crate::datafusion::RecordBatchStream;
 use crate::df::providers::table::TableProvider;
 use crate::df::providers::{get_schema_provider, get_table_ref};
 use crate::execution::TransactionHandle;
-use crate::schema::Table;
+use crate::schema::{DataFrame, OwnedRow, RowId, RowTrait, Table, TableIndex};
+use crate::storage::{KeyValueGroup, StorageHandler};
 use crate::utils::rowconverter;
+use crate::{table_row_key, Error};
 
 #[derive(Derivative, Clone)]
 #[derivative(Debug)]
 pub struct Sink {
+  pub schema_name: Arc<str>,
   pub table: Arc<Table>,
   pub schema: SchemaRef,
   #[derivative(Debug = "ignore")]
   pub transaction: TransactionHandle,
+  /// `ON CONFLICT` clause of the insert query
+  pub on_conflict: Option<OnConflict>,
+  /// `RETURNING` clause of the insert query
+  #[derivative(Debug = "ignore")]
+  pub returning: Option<Arc<ReturningExprs>>,
 }
 
 impl DisplayAs for Sink {
@@ -56,22 +79,110 @@ impl DataSink for Sink {
 
   async fn write_all(
     &self,
-    mut data: RecordBatchStream,
-    _context: &Arc<TaskContext>,
+    data: RecordBatchStream,
+    context: &Arc<TaskContext>,
   ) -> Result<u64> {
+    self
+      .insert_rows(data, context)
+      .await
+      .map(|(modified_rows_count, _)| modified_rows_count)
+  }
+}
+
+impl Sink {
+  /// Inserts the rows and returns the number of modified rows and the
+  /// rows of the `RETURNING` clause if the query has it
+  async fn insert_rows(
+    &self,
+    data: RecordBatchStream,
+    context: &Arc<TaskContext>,
+  ) -> Result<(u64, Option<Vec<RecordBatch>>)> {
     // TODO: acquire read lock on the table schema so that
     // the table schema can't be updated by any other transaction
     // when this write is happening
     let mut modified_rows_count = 0;
+    let mut returned_rows = self.returning.as_ref().map(|_| vec![]);
 
-    if let Some(batch) = data.next().await {
-      let batch = batch?;
-      let row_count = batch.num_rows();
-      modified_rows_count += row_count;
+    // Read all the rows before inserting them so that the rows selected
+    // from the same table don't include the inserted rows
+    let batches = data.try_collect::<Vec<RecordBatch>>().await?;
+    // The scans of the query might be cancelled by a limit
+    self.transaction.wait_for_cancelled_scans().await;
+    if let Some(first_batch) = batches.first() {
+      let batch = concat_batches(&first_batch.schema(), &batches)?;
+      if let Some((_, col)) = self.table.active_columns().find(|(_, col)| {
+        col.generated_always() && batch.column_by_name(&col.name).is_some()
+      }) {
+        return Err(
+          Error::InvalidQuery(format!(
+            r#"cannot insert a non-DEFAULT value into column "{}""#,
+            col.name
+          ))
+          .into(),
+        );
+      }
+      let table_exprs = TableExprs::new(&self.table, context)?;
+      let conflict_handler = self
+        .on_conflict
+        .as_ref()
+        .map(|on_conflict| {
+          ConflictHandler::new(&self.table, context, on_conflict)
+        })
+        .transpose()?;
+      let batch = table_exprs.fill_defaults(&batch)?;
+      table_exprs.check(&batch)?;
 
       let rows = rowconverter::convert_to_rows(&self.table, &batch, false)?;
+      let foreign_keys =
+        ForeignKeys::new(&self.transaction, &self.schema_name, &self.table);
       let storage_handler = self.transaction.lock(true)?;
-      for row in rows.iter() {
+      // Ids of the rows inserted or updated by this query
+      let mut modified_row_ids = HashSet::new();
+      // Inserted or updated rows; only used for `RETURNING` clause
+      let mut modified_rows = vec![];
+      let mut inserted_rows_count = 0;
+      // References are checked after all the rows are written so that the
+      // rows can reference the rows that come later in the same query.
+      // These are the inserted rows and the (old, new) updated rows
+      let mut inserted_rows = vec![];
+      let mut updated_rows = vec![];
+      for (idx, row) in rows.iter().enumerate() {
+        if let Some(handler) = &conflict_handler {
+          if let Some(row_id) =
+            handler.find_conflicting_row(&storage_handler, row)?
+          {
+            if let Some(update) = &handler.update {
+              if !modified_row_ids.insert(row_id.clone()) {
+                return Err(
+                  Error::InvalidQuery(
+                    "ON CONFLICT DO UPDATE command cannot affect row a \
+                     second time"
+                      .to_owned(),
+                  )
+                  .into(),
+                );
+              }
+              if let Some((updated_batch, old_row, new_row)) = self
+                .update_conflicting_row(
+                  &storage_handler,
+                  &foreign_keys,
+                  &table_exprs,
+                  update,
+                  &row_id,
+                  &batch.slice(idx, 1),
+                )?
+              {
+                if returned_rows.is_some() {
+                  modified_rows.push(updated_batch);
+                }
+                updated_rows.push((old_row, new_row));
+                modified_rows_count += 1;
+              }
+            }
+            continue;
+          }
+        }
+
         let row_id = storage_handler.generate_next_row_id(&self.table)?;
         for table_index in &self.table.indexes {
           storage_handler.add_row_to_index(
@@ -82,9 +193,339 @@ impl DataSink for Sink {
           )?;
         }
         storage_handler.insert_row(&self.table, &row_id, &row)?;
+        inserted_rows.push(row);
+        modified_row_ids.insert(row_id);
+        if returned_rows.is_some() {
+          modified_rows.push(batch.slice(idx, 1));
+        }
+        modified_rows_count += 1;
+        inserted_rows_count += 1;
+      }
+      for row in inserted_rows {
+        foreign_keys.check_references(
+          &storage_handler,
+          &self.table,
+          row,
+          None,
+        )?;
+      }
+      for (old_row, new_row) in &updated_rows {
+        foreign_keys.check_references(
+          &storage_handler,
+          &self.table,
+          new_row,
+          Some(old_row),
+        )?;
+      }
+      foreign_keys.check_no_action(&storage_handler)?;
+      storage_handler
+        .update_table_row_count(&self.table, inserted_rows_count)?;
+
+      if let (Some(returning), Some(returned_rows)) =
+        (&self.returning, returned_rows.as_mut())
+      {
+        let modified_rows = concat_batches(&batch.schema(), &modified_rows)?;
+        returned_rows.push(returning.evaluate(&modified_rows)?);
+      }
+    }
+    Ok((modified_rows_count as u64, returned_rows))
+  }
+
+  /// Updates the existing row that conflicts with the row proposed for
+  /// insertion (`excluded`) and returns the updated row and the old and
+  /// new rows. Returns None if the row wasn't updated. The references of
+  /// the new row aren't checked
+  fn update_conflicting_row(
+    &self,
+    storage_handler: &StorageHandler,
+    foreign_keys: &ForeignKeys,
+    table_exprs: &TableExprs,
+    update: &ConflictUpdateExprs,
+    row_id: &[u8],
+    excluded: &RecordBatch,
+  ) -> crate::Result<Option<(RecordBatch, OwnedRow, OwnedRow)>> {
+    let table = &self.table;
+    let row_bytes = storage_handler
+      .kv
+      .get(KeyValueGroup::Rows, &table_row_key!(table.id, row_id))?
+      .ok_or_else(|| {
+        Error::IOError(format!(
+          "Couldn't find row data for rowid: {:?}",
+          RowId::deserialize(row_id)
+        ))
+      })?;
+    let old_row = storage_handler.deserialize_row(table, row_id, &row_bytes)?;
+
+    let active_columns = table.active_columns().collect::<Vec<_>>();
+    let mut existing = DataFrame::with_capacity(
+      1,
+      active_columns
+        .iter()
+        .map(|(_, col)| (col.name.clone(), col.data_type.clone()))
+        .collect(),
+    );
+    existing.append_row(
+      row_id,
+      &old_row.project(
+        &active_columns
+          .iter()
+          .map(|(idx, _)| *idx)
+          .collect::<Vec<usize>>(),
+      ),
+    );
+
+    let Some(batch) = update.evaluate(existing, excluded)? else {
+      return Ok(None);
+    };
+    table_exprs.check(&batch)?;
+    let new_row = rowconverter::convert_to_rows(table, &batch, false)?
+      .pop()
+      .unwrap();
+    let old_row =
+      storage_handler.deserialize_owned_row(table, row_id, &row_bytes)?;
+
+    for table_index in &table.indexes {
+      storage_handler.delete_row_from_index(table_index, row_id, &old_row)?;
+      storage_handler.add_row_to_index(table, table_index, row_id, &new_row)?;
+    }
+    storage_handler.delete_row(table, row_id)?;
+    storage_handler.insert_row(table, row_id, &new_row)?;
+    foreign_keys.on_update(storage_handler, table, &old_row, &new_row)?;
+    Ok(Some((batch, old_row, new_row)))
+  }
+}
+
+/// Execution plan of the insert query with `RETURNING` clause. Datafusion's
+/// [`FileSinkExec`] only returns the number of inserted rows, so this is
+/// used to return the inserted rows instead
+///
+/// [`FileSinkExec`]: datafusion::physical_plan::insert::FileSinkExec
+#[derive(Derivative)]
+#[derivative(Debug)]
+pub struct InsertRowsExecutionPlan {
+  input: Arc<dyn ExecutionPlan>,
+  sink: Sink,
+  schema: SchemaRef,
+}
+
+impl InsertRowsExecutionPlan {
+  pub fn new(input: Arc<dyn ExecutionPlan>, sink: Sink) -> Self {
+    let schema = sink.returning.as_ref().unwrap().schema();
+    Self {
+      input,
+      sink,
+      schema,
+    }
+  }
+}
+
+impl DisplayAs for InsertRowsExecutionPlan {
+  fn fmt_as(
+    &self,
+    _t: DisplayFormatType,
+    f: &mut Formatter,
+  ) -> std::fmt::Result {
+    write!(f, "{:?}", self)
+  }
+}
+
+impl ExecutionPlan for InsertRowsExecutionPlan {
+  fn as_any(&self) -> &dyn Any {
+    self
+  }
+
+  fn execute(
+    &self,
+    partition: usize,
+    context: Arc<TaskContext>,
+  ) -> Result<SendableRecordBatchStream> {
+    let schema = self.schema();
+    let input = self.input.clone();
+    let sink = self.sink.clone();
+    let insert_fut = async move {
+      let data = input.execute(partition, context.clone())?;
+      let (modified_rows_count, returned_rows) =
+        sink.insert_rows(data, &context).await?;
+      returning::create_dml_result(
+        schema,
+        modified_rows_count as usize,
+        returned_rows,
+      )
+    };
+
+    let stream = futures::stream::once(async move { insert_fut.await }).boxed();
+    Ok(Box::pin(RecordBatchStreamAdapter::new(
+      self.schema(),
+      stream,
+    )))
+  }
+
+  fn schema(&self) -> SchemaRef {
+    self.schema.clone()
+  }
+
+  fn with_new_children(
+    self: Arc<Self>,
+    _children: Vec<Arc<dyn ExecutionPlan>>,
+  ) -> Result<Arc<dyn ExecutionPlan>> {
+    unimplemented!()
+  }
+
+  fn children(&self) -> Vec<Arc<dyn ExecutionPlan>> {
+    vec![self.input.clone()]
+  }
+
+  fn output_ordering(&self) -> Option<&[PhysicalSortExpr]> {
+    None
+  }
+
+  fn output_partitioning(&self) -> Partitioning {
+    Partitioning::UnknownPartitioning(1)
+  }
+
+  fn metrics(&self) -> Option<MetricsSet> {
+    None
+  }
+
+  fn statistics(&self) -> Result<Statistics> {
+    Ok(Statistics::new_unknown(&Schema::empty()))
+  }
+}
+
+/// `ON CONFLICT` clause of the insert query resolved against the table
+struct ConflictHandler {
+  // Unique indexes used to check whether the row conflicts with
+  // the existing rows
+  arbiter_indexes: Vec<TableIndex>,
+  // None if the conflict action is `DO NOTHING`
+  update: Option<ConflictUpdateExprs>,
+}
+
+impl ConflictHandler {
+  fn new(
+    table: &Arc<Table>,
+    context: &TaskContext,
+    on_conflict: &OnConflict,
+  ) -> crate::Result<Self> {
+    let arbiter_indexes = match &on_conflict.conflict_target {
+      Some(ConflictTarget::Columns(columns)) => {
+        let mut columns = columns
+          .iter()
+          .map(|col| {
+            table
+              .find_column(&col.value)
+              .ok_or_else(|| Error::ColumnDoesntExist(col.value.clone()))
+          })
+          .collect::<crate::Result<Vec<usize>>>()?;
+        columns.sort();
+        table
+          .indexes
+          .iter()
+          .filter(|index| {
+            let mut index_columns = index.columns().clone();
+            index_columns.sort();
+            index.is_unique()
+              && index.json_path().is_none()
+              && index_columns == columns
+          })
+          .cloned()
+          .collect::<Vec<TableIndex>>()
       }
+      Some(ConflictTarget::OnConstraint(name)) => {
+        let name = name.to_string();
+        let index = table
+          .indexes
+          .iter()
+          .find(|index| index.name == name && index.is_unique())
+          .ok_or_else(|| {
+            Error::InvalidQuery(format!(
+              r#"constraint "{}" for table "{}" does not exist"#,
+              name, table.name
+            ))
+          })?;
+        vec![index.clone()]
+      }
+      None => match &on_conflict.action {
+        OnConflictAction::DoNothing => table
+          .indexes
+          .iter()
+          .filter(|index| index.is_unique())
+          .cloned()
+          .collect(),
+        OnConflictAction::DoUpdate(_) => {
+          return Err(Error::InvalidQuery(
+            "ON CONFLICT DO UPDATE requires inference specification or \
+             constraint name"
+              .to_owned(),
+          ));
+        }
+      },
+    };
+
+    if on_conflict.conflict_target.is_some() && arbiter_indexes.is_empty() {
+      return Err(Error::InvalidQuery(
+        "there is no unique or exclusion constraint matching the \
+         ON CONFLICT specification"
+          .to_owned(),
+      ));
+    }
+
+    let update = match &on_conflict.action {
+      OnConflictAction::DoNothing => None,
+      OnConflictAction::DoUpdate(update) => Some(ConflictUpdateExprs::new(
+        table,
+        context,
+        &update.assignments,
+        update.selection.as_ref(),
+      )?),
+    };
+
+    Ok(Self {
+      arbiter_indexes,
+      update,
+    })
+  }
+
+  /// Returns the id of the existing row that conflicts with the given row
+  /// in any of the arbiter indexes
+  fn find_conflicting_row(
+    &self,
+    storage_handler: &StorageHandler,
+    row: &OwnedRow,
+  ) -> crate::Result<Option<Vec<u8>>> {
+    for index in &self.arbiter_indexes {
+      if let Some(row_id) =
+        storage_handler.get_conflicting_row_id(index, row)?
+      {
+        return Ok(Some(row_id));
+      }
+    }
+    Ok(None)
+  }
+}
+
+/// Returns the `ON CONFLICT` clause of the insert query
+pub fn get_on_conflict_clause(stmt: &SQLStatement) -> Option<&OnConflict> {
+  match stmt {
+    SQLStatement::Insert {
+      on: Some(OnInsert::OnConflict(on_conflict)),
+      ..
+    } => Some(on_conflict),
+    _ => None,
+  }
+}
+
+/// Removes the `ON CONFLICT` clause from the insert query since
+/// Datafusion doesn't support it. The clause is passed to the [`Sink`]
+/// using the active statement of the transaction instead
+pub fn remove_on_conflict_clause(stmt: &mut SQLStatement) {
+  match stmt {
+    SQLStatement::Insert { ref mut on, .. }
+      if matches!(on, Some(OnInsert::OnConflict(_))) =>
+    {
+      *on = None;
     }
-    Ok(modified_rows_count as u64)
+    _ => {}
   }
 }
 
@@ -117,9 +558,8 @@ pub async fn set_explicit_columns_in_insert_query(
             .table();
 
           *columns = table
-            .columns
-            .iter()
-            .map(|col| Ident::new(col.name.clone()))
+            .active_columns()
+            .map(|(_, col)| Ident::new(col.name.clone()))
             .collect::<Vec<Ident>>();
         }
       }
@@ -1,8 +1,17 @@
 pub(crate) mod advisory_lock;
 pub(crate) mod alter_table;
+pub(crate) mod analyze;
 pub(crate) mod create_index;
 pub(crate) mod delete_rows;
+pub(crate) mod foreign_keys;
+pub(crate) mod index_order;
 pub(crate) mod insert_rows;
+pub(crate) mod returning;
+pub(crate) mod savepoint;
 pub(crate) mod scan_table;
+pub(crate) mod sequence;
 pub(crate) mod set_parameter;
+pub(crate) mod table_exprs;
+pub(crate) mod unnest;
 pub(crate) mod update_rows;
+pub(crate) mod vector_search;
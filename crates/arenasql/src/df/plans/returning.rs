@@ -0,0 +1,115 @@
+use std::sync::Arc;
+
+use datafusion::arrow::array::UInt64Array;
+use datafusion::arrow::compute::concat_batches;
+use datafusion::arrow::datatypes::SchemaRef;
+use datafusion::arrow::record_batch::RecordBatch;
+use datafusion::common::DFSchema;
+use datafusion::error::{DataFusionError, Result};
+use datafusion::execution::context::SessionState;
+use datafusion::logical_expr::{DmlStatement, LogicalPlan};
+use sqlparser::ast::{SelectItem, Statement as SQLStatement};
+
+use super::table_exprs::ReturningExprs;
+use crate::df::providers::table::TableProvider;
+use crate::df::providers::{get_schema_provider, get_table_ref};
+use crate::execution::TransactionHandle;
+use crate::schema::Table;
+use crate::Error;
+
+/// Returns the `RETURNING` clause of INSERT, UPDATE and DELETE query
+pub fn get_returning_clause(stmt: &SQLStatement) -> Option<&Vec<SelectItem>> {
+  match stmt {
+    SQLStatement::Insert { returning, .. }
+    | SQLStatement::Update { returning, .. }
+    | SQLStatement::Delete { returning, .. } => returning.as_ref(),
+    _ => None,
+  }
+}
+
+/// Removes the `RETURNING` clause from the query since Datafusion doesn't
+/// support it and returns the removed clause. The clause is passed to
+/// the execution plans using the active statement of the transaction
+pub fn remove_returning_clause(
+  stmt: &mut SQLStatement,
+) -> Option<Vec<SelectItem>> {
+  match stmt {
+    SQLStatement::Insert { returning, .. }
+    | SQLStatement::Update { returning, .. }
+    | SQLStatement::Delete { returning, .. } => returning.take(),
+    _ => None,
+  }
+}
+
+/// Returns the DML plan with the schema of the rows returned by the
+/// `RETURNING` clause instead of the schema of the table so that the
+/// returned rows can be described before the query is executed
+pub async fn set_returning_schema(
+  state: &SessionState,
+  plan: LogicalPlan,
+  items: &Vec<SelectItem>,
+) -> Result<LogicalPlan> {
+  match plan {
+    LogicalPlan::Dml(dml) => {
+      let table_name = dml.table_name.to_string();
+      let table_ref = get_table_ref(&state, &table_name);
+      let schema_provider = get_schema_provider(state, &table_ref)?;
+      let table_name = table_ref.table.as_ref().to_owned();
+      let table_provider = schema_provider
+        .table(&table_name)
+        .await
+        .ok_or_else(|| Error::RelationDoesntExist(table_name))?;
+      let table = table_provider
+        .as_any()
+        .downcast_ref::<TableProvider>()
+        .unwrap()
+        .table();
+
+      let returning = ReturningExprs::new(table, &state.task_ctx(), items)?;
+      Ok(LogicalPlan::Dml(DmlStatement {
+        table_schema: Arc::new(DFSchema::try_from(
+          returning.schema().as_ref().clone(),
+        )?),
+        ..dml
+      }))
+    }
+    plan => Ok(plan),
+  }
+}
+
+/// Returns the expressions of the `RETURNING` clause of the active
+/// statement of the transaction
+pub(crate) fn get_returning_exprs(
+  state: &SessionState,
+  table: &Arc<Table>,
+  transaction: &TransactionHandle,
+) -> Result<Option<Arc<ReturningExprs>>> {
+  Ok(
+    transaction
+      .active_statement()
+      .as_ref()
+      .and_then(|stmt| get_returning_clause(stmt))
+      .map(|items| ReturningExprs::new(table, &state.task_ctx(), items))
+      .transpose()?
+      .map(Arc::new),
+  )
+}
+
+/// Returns the result of the DML query; the returned rows if the query
+/// has `RETURNING` clause, otherwise the number of modified rows
+pub(crate) fn create_dml_result(
+  schema: SchemaRef,
+  modified_rows_count: usize,
+  returned_rows: Option<Vec<RecordBatch>>,
+) -> Result<RecordBatch> {
+  match returned_rows {
+    Some(rows) => concat_batches(&schema, &rows),
+    None => RecordBatch::try_new(
+      schema,
+      vec![Arc::new(UInt64Array::from(vec![
+        modified_rows_count as u64,
+      ]))],
+    ),
+  }
+  .map_err(|e| DataFusionError::ArrowError(e))
+}
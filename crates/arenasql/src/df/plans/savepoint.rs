@@ -0,0 +1,76 @@
+use std::pin::Pin;
+use std::sync::Arc;
+
+use datafusion::arrow::datatypes::{Field, Schema, SchemaRef};
+use datafusion::execution::TaskContext;
+use datafusion::logical_expr::{Expr, LogicalPlan};
+use futures::{FutureExt, Stream};
+use sqlparser::ast::Statement as SQLStatement;
+
+use crate::ast::statement::{get_savepoint_name, StatementType};
+use crate::execution::{CustomExecutionPlan, Transaction};
+use crate::schema::DataFrame;
+use crate::Result;
+
+#[tracing::instrument(skip_all, fields(name = "savepoint"), level = "trace")]
+pub fn extension(
+  transaction: &Transaction,
+  stmt: &SQLStatement,
+) -> Result<Option<Arc<dyn CustomExecutionPlan>>> {
+  let Some(name) = get_savepoint_name(stmt) else {
+    return Ok(None);
+  };
+  let command = match StatementType::from(stmt) {
+    StatementType::Savepoint => Command::Set,
+    StatementType::ReleaseSavepoint => Command::Release,
+    StatementType::RollbackToSavepoint => Command::Rollback,
+    _ => return Ok(None),
+  };
+  Ok(Some(Arc::new(SavepointExecution {
+    transaction: transaction.clone(),
+    name,
+    command,
+  })))
+}
+
+#[derive(Debug, Clone, Copy)]
+enum Command {
+  Set,
+  Release,
+  Rollback,
+}
+
+#[derive(Clone)]
+pub struct SavepointExecution {
+  transaction: Transaction,
+  name: String,
+  command: Command,
+}
+
+impl CustomExecutionPlan for SavepointExecution {
+  fn schema(&self) -> SchemaRef {
+    SchemaRef::new(Schema::new(Vec::<Field>::new()))
+  }
+
+  fn execute(
+    &self,
+    _partition: usize,
+    _context: Arc<TaskContext>,
+    _exprs: Vec<Expr>,
+    _inputs: Vec<LogicalPlan>,
+  ) -> Result<Pin<Box<dyn Stream<Item = Result<DataFrame>> + Send>>> {
+    let handle = self.transaction.handle().clone();
+    let name = self.name.clone();
+    let command = self.command;
+    let fut = async move {
+      match command {
+        Command::Set => handle.set_savepoint(&name)?,
+        Command::Release => handle.release_savepoint(&name)?,
+        Command::Rollback => handle.rollback_to_savepoint(&name)?,
+      }
+      Ok(DataFrame::empty())
+    }
+    .boxed();
+    Ok(Box::pin(futures::stream::once(fut)))
+  }
+}
@@ -1,35 +1,137 @@
 use std::any::Any;
+use std::pin::Pin;
 use std::sync::Arc;
+use std::task::{Context, Poll};
 
 use datafusion::arrow::datatypes::SchemaRef;
+use datafusion::common::stats::Precision;
+use datafusion::common::ColumnStatistics;
 use datafusion::error::DataFusionError;
+use datafusion::execution::memory_pool::MemoryConsumer;
 use datafusion::execution::TaskContext;
 use datafusion::physical_expr::PhysicalSortExpr;
-use datafusion::physical_plan::stream::RecordBatchStreamAdapter;
+use datafusion::physical_plan::metrics::{
+  BaselineMetrics, ExecutionPlanMetricsSet, MetricsSet,
+};
+use datafusion::physical_plan::stream::RecordBatchReceiverStream;
 use datafusion::physical_plan::{
   DisplayAs, DisplayFormatType, ExecutionPlan, Partitioning, Statistics,
 };
 use derivative::Derivative;
 use derive_builder::Builder;
-use futures::StreamExt;
+use futures::{Stream, StreamExt};
+use getset::Getters;
+use tokio::sync::oneshot;
 
 use crate::datafusion::{RecordBatch, RecordBatchStream};
 use crate::execution::filter::Filter;
-use crate::execution::iterators::{HeapIterator, IndexIterator};
+use crate::execution::iterators::{
+  HeapIterator, IndexIterator, IndexOrder, RecordBatchWriter,
+  VectorIndexIterator,
+};
 use crate::execution::TransactionHandle;
-use crate::schema::{DataFrame, DataType, Table};
+use crate::schema::{
+  DataType, OwnedSerializedCell, Table, TableIndex, TableStatistics,
+};
+use crate::storage::StorageHandler;
+
+/// Number of record batches that are buffered before the scan waits for
+/// the batches to be consumed
+const SCAN_BUFFER_BATCHES: usize = 2;
 
-#[derive(Derivative, Clone, Builder)]
+#[derive(Derivative, Clone, Builder, Getters)]
 #[derivative(Debug)]
 pub struct TableScaner {
+  #[getset(get = "pub(crate)")]
   table: Arc<Table>,
   /// vec of selected columns by index
   projection: Vec<usize>,
   projected_schema: SchemaRef,
   #[derivative(Debug = "ignore")]
   pub transaction: TransactionHandle,
+  #[getset(get = "pub(crate)")]
   filters: Vec<Filter>,
   limit: Option<usize>,
+  /// If set, only the rows returned by the vector index search
+  /// are scanned
+  #[builder(default = "None")]
+  #[getset(get = "pub(crate)")]
+  vector_search: Option<VectorSearch>,
+  /// If set, only the first rows in the order of the index column
+  /// are scanned
+  #[builder(default = "None")]
+  ordered_scan: Option<OrderedIndexScan>,
+  /// Row count and time taken to scan the table; shown by
+  /// `EXPLAIN ANALYZE`
+  #[builder(default)]
+  metrics: ExecutionPlanMetricsSet,
+  /// Statistics of the table; `None` if the rows of the table aren't
+  /// counted
+  #[builder(default)]
+  statistics: Option<Arc<TableStatistics>>,
+}
+
+/// Approximate nearest neighbour search using a vector index
+#[derive(Debug, Clone)]
+pub struct VectorSearch {
+  pub index: TableIndex,
+  /// Namespace to search in if the index is partitioned by namespace
+  pub namespace: Option<OwnedSerializedCell>,
+  pub query: Vec<f32>,
+  pub k: usize,
+}
+
+/// Scan of an index in the order of one of its columns to return the
+/// first rows of `ORDER BY column LIMIT n` without sorting the table
+#[derive(Debug, Clone)]
+pub struct OrderedIndexScan {
+  pub index: TableIndex,
+  pub order: IndexOrder,
+}
+
+/// Stream of the record batches of a table scan that runs in a blocking
+/// thread. The scan holds the read lock of the transaction until the
+/// thread is done. Since the scan checks whether the stream is dropped
+/// before every row, the lock is released soon after the stream is
+/// dropped; the transaction waits for it before modifying the rows
+struct TableScanStream {
+  schema: SchemaRef,
+  stream: Option<RecordBatchStream>,
+  transaction: TransactionHandle,
+  /// Disconnected when the scan is done
+  scan_done: Option<oneshot::Receiver<()>>,
+}
+
+impl Stream for TableScanStream {
+  type Item = Result<RecordBatch, DataFusionError>;
+
+  fn poll_next(
+    mut self: Pin<&mut Self>,
+    cx: &mut Context<'_>,
+  ) -> Poll<Option<Self::Item>> {
+    match self.stream.as_mut() {
+      Some(stream) => stream.poll_next_unpin(cx),
+      None => Poll::Ready(None),
+    }
+  }
+}
+
+impl datafusion::physical_plan::RecordBatchStream for TableScanStream {
+  fn schema(&self) -> SchemaRef {
+    self.schema.clone()
+  }
+}
+
+impl Drop for TableScanStream {
+  fn drop(&mut self) {
+    // Drop the receiver of the batches first so that the scan stops
+    self.stream.take();
+    if let Some(mut scan_done) = self.scan_done.take() {
+      if scan_done.try_recv() == Err(oneshot::error::TryRecvError::Empty) {
+        self.transaction.add_cancelled_scan(scan_done);
+      }
+    }
+  }
 }
 
 impl DisplayAs for TableScaner {
@@ -38,7 +140,49 @@ impl DisplayAs for TableScaner {
     _t: DisplayFormatType,
     f: &mut std::fmt::Formatter,
   ) -> std::fmt::Result {
-    write!(f, "TableScaner")
+    if let Some(search) = &self.vector_search {
+      return write!(
+        f,
+        "TableScaner: table={}, vector_index={}, k={}",
+        self.table.name, search.index.name, search.k
+      );
+    }
+
+    let column_projection =
+      Self::get_physical_column_projection(&self.table, &self.projection);
+    let index = match &self.ordered_scan {
+      Some(scan) => Some(&scan.index),
+      None => Self::find_index_to_scan(
+        &self.table,
+        &column_projection,
+        &self.filters,
+        self.statistics.as_deref(),
+      ),
+    };
+    match index {
+      Some(index) => write!(
+        f,
+        "TableScaner: table={}, scan=index, index={}",
+        self.table.name, index.name
+      )?,
+      None => write!(f, "TableScaner: table={}, scan=heap", self.table.name)?,
+    };
+    if let Some(scan) = &self.ordered_scan {
+      let order = if scan.order.descending { "desc" } else { "asc" };
+      write!(f, ", order={}", order)?;
+    }
+    if !self.filters.is_empty() {
+      let filters = self
+        .filters
+        .iter()
+        .map(|filter| filter.to_string())
+        .collect::<Vec<String>>();
+      write!(f, ", filters=[{}]", filters.join(", "))?;
+    }
+    if let Some(limit) = self.limit {
+      write!(f, ", limit={}", limit)?;
+    }
+    Ok(())
   }
 }
 
@@ -72,87 +216,253 @@ impl ExecutionPlan for TableScaner {
 
   fn execute(
     &self,
-    _partition: usize,
-    _context: Arc<TaskContext>,
+    partition: usize,
+    context: Arc<TaskContext>,
   ) -> Result<RecordBatchStream, DataFusionError> {
-    let scan_fut = Self::scan_table(
-      self.table.clone(),
-      self.projection.clone(),
-      self.schema(),
-      self.filters.clone(),
-      self.limit.clone(),
-      self.transaction.clone(),
-    );
-    let stream = futures::stream::once(async move { scan_fut.await }).boxed();
-    Ok(Box::pin(RecordBatchStreamAdapter::new(
-      self.schema(),
-      stream,
-    )))
+    let batch_size = context.session_config().batch_size();
+    // Memory used by the batch that's being sent
+    let mut reservation = MemoryConsumer::new(format!(
+      "TableScaner[{}, partition={}]",
+      self.table.name, partition
+    ))
+    .register(context.memory_pool());
+    let baseline_metrics = BaselineMetrics::new(&self.metrics, partition);
+
+    // The rows are read from the storage in a blocking thread and each
+    // batch is sent as soon as it's full. The scan stops if the stream
+    // is dropped before all the rows are read, for example, when the
+    // rows are limited by the plans above the scanner
+    let mut builder =
+      RecordBatchReceiverStream::builder(self.schema(), SCAN_BUFFER_BATCHES);
+    let tx = builder.tx();
+    let (scan_done_tx, scan_done) = oneshot::channel::<()>();
+    // The read lock is owned by the scan and released when it's done
+    let storage = self.transaction.lock(false)?;
+    let scanner = self.clone();
+    builder.spawn_blocking(move || {
+      // Dropped when the scan is done
+      let _scan_done_tx = scan_done_tx;
+      let mut timer = baseline_metrics.elapsed_compute().timer();
+      let mut send_batch = |batch: RecordBatch| -> crate::Result<bool> {
+        timer.stop();
+        reservation.try_resize(batch.get_array_memory_size())?;
+        baseline_metrics.record_output(batch.num_rows());
+        let is_sent = tx.blocking_send(Ok(batch)).is_ok();
+        timer.restart();
+        Ok(is_sent)
+      };
+      let is_cancelled = || tx.is_closed();
+      let mut writer = RecordBatchWriter::new(
+        scanner.schema(),
+        scanner.get_physical_columns(),
+        batch_size,
+        scanner.limit,
+        &mut send_batch,
+      )
+      .with_cancellation(&is_cancelled);
+      let result = scanner.scan_table(storage, &mut writer);
+      drop(writer);
+      timer.done();
+      baseline_metrics.done();
+      if let Err(err) = result {
+        tx.blocking_send(Err(err.into())).ok();
+      }
+      Ok(())
+    });
+    Ok(Box::pin(TableScanStream {
+      schema: self.schema(),
+      stream: Some(builder.build()),
+      transaction: self.transaction.clone(),
+      scan_done: Some(scan_done),
+    }))
   }
 
   fn statistics(&self) -> Result<Statistics, DataFusionError> {
-    Ok(Statistics::new_unknown(&self.projected_schema))
+    let Some(statistics) = &self.statistics else {
+      return Ok(Statistics::new_unknown(&self.projected_schema));
+    };
+    Ok(Statistics {
+      num_rows: Precision::Inexact(Self::estimate_row_count(
+        &self.table,
+        &self.filters,
+        self.limit,
+        self.vector_search.as_ref(),
+        statistics,
+      )),
+      total_byte_size: Precision::Absent,
+      column_statistics: self
+        .projection
+        .iter()
+        .map(|column| ColumnStatistics {
+          distinct_count: statistics
+            .get_column_distinct_count(&self.table.indexes, *column)
+            .map(|count| Precision::Inexact(count as usize))
+            .unwrap_or(Precision::Absent),
+          ..ColumnStatistics::new_unknown()
+        })
+        .collect(),
+    })
+  }
+
+  fn metrics(&self) -> Option<MetricsSet> {
+    Some(self.metrics.clone_inner())
   }
 }
 
 impl TableScaner {
-  pub async fn scan_table(
-    table: Arc<Table>,
-    // List of selected column indexes
-    column_projection: Vec<usize>,
-    schema: SchemaRef,
-    filters: Vec<Filter>,
-    limit: Option<usize>,
-    transaction: TransactionHandle,
-  ) -> Result<RecordBatch, DataFusionError> {
-    // Only pass in physical columns since virutal columns like
-    // ctid are default included
-    let physical_columns: Vec<(String, DataType)> = column_projection
-      .iter()
-      .zip(schema.fields.iter())
-      .map(|(_, field)| {
-        (field.name().clone(), DataType::from_field(field).unwrap())
-      })
-      .collect();
+  /// Returns a copy of the scanner that uses the vector index to
+  /// only scan the rows closest to the search query
+  pub(crate) fn with_vector_search(&self, search: VectorSearch) -> Self {
+    let mut scanner = self.clone();
+    scanner.vector_search = Some(search);
+    scanner.metrics = ExecutionPlanMetricsSet::new();
+    scanner
+  }
 
-    // Don't include virtual columns in column projection
-    // since they are auto added
-    let column_projection = column_projection
-      .into_iter()
-      .filter(|idx| *idx < table.columns.len())
-      .collect::<Vec<usize>>();
+  /// Returns a copy of the scanner that only scans the first rows in the
+  /// order of the index column
+  pub(crate) fn with_ordered_index_scan(&self, scan: OrderedIndexScan) -> Self {
+    let mut scanner = self.clone();
+    scanner.limit = Some(scan.order.limit);
+    scanner.ordered_scan = Some(scan);
+    scanner.metrics = ExecutionPlanMetricsSet::new();
+    scanner
+  }
 
-    let storage = transaction.lock(false)?;
-    let index_with_lowest_cost =
-      Filter::find_index_with_lowest_cost(&table.indexes, &filters);
+  /// Returns the selected columns without the virtual columns
+  /// since they are auto added
+  fn get_physical_column_projection(
+    table: &Table,
+    column_projection: &Vec<usize>,
+  ) -> Vec<usize> {
+    column_projection
+      .iter()
+      .filter(|idx| **idx < table.columns.len())
+      .cloned()
+      .collect()
+  }
+
+  /// Returns the index to scan the table with; `None` if the entire
+  /// table should be scanned
+  fn find_index_to_scan<'a>(
+    table: &'a Table,
+    column_projection: &Vec<usize>,
+    filters: &'a Vec<Filter>,
+    statistics: Option<&TableStatistics>,
+  ) -> Option<&'a TableIndex> {
+    let index_with_lowest_cost = Filter::find_index_with_lowest_cost(
+      &table.indexes,
+      filters,
+      column_projection,
+      statistics,
+    );
 
-    let maybe_use_index = index_with_lowest_cost.or_else(|| {
+    index_with_lowest_cost.or_else(|| {
       // If an index with lowest cost isn't found, check if there's
       // an index that has all the columns the query needs
       // TODO: what if there are more than one index with all columns?
       table.indexes.iter().find(|index| {
+        // vector index doesn't store the rows in the column order and
+        // JSON path index doesn't store the column
+        if index.is_vector_index() || index.json_path().is_some() {
+          return false;
+        }
         let index_cols = index.columns();
         column_projection
           .iter()
           .all(|proj| index_cols.contains(proj))
       })
-    });
-
-    // TODO: customize the DF capacity based on statistics
-    let mut dataframe =
-      DataFrame::with_capacity(limit.unwrap_or(1_000), physical_columns);
+    })
+  }
 
-    // TODO: if some filter is used, use index that has all the columns
-    // from the filter even if all the selected columns are not in the index
-    if let Some(index) = maybe_use_index {
-      IndexIterator::new(&storage, &table, index, &filters, &column_projection)
-        .fill_into(&mut dataframe)?;
-    } else {
-      HeapIterator::new(&storage, &table, &column_projection)
-        .fill_into(&mut dataframe)?;
+  /// Returns the estimated number of rows returned by the scan
+  fn estimate_row_count(
+    table: &Table,
+    filters: &Vec<Filter>,
+    limit: Option<usize>,
+    vector_search: Option<&VectorSearch>,
+    statistics: &TableStatistics,
+  ) -> usize {
+    let row_count = match vector_search {
+      Some(search) => search.k.min(statistics.row_count as usize),
+      None => {
+        let selectivity = filters
+          .iter()
+          .filter_map(|filter| {
+            filter.estimate_selectivity(&table.indexes, statistics)
+          })
+          .product::<f64>();
+        (statistics.row_count as f64 * selectivity).ceil() as usize
+      }
     };
+    limit.map(|limit| limit.min(row_count)).unwrap_or(row_count)
+  }
+
+  /// Returns the names and types of the selected physical columns since
+  /// the virtual columns like ctid are included by default
+  fn get_physical_columns(&self) -> Vec<(String, DataType)> {
+    self
+      .projection
+      .iter()
+      .zip(self.projected_schema.fields.iter())
+      .map(|(_, field)| {
+        (field.name().clone(), DataType::from_field(field).unwrap())
+      })
+      .collect()
+  }
 
-    // TODO: try if sending rows in batches improves per
-    Ok(dataframe.to_record_batch(schema)?)
+  /// Scans the rows of the table and writes the selected columns of the
+  /// rows to the writer until all the rows are scanned or the writer
+  /// doesn't need more rows
+  fn scan_table(
+    &self,
+    storage: StorageHandler,
+    writer: &mut RecordBatchWriter<'_>,
+  ) -> crate::Result<()> {
+    let table = &self.table;
+    let filters = &self.filters;
+    let column_projection =
+      Self::get_physical_column_projection(table, &self.projection);
+
+    if let Some(search) = &self.vector_search {
+      VectorIndexIterator::new(
+        &storage,
+        table,
+        &search.index,
+        search.namespace.as_ref(),
+        &search.query,
+        search.k,
+        &column_projection,
+      )
+      .fill_into(writer)?;
+    } else if let Some(scan) = &self.ordered_scan {
+      IndexIterator::new(
+        &storage,
+        table,
+        &scan.index,
+        filters,
+        &column_projection,
+      )
+      .with_order(Some(&scan.order))
+      .fill_into(writer)?;
+    } else if let Some(index) = Self::find_index_to_scan(
+      table,
+      &column_projection,
+      filters,
+      self.statistics.as_deref(),
+    ) {
+      // TODO: if some filter is used, use index that has all the columns
+      // from the filter even if all the selected columns are not in the
+      // index
+      IndexIterator::new(&storage, table, index, filters, &column_projection)
+        .fill_into(writer)?;
+    } else {
+      HeapIterator::new(&storage, table, &column_projection)
+        .fill_into(writer)?;
+    }
+    // Release the read lock of the transaction before the last batch is
+    // sent so that the lock isn't held once the stream is done
+    drop(storage);
+    writer.finish()
   }
 }
@@ -0,0 +1,204 @@
+use std::fmt;
+use std::sync::Arc;
+
+use datafusion::arrow::datatypes::{Schema, SchemaRef};
+use datafusion::execution::TaskContext;
+use datafusion::logical_expr::{Expr, LogicalPlan};
+use datafusion::physical_plan::{DisplayAs, DisplayFormatType};
+use derivative::Derivative;
+use derive_builder::Builder;
+use futures::StreamExt;
+use sqlparser::ast::{ObjectType, Statement as SQLStatement};
+
+use crate::df::providers::{get_schema_provider, get_table_ref};
+use crate::error::Error;
+use crate::execution::{CustomExecutionPlan, Transaction};
+use crate::execution::{ExecutionPlanResponse, TransactionHandle};
+use crate::schema::{DataFrame, Sequence};
+use crate::{bail, Result};
+
+/// Returns a custom execution plan extension to create and drop sequences
+#[tracing::instrument(skip_all, fields(name = "sequence"), level = "trace")]
+pub fn extension(
+  transaction: &Transaction,
+  stmt: &SQLStatement,
+) -> Result<Option<Arc<dyn CustomExecutionPlan>>> {
+  let state = transaction.datafusion_context().state();
+  let command = match stmt {
+    SQLStatement::CreateSequence {
+      temporary,
+      if_not_exists,
+      name,
+      data_type,
+      sequence_options,
+      owned_by,
+    } => {
+      if *temporary {
+        bail!(Error::UnsupportedQuery(format!(
+          "`TEMPORARY` sequence is not supported yet"
+        )));
+      }
+      let name = name.to_string();
+      let sequence_ref = get_table_ref(&state, &name);
+      // Make sure the schema exists
+      get_schema_provider(&state, &sequence_ref)?;
+
+      let mut sequence = Sequence::new(
+        sequence_ref.table.as_ref(),
+        data_type.as_ref(),
+        sequence_options,
+      )?;
+      // `OWNED BY table.column`; `OWNED BY NONE` has only one identifier
+      sequence.owned_by = owned_by
+        .as_ref()
+        .filter(|owned_by| owned_by.0.len() > 1)
+        .map(|owned_by| owned_by.0[owned_by.0.len() - 2].value.clone());
+
+      Command::Create {
+        catalog: sequence_ref.catalog.as_ref().into(),
+        schema: sequence_ref.schema.as_ref().into(),
+        sequence,
+        if_not_exists: *if_not_exists,
+      }
+    }
+    SQLStatement::Drop {
+      object_type: ObjectType::Sequence,
+      if_exists,
+      names,
+      cascade,
+      ..
+    } => {
+      if *cascade {
+        bail!(Error::UnsupportedQuery(format!(
+          "`CASCADE` is not supported yet"
+        )));
+      }
+      Command::Drop {
+        sequences: names
+          .iter()
+          .map(|name| {
+            let name = name.to_string();
+            let sequence_ref = get_table_ref(&state, &name);
+            (
+              sequence_ref.catalog.as_ref().into(),
+              sequence_ref.schema.as_ref().into(),
+              sequence_ref.table.as_ref().to_owned(),
+            )
+          })
+          .collect(),
+        if_exists: *if_exists,
+      }
+    }
+    _ => return Ok(None),
+  };
+
+  Ok(Some(Arc::new(
+    SequenceExecutionPlanBuilder::default()
+      .transaction(transaction.handle().clone())
+      .command(command)
+      .build()
+      .unwrap(),
+  )))
+}
+
+#[derive(Builder, Derivative)]
+#[derivative(Debug)]
+pub struct SequenceExecutionPlan {
+  #[derivative(Debug = "ignore")]
+  transaction: TransactionHandle,
+  command: Command,
+}
+
+#[derive(Debug, Clone)]
+pub enum Command {
+  Create {
+    catalog: Arc<str>,
+    schema: Arc<str>,
+    sequence: Sequence,
+    if_not_exists: bool,
+  },
+  Drop {
+    /// (catalog, schema, sequence)
+    sequences: Vec<(Arc<str>, Arc<str>, String)>,
+    if_exists: bool,
+  },
+}
+
+impl DisplayAs for SequenceExecutionPlan {
+  fn fmt_as(
+    &self,
+    _t: DisplayFormatType,
+    f: &mut fmt::Formatter,
+  ) -> fmt::Result {
+    write!(f, "{:?}", self)
+  }
+}
+
+impl CustomExecutionPlan for SequenceExecutionPlan {
+  fn schema(&self) -> SchemaRef {
+    Arc::new(Schema::empty())
+  }
+
+  fn execute(
+    &self,
+    _partition: usize,
+    _context: Arc<TaskContext>,
+    _exprs: Vec<Expr>,
+    _inputs: Vec<LogicalPlan>,
+  ) -> crate::Result<ExecutionPlanResponse> {
+    let command = self.command.clone();
+    let transaction = self.transaction.clone();
+    let stream = futures::stream::once(async move {
+      let storage_handler = transaction.lock(true)?;
+      match command {
+        Command::Create {
+          catalog,
+          schema,
+          sequence,
+          if_not_exists,
+        } => {
+          let exists = storage_handler
+            .get_sequence(&catalog, &schema, &sequence.name)?
+            .is_some();
+          if exists && if_not_exists {
+            return Ok(DataFrame::empty());
+          }
+          storage_handler.create_sequence(&catalog, &schema, &sequence)?;
+        }
+        Command::Drop {
+          sequences,
+          if_exists,
+        } => {
+          for (catalog, schema, name) in sequences {
+            let Some(sequence) =
+              storage_handler.get_sequence(&catalog, &schema, &name)?
+            else {
+              if if_exists {
+                continue;
+              }
+              bail!(Error::RelationDoesntExist(name));
+            };
+            // The sequence is used by the default value of the table column
+            let is_used = sequence
+              .owned_by
+              .as_ref()
+              .map(|table| transaction.get_table(&schema, table).is_some())
+              .unwrap_or(false);
+            if is_used {
+              bail!(Error::InvalidQuery(format!(
+                "cannot drop sequence \"{}\" because other objects \
+                depend on it",
+                name
+              )));
+            }
+            storage_handler.delete_sequence(&catalog, &schema, &name)?;
+          }
+        }
+      }
+      Ok(DataFrame::empty())
+    })
+    .boxed();
+
+    Ok(Box::pin(stream))
+  }
+}
@@ -0,0 +1,590 @@
+use std::ops::ControlFlow;
+use std::sync::Arc;
+
+use datafusion::arrow::array::{new_null_array, ArrayRef, StringArray};
+use datafusion::arrow::compute::cast;
+use datafusion::arrow::datatypes::{
+  DataType as DfDataType, Field, Schema, SchemaRef,
+};
+use datafusion::arrow::record_batch::RecordBatch;
+use datafusion::common::cast::as_boolean_array;
+use datafusion::common::{plan_err, DFField, DFSchema, DFSchemaRef};
+use datafusion::config::ConfigOptions;
+use datafusion::error::{DataFusionError, Result as DataFusionResult};
+use datafusion::execution::context::ExecutionProps;
+use datafusion::execution::{FunctionRegistry, TaskContext};
+use datafusion::logical_expr::{
+  AggregateUDF, ExprSchemable, ScalarUDF, TableSource, WindowUDF,
+};
+use datafusion::optimizer::simplify_expressions::{
+  ExprSimplifier, SimplifyContext,
+};
+use datafusion::physical_expr::expressions::Column;
+use datafusion::physical_expr::{create_physical_expr, PhysicalExpr};
+use datafusion::physical_plan::ColumnarValue;
+use datafusion::sql::planner::{ContextProvider, PlannerContext, SqlToRel};
+use datafusion::sql::TableReference;
+use sqlparser::ast::{
+  visit_expressions_mut, Assignment, Expr as SQLExpr, FunctionArg,
+  FunctionArgExpr, Ident, SelectItem, Value as SQLValue,
+};
+use sqlparser::dialect::PostgreSqlDialect;
+use sqlparser::parser::Parser;
+
+use crate::schema::{Constraint, DataFrame, Table};
+use crate::{Error, Result};
+
+/// Column defaults and check constraints of a table compiled to
+/// Datafusion physical expressions so that they can be evaluated for
+/// each batch of rows
+pub(crate) struct TableExprs {
+  table: Arc<Table>,
+  // Schema of the active table columns; virtual columns aren't included
+  schema: SchemaRef,
+  // Default of each column
+  defaults: Vec<Option<ColumnDefault>>,
+  // (constraint name, expression)
+  checks: Vec<(String, Arc<dyn PhysicalExpr>)>,
+  // Only set if any of the columns use a sequence
+  nextval: Option<Arc<ScalarUDF>>,
+}
+
+enum ColumnDefault {
+  Expr(Arc<dyn PhysicalExpr>),
+  /// Name of the sequence of `nextval('<sequence>')` default. This is
+  /// evaluated separately since the function would only be called once
+  /// per batch if it's evaluated as an expression; its argument is a
+  /// literal
+  Sequence(String),
+}
+
+impl TableExprs {
+  pub fn new(table: &Arc<Table>, context: &TaskContext) -> Result<Self> {
+    // Note: all fields are nullable so that NULLs in NOT NULL columns
+    // are reported as constraint violation when the rows are converted
+    let schema = Arc::new(Schema::new(
+      table
+        .active_columns()
+        .map(|(_, col)| col.to_field(table).with_nullable(true))
+        .collect::<Vec<Field>>(),
+    ));
+    let df_schema: DFSchemaRef =
+      Arc::new(DFSchema::try_from(schema.as_ref().clone())?);
+    let planner = ExprPlanner {
+      context,
+      schema: &schema,
+      df_schema: &df_schema,
+      props: ExecutionProps::new(),
+    };
+
+    let defaults = table
+      .active_columns()
+      .map(|(_, col)| col)
+      .zip(schema.fields().iter())
+      .map(|(col, field)| {
+        col
+          .default_expr
+          .as_ref()
+          .map(|expr| planner.create_default(expr, field.data_type()))
+          .transpose()
+      })
+      .collect::<Result<Vec<Option<ColumnDefault>>>>()?;
+
+    let checks = table
+      .constraints
+      .iter()
+      .filter_map(|constraint| match constraint {
+        Constraint::Check(check) => Some(check),
+        _ => None,
+      })
+      .map(|check| {
+        Ok((
+          check.name.clone(),
+          planner.create_physical_expr(&check.expr, &DfDataType::Boolean)?,
+        ))
+      })
+      .collect::<Result<Vec<(String, Arc<dyn PhysicalExpr>)>>>()?;
+
+    let nextval = defaults
+      .iter()
+      .any(|default| matches!(default, Some(ColumnDefault::Sequence(_))))
+      .then(|| context.udf("nextval"))
+      .transpose()?;
+
+    Ok(Self {
+      table: table.clone(),
+      schema,
+      defaults,
+      checks,
+      nextval,
+    })
+  }
+
+  /// Returns the batch with all the active columns of the table. The
+  /// columns that aren't in the given batch are set to their default
+  /// values or NULL if the column doesn't have a default
+  pub fn fill_defaults(&self, batch: &RecordBatch) -> Result<RecordBatch> {
+    let num_rows = batch.num_rows();
+    let columns = self
+      .table
+      .active_columns()
+      .map(|(_, col)| col)
+      .zip(self.defaults.iter())
+      .zip(self.schema.fields().iter())
+      .map(|((col, default), field)| {
+        match (batch.column_by_name(&col.name), default) {
+          (Some(values), _) => Ok(values.clone()),
+          // Default expressions can't reference any columns, so it's
+          // fine to evaluate them with the given batch
+          (None, Some(ColumnDefault::Expr(default))) => {
+            Ok(default.evaluate(batch)?.into_array(num_rows)?)
+          }
+          (None, Some(ColumnDefault::Sequence(sequence))) => {
+            let nextval = self.nextval.as_ref().unwrap();
+            let names = StringArray::from(vec![sequence.as_str(); num_rows]);
+            let values =
+              (nextval.fun)(&[ColumnarValue::Array(Arc::new(names))])?
+                .into_array(num_rows)?;
+            cast(&values, field.data_type()).map_err(|e| {
+              Error::DataFusionError(DataFusionError::ArrowError(e).into())
+            })
+          }
+          (None, None) => Ok(new_null_array(field.data_type(), num_rows)),
+        }
+      })
+      .collect::<Result<Vec<ArrayRef>>>()?;
+    self.to_table_batch(columns)
+  }
+
+  /// Returns error if any of the rows in the batch violates the
+  /// check constraints. The batch must have all the active columns of
+  /// the table
+  pub fn check(&self, batch: &RecordBatch) -> Result<()> {
+    if self.checks.is_empty() {
+      return Ok(());
+    }
+    let batch = project_table_columns(&self.table, &self.schema, batch)?;
+
+    for (name, expr) in &self.checks {
+      let result = expr.evaluate(&batch)?.into_array(batch.num_rows())?;
+      // Note: the constraint is satisfied if the expression is NULL
+      if as_boolean_array(&result)?
+        .iter()
+        .any(|value| value == Some(false))
+      {
+        return Err(Error::CheckConstraintViolated {
+          constraint: name.clone(),
+          table: self.table.name.clone(),
+        });
+      }
+    }
+    Ok(())
+  }
+
+  fn to_table_batch(&self, columns: Vec<ArrayRef>) -> Result<RecordBatch> {
+    to_record_batch(self.schema.clone(), columns)
+  }
+}
+
+/// Assignments and condition of `INSERT ... ON CONFLICT DO UPDATE`
+/// compiled to Datafusion physical expressions. The expressions can
+/// reference the columns of the existing row and the row proposed for
+/// insertion using the `EXCLUDED` table
+pub(crate) struct ConflictUpdateExprs {
+  // Schema of the table columns followed by the `EXCLUDED` columns
+  schema: SchemaRef,
+  // Schema of the active table columns
+  table_schema: SchemaRef,
+  // (position of the column in the active columns, expression)
+  assignments: Vec<(usize, Arc<dyn PhysicalExpr>)>,
+  selection: Option<Arc<dyn PhysicalExpr>>,
+}
+
+impl ConflictUpdateExprs {
+  pub fn new(
+    table: &Arc<Table>,
+    context: &TaskContext,
+    assignments: &Vec<Assignment>,
+    selection: Option<&SQLExpr>,
+  ) -> Result<Self> {
+    let fields = table
+      .active_columns()
+      .map(|(_, col)| col.to_field(table).with_nullable(true))
+      .collect::<Vec<Field>>();
+    let table_schema = Arc::new(Schema::new(fields.clone()));
+    let schema = Arc::new(Schema::new(
+      fields
+        .iter()
+        .chain(fields.iter())
+        .cloned()
+        .collect::<Vec<Field>>(),
+    ));
+    let df_schema: DFSchemaRef = Arc::new(DFSchema::new(
+      [table.name.as_str(), EXCLUDED_TABLE]
+        .iter()
+        .flat_map(|qualifier| {
+          fields.iter().map(|field| {
+            DFField::from_qualified(
+              TableReference::bare(*qualifier),
+              Arc::new(field.clone()),
+            )
+          })
+        })
+        .collect(),
+    )?);
+    let planner = ExprPlanner {
+      context,
+      schema: &schema,
+      df_schema: &df_schema,
+      props: ExecutionProps::new(),
+    };
+
+    let assignments = assignments
+      .iter()
+      .map(|assignment| {
+        let name = assignment.id.last().map(|id| &id.value);
+        let (position, field) = name
+          .and_then(|name| {
+            fields.iter().enumerate().find(|(_, f)| f.name() == name)
+          })
+          .ok_or_else(|| {
+            Error::ColumnDoesntExist(
+              name.map(|n| n.to_owned()).unwrap_or_default(),
+            )
+          })?;
+        let value = qualify_table_columns(table, assignment.value.clone());
+        Ok((
+          position,
+          planner
+            .create_physical_expr_from_sql(value, Some(field.data_type()))?,
+        ))
+      })
+      .collect::<Result<Vec<(usize, Arc<dyn PhysicalExpr>)>>>()?;
+
+    let selection = selection
+      .map(|expr| {
+        let expr = qualify_table_columns(table, expr.clone());
+        planner.create_physical_expr_from_sql(expr, Some(&DfDataType::Boolean))
+      })
+      .transpose()?;
+
+    Ok(Self {
+      schema,
+      table_schema,
+      assignments,
+      selection,
+    })
+  }
+
+  /// Returns the updated row or None if the row doesn't match the
+  /// condition of the update. Both the existing row and the excluded
+  /// row must have a single row with all the active columns
+  pub fn evaluate(
+    &self,
+    existing: DataFrame,
+    excluded: &RecordBatch,
+  ) -> Result<Option<RecordBatch>> {
+    let existing = existing.to_record_batch(self.table_schema.clone())?;
+    let batch = to_record_batch(
+      self.schema.clone(),
+      existing
+        .columns()
+        .iter()
+        .chain(excluded.columns().iter())
+        .cloned()
+        .collect(),
+    )?;
+
+    if let Some(selection) = &self.selection {
+      let result = selection.evaluate(&batch)?.into_array(1)?;
+      // Note: the row isn't updated if the condition is NULL
+      if as_boolean_array(&result)?.iter().any(|v| v != Some(true)) {
+        return Ok(None);
+      }
+    }
+
+    let mut columns = existing.columns().to_vec();
+    for (position, expr) in &self.assignments {
+      columns[*position] = expr.evaluate(&batch)?.into_array(1)?;
+    }
+    to_record_batch(self.table_schema.clone(), columns).map(Some)
+  }
+}
+
+/// Expressions of the `RETURNING` clause of INSERT, UPDATE and DELETE
+/// compiled to Datafusion physical expressions. The expressions are
+/// evaluated with the rows modified by the query
+pub(crate) struct ReturningExprs {
+  table: Arc<Table>,
+  // Schema of the active table columns
+  table_schema: SchemaRef,
+  // Schema of the returned rows
+  schema: SchemaRef,
+  exprs: Vec<Arc<dyn PhysicalExpr>>,
+}
+
+impl ReturningExprs {
+  pub fn new(
+    table: &Arc<Table>,
+    context: &TaskContext,
+    items: &Vec<SelectItem>,
+  ) -> Result<Self> {
+    let table_schema = Arc::new(Schema::new(
+      table
+        .active_columns()
+        .map(|(_, col)| col.to_field(table).with_nullable(true))
+        .collect::<Vec<Field>>(),
+    ));
+    let df_schema: DFSchemaRef = Arc::new(DFSchema::try_from_qualified_schema(
+      TableReference::bare(table.name.as_str()),
+      &table_schema,
+    )?);
+    let planner = ExprPlanner {
+      context,
+      schema: &table_schema,
+      df_schema: &df_schema,
+      props: ExecutionProps::new(),
+    };
+
+    let mut fields = vec![];
+    let mut exprs = vec![];
+    for item in items {
+      let (expr, name) = match item {
+        SelectItem::Wildcard(_) | SelectItem::QualifiedWildcard(..) => {
+          for (idx, field) in table_schema.fields().iter().enumerate() {
+            fields.push(field.as_ref().clone());
+            exprs
+              .push(Arc::new(Column::new(field.name(), idx))
+                as Arc<dyn PhysicalExpr>);
+          }
+          continue;
+        }
+        SelectItem::UnnamedExpr(expr) => (expr, get_column_name(expr)),
+        SelectItem::ExprWithAlias { expr, alias } => {
+          (expr, normalize_ident(alias))
+        }
+      };
+
+      let expr = planner.create_physical_expr_from_sql(expr.clone(), None)?;
+      // Keep the field metadata of the column so that the data types that
+      // datafusion doesn't support like JSONB are returned correctly
+      let field = match expr.as_any().downcast_ref::<Column>() {
+        Some(column) => table_schema.field(column.index()).clone(),
+        None => Field::new("", expr.data_type(&table_schema)?, true),
+      };
+      fields.push(field.with_name(name));
+      exprs.push(expr);
+    }
+
+    Ok(Self {
+      table: table.clone(),
+      table_schema,
+      schema: Arc::new(Schema::new(fields)),
+      exprs,
+    })
+  }
+
+  #[inline]
+  pub fn schema(&self) -> SchemaRef {
+    self.schema.clone()
+  }
+
+  /// Returns the rows of the `RETURNING` clause. The batch must have all
+  /// the active columns of the table
+  pub fn evaluate(&self, batch: &RecordBatch) -> Result<RecordBatch> {
+    let batch = project_table_columns(&self.table, &self.table_schema, batch)?;
+    let columns = self
+      .exprs
+      .iter()
+      .map(|expr| Ok(expr.evaluate(&batch)?.into_array(batch.num_rows())?))
+      .collect::<Result<Vec<ArrayRef>>>()?;
+    to_record_batch(self.schema.clone(), columns)
+  }
+}
+
+/// Returns the name of the column of the returned row for the expression
+/// without an alias. Same as Postgres, it's the name of the column or the
+/// function if the expression is a column or a function call
+fn get_column_name(expr: &SQLExpr) -> String {
+  match expr {
+    SQLExpr::Identifier(ident) => normalize_ident(ident),
+    SQLExpr::CompoundIdentifier(idents) => {
+      idents.last().map(normalize_ident).unwrap_or_default()
+    }
+    SQLExpr::Function(function) => function
+      .name
+      .0
+      .last()
+      .map(normalize_ident)
+      .unwrap_or_default(),
+    SQLExpr::Cast { expr, .. } | SQLExpr::Nested(expr) => get_column_name(expr),
+    _ => "?column?".to_owned(),
+  }
+}
+
+fn normalize_ident(ident: &Ident) -> String {
+  match ident.quote_style {
+    Some(_) => ident.value.clone(),
+    None => ident.value.to_ascii_lowercase(),
+  }
+}
+
+/// Name of the table that has the row proposed for insertion in the
+/// `ON CONFLICT DO UPDATE` expressions
+const EXCLUDED_TABLE: &str = "excluded";
+
+/// Qualifies the unqualified column references in the expression with
+/// the table name since the columns of the table and `EXCLUDED` have the
+/// same names. Unqualified columns refer to the existing row
+fn qualify_table_columns(table: &Table, mut expr: SQLExpr) -> SQLExpr {
+  let _ = visit_expressions_mut(&mut expr, |expr| {
+    if let SQLExpr::Identifier(ident) = expr {
+      *expr = SQLExpr::CompoundIdentifier(vec![
+        Ident::with_quote('"', table.name.clone()),
+        ident.clone(),
+      ]);
+    }
+    ControlFlow::<()>::Continue(())
+  });
+  expr
+}
+
+/// Returns the batch with the active columns of the table from the
+/// given batch; the batch can have other columns like `ctid`
+fn project_table_columns(
+  table: &Table,
+  schema: &SchemaRef,
+  batch: &RecordBatch,
+) -> Result<RecordBatch> {
+  let columns = table
+    .active_columns()
+    .map(|(_, col)| {
+      batch
+        .column_by_name(&col.name)
+        .cloned()
+        .ok_or_else(|| Error::ColumnDoesntExist(col.name.clone()))
+    })
+    .collect::<Result<Vec<ArrayRef>>>()?;
+  to_record_batch(schema.clone(), columns)
+}
+
+fn to_record_batch(
+  schema: SchemaRef,
+  columns: Vec<ArrayRef>,
+) -> Result<RecordBatch> {
+  RecordBatch::try_new(schema, columns)
+    .map_err(|e| Error::DataFusionError(DataFusionError::ArrowError(e).into()))
+}
+
+struct ExprPlanner<'a> {
+  context: &'a TaskContext,
+  schema: &'a SchemaRef,
+  df_schema: &'a DFSchemaRef,
+  props: ExecutionProps,
+}
+
+impl<'a> ExprPlanner<'a> {
+  fn create_default(
+    &self,
+    sql: &str,
+    data_type: &DfDataType,
+  ) -> Result<ColumnDefault> {
+    let sql_expr = Parser::new(&PostgreSqlDialect {})
+      .try_with_sql(sql)?
+      .parse_expr()?;
+    match sql_expr {
+      SQLExpr::Function(function)
+        if function.name.to_string().eq_ignore_ascii_case("nextval") =>
+      {
+        match function.args.as_slice() {
+          [FunctionArg::Unnamed(FunctionArgExpr::Expr(SQLExpr::Value(
+            SQLValue::SingleQuotedString(sequence),
+          )))] => return Ok(ColumnDefault::Sequence(sequence.clone())),
+          _ => {}
+        }
+      }
+      _ => {}
+    }
+    Ok(ColumnDefault::Expr(
+      self.create_physical_expr(sql, data_type)?,
+    ))
+  }
+
+  /// Creates a physical expression from the SQL expression. The result
+  /// of the expression is casted to the given data type
+  fn create_physical_expr(
+    &self,
+    sql: &str,
+    data_type: &DfDataType,
+  ) -> Result<Arc<dyn PhysicalExpr>> {
+    let sql_expr = Parser::new(&PostgreSqlDialect {})
+      .try_with_sql(sql)?
+      .parse_expr()?;
+    self.create_physical_expr_from_sql(sql_expr, Some(data_type))
+  }
+
+  /// Same as [`ExprPlanner::create_physical_expr`] but the result is
+  /// only casted if the data type is given
+  fn create_physical_expr_from_sql(
+    &self,
+    sql_expr: SQLExpr,
+    data_type: Option<&DfDataType>,
+  ) -> Result<Arc<dyn PhysicalExpr>> {
+    let mut expr = SqlToRel::new(&ExprContextProvider {
+      context: self.context,
+    })
+    .sql_to_expr(sql_expr, self.df_schema, &mut PlannerContext::new())?;
+    if let Some(data_type) = data_type {
+      expr = expr.cast_to(data_type, self.df_schema.as_ref())?;
+    }
+
+    let expr = ExprSimplifier::new(
+      SimplifyContext::new(&self.props).with_schema(self.df_schema.clone()),
+    )
+    .coerce(expr, self.df_schema.clone())?;
+    Ok(create_physical_expr(
+      &expr,
+      self.df_schema,
+      self.schema,
+      &self.props,
+    )?)
+  }
+}
+
+/// Provides the functions registered in the session to the expressions.
+/// Tables can't be used in the column default and check expressions
+struct ExprContextProvider<'a> {
+  context: &'a TaskContext,
+}
+
+impl<'a> ContextProvider for ExprContextProvider<'a> {
+  fn get_table_source(
+    &self,
+    name: TableReference,
+  ) -> DataFusionResult<Arc<dyn TableSource>> {
+    plan_err!("Table \"{}\" can't be used in the expression", name)
+  }
+
+  fn get_function_meta(&self, name: &str) -> Option<Arc<ScalarUDF>> {
+    self.context.udf(name).ok()
+  }
+
+  fn get_aggregate_meta(&self, _name: &str) -> Option<Arc<AggregateUDF>> {
+    None
+  }
+
+  fn get_window_meta(&self, _name: &str) -> Option<Arc<WindowUDF>> {
+    None
+  }
+
+  fn get_variable_type(
+    &self,
+    _variable_names: &[String],
+  ) -> Option<DfDataType> {
+    None
+  }
+
+  fn options(&self) -> &ConfigOptions {
+    self.context.session_config().options()
+  }
+}
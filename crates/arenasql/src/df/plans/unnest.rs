@@ -0,0 +1,85 @@
+use datafusion::common::{Column, UnnestOptions};
+use datafusion::logical_expr::{LogicalPlan, LogicalPlanBuilder};
+use sqlparser::ast::{
+  Expr as SQLExpr, FunctionArg, FunctionArgExpr, Ident, SelectItem, SetExpr,
+  Statement as SQLStatement,
+};
+
+use crate::{Error, Result};
+
+/// Datafusion doesn't support `unnest` in SQL yet. So, the `unnest(tags)`
+/// select item is replaced with `tags AS unnest` and the name of the
+/// column is returned so that the unnest can be added on top of the
+/// projection once the plan is created.
+///
+/// Only `unnest` in the top level select list is supported
+pub(crate) fn extract_unnest_column(
+  stmt: &mut SQLStatement,
+) -> Result<Option<String>> {
+  let select = match stmt {
+    SQLStatement::Query(query) => match query.body.as_mut() {
+      SetExpr::Select(select) => select,
+      _ => return Ok(None),
+    },
+    _ => return Ok(None),
+  };
+
+  let mut unnest_column = None;
+  for item in select.projection.iter_mut() {
+    let (expr, alias) = match item {
+      SelectItem::UnnamedExpr(expr) => (expr, None),
+      SelectItem::ExprWithAlias { expr, alias } => (expr, Some(alias.clone())),
+      _ => continue,
+    };
+    let array = match expr {
+      SQLExpr::Function(func)
+        if func.name.to_string().eq_ignore_ascii_case("unnest") =>
+      {
+        match func.args.as_slice() {
+          [FunctionArg::Unnamed(FunctionArgExpr::Expr(array))] => array.clone(),
+          _ => {
+            return Err(Error::InvalidQuery(
+              "unnest expects a single array argument".to_owned(),
+            ))
+          }
+        }
+      }
+      _ => continue,
+    };
+    if unnest_column.is_some() {
+      return Err(Error::UnsupportedQuery(
+        "Only one unnest is supported in a query".to_owned(),
+      ));
+    }
+
+    let alias = alias.unwrap_or_else(|| Ident::new("unnest"));
+    unnest_column = Some(alias.value.clone());
+    *item = SelectItem::ExprWithAlias { expr: array, alias };
+  }
+  Ok(unnest_column)
+}
+
+/// Adds unnest of the given column on top of the query projection.
+/// NULL and empty arrays don't produce any rows, same as Postgres
+pub(crate) fn unnest_column(
+  plan: LogicalPlan,
+  column: &str,
+) -> Result<LogicalPlan> {
+  match plan {
+    LogicalPlan::Projection(_) => Ok(
+      LogicalPlanBuilder::from(plan)
+        .unnest_column_with_options(
+          Column::from_name(column),
+          UnnestOptions::new().with_preserve_nulls(false),
+        )?
+        .build()?,
+    ),
+    LogicalPlan::Sort(_) | LogicalPlan::Limit(_) | LogicalPlan::Distinct(_) => {
+      let input = unnest_column(plan.inputs()[0].clone(), column)?;
+      Ok(plan.with_new_inputs(&[input])?)
+    }
+    _ => Err(Error::UnsupportedQuery(
+      "unnest is only supported in the select list".to_owned(),
+    )),
+  }
+}
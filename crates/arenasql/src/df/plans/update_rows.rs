@@ -2,7 +2,6 @@ use std::any::Any;
 use std::fmt;
 use std::sync::Arc;
 
-use datafusion::arrow::array::UInt64Array;
 use datafusion::arrow::datatypes::{DataType, Field, Schema, SchemaRef};
 use datafusion::arrow::record_batch::RecordBatch;
 use datafusion::common::Statistics;
@@ -19,29 +18,39 @@ use derivative::Derivative;
 use derive_builder::Builder;
 use futures::{StreamExt, TryStreamExt};
 
+use super::foreign_keys::ForeignKeys;
+use super::returning;
+use super::table_exprs::{ReturningExprs, TableExprs};
 use crate::execution::TransactionHandle;
 use crate::schema::{RowId, Table};
 use crate::utils::rowconverter;
-use crate::Error;
 
 #[derive(Builder, Derivative)]
 #[derivative(Debug)]
 pub struct UpdateRowsExecutionPlan {
+  schema_name: Arc<str>,
   table: Arc<Table>,
   pub(crate) scanner: Arc<dyn ExecutionPlan>,
   #[builder(setter(skip), default = "self.default_schema()")]
   schema: SchemaRef,
   #[derivative(Debug = "ignore")]
   transaction: TransactionHandle,
+  /// `RETURNING` clause of the query
+  #[builder(default)]
+  #[derivative(Debug = "ignore")]
+  returning: Option<Arc<ReturningExprs>>,
 }
 
 impl UpdateRowsExecutionPlanBuilder {
   fn default_schema(&self) -> SchemaRef {
-    Arc::new(Schema::new(vec![Field::new(
-      "count",
-      DataType::UInt64,
-      false,
-    )]))
+    match self.returning.clone().flatten() {
+      Some(returning) => returning.schema(),
+      None => Arc::new(Schema::new(vec![Field::new(
+        "count",
+        DataType::UInt64,
+        false,
+      )])),
+    }
   }
 }
 
@@ -69,15 +78,30 @@ impl ExecutionPlan for UpdateRowsExecutionPlan {
     let schema = self.schema();
     let table = self.table.clone();
 
+    let foreign_keys =
+      ForeignKeys::new(&self.transaction, &self.schema_name, &self.table);
+    let table_exprs = TableExprs::new(&self.table, &context)?;
     let transaction = self.transaction.clone();
     let table_scanner = self.scanner.clone();
+    let returning = self.returning.clone();
     let update_fut = async move {
-      let stream = table_scanner.execute(partition, context)?;
-
-      let modified_rows_count: usize = stream
-        .map(move |batch| {
+      // Scan all the rows before modifying them so that the scan doesn't
+      // return the modified rows and its read lock is released
+      let batches = table_scanner
+        .execute(partition, context)?
+        .try_collect::<Vec<RecordBatch>>()
+        .await?;
+      transaction.wait_for_cancelled_scans().await;
+      let stream = futures::stream::iter(
+        batches.into_iter().map(Ok::<RecordBatch, DataFusionError>),
+      );
+
+      let returning_exprs = returning.clone();
+      let results = stream
+        .map(|batch| {
           let transaction = transaction.lock(true)?;
           batch.and_then(|batch| {
+            table_exprs.check(&batch)?;
             let rows =
               rowconverter::convert_to_rows(&table, &batch, true).unwrap();
             rows
@@ -91,7 +115,11 @@ impl ExecutionPlan for UpdateRowsExecutionPlan {
                 let old_row =
                   transaction.get_row(&table, &row_id_bytes)?.unwrap();
                 for table_index in &table.indexes {
-                  transaction.delete_row_from_index(&table_index, &old_row)?;
+                  transaction.delete_row_from_index(
+                    &table_index,
+                    &row_id_bytes,
+                    &old_row,
+                  )?;
                   transaction.add_row_to_index(
                     &table,
                     &table_index,
@@ -101,30 +129,38 @@ impl ExecutionPlan for UpdateRowsExecutionPlan {
                 }
                 transaction.delete_row(&table, &row_id_bytes)?;
                 transaction.insert_row(&table, &row_id_bytes, new_row)?;
+                foreign_keys.check_references(
+                  &transaction,
+                  &table,
+                  new_row,
+                  Some(&old_row),
+                )?;
+                foreign_keys.on_update(
+                  &transaction,
+                  &table,
+                  &old_row,
+                  new_row,
+                )?;
 
                 Ok(())
               })
               .collect::<Result<Vec<()>>>()?;
 
-            Ok(batch.num_rows())
+            let returned_rows = returning_exprs
+              .as_ref()
+              .map(|returning| returning.evaluate(&batch))
+              .transpose()?;
+            Ok((batch.num_rows(), returned_rows))
           })
         })
-        .try_collect::<Vec<usize>>()
-        .await?
-        .iter()
-        .sum();
-
-      Ok(
-        RecordBatch::try_new(
-          schema,
-          vec![Arc::new(UInt64Array::from(vec![
-            modified_rows_count as u64,
-          ]))],
-        )
-        .map_err(|e| {
-          Error::DataFusionError(DataFusionError::ArrowError(e).into())
-        })?,
-      )
+        .try_collect::<Vec<(usize, Option<RecordBatch>)>>()
+        .await?;
+      foreign_keys.check_no_action(&transaction.lock(true)?)?;
+
+      let modified_rows_count = results.iter().map(|(count, _)| count).sum();
+      let returned_rows = returning
+        .map(|_| results.into_iter().filter_map(|(_, rows)| rows).collect());
+      returning::create_dml_result(schema, modified_rows_count, returned_rows)
     };
 
     let stream = futures::stream::once(async move { update_fut.await }).boxed();
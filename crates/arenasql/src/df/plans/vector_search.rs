@@ -0,0 +1,220 @@
+use std::sync::Arc;
+
+use datafusion::common::tree_node::{Transformed, TreeNode};
+use datafusion::error::Result;
+use datafusion::physical_expr::expressions::{Column, Literal};
+use datafusion::physical_expr::{
+  split_conjunction, PhysicalSortExpr, ScalarFunctionExpr,
+};
+use datafusion::physical_plan::coalesce_batches::CoalesceBatchesExec;
+use datafusion::physical_plan::coalesce_partitions::CoalescePartitionsExec;
+use datafusion::physical_plan::filter::FilterExec;
+use datafusion::physical_plan::projection::ProjectionExec;
+use datafusion::physical_plan::repartition::RepartitionExec;
+use datafusion::physical_plan::sorts::sort::SortExec;
+use datafusion::physical_plan::ExecutionPlan;
+use datafusion::scalar::ScalarValue;
+
+use super::scan_table::{TableScaner, VectorSearch};
+use crate::schema::{DataType, IndexProvider, VectorMetric};
+
+/// Rewrites the physical plan of `ORDER BY l2(column, '[...]') LIMIT k`
+/// queries (or `inner_product`/`cosine_similarity` in descending order and
+/// `negative_inner_product`/`cosine_distance` in ascending order)
+/// such that only the k nearest rows returned by the vector index
+/// of the column are scanned, instead of the entire table.
+///
+/// If the vector index is partitioned by a namespace column, the rows must
+/// be filtered by `namespace_column = <value>` and only the vectors in that
+/// namespace are searched.
+///
+/// The scanned rows are still sorted by the sort expression, so the order
+/// of the returned rows is exact even though the index search is not.
+pub fn use_vector_index(
+  plan: Arc<dyn ExecutionPlan>,
+) -> Result<Arc<dyn ExecutionPlan>> {
+  plan.transform_down(&|plan| match with_vector_search(&plan)? {
+    Some(new_plan) => Ok(Transformed::Yes(new_plan)),
+    None => Ok(Transformed::No(plan)),
+  })
+}
+
+fn with_vector_search(
+  plan: &Arc<dyn ExecutionPlan>,
+) -> Result<Option<Arc<dyn ExecutionPlan>>> {
+  let Some(sort) = plan.as_any().downcast_ref::<SortExec>() else {
+    return Ok(None);
+  };
+  let (Some(k), [sort_expr]) = (sort.fetch(), sort.expr()) else {
+    return Ok(None);
+  };
+  let Some((metric, column, query)) = parse_vector_sort_expr(sort_expr) else {
+    return Ok(None);
+  };
+
+  match push_down_vector_search(
+    sort.input(),
+    column.index(),
+    &metric,
+    &query,
+    k,
+    0,
+  )? {
+    Some(input) => Ok(Some(plan.clone().with_new_children(vec![input])?)),
+    None => Ok(None),
+  }
+}
+
+/// Returns the vector metric, the vector column and the query vector
+/// if the sort expr orders the rows by the vector distance
+fn parse_vector_sort_expr(
+  sort_expr: &PhysicalSortExpr,
+) -> Option<(VectorMetric, &Column, Vec<f32>)> {
+  let function = sort_expr
+    .expr
+    .as_any()
+    .downcast_ref::<ScalarFunctionExpr>()?;
+  let metric = match (function.name(), sort_expr.options.descending) {
+    ("l2", false) => VectorMetric::L2,
+    ("inner_product", true) => VectorMetric::Dot,
+    ("cosine_similarity", true) => VectorMetric::Cos,
+    ("negative_inner_product", false) => VectorMetric::Dot,
+    ("cosine_distance", false) => VectorMetric::Cos,
+    _ => return None,
+  };
+
+  let [column, query] = function.args() else {
+    return None;
+  };
+  let column = column.as_any().downcast_ref::<Column>()?;
+  let query = match query.as_any().downcast_ref::<Literal>()?.value() {
+    ScalarValue::Utf8(Some(query)) => {
+      serde_json::from_str::<Vec<f32>>(query).ok()?
+    }
+    _ => return None,
+  };
+  Some((metric, column, query))
+}
+
+/// Finds the table scanner that provides the vector column and sets the
+/// vector search in it. Returns `None` if the table scanner can't be reached
+/// without changing the rows or if the column doesn't have a vector index.
+///
+/// `filter_count` is the number of filter predicates applied on top of
+/// the plan so far.
+fn push_down_vector_search(
+  plan: &Arc<dyn ExecutionPlan>,
+  column_index: usize,
+  metric: &VectorMetric,
+  query: &[f32],
+  k: usize,
+  filter_count: usize,
+) -> Result<Option<Arc<dyn ExecutionPlan>>> {
+  let plan_any = plan.as_any();
+  if let Some(scanner) = plan_any.downcast_ref::<TableScaner>() {
+    // Since the filter pushdown is inexact, the filters of the scanner are
+    // also applied by the filter plans above it. So, if there are more
+    // filters above than in the scanner, some of those filters can't be
+    // pushed down and rows that match them might not be in the nearest k
+    if scanner.filters().len() != filter_count {
+      return Ok(None);
+    }
+    let schema = plan.schema();
+    let column_name = schema.field(column_index).name();
+    let table = scanner.table();
+    let Some(column) = table.find_column(column_name) else {
+      return Ok(None);
+    };
+    // Let the scalar function return error if the query vector length
+    // doesn't match
+    match table.columns[column].data_type {
+      DataType::Vector { len } | DataType::QuantizedVector { len, .. }
+        if len == query.len() => {}
+      _ => return Ok(None),
+    }
+
+    return Ok(
+      table
+        .indexes
+        .iter()
+        .filter(|index| match &index.provider {
+          IndexProvider::HNSWIndex {
+            columns,
+            metric: index_metric,
+            ..
+          } => columns[0] == column && index_metric == metric,
+          _ => false,
+        })
+        .find_map(|index| {
+          let namespace = match index.namespace_column() {
+            // Rows that match the filters might not be in the nearest k rows
+            None if scanner.filters().is_empty() => None,
+            // All the filters must select the same namespace such that all
+            // the rows in the namespace match the filters
+            Some(_) => {
+              let namespaces = scanner
+                .filters()
+                .iter()
+                .map(|filter| filter.get_vector_index_namespace(index))
+                .collect::<Option<Vec<_>>>()?;
+              match namespaces.as_slice() {
+                [namespace, rest @ ..]
+                  if rest.iter().all(|n| n == namespace) =>
+                {
+                  Some(namespace.clone())
+                }
+                _ => return None,
+              }
+            }
+            _ => return None,
+          };
+          Some(Arc::new(scanner.with_vector_search(VectorSearch {
+            index: index.clone(),
+            namespace,
+            query: query.to_vec(),
+            k,
+          })) as Arc<dyn ExecutionPlan>)
+        }),
+    );
+  }
+
+  let mut filter_count = filter_count;
+  let column_index =
+    if let Some(projection) = plan_any.downcast_ref::<ProjectionExec>() {
+      match projection.expr()[column_index]
+        .0
+        .as_any()
+        .downcast_ref::<Column>()
+      {
+        Some(column) => column.index(),
+        None => return Ok(None),
+      }
+    } else if let Some(filter) = plan_any.downcast_ref::<FilterExec>() {
+      // Filter doesn't change the schema
+      filter_count += split_conjunction(filter.predicate()).len();
+      column_index
+    } else if plan_any.is::<CoalesceBatchesExec>()
+      || plan_any.is::<CoalescePartitionsExec>()
+      || plan_any.is::<RepartitionExec>()
+    {
+      column_index
+    } else {
+      return Ok(None);
+    };
+
+  let children = plan.children();
+  let [input] = children.as_slice() else {
+    return Ok(None);
+  };
+  match push_down_vector_search(
+    input,
+    column_index,
+    metric,
+    query,
+    k,
+    filter_count,
+  )? {
+    Some(input) => Ok(Some(plan.clone().with_new_children(vec![input])?)),
+    None => Ok(None),
+  }
+}
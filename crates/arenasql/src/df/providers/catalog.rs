@@ -7,6 +7,10 @@ use datafusion::catalog::{
 };
 use derive_builder::Builder;
 
+use super::information_schema::InformationSchemaProvider;
+use super::pg_catalog::{
+  PgCatalogSchemaProvider, INFORMATION_SCHEMA, PG_CATALOG_SCHEMA,
+};
 use super::schema::SchemaProviderBuilder;
 use crate::execution::TransactionHandle;
 
@@ -105,7 +109,19 @@ impl DfCatalogProvider for CatalogProvider {
   }
 
   fn schema(&self, schema_name: &str) -> Option<Arc<dyn DfSchemaProvider>> {
-    if self.schemas.iter().any(|s| s.as_str() == schema_name) {
+    if schema_name == PG_CATALOG_SCHEMA {
+      Some(Arc::new(PgCatalogSchemaProvider {
+        catalog: self.catalog.clone(),
+        schemas: self.schemas.clone(),
+        transaction: self.transaction.clone(),
+      }))
+    } else if schema_name == INFORMATION_SCHEMA {
+      Some(Arc::new(InformationSchemaProvider {
+        catalog: self.catalog.clone(),
+        schemas: self.schemas.clone(),
+        transaction: self.transaction.clone(),
+      }))
+    } else if self.schemas.iter().any(|s| s.as_str() == schema_name) {
       Some(Arc::new(
         SchemaProviderBuilder::default()
           .catalog(self.catalog.clone())
@@ -120,6 +136,16 @@ impl DfCatalogProvider for CatalogProvider {
   }
 
   fn schema_names(&self) -> Vec<String> {
-    self.schemas.as_ref().clone()
+    [PG_CATALOG_SCHEMA, INFORMATION_SCHEMA]
+      .into_iter()
+      .chain(
+        self
+          .schemas
+          .iter()
+          .map(|s| s.as_str())
+          .filter(|s| *s != PG_CATALOG_SCHEMA && *s != INFORMATION_SCHEMA),
+      )
+      .map(|s| s.to_owned())
+      .collect()
   }
 }
@@ -0,0 +1,221 @@
+use std::any::Any;
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use datafusion::arrow::array::{Int32Array, StringArray};
+use datafusion::arrow::datatypes::{
+  DataType as DfDataType, Field, Schema, SchemaRef,
+};
+use datafusion::arrow::record_batch::RecordBatch;
+use datafusion::catalog::schema::SchemaProvider as DfSchemaProvider;
+use datafusion::datasource::TableProvider as DfTableProvider;
+use datafusion::error::Result;
+
+use super::pg_catalog::{
+  is_not_null, list_tables, sql_type_name, to_mem_table, visible_columns,
+  INFORMATION_SCHEMA, PG_CATALOG_SCHEMA,
+};
+use crate::execution::TransactionHandle;
+use crate::schema::{DataType, VECTOR_OID};
+
+/// Read only `information_schema` with the tables derived from the tables
+/// of the schemas of the session. This replaces Datafusion's information
+/// schema since the columns of its tables aren't the same as Postgres'
+pub struct InformationSchemaProvider {
+  pub catalog: Arc<str>,
+  pub schemas: Arc<Vec<String>>,
+  pub transaction: TransactionHandle,
+}
+
+impl InformationSchemaProvider {
+  const TABLES: [&'static str; 3] = ["columns", "schemata", "tables"];
+
+  fn load_table(&self, name: &str) -> Result<Option<RecordBatch>> {
+    let batch = match name {
+      "columns" => self.columns()?,
+      "schemata" => self.schemata()?,
+      "tables" => self.tables()?,
+      _ => return Ok(None),
+    };
+    Ok(Some(batch))
+  }
+
+  fn schemata(&self) -> Result<RecordBatch> {
+    let schema = SchemaRef::new(Schema::new(vec![
+      Field::new("catalog_name", DfDataType::Utf8, false),
+      Field::new("schema_name", DfDataType::Utf8, false),
+      Field::new("schema_owner", DfDataType::Utf8, false),
+    ]));
+
+    let names = [PG_CATALOG_SCHEMA, INFORMATION_SCHEMA]
+      .into_iter()
+      .chain(self.schemas.iter().map(|s| s.as_str()))
+      .collect::<Vec<&str>>();
+    let count = names.len();
+    Ok(RecordBatch::try_new(
+      schema,
+      vec![
+        Arc::new(StringArray::from(vec![self.catalog.as_ref(); count])),
+        Arc::new(StringArray::from(names)),
+        Arc::new(StringArray::from(vec!["postgres"; count])),
+      ],
+    )?)
+  }
+
+  fn tables(&self) -> Result<RecordBatch> {
+    let schema = SchemaRef::new(Schema::new(vec![
+      Field::new("table_catalog", DfDataType::Utf8, false),
+      Field::new("table_schema", DfDataType::Utf8, false),
+      Field::new("table_name", DfDataType::Utf8, false),
+      Field::new("table_type", DfDataType::Utf8, false),
+    ]));
+
+    let tables = list_tables(&self.transaction, &self.schemas);
+    let count = tables.len();
+    Ok(RecordBatch::try_new(
+      schema,
+      vec![
+        Arc::new(StringArray::from(vec![self.catalog.as_ref(); count])),
+        Arc::new(StringArray::from_iter_values(
+          tables.iter().map(|(schema, _)| schema),
+        )),
+        Arc::new(StringArray::from_iter_values(
+          tables.iter().map(|(_, table)| &table.name),
+        )),
+        Arc::new(StringArray::from(vec!["BASE TABLE"; count])),
+      ],
+    )?)
+  }
+
+  fn columns(&self) -> Result<RecordBatch> {
+    let schema = SchemaRef::new(Schema::new(vec![
+      Field::new("table_catalog", DfDataType::Utf8, false),
+      Field::new("table_schema", DfDataType::Utf8, false),
+      Field::new("table_name", DfDataType::Utf8, false),
+      Field::new("column_name", DfDataType::Utf8, false),
+      Field::new("ordinal_position", DfDataType::Int32, false),
+      Field::new("column_default", DfDataType::Utf8, true),
+      Field::new("is_nullable", DfDataType::Utf8, false),
+      Field::new("data_type", DfDataType::Utf8, false),
+      Field::new("character_maximum_length", DfDataType::Int32, true),
+      Field::new("numeric_precision", DfDataType::Int32, true),
+      Field::new("numeric_scale", DfDataType::Int32, true),
+      Field::new("udt_name", DfDataType::Utf8, false),
+      Field::new("is_identity", DfDataType::Utf8, false),
+      Field::new("identity_generation", DfDataType::Utf8, true),
+    ]));
+
+    let mut table_schemas = vec![];
+    let mut table_names = vec![];
+    let mut column_names = vec![];
+    let mut positions = vec![];
+    let mut defaults = vec![];
+    let mut nullable = vec![];
+    let mut data_types = vec![];
+    let mut max_lengths = vec![];
+    let mut precisions = vec![];
+    let mut scales = vec![];
+    let mut udt_names = vec![];
+    let mut identity = vec![];
+    let mut identity_generation = vec![];
+    for (schema_name, table) in list_tables(&self.transaction, &self.schemas) {
+      for column in visible_columns(&table) {
+        let pg_type = column.data_type.pg_type();
+        table_schemas.push(schema_name.clone());
+        table_names.push(table.name.clone());
+        column_names.push(column.name.clone());
+        positions.push(column.id as i32 + 1);
+        defaults.push(column.default_expr.clone());
+        nullable.push(match is_not_null(&table, column) {
+          true => "NO",
+          false => "YES",
+        });
+        data_types.push(match pg_type.oid() {
+          VECTOR_OID => "USER-DEFINED".to_owned(),
+          _ => sql_type_name(&pg_type),
+        });
+        max_lengths.push(match column.data_type {
+          DataType::Varchar { len } => len.map(|len| len as i32),
+          _ => None,
+        });
+        let (precision, scale) = numeric_precision_and_scale(&column.data_type);
+        precisions.push(precision);
+        scales.push(scale);
+        udt_names.push(pg_type.name().to_owned());
+        identity.push(if column.generated_always() {
+          "YES"
+        } else {
+          "NO"
+        });
+        identity_generation.push(column.generated_always().then_some("ALWAYS"));
+      }
+    }
+
+    let count = table_names.len();
+    Ok(RecordBatch::try_new(
+      schema,
+      vec![
+        Arc::new(StringArray::from(vec![self.catalog.as_ref(); count])),
+        Arc::new(StringArray::from(table_schemas)),
+        Arc::new(StringArray::from(table_names)),
+        Arc::new(StringArray::from(column_names)),
+        Arc::new(Int32Array::from(positions)),
+        Arc::new(StringArray::from(defaults)),
+        Arc::new(StringArray::from(nullable)),
+        Arc::new(StringArray::from(data_types)),
+        Arc::new(Int32Array::from(max_lengths)),
+        Arc::new(Int32Array::from(precisions)),
+        Arc::new(Int32Array::from(scales)),
+        Arc::new(StringArray::from(udt_names)),
+        Arc::new(StringArray::from(identity)),
+        Arc::new(StringArray::from(identity_generation)),
+      ],
+    )?)
+  }
+}
+
+#[async_trait]
+impl DfSchemaProvider for InformationSchemaProvider {
+  fn as_any(&self) -> &dyn Any {
+    self
+  }
+
+  fn table_names(&self) -> Vec<String> {
+    Self::TABLES.iter().map(|t| t.to_string()).collect()
+  }
+
+  async fn table(&self, name: &str) -> Option<Arc<dyn DfTableProvider>> {
+    match self.load_table(name) {
+      Ok(batch) => batch.and_then(to_mem_table),
+      Err(err) => {
+        tracing::error!(
+          "Error loading {}.{}: {:?}",
+          INFORMATION_SCHEMA,
+          name,
+          err
+        );
+        None
+      }
+    }
+  }
+
+  fn table_exist(&self, name: &str) -> bool {
+    Self::TABLES.contains(&name)
+  }
+}
+
+/// Returns the precision and scale of the numeric types in base 2 for
+/// integers and floats and in base 10 for decimals
+fn numeric_precision_and_scale(
+  data_type: &DataType,
+) -> (Option<i32>, Option<i32>) {
+  match data_type {
+    DataType::Int16 => (Some(16), Some(0)),
+    DataType::Int32 | DataType::UInt32 => (Some(32), Some(0)),
+    DataType::Int64 | DataType::UInt64 => (Some(64), Some(0)),
+    DataType::Float32 => (Some(24), None),
+    DataType::Float64 => (Some(53), None),
+    DataType::Decimal { p, s } => (Some(*p as i32), Some(*s as i32)),
+    _ => (None, None),
+  }
+}
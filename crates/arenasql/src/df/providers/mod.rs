@@ -1,4 +1,6 @@
 pub(crate) mod catalog;
+pub(crate) mod information_schema;
+pub(crate) mod pg_catalog;
 pub(crate) mod schema;
 pub(crate) mod table;
 
@@ -6,5 +8,6 @@ pub use catalog::{
   CatalogListProvider, CatalogProvider, NoopCatalogListProvider,
   SingleCatalogListProvider,
 };
+pub use pg_catalog::{pg_database, INFORMATION_SCHEMA, PG_CATALOG_SCHEMA};
 pub use schema::{get_schema_provider, SchemaProviderBuilder};
 pub use table::get_table_ref;
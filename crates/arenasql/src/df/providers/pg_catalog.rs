@@ -0,0 +1,724 @@
+use std::any::Any;
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use datafusion::arrow::array::{
+  ArrayRef, BooleanArray, Int16Array, Int32Array, StringArray,
+};
+use datafusion::arrow::datatypes::{
+  DataType as DfDataType, Field, Schema, SchemaRef,
+};
+use datafusion::arrow::record_batch::RecordBatch;
+use datafusion::catalog::schema::SchemaProvider as DfSchemaProvider;
+use datafusion::datasource::{MemTable, TableProvider as DfTableProvider};
+use datafusion::error::Result;
+use postgres_types::{Kind, Type};
+
+use crate::execution::TransactionHandle;
+use crate::schema::{
+  Column, Constraint, DataType, IndexProvider, Table, TableIndex, VECTOR_OID,
+  VECTOR_PG_TYPE,
+};
+
+pub const PG_CATALOG_SCHEMA: &str = "pg_catalog";
+pub const INFORMATION_SCHEMA: &str = "information_schema";
+
+/// Oid of the owner of all the objects; same as the bootstrap superuser
+/// in Postgres
+const OWNER_OID: i32 = 10;
+const PG_CATALOG_NAMESPACE_OID: i32 = 11;
+const PUBLIC_NAMESPACE_OID: i32 = 2200;
+const INFORMATION_SCHEMA_NAMESPACE_OID: i32 = 13000;
+/// Oids of the other schemas start from here in the order of the schemas
+/// of the session
+const SCHEMA_OID_OFFSET: i32 = 14000;
+/// Table and index ids are unique to the database, so they are offset
+/// to get their oids. The offsets are far apart so that the oids of the
+/// tables and indexes don't overlap with each other nor with the types
+const TABLE_OID_OFFSET: i32 = 1 << 17;
+const INDEX_OID_OFFSET: i32 = 3 << 16;
+/// Oid of the btree access method
+const BTREE_AM_OID: i32 = 403;
+/// Oid of the hnsw access method of the vector indexes; next to the oid
+/// of the vector type since it isn't a builtin access method
+const HNSW_AM_OID: i32 = 16386;
+
+/// Read only `pg_catalog` schema whose tables are derived from the
+/// tables of the schemas of the session. Only the columns that are
+/// commonly used by the clients and ORMs are included
+pub struct PgCatalogSchemaProvider {
+  pub catalog: Arc<str>,
+  pub schemas: Arc<Vec<String>>,
+  pub transaction: TransactionHandle,
+}
+
+impl PgCatalogSchemaProvider {
+  const TABLES: [&'static str; 7] = [
+    "pg_am",
+    "pg_attribute",
+    "pg_class",
+    "pg_database",
+    "pg_index",
+    "pg_namespace",
+    "pg_type",
+  ];
+
+  fn load_table(&self, name: &str) -> Result<Option<RecordBatch>> {
+    let batch = match name {
+      "pg_am" => pg_am()?,
+      "pg_attribute" => self.pg_attribute()?,
+      "pg_class" => self.pg_class()?,
+      "pg_database" => pg_database(&[self.catalog.to_string()])?,
+      "pg_index" => self.pg_index()?,
+      "pg_namespace" => self.pg_namespace()?,
+      "pg_type" => pg_type()?,
+      _ => return Ok(None),
+    };
+    Ok(Some(batch))
+  }
+
+  fn pg_namespace(&self) -> Result<RecordBatch> {
+    let schema = SchemaRef::new(Schema::new(vec![
+      Field::new("oid", DfDataType::Int32, false),
+      Field::new("nspname", DfDataType::Utf8, false),
+      Field::new("nspowner", DfDataType::Int32, false),
+    ]));
+
+    let names = [PG_CATALOG_SCHEMA, INFORMATION_SCHEMA]
+      .into_iter()
+      .chain(self.schemas.iter().map(|s| s.as_str()))
+      .collect::<Vec<&str>>();
+    let oids = names
+      .iter()
+      .map(|name| namespace_oid(&self.schemas, name))
+      .collect::<Vec<i32>>();
+    let count = names.len();
+    Ok(RecordBatch::try_new(
+      schema,
+      vec![
+        Arc::new(Int32Array::from(oids)),
+        Arc::new(StringArray::from(names)),
+        Arc::new(Int32Array::from(vec![OWNER_OID; count])),
+      ],
+    )?)
+  }
+
+  fn pg_class(&self) -> Result<RecordBatch> {
+    let schema = SchemaRef::new(Schema::new(vec![
+      Field::new("oid", DfDataType::Int32, false),
+      Field::new("relname", DfDataType::Utf8, false),
+      Field::new("relnamespace", DfDataType::Int32, false),
+      Field::new("reltype", DfDataType::Int32, false),
+      Field::new("relowner", DfDataType::Int32, false),
+      Field::new("relam", DfDataType::Int32, false),
+      Field::new("relkind", DfDataType::Utf8, false),
+      Field::new("relnatts", DfDataType::Int16, false),
+      Field::new("relhasindex", DfDataType::Boolean, false),
+      Field::new("relpersistence", DfDataType::Utf8, false),
+      Field::new("relispartition", DfDataType::Boolean, false),
+    ]));
+
+    let mut rows = vec![];
+    for (schema_name, table) in self.tables() {
+      let namespace = namespace_oid(&self.schemas, &schema_name);
+      rows.push(PgClassRow {
+        oid: table_oid(&table),
+        name: table.name.clone(),
+        namespace,
+        access_method: 0,
+        kind: "r",
+        num_attributes: visible_columns(&table).count() as i16,
+        has_index: !table.indexes.is_empty(),
+      });
+      for index in &table.indexes {
+        rows.push(PgClassRow {
+          oid: index_oid(index),
+          name: index.name.clone(),
+          namespace,
+          access_method: match index.is_vector_index() {
+            true => HNSW_AM_OID,
+            false => BTREE_AM_OID,
+          },
+          kind: "i",
+          num_attributes: index.provider.columns().len() as i16,
+          has_index: false,
+        });
+      }
+    }
+
+    let count = rows.len();
+    Ok(RecordBatch::try_new(
+      schema,
+      vec![
+        Arc::new(Int32Array::from_iter_values(rows.iter().map(|r| r.oid))),
+        Arc::new(StringArray::from_iter_values(rows.iter().map(|r| &r.name))),
+        Arc::new(Int32Array::from_iter_values(
+          rows.iter().map(|r| r.namespace),
+        )),
+        Arc::new(Int32Array::from(vec![0; count])),
+        Arc::new(Int32Array::from(vec![OWNER_OID; count])),
+        Arc::new(Int32Array::from_iter_values(
+          rows.iter().map(|r| r.access_method),
+        )),
+        Arc::new(StringArray::from_iter_values(rows.iter().map(|r| r.kind))),
+        Arc::new(Int16Array::from_iter_values(
+          rows.iter().map(|r| r.num_attributes),
+        )),
+        Arc::new(BooleanArray::from(
+          rows.iter().map(|r| r.has_index).collect::<Vec<bool>>(),
+        )),
+        Arc::new(StringArray::from(vec!["p"; count])),
+        Arc::new(BooleanArray::from(vec![false; count])),
+      ],
+    )?)
+  }
+
+  fn pg_attribute(&self) -> Result<RecordBatch> {
+    let schema = SchemaRef::new(Schema::new(vec![
+      Field::new("attrelid", DfDataType::Int32, false),
+      Field::new("attname", DfDataType::Utf8, false),
+      Field::new("atttypid", DfDataType::Int32, false),
+      Field::new("attlen", DfDataType::Int16, false),
+      Field::new("attnum", DfDataType::Int16, false),
+      Field::new("atttypmod", DfDataType::Int32, false),
+      Field::new("attnotnull", DfDataType::Boolean, false),
+      Field::new("atthasdef", DfDataType::Boolean, false),
+      Field::new("attidentity", DfDataType::Utf8, false),
+      Field::new("attgenerated", DfDataType::Utf8, false),
+      Field::new("attisdropped", DfDataType::Boolean, false),
+    ]));
+
+    let mut relids = vec![];
+    let mut names = vec![];
+    let mut type_oids = vec![];
+    let mut lengths = vec![];
+    let mut numbers = vec![];
+    let mut type_modifiers = vec![];
+    let mut not_null = vec![];
+    let mut has_default = vec![];
+    let mut identity = vec![];
+    let mut dropped = vec![];
+    for (_, table) in self.tables() {
+      // Include the dropped columns so that the attribute numbers of the
+      // columns match the column ids
+      for column in &table.columns {
+        let pg_type = column.data_type.pg_type();
+        relids.push(table_oid(&table));
+        names.push(column.name.clone());
+        type_oids.push(pg_type.oid() as i32);
+        lengths.push(type_length(&pg_type));
+        numbers.push(column.id as i16 + 1);
+        type_modifiers.push(type_modifier(&column.data_type));
+        not_null.push(is_not_null(&table, column));
+        has_default.push(column.default_expr.is_some());
+        identity.push(if column.generated_always() { "a" } else { "" });
+        dropped.push(column.archived());
+      }
+
+      for index in &table.indexes {
+        for (position, column) in index
+          .provider
+          .columns()
+          .iter()
+          .filter_map(|col| table.columns.get(*col))
+          .enumerate()
+        {
+          let pg_type = column.data_type.pg_type();
+          relids.push(index_oid(index));
+          names.push(column.name.clone());
+          type_oids.push(pg_type.oid() as i32);
+          lengths.push(type_length(&pg_type));
+          numbers.push(position as i16 + 1);
+          type_modifiers.push(type_modifier(&column.data_type));
+          not_null.push(false);
+          has_default.push(false);
+          identity.push("");
+          dropped.push(false);
+        }
+      }
+    }
+
+    let count = relids.len();
+    Ok(RecordBatch::try_new(
+      schema,
+      vec![
+        Arc::new(Int32Array::from(relids)),
+        Arc::new(StringArray::from(names)),
+        Arc::new(Int32Array::from(type_oids)),
+        Arc::new(Int16Array::from(lengths)),
+        Arc::new(Int16Array::from(numbers)),
+        Arc::new(Int32Array::from(type_modifiers)),
+        Arc::new(BooleanArray::from(not_null)),
+        Arc::new(BooleanArray::from(has_default)),
+        Arc::new(StringArray::from(identity)),
+        Arc::new(StringArray::from(vec![""; count])),
+        Arc::new(BooleanArray::from(dropped)),
+      ],
+    )?)
+  }
+
+  fn pg_index(&self) -> Result<RecordBatch> {
+    let schema = SchemaRef::new(Schema::new(vec![
+      Field::new("indexrelid", DfDataType::Int32, false),
+      Field::new("indrelid", DfDataType::Int32, false),
+      Field::new("indnatts", DfDataType::Int16, false),
+      Field::new("indnkeyatts", DfDataType::Int16, false),
+      Field::new("indisunique", DfDataType::Boolean, false),
+      Field::new("indisprimary", DfDataType::Boolean, false),
+      Field::new("indisvalid", DfDataType::Boolean, false),
+      Field::new("indisready", DfDataType::Boolean, false),
+      // `int2vector` is returned as space separated column numbers which
+      // is its text representation in Postgres
+      Field::new("indkey", DfDataType::Utf8, false),
+    ]));
+
+    let mut index_oids = vec![];
+    let mut table_oids = vec![];
+    let mut num_columns = vec![];
+    let mut unique = vec![];
+    let mut primary = vec![];
+    let mut keys = vec![];
+    for (_, table) in self.tables() {
+      for index in &table.indexes {
+        let columns = index.provider.columns();
+        index_oids.push(index_oid(index));
+        table_oids.push(table_oid(&table));
+        num_columns.push(columns.len() as i16);
+        unique.push(index.provider.is_unique());
+        primary.push(is_primary_key(&table, index));
+        keys.push(
+          columns
+            .iter()
+            .map(|col| (col + 1).to_string())
+            .collect::<Vec<String>>()
+            .join(" "),
+        );
+      }
+    }
+
+    let count = index_oids.len();
+    Ok(RecordBatch::try_new(
+      schema,
+      vec![
+        Arc::new(Int32Array::from(index_oids)),
+        Arc::new(Int32Array::from(table_oids)),
+        Arc::new(Int16Array::from(num_columns.clone())),
+        Arc::new(Int16Array::from(num_columns)),
+        Arc::new(BooleanArray::from(unique)),
+        Arc::new(BooleanArray::from(primary)),
+        Arc::new(BooleanArray::from(vec![true; count])),
+        Arc::new(BooleanArray::from(vec![true; count])),
+        Arc::new(StringArray::from(keys)),
+      ],
+    )?)
+  }
+
+  /// Returns all the tables of the schemas of the session sorted by
+  /// their names
+  fn tables(&self) -> Vec<(String, Arc<Table>)> {
+    list_tables(&self.transaction, &self.schemas)
+  }
+}
+
+#[async_trait]
+impl DfSchemaProvider for PgCatalogSchemaProvider {
+  fn as_any(&self) -> &dyn Any {
+    self
+  }
+
+  fn table_names(&self) -> Vec<String> {
+    Self::TABLES.iter().map(|t| t.to_string()).collect()
+  }
+
+  async fn table(&self, name: &str) -> Option<Arc<dyn DfTableProvider>> {
+    match self.load_table(name) {
+      Ok(batch) => batch.and_then(to_mem_table),
+      Err(err) => {
+        tracing::error!(
+          "Error loading {}.{}: {:?}",
+          PG_CATALOG_SCHEMA,
+          name,
+          err
+        );
+        None
+      }
+    }
+  }
+
+  fn table_exist(&self, name: &str) -> bool {
+    Self::TABLES.contains(&name)
+  }
+}
+
+struct PgClassRow {
+  oid: i32,
+  name: String,
+  namespace: i32,
+  access_method: i32,
+  kind: &'static str,
+  num_attributes: i16,
+  has_index: bool,
+}
+
+/// Returns `pg_database` table with the given databases
+pub fn pg_database(databases: &[String]) -> Result<RecordBatch> {
+  let schema = SchemaRef::new(Schema::new(vec![
+    Field::new("oid", DfDataType::Int32, false),
+    Field::new("datname", DfDataType::Utf8, false),
+    Field::new("datdba", DfDataType::Int32, false),
+    Field::new("encoding", DfDataType::Int32, false),
+    Field::new("datcollate", DfDataType::Utf8, false),
+    Field::new("datctype", DfDataType::Utf8, false),
+    Field::new("datistemplate", DfDataType::Boolean, false),
+    Field::new("datallowconn", DfDataType::Boolean, false),
+    Field::new("datconnlimit", DfDataType::Int32, false),
+  ]));
+
+  let count = databases.len();
+  // Databases don't have ids, so use their position as the oid
+  let oids = (1..=count as i32).collect::<Vec<i32>>();
+  let columns: Vec<ArrayRef> = vec![
+    Arc::new(Int32Array::from(oids)),
+    Arc::new(StringArray::from(databases.to_vec())),
+    Arc::new(Int32Array::from(vec![OWNER_OID; count])),
+    // UTF8
+    Arc::new(Int32Array::from(vec![6; count])),
+    Arc::new(StringArray::from(vec!["C"; count])),
+    Arc::new(StringArray::from(vec!["C"; count])),
+    Arc::new(BooleanArray::from(vec![false; count])),
+    Arc::new(BooleanArray::from(vec![true; count])),
+    Arc::new(Int32Array::from(vec![-1; count])),
+  ];
+  Ok(RecordBatch::try_new(schema, columns)?)
+}
+
+/// Returns `pg_am` table with the access methods of the indexes
+fn pg_am() -> Result<RecordBatch> {
+  let schema = SchemaRef::new(Schema::new(vec![
+    Field::new("oid", DfDataType::Int32, false),
+    Field::new("amname", DfDataType::Utf8, false),
+    Field::new("amtype", DfDataType::Utf8, false),
+  ]));
+
+  Ok(RecordBatch::try_new(
+    schema,
+    vec![
+      Arc::new(Int32Array::from(vec![BTREE_AM_OID, HNSW_AM_OID])),
+      Arc::new(StringArray::from(vec!["btree", "hnsw"])),
+      Arc::new(StringArray::from(vec!["i", "i"])),
+    ],
+  )?)
+}
+
+/// Returns `pg_type` table with the types that the columns can have
+fn pg_type() -> Result<RecordBatch> {
+  let schema = SchemaRef::new(Schema::new(vec![
+    Field::new("oid", DfDataType::Int32, false),
+    Field::new("typname", DfDataType::Utf8, false),
+    Field::new("typnamespace", DfDataType::Int32, false),
+    Field::new("typowner", DfDataType::Int32, false),
+    Field::new("typlen", DfDataType::Int16, false),
+    Field::new("typtype", DfDataType::Utf8, false),
+    Field::new("typcategory", DfDataType::Utf8, false),
+    Field::new("typelem", DfDataType::Int32, false),
+    Field::new("typbasetype", DfDataType::Int32, false),
+    Field::new("typtypmod", DfDataType::Int32, false),
+    Field::new("typnotnull", DfDataType::Boolean, false),
+  ]));
+
+  let types = supported_types();
+  let count = types.len();
+  Ok(RecordBatch::try_new(
+    schema,
+    vec![
+      Arc::new(Int32Array::from_iter_values(
+        types.iter().map(|t| t.oid() as i32),
+      )),
+      Arc::new(StringArray::from_iter_values(
+        types.iter().map(|t| t.name()),
+      )),
+      Arc::new(Int32Array::from_iter_values(types.iter().map(|t| {
+        match t.schema() {
+          "public" => PUBLIC_NAMESPACE_OID,
+          _ => PG_CATALOG_NAMESPACE_OID,
+        }
+      }))),
+      Arc::new(Int32Array::from(vec![OWNER_OID; count])),
+      Arc::new(Int16Array::from_iter_values(types.iter().map(type_length))),
+      Arc::new(StringArray::from(vec!["b"; count])),
+      Arc::new(StringArray::from_iter_values(
+        types.iter().map(type_category),
+      )),
+      Arc::new(Int32Array::from_iter_values(types.iter().map(
+        |t| match t.kind() {
+          Kind::Array(element) => element.oid() as i32,
+          _ => 0,
+        },
+      ))),
+      Arc::new(Int32Array::from(vec![0; count])),
+      Arc::new(Int32Array::from(vec![-1; count])),
+      Arc::new(BooleanArray::from(vec![false; count])),
+    ],
+  )?)
+}
+
+/// Returns all the tables of the given schemas sorted by their names
+pub(super) fn list_tables(
+  transaction: &TransactionHandle,
+  schemas: &[String],
+) -> Vec<(String, Arc<Table>)> {
+  schemas
+    .iter()
+    .flat_map(|schema| {
+      let mut names = transaction.table_names(schema);
+      names.sort();
+      names
+        .into_iter()
+        .filter_map(|name| transaction.get_table(schema, &name))
+        .map(|table| (schema.clone(), table))
+        .collect::<Vec<(String, Arc<Table>)>>()
+    })
+    .collect()
+}
+
+pub(super) fn to_mem_table(
+  batch: RecordBatch,
+) -> Option<Arc<dyn DfTableProvider>> {
+  MemTable::try_new(batch.schema(), vec![vec![batch]])
+    .map(|table| Arc::new(table) as Arc<dyn DfTableProvider>)
+    .ok()
+}
+
+/// Returns the columns of the table that aren't dropped
+pub(super) fn visible_columns(table: &Table) -> impl Iterator<Item = &Column> {
+  table.columns.iter().filter(|col| !col.archived())
+}
+
+fn namespace_oid(schemas: &[String], schema: &str) -> i32 {
+  match schema {
+    PG_CATALOG_SCHEMA => PG_CATALOG_NAMESPACE_OID,
+    INFORMATION_SCHEMA => INFORMATION_SCHEMA_NAMESPACE_OID,
+    "public" => PUBLIC_NAMESPACE_OID,
+    _ => {
+      SCHEMA_OID_OFFSET
+        + schemas.iter().position(|s| s == schema).unwrap_or_default() as i32
+    }
+  }
+}
+
+pub(crate) fn table_oid(table: &Table) -> i32 {
+  TABLE_OID_OFFSET + table.id as i32
+}
+
+pub(crate) fn index_oid(index: &TableIndex) -> i32 {
+  INDEX_OID_OFFSET + index.id as i32
+}
+
+/// Returns the index of the given oid if it's an index oid
+pub(crate) fn index_id_from_oid(oid: i64) -> Option<u16> {
+  u16::try_from(oid - INDEX_OID_OFFSET as i64).ok()
+}
+
+/// Returns whether the column is `NOT NULL`; primary key columns are
+/// always not null
+pub(super) fn is_not_null(table: &Table, column: &Column) -> bool {
+  !column.nullable()
+    || table.constraints.iter().any(|constraint| match constraint {
+      Constraint::PrimaryKey(columns) => {
+        columns.contains(&(column.id as usize))
+      }
+      _ => false,
+    })
+}
+
+fn is_primary_key(table: &Table, index: &TableIndex) -> bool {
+  table.constraints.iter().any(|constraint| match constraint {
+    Constraint::PrimaryKey(columns) => {
+      index.provider.is_unique() && index.provider.columns() == columns
+    }
+    _ => false,
+  })
+}
+
+/// Returns `atttypmod` of the column; i.e. the length of `VARCHAR`, the
+/// precision and scale of `NUMERIC` and the dimension of `VECTOR`
+pub(super) fn type_modifier(data_type: &DataType) -> i32 {
+  // Postgres adds the size of the varlena header to the modifiers of
+  // VARCHAR and NUMERIC
+  const VARHDRSZ: i32 = 4;
+  match data_type {
+    DataType::Varchar { len: Some(len) } => *len as i32 + VARHDRSZ,
+    DataType::Decimal { p, s } => {
+      ((*p as i32) << 16 | (*s as i32 & 0xffff)) + VARHDRSZ
+    }
+    DataType::Vector { len } | DataType::QuantizedVector { len, .. } => {
+      *len as i32
+    }
+    DataType::Array { element } => type_modifier(element),
+    _ => -1,
+  }
+}
+
+/// Returns the type the way Postgres' `format_type(oid, typmod)` does;
+/// e.g. `character varying(255)` and `integer[]`
+pub(crate) fn format_type(oid: u32, typmod: i32) -> Option<String> {
+  let pg_type = match oid {
+    VECTOR_OID => VECTOR_PG_TYPE.clone(),
+    oid => Type::from_oid(oid)?,
+  };
+  if let Kind::Array(element) = pg_type.kind() {
+    return Some(format!("{}[]", format_type(element.oid(), typmod)?));
+  }
+
+  let name = sql_type_name(&pg_type);
+  const VARHDRSZ: i32 = 4;
+  Some(match typmod {
+    typmod if typmod < 0 => name,
+    typmod if pg_type == Type::VARCHAR => {
+      format!("{}({})", name, typmod - VARHDRSZ)
+    }
+    typmod if pg_type == Type::NUMERIC => {
+      let typmod = typmod - VARHDRSZ;
+      format!("{}({},{})", name, (typmod >> 16) & 0xffff, typmod & 0xffff)
+    }
+    typmod if oid == VECTOR_OID => format!("{}({})", name, typmod),
+    _ => name,
+  })
+}
+
+/// Returns the SQL name of the type; e.g. `integer` for `int4`. Arrays are
+/// named `ARRAY` the way `information_schema` names them
+pub(super) fn sql_type_name(pg_type: &Type) -> String {
+  match *pg_type {
+    Type::BOOL => "boolean",
+    Type::INT2 => "smallint",
+    Type::INT4 => "integer",
+    Type::INT8 => "bigint",
+    Type::FLOAT4 => "real",
+    Type::FLOAT8 => "double precision",
+    Type::VARCHAR => "character varying",
+    Type::TIMESTAMP => "timestamp without time zone",
+    Type::TIMESTAMPTZ => "timestamp with time zone",
+    Type::TIME => "time without time zone",
+    _ => match pg_type.kind() {
+      Kind::Array(_) => "ARRAY",
+      _ => pg_type.name(),
+    },
+  }
+  .to_owned()
+}
+
+/// Returns the types of the columns and their array types
+fn supported_types() -> Vec<Type> {
+  let types = vec![
+    Type::BOOL,
+    Type::BYTEA,
+    Type::INT2,
+    Type::INT4,
+    Type::INT8,
+    Type::VARCHAR,
+    Type::TEXT,
+    Type::FLOAT4,
+    Type::FLOAT8,
+    Type::NUMERIC,
+    Type::JSONB,
+    Type::TIMESTAMP,
+    Type::UUID,
+    Type::DATE,
+    Type::TIME,
+    Type::TIMESTAMPTZ,
+    Type::INTERVAL,
+  ];
+  let arrays = types
+    .iter()
+    .filter_map(crate::schema::array_pg_type)
+    .collect::<Vec<Type>>();
+  types
+    .into_iter()
+    .chain(arrays)
+    .chain([VECTOR_PG_TYPE.clone()])
+    .collect()
+}
+
+/// Returns `typlen`; i.e. the size of the fixed size types and -1 for
+/// the variable length types
+fn type_length(pg_type: &Type) -> i16 {
+  match *pg_type {
+    Type::BOOL => 1,
+    Type::INT2 => 2,
+    Type::INT4 | Type::FLOAT4 | Type::DATE => 4,
+    Type::INT8
+    | Type::FLOAT8
+    | Type::TIME
+    | Type::TIMESTAMP
+    | Type::TIMESTAMPTZ => 8,
+    Type::INTERVAL | Type::UUID => 16,
+    _ => -1,
+  }
+}
+
+/// Returns `typcategory` of the type
+fn type_category(pg_type: &Type) -> &'static str {
+  match *pg_type {
+    Type::BOOL => "B",
+    Type::INT2
+    | Type::INT4
+    | Type::INT8
+    | Type::FLOAT4
+    | Type::FLOAT8
+    | Type::NUMERIC => "N",
+    Type::VARCHAR | Type::TEXT => "S",
+    Type::DATE | Type::TIME | Type::TIMESTAMP | Type::TIMESTAMPTZ => "D",
+    Type::INTERVAL => "T",
+    _ => match pg_type.kind() {
+      Kind::Array(_) => "A",
+      _ => "U",
+    },
+  }
+}
+
+/// Returns the `CREATE INDEX` statement of the index the way
+/// `pg_get_indexdef` does
+pub(crate) fn index_definition(
+  schema: &str,
+  table: &Table,
+  index: &TableIndex,
+) -> String {
+  let column_name = |col: &usize| {
+    table
+      .columns
+      .get(*col)
+      .map(|c| c.name.clone())
+      .unwrap_or_default()
+  };
+  let (method, columns) = match &index.provider {
+    IndexProvider::BasicIndex { columns, .. } => {
+      ("btree", columns.iter().map(column_name).collect::<Vec<_>>())
+    }
+    IndexProvider::HNSWIndex { columns, .. } => {
+      ("hnsw", columns.iter().map(column_name).collect::<Vec<_>>())
+    }
+    IndexProvider::JsonPathIndex { columns, path, .. } => (
+      "btree",
+      columns
+        .iter()
+        .map(|col| {
+          format!("({} #> '{{{}}}')", column_name(col), path.join(","))
+        })
+        .collect::<Vec<_>>(),
+    ),
+  };
+  format!(
+    "CREATE {}INDEX {} ON {}.{} USING {} ({})",
+    if index.provider.is_unique() {
+      "UNIQUE "
+    } else {
+      ""
+    },
+    index.name,
+    schema,
+    table.name,
+    method,
+    columns.join(", ")
+  )
+}
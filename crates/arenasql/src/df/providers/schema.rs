@@ -12,9 +12,11 @@ use tokio::runtime::Handle;
 
 use super::table::TableProvider;
 use crate::execution::TransactionHandle;
-use crate::schema::{IndexProvider, Table, TableIndex};
+use sqlparser::ast::Statement as SQLStatement;
+
+use crate::schema::{IndexProvider, Sequence, Table, TableIndex};
 use crate::storage::KeyValueGroup;
-use crate::{index_rows_prefix_key, table_rows_prefix_key};
+use crate::table_rows_prefix_key;
 
 /// Returns error if schema isn't found for the given table
 pub fn get_schema_provider(
@@ -53,10 +55,11 @@ impl DfSchemaProvider for SchemaProvider {
   #[tracing::instrument(skip(self), level = "TRACE")]
   async fn table(&self, name: &str) -> Option<Arc<dyn DfTableProvider>> {
     let table = self.transaction.get_table(&self.schema, name)?;
-    Some(
-      Arc::new(TableProvider::new(table, self.transaction.clone()))
-        as Arc<dyn DfTableProvider>,
-    )
+    Some(Arc::new(TableProvider::new(
+      self.schema.clone(),
+      table,
+      self.transaction.clone(),
+    )) as Arc<dyn DfTableProvider>)
   }
 
   #[allow(unused_variables)]
@@ -76,6 +79,9 @@ impl DfSchemaProvider for SchemaProvider {
       table_provider,
       query_stmt.as_ref(),
     )?;
+    table.add_foreign_keys_from_query_stmt(query_stmt.as_ref(), |name| {
+      self.transaction.get_table(&self.schema, name)
+    })?;
     let constraints = table.constraints.clone();
     constraints
       .iter()
@@ -101,13 +107,30 @@ impl DfSchemaProvider for SchemaProvider {
     })?;
 
     storage_handler.put_table_schema(&self.catalog, &self.schema, &table)?;
+    // Count the rows of the table from the start so that the query planner
+    // knows the size of the table before it's analyzed
+    storage_handler.set_table_row_count(&table, Some(0))?;
+
+    // Create the sequences of the `SERIAL` and identity columns
+    if let SQLStatement::CreateTable { columns, .. } = query_stmt.as_ref() {
+      for column_def in columns {
+        if let Some(sequence) = Sequence::from_column_def(&name, column_def)? {
+          storage_handler.create_sequence(
+            &self.catalog,
+            &self.schema,
+            &sequence,
+          )?;
+        }
+      }
+    }
 
     handle.hold_table_schema_lock(table.clone(), schema_lock)?;
 
-    Ok(Some(
-      Arc::new(TableProvider::new(table, self.transaction.clone()))
-        as Arc<dyn DfTableProvider>,
-    ))
+    Ok(Some(Arc::new(TableProvider::new(
+      self.schema.clone(),
+      table,
+      self.transaction.clone(),
+    )) as Arc<dyn DfTableProvider>))
   }
 
   #[allow(unused_variables)]
@@ -121,6 +144,25 @@ impl DfSchemaProvider for SchemaProvider {
       None => return Ok(None),
     };
 
+    let is_referenced = self
+      .transaction
+      .table_names(&self.schema)
+      .iter()
+      .filter(|table_name| table_name.as_str() != name)
+      .filter_map(|table_name| {
+        self.transaction.get_table(&self.schema, table_name)
+      })
+      .any(|t| t.foreign_keys().any(|fk| fk.foreign_table == name));
+    if is_referenced {
+      return Err(
+        crate::Error::InvalidQuery(format!(
+          r#"cannot drop table "{}" because other objects depend on it"#,
+          name
+        ))
+        .into(),
+      );
+    }
+
     let storage_handler = self.transaction.lock(true)?;
 
     let schema_lock = tokio::task::block_in_place(|| {
@@ -134,18 +176,7 @@ impl DfSchemaProvider for SchemaProvider {
 
     // delete index rows
     for index in &table.indexes {
-      let mut index_rows_iter = storage_handler.kv.scan_with_prefix(
-        KeyValueGroup::IndexRows,
-        &index_rows_prefix_key!(index.id),
-      )?;
-
-      // TODO: is there a way to do bulk delete?
-      while let Some((index_row_key, _)) = index_rows_iter.get() {
-        storage_handler
-          .kv
-          .delete(KeyValueGroup::IndexRows, index_row_key)?;
-        index_rows_iter.next();
-      }
+      storage_handler.delete_index_rows(index)?;
     }
 
     // delete rows
@@ -167,14 +198,29 @@ impl DfSchemaProvider for SchemaProvider {
       &self.schema,
       &table.name,
     )?;
+    storage_handler.delete_table_statistics(&table)?;
+
+    // Drop the sequences owned by the table
+    for sequence in storage_handler
+      .get_all_sequences(&self.catalog, &self.schema)?
+      .iter()
+      .filter(|seq| seq.owned_by.as_deref() == Some(name))
+    {
+      storage_handler.delete_sequence(
+        &self.catalog,
+        &self.schema,
+        &sequence.name,
+      )?;
+    }
 
     self
       .transaction
       .hold_table_schema_lock(table.clone(), schema_lock)?;
-    Ok(Some(
-      Arc::new(TableProvider::new(table, self.transaction.clone()))
-        as Arc<dyn DfTableProvider>,
-    ))
+    Ok(Some(Arc::new(TableProvider::new(
+      self.schema.clone(),
+      table,
+      self.transaction.clone(),
+    )) as Arc<dyn DfTableProvider>))
   }
 
   fn table_exist(&self, name: &str) -> bool {
@@ -15,7 +15,8 @@ use datafusion::sql::TableReference;
 use getset::Getters;
 
 use crate::df::plans::delete_rows::DeleteRowsExecutionPlanBuilder;
-use crate::df::plans::insert_rows;
+use crate::df::plans::insert_rows::{self, InsertRowsExecutionPlan};
+use crate::df::plans::returning;
 use crate::df::plans::scan_table::TableScanerBuilder;
 use crate::df::plans::update_rows::UpdateRowsExecutionPlanBuilder;
 use crate::execution::filter::Filter;
@@ -36,6 +37,8 @@ pub fn get_table_ref<'a>(
 #[derive(Getters)]
 #[getset(get = "pub")]
 pub struct TableProvider {
+  // name of the schema that the table belongs to
+  schema_name: Arc<str>,
   table: Arc<schema::Table>,
   schema: SchemaRef,
   constraints: Option<Constraints>,
@@ -44,10 +47,12 @@ pub struct TableProvider {
 
 impl TableProvider {
   pub(crate) fn new(
+    schema_name: Arc<str>,
     table: Arc<schema::Table>,
     transaction: TransactionHandle,
   ) -> Self {
     Self {
+      schema_name,
       schema: table.get_df_schema(),
       constraints: table.get_df_constraints(),
       table,
@@ -57,15 +62,22 @@ impl TableProvider {
 
   pub(crate) async fn delete(
     &self,
+    state: &SessionState,
     // scanner execution plan scans the table with appropriate filters
     // and returns the rows that needs to be deleted
     scanner: Arc<dyn ExecutionPlan>,
   ) -> Result<Arc<dyn ExecutionPlan>> {
     Ok(Arc::new(
       DeleteRowsExecutionPlanBuilder::default()
+        .schema_name(self.schema_name.clone())
         .table(self.table.clone())
         .scanner(scanner)
         .transaction(self.transaction.clone())
+        .returning(returning::get_returning_exprs(
+          state,
+          &self.table,
+          &self.transaction,
+        )?)
         .build()
         .unwrap(),
     ))
@@ -73,15 +85,22 @@ impl TableProvider {
 
   pub(crate) async fn update(
     &self,
+    state: &SessionState,
     // scanner execution plan scans the table with appropriate filters
     // and returns the rows that needs to be deleted
     scanner: Arc<dyn ExecutionPlan>,
   ) -> Result<Arc<dyn ExecutionPlan>> {
     Ok(Arc::new(
       UpdateRowsExecutionPlanBuilder::default()
+        .schema_name(self.schema_name.clone())
         .table(self.table.clone())
         .scanner(scanner)
         .transaction(self.transaction.clone())
+        .returning(returning::get_returning_exprs(
+          state,
+          &self.table,
+          &self.transaction,
+        )?)
         .build()
         .unwrap(),
     ))
@@ -117,7 +136,7 @@ impl DfTableProvider for TableProvider {
     filters
       .iter()
       .map(|expr| {
-        let Ok(filter) = Filter::for_table(&self.table, *expr) else {
+        let Ok(filters) = Filter::split_for_table(&self.table, *expr) else {
           // If filter for table isn't supported, return unsupported
           return Ok(TableProviderFilterPushDown::Unsupported);
         };
@@ -127,9 +146,10 @@ impl DfTableProvider for TableProvider {
             .indexes
             .iter()
             .find_map(|index| {
-              if filter.is_filter_pushdown_suported()
-                && filter.is_supported_by_index(index)
-              {
+              if filters.iter().any(|filter| {
+                filter.is_filter_pushdown_suported()
+                  && filter.is_supported_by_index(index)
+              }) {
                 // Note: need to use inexact here because pushdown
                 // filter isn't applied properly.
                 // TODO: fix filter pushdown bug
@@ -153,23 +173,40 @@ impl DfTableProvider for TableProvider {
   ) -> Result<Arc<dyn ExecutionPlan>> {
     let projected_schema = project_schema(&self.schema, projection).unwrap();
 
+    // Map the indices of the schema fields to the indices of the table
+    // columns since archived columns aren't included in the schema.
+    // Virtual columns are mapped to the indices after the table columns
+    let columns: Vec<usize> =
+      self.table.active_columns().map(|(idx, _)| idx).collect();
+    let statistics = self
+      .transaction
+      .lock(false)?
+      .get_table_statistics(&self.table)?;
+    let to_column_index = |field: usize| {
+      columns
+        .get(field)
+        .copied()
+        .unwrap_or(self.table.columns.len() + field - columns.len())
+    };
     Ok(Arc::new(
       TableScanerBuilder::default()
         .table(self.table.clone())
         .projected_schema(projected_schema)
         .projection(
           projection
-            .map(|p| p.to_vec())
-            .unwrap_or_else(|| (0..self.table.columns.len()).collect()),
+            .map(|p| p.iter().map(|field| to_column_index(*field)).collect())
+            .unwrap_or_else(|| columns.clone()),
         )
         .transaction(self.transaction.clone())
         .filters(
           filters
             .iter()
-            .map(|expr| Filter::for_table(&self.table, expr))
-            .collect::<crate::Result<Vec<Filter>>>()?,
+            .map(|expr| Filter::split_for_table(&self.table, expr))
+            .collect::<crate::Result<Vec<Vec<Filter>>>>()?
+            .concat(),
         )
         .limit(limit)
+        .statistics(statistics.map(Arc::new))
         .build()
         .unwrap(),
     ))
@@ -177,7 +214,7 @@ impl DfTableProvider for TableProvider {
 
   async fn insert_into(
     &self,
-    _state: &SessionState,
+    state: &SessionState,
     input: Arc<dyn ExecutionPlan>,
     _overwrite: bool,
   ) -> Result<Arc<dyn ExecutionPlan>> {
@@ -188,10 +225,8 @@ impl DfTableProvider for TableProvider {
       .map(|field| {
         let idx = self
           .table
-          .columns
-          .iter()
-          .find(|c| c.name == *field.name())
-          .map(|c| c.id as usize);
+          .active_columns()
+          .position(|(_, c)| c.name == *field.name());
         idx.ok_or(DataFusionError::SchemaError(SchemaError::FieldNotFound {
           field: Box::new(Column::new_unqualified(field.name().to_owned())),
           valid_fields: vec![],
@@ -206,15 +241,31 @@ impl DfTableProvider for TableProvider {
           .map_err(|e| DataFusionError::ArrowError(e))
       })?;
 
-    Ok(Arc::new(FileSinkExec::new(
-      input,
-      Arc::new(insert_rows::Sink {
-        table: self.table.clone(),
-        schema: sink_schema.clone(),
-        transaction: self.transaction.clone(),
-      }),
-      sink_schema,
-      None,
-    )))
+    let sink = insert_rows::Sink {
+      schema_name: self.schema_name.clone(),
+      table: self.table.clone(),
+      schema: sink_schema.clone(),
+      transaction: self.transaction.clone(),
+      on_conflict: self
+        .transaction
+        .active_statement()
+        .as_ref()
+        .and_then(|stmt| insert_rows::get_on_conflict_clause(stmt))
+        .cloned(),
+      returning: returning::get_returning_exprs(
+        state,
+        &self.table,
+        &self.transaction,
+      )?,
+    };
+    match sink.returning.is_some() {
+      true => Ok(Arc::new(InsertRowsExecutionPlan::new(input, sink))),
+      false => Ok(Arc::new(FileSinkExec::new(
+        input,
+        Arc::new(sink),
+        sink_schema,
+        None,
+      ))),
+    }
   }
 }
@@ -34,13 +34,30 @@ pub enum Error {
     table: String,
     column: String,
   },
+  CheckConstraintViolated {
+    constraint: String,
+    table: String,
+  },
+  ForeignKeyConstraintViolated {
+    constraint: String,
+    // table that was modified
+    table: String,
+    // set if the row referenced by this table was updated or deleted
+    referencing_table: Option<String>,
+  },
   DatabaseDoesntExist(String),
   DatabaseAlreadyExists(String),
+  DatabaseInUse(String),
   // relation = table or index
   RelationAlreadyExists(String),
   RelationDoesntExist(String),
   SchemaDoesntExist(String),
   ColumnDoesntExist(String),
+  SavepointDoesntExist(String),
+  ColumnAlreadyExists {
+    table: String,
+    column: String,
+  },
   UnsupportedQueryFilter(String),
   UnsupportedQuery(String),
   InvalidQuery(String),
@@ -78,15 +95,27 @@ impl Error {
       Self::InvalidTransactionState(_) => "25000",
       // unique_violation
       Self::UniqueConstaintViolated { .. } => "23505",
+      // not_null_violation
+      Self::NullConstraintViolated { .. } => "23502",
+      // check_violation
+      Self::CheckConstraintViolated { .. } => "23514",
+      // foreign_key_violation
+      Self::ForeignKeyConstraintViolated { .. } => "23503",
       // insufficient_privilege
       Self::InsufficientPrivilege => "42501",
       Self::DatabaseDoesntExist(_) => "3D000",
+      // object_in_use
+      Self::DatabaseInUse(_) => "55006",
       // undefined_table or index
       Self::RelationDoesntExist(_) => "42P01",
       // duplicate_table or duplicate index
       Self::RelationAlreadyExists(_) => "42P07",
       // undefined_column
       Self::ColumnDoesntExist(_) => "42703",
+      // duplicate_column
+      Self::ColumnAlreadyExists { .. } => "42701",
+      // invalid_savepoint_specification
+      Self::SavepointDoesntExist(_) => "3B001",
       // internal_error
       Self::UnsupportedOperation(_)
       | Self::UnsupportedDataType(_)
@@ -94,7 +123,6 @@ impl Error {
       | Self::UnsupportedQueryFilter(_)
       | Self::UnsupportedQuery(_)
       | Self::InvalidQuery(_)
-      | Self::NullConstraintViolated { .. }
       | Self::DatabaseAlreadyExists(_)
       | Self::SchemaDoesntExist(_)
       | Self::IOError(_)
@@ -104,8 +132,20 @@ impl Error {
       | Self::InternalError(_)
       | Self::DatabaseClosed
       | Self::ReservedWord(_)
-      | Self::InvalidParameter(_)
-      | Self::DataFusionError(_) => "XX000",
+      | Self::InvalidParameter(_) => "XX000",
+      // Errors returned by the execution plans are wrapped by datafusion
+      Self::DataFusionError(df_err) => Self::unwrap_df_error(df_err)
+        .map(|err| err.code())
+        .unwrap_or("XX000"),
+    }
+  }
+
+  /// Returns the arenasql error wrapped in the datafusion error, if any
+  fn unwrap_df_error(err: &DataFusionError) -> Option<&Error> {
+    match err {
+      DataFusionError::External(err) => err.downcast_ref::<Error>(),
+      DataFusionError::Context(_, err) => Self::unwrap_df_error(err),
+      _ => None,
     }
   }
 
@@ -139,12 +179,33 @@ impl Error {
           column, table,
         )
       }
+      Self::CheckConstraintViolated { constraint, table } => format!(
+        r#"new row for relation "{}" violates check constraint "{}""#,
+        table, constraint
+      ),
+      Self::ForeignKeyConstraintViolated {
+        constraint,
+        table,
+        referencing_table,
+      } => match referencing_table {
+        Some(referencing_table) => format!(
+          r#"update or delete on table "{}" violates foreign key constraint "{}" on table "{}""#,
+          table, constraint, referencing_table
+        ),
+        None => format!(
+          r#"insert or update on table "{}" violates foreign key constraint "{}""#,
+          table, constraint
+        ),
+      },
       Self::DatabaseDoesntExist(db) => {
         format!(r#"database "{db}" doesn't exist"#)
       }
       Self::DatabaseAlreadyExists(db) => {
         format!(r#"database "{db}" already exists"#)
       }
+      Self::DatabaseInUse(db) => {
+        format!(r#"database "{db}" is being accessed by other users"#)
+      }
       Self::RelationAlreadyExists(rel) => {
         format!(r#"relation "{rel}" already exists"#)
       }
@@ -157,6 +218,12 @@ impl Error {
       Self::ColumnDoesntExist(col) => {
         format!(r#"column "{col}" does not exist"#)
       }
+      Self::ColumnAlreadyExists { table, column } => {
+        format!(r#"column "{column}" of relation "{table}" already exists"#)
+      }
+      Self::SavepointDoesntExist(name) => {
+        format!(r#"savepoint "{name}" does not exist"#)
+      }
       Self::DatabaseClosed => format!(r#"database already closed"#),
       Self::IOError(_)
       | Self::SerdeError(_)
@@ -281,6 +348,13 @@ impl From<rocksdb::Error> for Error {
   }
 }
 
+impl From<std::io::Error> for Error {
+  fn from(e: std::io::Error) -> Self {
+    tracing::warn!("IO error: {:?}", e);
+    Self::IOError(e.to_string())
+  }
+}
+
 impl From<DataFusionError> for Error {
   fn from(err: DataFusionError) -> Self {
     Error::from_df_error(&err)
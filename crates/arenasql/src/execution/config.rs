@@ -16,6 +16,8 @@ pub struct SessionConfig {
   pub df_runtime: Arc<DfRuntimeEnv>,
   pub catalog: Arc<str>,
   pub schemas: Arc<Vec<String>>,
+  /// Uses Datafusion's `information_schema` instead of the Postgres
+  /// compatible one that the catalog provides
   pub enable_information_schema: bool,
   pub privilege: Privilege,
   pub storage_factory: Arc<StorageFactory>,
@@ -52,6 +52,15 @@ impl SessionContext {
     })
   }
 
+  /// Returns true if the active transaction was started with `BEGIN`
+  fn in_transaction_block(&self) -> bool {
+    self
+      .active_transaction
+      .lock()
+      .as_ref()
+      .is_some_and(|txn| txn.handle.is_chained().load(Ordering::Acquire))
+  }
+
   /// The caller is responsible for committing the transaction returned.
   /// If not manually committed, the transaction will be rolled back.
   /// Instead of using this the transaction directly, execute query using
@@ -118,11 +127,22 @@ impl SessionContext {
       transaction.handle.is_chained().swap(true, Ordering::AcqRel);
       return Ok(ExecutionResponse::empty());
     } else if stmt_type.is_commit() {
+      self.wait_for_cancelled_scans().await;
       self.commit_active_transaction()?;
       return Ok(ExecutionResponse::empty());
     } else if stmt_type.is_rollback() {
+      self.wait_for_cancelled_scans().await;
       self.rollback_active_transaction()?;
       return Ok(ExecutionResponse::empty());
+    } else if stmt_type.is_savepoint() && !self.in_transaction_block() {
+      return Err(Error::InvalidTransactionState(format!(
+        "{} can only be used in transaction blocks",
+        match stmt_type {
+          StatementType::ReleaseSavepoint => "RELEASE SAVEPOINT",
+          StatementType::RollbackToSavepoint => "ROLLBACK TO SAVEPOINT",
+          _ => "SAVEPOINT",
+        }
+      )));
     }
 
     let transaction = unsafe { self.get_or_create_active_transaction() };
@@ -164,9 +184,14 @@ impl SessionContext {
             .lock()
             .take()
             .expect("Invalid active transaction");
-          response.set_stream_completion_hook(StreamCompletionHook::new(
-            Box::new(move || transaction.commit()),
-          ))?;
+          // Wait for the scans that were cancelled, e.g. by LIMIT, to
+          // release their locks before committing
+          response
+            .set_stream_completion_hook(StreamCompletionHook::new(async move {
+              transaction.handle().wait_for_cancelled_scans().await;
+              transaction.commit()
+            }))
+            .await?;
           Ok(response)
         }
       },
@@ -195,6 +220,15 @@ impl SessionContext {
     Ok(new_transaction)
   }
 
+  /// Waits for the cancelled table scans of the active transaction so that
+  /// the transaction can be closed
+  async fn wait_for_cancelled_scans(&self) {
+    let txn = self.active_transaction.lock().clone();
+    if let Some(txn) = txn {
+      txn.handle().wait_for_cancelled_scans().await;
+    }
+  }
+
   /// Commits the current transaction and create a new current transaction
   /// for the session
   #[tracing::instrument(skip_all, level = "TRACE")]
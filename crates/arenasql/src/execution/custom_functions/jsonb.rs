@@ -0,0 +1,397 @@
+use std::sync::Arc;
+
+use datafusion::arrow::array::{
+  Array, ArrayRef, BooleanArray, ListBuilder, StringArray, StringBuilder,
+};
+use datafusion::arrow::datatypes::{DataType, Field};
+use datafusion::common::cast::{
+  as_boolean_array, as_int64_array, as_list_array, as_string_array,
+};
+use datafusion::error::Result;
+use datafusion::logical_expr::{
+  ReturnTypeFunction, ScalarFunctionImplementation, ScalarUDF, Signature,
+  TypeSignature, Volatility,
+};
+use datafusion::physical_plan::ColumnarValue;
+use serde_json::Value;
+
+use super::{to_array, to_columnar_value};
+use crate::ast::parse_array_elements;
+use crate::schema::{
+  get_json_value, json_contains, json_key_exists, json_value_to_text,
+  set_json_value,
+};
+use crate::{df_error, Error};
+
+macro_rules! invalid_query {
+  ($($arg:tt)*) => {
+    df_error!(Error::InvalidQuery(format!($($arg)*)))
+  };
+}
+
+/// Key used by `->` and `->>` operators; text key only matches the
+/// object fields and integer key only matches the array elements
+enum JsonKey {
+  Field(String),
+  Index(i64),
+}
+
+/// Returns the JSONB functions and the functions used by the JSONB
+/// operators:
+///  - `jsonb_get(json, key)` and `jsonb_get_text(json, key)` for `->`
+///    and `->>`
+///  - `jsonb_get_path(json, path)` and `jsonb_get_path_text(json, path)`
+///    for `#>` and `#>>`
+///  - `jsonb_contains(json, other)` for `@>` and `<@`
+///  - `jsonb_exists(json, key)`, `jsonb_exists_any(json, keys)` and
+///    `jsonb_exists_all(json, keys)` for `?`, `?|` and `?&`
+///  - `jsonb_set(json, path, value [, create_missing])`
+///  - `jsonb_to_array(json)` and `jsonb_to_text_array(json)` used by
+///    `jsonb_array_elements` and `jsonb_array_elements_text`
+pub fn create_jsonb_functions() -> Vec<ScalarUDF> {
+  let json = DataType::Utf8;
+  let text_array =
+    DataType::List(Arc::new(Field::new("item", DataType::Utf8, true)));
+  let key_signature = signature(vec![
+    vec![json.clone(), DataType::Utf8],
+    vec![json.clone(), DataType::Int64],
+  ]);
+  let path_signature = signature(vec![
+    vec![json.clone(), DataType::Utf8],
+    vec![json.clone(), text_array.clone()],
+  ]);
+
+  vec![
+    udf(
+      "jsonb_get",
+      &key_signature,
+      DataType::Utf8,
+      Arc::new(jsonb_get),
+    ),
+    udf(
+      "jsonb_get_text",
+      &key_signature,
+      DataType::Utf8,
+      Arc::new(jsonb_get_text),
+    ),
+    udf(
+      "jsonb_get_path",
+      &path_signature,
+      DataType::Utf8,
+      Arc::new(jsonb_get_path),
+    ),
+    udf(
+      "jsonb_get_path_text",
+      &path_signature,
+      DataType::Utf8,
+      Arc::new(jsonb_get_path_text),
+    ),
+    udf(
+      "jsonb_contains",
+      &signature(vec![vec![json.clone(), json.clone()]]),
+      DataType::Boolean,
+      Arc::new(jsonb_contains),
+    ),
+    udf(
+      "jsonb_exists",
+      &signature(vec![vec![json.clone(), DataType::Utf8]]),
+      DataType::Boolean,
+      Arc::new(jsonb_exists),
+    ),
+    udf(
+      "jsonb_exists_any",
+      &path_signature,
+      DataType::Boolean,
+      Arc::new(jsonb_exists_any),
+    ),
+    udf(
+      "jsonb_exists_all",
+      &path_signature,
+      DataType::Boolean,
+      Arc::new(jsonb_exists_all),
+    ),
+    udf(
+      "jsonb_set",
+      &signature(vec![
+        vec![json.clone(), DataType::Utf8, json.clone()],
+        vec![json.clone(), text_array.clone(), json.clone()],
+        vec![
+          json.clone(),
+          DataType::Utf8,
+          json.clone(),
+          DataType::Boolean,
+        ],
+        vec![
+          json.clone(),
+          text_array.clone(),
+          json.clone(),
+          DataType::Boolean,
+        ],
+      ]),
+      DataType::Utf8,
+      Arc::new(jsonb_set),
+    ),
+    udf(
+      "jsonb_to_array",
+      &signature(vec![vec![json.clone()]]),
+      text_array.clone(),
+      Arc::new(|args| jsonb_to_array(args, |value| Some(value.to_string()))),
+    ),
+    udf(
+      "jsonb_to_text_array",
+      &signature(vec![vec![json]]),
+      text_array,
+      Arc::new(|args| jsonb_to_array(args, json_value_to_text)),
+    ),
+  ]
+}
+
+fn signature(args: Vec<Vec<DataType>>) -> Signature {
+  Signature::one_of(
+    args.into_iter().map(TypeSignature::Exact).collect(),
+    Volatility::Immutable,
+  )
+}
+
+fn udf(
+  name: &str,
+  signature: &Signature,
+  return_type: DataType,
+  fun: ScalarFunctionImplementation,
+) -> ScalarUDF {
+  let return_type: ReturnTypeFunction =
+    Arc::new(move |_| Ok(Arc::new(return_type.clone())));
+  ScalarUDF::new(name, signature, &return_type, &fun)
+}
+
+fn jsonb_get(args: &[ColumnarValue]) -> Result<ColumnarValue> {
+  map_json_keys(args, |value| Some(value.to_string()))
+}
+
+fn jsonb_get_text(args: &[ColumnarValue]) -> Result<ColumnarValue> {
+  map_json_keys(args, json_value_to_text)
+}
+
+fn jsonb_get_path(args: &[ColumnarValue]) -> Result<ColumnarValue> {
+  map_json_paths(args, |value| Some(value.to_string()))
+}
+
+fn jsonb_get_path_text(args: &[ColumnarValue]) -> Result<ColumnarValue> {
+  map_json_paths(args, json_value_to_text)
+}
+
+fn jsonb_contains(args: &[ColumnarValue]) -> Result<ColumnarValue> {
+  let values = to_json_values(&args[0], args)?
+    .iter()
+    .zip(to_json_values(&args[1], args)?.iter())
+    .map(|(json, other)| match (json, other) {
+      (Some(json), Some(other)) => Some(json_contains(json, other)),
+      _ => None,
+    })
+    .collect::<BooleanArray>();
+  to_columnar_value(args, Arc::new(values))
+}
+
+fn jsonb_exists(args: &[ColumnarValue]) -> Result<ColumnarValue> {
+  let keys = to_array(&args[1], args)?;
+  let values = to_json_values(&args[0], args)?
+    .iter()
+    .zip(as_string_array(&keys)?.iter())
+    .map(|(json, key)| Some(json_key_exists(json.as_ref()?, key?)))
+    .collect::<BooleanArray>();
+  to_columnar_value(args, Arc::new(values))
+}
+
+fn jsonb_exists_any(args: &[ColumnarValue]) -> Result<ColumnarValue> {
+  map_json_keys_exist(args, |json, keys| {
+    keys.iter().any(|key| json_key_exists(json, key))
+  })
+}
+
+fn jsonb_exists_all(args: &[ColumnarValue]) -> Result<ColumnarValue> {
+  map_json_keys_exist(args, |json, keys| {
+    keys.iter().all(|key| json_key_exists(json, key))
+  })
+}
+
+fn jsonb_set(args: &[ColumnarValue]) -> Result<ColumnarValue> {
+  let paths = to_json_paths(&args[1], args)?;
+  let new_values = to_json_values(&args[2], args)?;
+  let create_missing = args
+    .get(3)
+    .map(|create_missing| to_array(create_missing, args))
+    .transpose()?;
+  let create_missing = create_missing
+    .as_ref()
+    .map(|create_missing| as_boolean_array(create_missing))
+    .transpose()?;
+
+  let values = to_json_values(&args[0], args)?
+    .into_iter()
+    .zip(paths.iter().zip(new_values))
+    .enumerate()
+    .map(|(row, (json, (path, new_value)))| {
+      let (mut json, path, new_value) = (json?, path.as_ref()?, new_value?);
+      let create_missing = match create_missing {
+        Some(create_missing) => match create_missing.is_null(row) {
+          true => return None,
+          false => create_missing.value(row),
+        },
+        None => true,
+      };
+      set_json_value(&mut json, path, new_value, create_missing);
+      Some(json.to_string())
+    })
+    .collect::<StringArray>();
+  to_columnar_value(args, Arc::new(values))
+}
+
+fn jsonb_to_array(
+  args: &[ColumnarValue],
+  to_element: fn(&Value) -> Option<String>,
+) -> Result<ColumnarValue> {
+  let mut builder = ListBuilder::new(StringBuilder::new());
+  for json in to_json_values(&args[0], args)? {
+    match json {
+      Some(Value::Array(elements)) => {
+        elements
+          .iter()
+          .for_each(|e| builder.values().append_option(to_element(e)));
+        builder.append(true);
+      }
+      None | Some(Value::Null) => builder.append(false),
+      Some(_) => {
+        return Err(invalid_query!("cannot extract elements from a scalar"))
+      }
+    }
+  }
+  to_columnar_value(args, Arc::new(builder.finish()))
+}
+
+fn map_json_keys(
+  args: &[ColumnarValue],
+  to_output: fn(&Value) -> Option<String>,
+) -> Result<ColumnarValue> {
+  let keys = to_json_keys(&args[1], args)?;
+  let values = to_json_values(&args[0], args)?
+    .iter()
+    .zip(keys.iter())
+    .map(|(json, key)| {
+      let value = match (json.as_ref()?, key.as_ref()?) {
+        (json @ Value::Object(_), JsonKey::Field(key)) => {
+          get_json_value(json, &[key])
+        }
+        (json @ Value::Array(_), JsonKey::Index(index)) => {
+          get_json_value(json, &[index.to_string()])
+        }
+        _ => None,
+      };
+      value.and_then(to_output)
+    })
+    .collect::<StringArray>();
+  to_columnar_value(args, Arc::new(values))
+}
+
+fn map_json_paths(
+  args: &[ColumnarValue],
+  to_output: fn(&Value) -> Option<String>,
+) -> Result<ColumnarValue> {
+  let paths = to_json_paths(&args[1], args)?;
+  let values = to_json_values(&args[0], args)?
+    .iter()
+    .zip(paths.iter())
+    .map(|(json, path)| {
+      get_json_value(json.as_ref()?, path.as_ref()?).and_then(to_output)
+    })
+    .collect::<StringArray>();
+  to_columnar_value(args, Arc::new(values))
+}
+
+fn map_json_keys_exist(
+  args: &[ColumnarValue],
+  exists: fn(&Value, &[String]) -> bool,
+) -> Result<ColumnarValue> {
+  let keys = to_json_paths(&args[1], args)?;
+  let values = to_json_values(&args[0], args)?
+    .iter()
+    .zip(keys.iter())
+    .map(|(json, keys)| Some(exists(json.as_ref()?, keys.as_ref()?)))
+    .collect::<BooleanArray>();
+  to_columnar_value(args, Arc::new(values))
+}
+
+fn to_json_values(
+  value: &ColumnarValue,
+  args: &[ColumnarValue],
+) -> Result<Vec<Option<Value>>> {
+  let array = to_array(value, args)?;
+  as_string_array(&array)?
+    .iter()
+    .map(|json| {
+      json
+        .map(|json| {
+          serde_json::from_str(json).map_err(|_| {
+            invalid_query!("invalid input syntax for type json: \"{}\"", json)
+          })
+        })
+        .transpose()
+    })
+    .collect()
+}
+
+fn to_json_keys(
+  value: &ColumnarValue,
+  args: &[ColumnarValue],
+) -> Result<Vec<Option<JsonKey>>> {
+  let array = to_array(value, args)?;
+  Ok(match array.data_type() {
+    DataType::Int64 => as_int64_array(&array)?
+      .iter()
+      .map(|index| index.map(JsonKey::Index))
+      .collect(),
+    _ => as_string_array(&array)?
+      .iter()
+      .map(|key| key.map(|key| JsonKey::Field(key.to_owned())))
+      .collect(),
+  })
+}
+
+/// Returns the paths from text array or the text representation of the
+/// array, i.e. `'{a,b}'`. Paths with NULL elements are returned as NULL
+fn to_json_paths(
+  value: &ColumnarValue,
+  args: &[ColumnarValue],
+) -> Result<Vec<Option<Vec<String>>>> {
+  let array: ArrayRef = to_array(value, args)?;
+  match array.data_type() {
+    DataType::List(_) => {
+      let list = as_list_array(&array)?;
+      (0..list.len())
+        .map(|row| {
+          if list.is_null(row) {
+            return Ok(None);
+          }
+          let path = list.value(row);
+          Ok(
+            as_string_array(&path)?
+              .iter()
+              .map(|key| key.map(|key| key.to_owned()))
+              .collect(),
+          )
+        })
+        .collect()
+    }
+    _ => as_string_array(&array)?
+      .iter()
+      .map(|path| {
+        let Some(path) = path else {
+          return Ok(None);
+        };
+        let path = parse_array_elements(path).map_err(|_| {
+          invalid_query!("malformed array literal: \"{}\"", path)
+        })?;
+        Ok(path.into_iter().collect())
+      })
+      .collect(),
+  }
+}
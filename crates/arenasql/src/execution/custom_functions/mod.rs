@@ -1,12 +1,86 @@
+use std::sync::Arc;
+
+use datafusion::arrow::array::ArrayRef;
+use datafusion::error::Result;
 use datafusion::execution::context::SessionContext as DfSessionContext;
+use datafusion::physical_plan::ColumnarValue;
+use datafusion::scalar::ScalarValue;
+use parking_lot::RwLock;
 
 mod current_schema;
+mod jsonb;
+mod pg_catalog;
+mod sequence;
 mod vector;
 
+use super::{SessionState, TransactionHandle};
 use current_schema::CURRENT_SCHEMA;
-use vector::L2_DISTANCE;
+use jsonb::create_jsonb_functions;
+use pg_catalog::{create_pg_catalog_functions, FORMAT_TYPE, VERSION};
+use sequence::create_sequence_functions;
+use vector::{
+  COSINE_DISTANCE, COSINE_SIMILARITY, INNER_PRODUCT, L2_DISTANCE,
+  NEGATIVE_INNER_PRODUCT,
+};
 
 pub fn register_all(context: &DfSessionContext) {
   context.register_udf(L2_DISTANCE.clone());
+  context.register_udf(INNER_PRODUCT.clone());
+  context.register_udf(COSINE_SIMILARITY.clone());
+  context.register_udf(NEGATIVE_INNER_PRODUCT.clone());
+  context.register_udf(COSINE_DISTANCE.clone());
   context.register_udf(CURRENT_SCHEMA.clone());
+  context.register_udf(FORMAT_TYPE.clone());
+  context.register_udf(VERSION.clone());
+  create_jsonb_functions()
+    .into_iter()
+    .for_each(|udf| context.register_udf(udf));
+}
+
+/// Registers the functions that need access to the transaction
+pub fn register_transaction_functions(
+  context: &DfSessionContext,
+  transaction: &TransactionHandle,
+  catalog: Arc<str>,
+  schemas: Arc<Vec<String>>,
+  session_state: Arc<RwLock<SessionState>>,
+) {
+  create_pg_catalog_functions(transaction, catalog.clone(), schemas.clone())
+    .into_iter()
+    .for_each(|udf| context.register_udf(udf));
+  create_sequence_functions(transaction, catalog, schemas, session_state)
+    .into_iter()
+    .for_each(|udf| context.register_udf(udf));
+}
+
+/// Returns the argument as an array with the same length as the array
+/// arguments. Returns array of length 1 if all arguments are scalars
+fn to_array(value: &ColumnarValue, args: &[ColumnarValue]) -> Result<ArrayRef> {
+  match value {
+    ColumnarValue::Array(array) => Ok(array.clone()),
+    ColumnarValue::Scalar(scalar) => {
+      let num_rows = args
+        .iter()
+        .find_map(|arg| match arg {
+          ColumnarValue::Array(array) => Some(array.len()),
+          ColumnarValue::Scalar(_) => None,
+        })
+        .unwrap_or(1);
+      scalar.to_array_of_size(num_rows)
+    }
+  }
+}
+
+/// Returns scalar value if all the arguments are scalars
+fn to_columnar_value(
+  args: &[ColumnarValue],
+  array: ArrayRef,
+) -> Result<ColumnarValue> {
+  let is_scalar = args
+    .iter()
+    .all(|arg| matches!(arg, ColumnarValue::Scalar(_)));
+  Ok(match is_scalar {
+    true => ColumnarValue::Scalar(ScalarValue::try_from_array(&array, 0)?),
+    false => ColumnarValue::Array(array),
+  })
 }
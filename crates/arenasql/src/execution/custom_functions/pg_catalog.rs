@@ -0,0 +1,123 @@
+use std::sync::Arc;
+
+use datafusion::arrow::array::StringArray;
+use datafusion::arrow::datatypes::DataType;
+use datafusion::common::cast::as_int64_array;
+use datafusion::error::Result;
+use datafusion::logical_expr::{
+  create_udf, ReturnTypeFunction, ScalarFunctionImplementation, ScalarUDF,
+  Signature, Volatility,
+};
+use datafusion::physical_plan::ColumnarValue;
+use datafusion::scalar::ScalarValue;
+use once_cell::sync::Lazy;
+
+use super::{to_array, to_columnar_value};
+use crate::df::providers::pg_catalog::{
+  format_type, index_definition, index_id_from_oid, list_tables,
+};
+use crate::execution::TransactionHandle;
+
+/// Postgres version reported by `version()`
+const POSTGRES_VERSION: &str = "15.0";
+
+pub const FORMAT_TYPE: Lazy<ScalarUDF> = Lazy::new(|| {
+  create_udf(
+    "format_type",
+    vec![DataType::Int64, DataType::Int64],
+    Arc::new(DataType::Utf8),
+    Volatility::Immutable,
+    Arc::new(pg_format_type),
+  )
+});
+
+pub const VERSION: Lazy<ScalarUDF> = Lazy::new(|| {
+  create_udf(
+    "version",
+    vec![],
+    Arc::new(DataType::Utf8),
+    Volatility::Immutable,
+    Arc::new(version),
+  )
+});
+
+/// Returns the name of the type with the given oid and type modifier;
+/// e.g. `format_type(1043, 259)` returns `character varying(255)`
+fn pg_format_type(args: &[ColumnarValue]) -> Result<ColumnarValue> {
+  let oids = to_array(&args[0], args)?;
+  let oids = as_int64_array(&oids)?;
+  let typmods = to_array(&args[1], args)?;
+  let typmods = as_int64_array(&typmods)?;
+
+  let names = oids
+    .iter()
+    .zip(typmods.iter())
+    .map(|(oid, typmod)| {
+      let oid = u32::try_from(oid?).ok()?;
+      format_type(oid, typmod.unwrap_or(-1) as i32)
+    })
+    .collect::<StringArray>();
+  to_columnar_value(args, Arc::new(names))
+}
+
+fn version(_args: &[ColumnarValue]) -> Result<ColumnarValue> {
+  Ok(ColumnarValue::Scalar(ScalarValue::Utf8(Some(format!(
+    "PostgreSQL {} (arenasql {})",
+    POSTGRES_VERSION,
+    env!("CARGO_PKG_VERSION")
+  )))))
+}
+
+/// Returns `current_database()` and `pg_get_indexdef(oid)` functions
+/// that use the given catalog
+pub fn create_pg_catalog_functions(
+  transaction: &TransactionHandle,
+  catalog: Arc<str>,
+  schemas: Arc<Vec<String>>,
+) -> Vec<ScalarUDF> {
+  let return_type: ReturnTypeFunction =
+    Arc::new(|_| Ok(Arc::new(DataType::Utf8)));
+
+  let current_database: ScalarFunctionImplementation = Arc::new(move |_| {
+    Ok(ColumnarValue::Scalar(ScalarValue::Utf8(Some(
+      catalog.to_string(),
+    ))))
+  });
+
+  let transaction = transaction.clone();
+  let pg_get_indexdef: ScalarFunctionImplementation = Arc::new(move |args| {
+    let oids = to_array(&args[0], args)?;
+    let oids = as_int64_array(&oids)?;
+
+    let tables = list_tables(&transaction, &schemas);
+    let definitions = oids
+      .iter()
+      .map(|oid| {
+        let index_id = index_id_from_oid(oid?)?;
+        tables.iter().find_map(|(schema, table)| {
+          table
+            .indexes
+            .iter()
+            .find(|index| index.id == index_id)
+            .map(|index| index_definition(schema, table, index))
+        })
+      })
+      .collect::<StringArray>();
+    to_columnar_value(args, Arc::new(definitions))
+  });
+
+  vec![
+    ScalarUDF::new(
+      "current_database",
+      &Signature::exact(vec![], Volatility::Stable),
+      &return_type,
+      &current_database,
+    ),
+    ScalarUDF::new(
+      "pg_get_indexdef",
+      &Signature::exact(vec![DataType::Int64], Volatility::Stable),
+      &return_type,
+      &pg_get_indexdef,
+    ),
+  ]
+}
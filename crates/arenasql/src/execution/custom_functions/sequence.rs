@@ -0,0 +1,235 @@
+use std::collections::{BTreeMap, HashMap};
+use std::sync::Arc;
+
+use datafusion::arrow::array::{Array, Int64Array};
+use datafusion::arrow::datatypes::DataType;
+use datafusion::common::cast::{
+  as_boolean_array, as_int64_array, as_string_array,
+};
+use datafusion::error::Result;
+use datafusion::logical_expr::{
+  ReturnTypeFunction, ScalarFunctionImplementation, ScalarUDF, Signature,
+  TypeSignature, Volatility,
+};
+use datafusion::physical_plan::ColumnarValue;
+use datafusion::sql::TableReference;
+use parking_lot::RwLock;
+
+use super::{to_array, to_columnar_value};
+use crate::execution::{SessionState, TransactionHandle};
+use crate::schema::Sequence;
+use crate::storage::StorageHandler;
+use crate::Error;
+
+/// Last values returned by `nextval` in the session, keyed by
+/// (schema, sequence)
+#[derive(Default)]
+struct SessionSequenceValues(HashMap<(String, String), i64>);
+
+/// Resolves the sequences used by the sequence functions and keeps
+/// track of the values returned in the session for `currval`
+#[derive(Clone)]
+struct Sequences {
+  transaction: TransactionHandle,
+  catalog: Arc<str>,
+  // Sequence name without schema is resolved to the first schema
+  schemas: Arc<Vec<String>>,
+  session_state: Arc<RwLock<SessionState>>,
+}
+
+/// Returns `nextval(name)`, `currval(name)` and `setval(name, value
+/// [, is_called])` functions that use the sequences in the given catalog
+pub fn create_sequence_functions(
+  transaction: &TransactionHandle,
+  catalog: Arc<str>,
+  schemas: Arc<Vec<String>>,
+  session_state: Arc<RwLock<SessionState>>,
+) -> Vec<ScalarUDF> {
+  let sequences = Sequences {
+    transaction: transaction.clone(),
+    catalog,
+    schemas,
+    session_state,
+  };
+  let return_type: ReturnTypeFunction =
+    Arc::new(|_| Ok(Arc::new(DataType::Int64)));
+
+  let nextval: ScalarFunctionImplementation = {
+    let sequences = sequences.clone();
+    Arc::new(move |args| sequences.nextval(args))
+  };
+  let currval: ScalarFunctionImplementation = {
+    let sequences = sequences.clone();
+    Arc::new(move |args| sequences.currval(args))
+  };
+  let setval: ScalarFunctionImplementation =
+    Arc::new(move |args| sequences.setval(args));
+
+  vec![
+    ScalarUDF::new(
+      "nextval",
+      &Signature::exact(vec![DataType::Utf8], Volatility::Volatile),
+      &return_type,
+      &nextval,
+    ),
+    ScalarUDF::new(
+      "currval",
+      &Signature::exact(vec![DataType::Utf8], Volatility::Volatile),
+      &return_type,
+      &currval,
+    ),
+    ScalarUDF::new(
+      "setval",
+      &Signature::one_of(
+        vec![
+          TypeSignature::Exact(vec![DataType::Utf8, DataType::Int64]),
+          TypeSignature::Exact(vec![
+            DataType::Utf8,
+            DataType::Int64,
+            DataType::Boolean,
+          ]),
+        ],
+        Volatility::Volatile,
+      ),
+      &return_type,
+      &setval,
+    ),
+  ]
+}
+
+impl Sequences {
+  fn nextval(&self, args: &[ColumnarValue]) -> Result<ColumnarValue> {
+    let names = to_array(&args[0], args)?;
+    let names = as_string_array(&names)?;
+
+    // Get all the values of a sequence at once
+    let mut rows_by_sequence: BTreeMap<&str, Vec<usize>> = BTreeMap::new();
+    names.iter().enumerate().for_each(|(row, name)| {
+      if let Some(name) = name {
+        rows_by_sequence.entry(name).or_default().push(row);
+      }
+    });
+
+    let storage = self.storage_handler();
+    let mut values = vec![None; names.len()];
+    for (name, rows) in rows_by_sequence {
+      let (schema, sequence) = self.get_sequence(&storage, name)?;
+      let next_values = storage.next_sequence_values(
+        &self.catalog,
+        &schema,
+        &sequence,
+        rows.len(),
+      )?;
+      if let Some(last_value) = next_values.last() {
+        let mut state = self.session_state.write();
+        let mut session_values = state
+          .remove::<SessionSequenceValues>()
+          .map(|values| *values)
+          .unwrap_or_default();
+        session_values
+          .0
+          .insert((schema, sequence.name.clone()), *last_value);
+        state.put(session_values);
+      }
+      rows
+        .into_iter()
+        .zip(next_values)
+        .for_each(|(row, value)| values[row] = Some(value));
+    }
+    to_columnar_value(args, Arc::new(Int64Array::from(values)))
+  }
+
+  fn currval(&self, args: &[ColumnarValue]) -> Result<ColumnarValue> {
+    let names = to_array(&args[0], args)?;
+    let names = as_string_array(&names)?;
+
+    let storage = self.storage_handler();
+    let state = self.session_state.read();
+    let session_values = state.try_borrow::<SessionSequenceValues>();
+    let values = names
+      .iter()
+      .map(|name| {
+        let Some(name) = name else {
+          return Ok(None);
+        };
+        let (schema, sequence) = self.get_sequence(&storage, name)?;
+        match session_values
+          .and_then(|values| values.0.get(&(schema, sequence.name)))
+        {
+          Some(value) => Ok(Some(*value)),
+          None => Err(Error::InvalidQuery(format!(
+            "currval of sequence \"{}\" is not yet defined in this session",
+            name
+          ))),
+        }
+      })
+      .collect::<crate::Result<Vec<Option<i64>>>>()?;
+    to_columnar_value(args, Arc::new(Int64Array::from(values)))
+  }
+
+  fn setval(&self, args: &[ColumnarValue]) -> Result<ColumnarValue> {
+    let names = to_array(&args[0], args)?;
+    let names = as_string_array(&names)?;
+    let new_values = to_array(&args[1], args)?;
+    let new_values = as_int64_array(&new_values)?;
+    let is_called = args
+      .get(2)
+      .map(|is_called| to_array(is_called, args))
+      .transpose()?;
+    let is_called = is_called
+      .as_ref()
+      .map(|is_called| as_boolean_array(is_called))
+      .transpose()?;
+
+    let storage = self.storage_handler();
+    let values = (0..names.len())
+      .map(|row| {
+        if names.is_null(row) || new_values.is_null(row) {
+          return Ok(None);
+        }
+        let (name, value) = (names.value(row), new_values.value(row));
+        let is_called = is_called
+          .map(|is_called| is_called.is_null(row) || is_called.value(row))
+          .unwrap_or(true);
+        let (schema, sequence) = self.get_sequence(&storage, name)?;
+        storage.set_sequence_value(
+          &self.catalog,
+          &schema,
+          &sequence,
+          value,
+          is_called,
+        )?;
+        Ok(Some(value))
+      })
+      .collect::<crate::Result<Vec<Option<i64>>>>()?;
+    to_columnar_value(args, Arc::new(Int64Array::from(values)))
+  }
+
+  /// Returns the (schema, sequence) of the given sequence name. The name
+  /// can be qualified with the schema; e.g. `public.users_id_seq`.
+  /// Unquoted names are case insensitive like the table names
+  fn get_sequence(
+    &self,
+    storage: &StorageHandler,
+    name: &str,
+  ) -> crate::Result<(String, Sequence)> {
+    let sequence_ref =
+      TableReference::parse_str(name).resolve(&self.catalog, &self.schemas[0]);
+    let sequence = storage
+      .get_sequence(&self.catalog, &sequence_ref.schema, &sequence_ref.table)?
+      .ok_or_else(|| Error::RelationDoesntExist(name.to_owned()))?;
+    Ok((sequence_ref.schema.to_string(), sequence))
+  }
+
+  /// The sequence functions don't acquire the transaction lock since the
+  /// sequence values are updated atomically outside of the transaction
+  /// and these functions can be called while inserting rows, when the
+  /// lock is already held
+  fn storage_handler(&self) -> StorageHandler {
+    StorageHandler {
+      kv: self.transaction.kvstore().clone(),
+      serializer: self.transaction.serializer().clone(),
+      transaction_lock: None,
+    }
+  }
+}
@@ -5,7 +5,9 @@ use datafusion::arrow::array::{
 };
 use datafusion::arrow::datatypes::{DataType, Field};
 use datafusion::error::Result;
-use datafusion::logical_expr::{create_udf, ScalarUDF, Volatility};
+use datafusion::logical_expr::{
+  create_udf, ScalarFunctionImplementation, ScalarUDF, Volatility,
+};
 use datafusion::physical_plan::ColumnarValue;
 use datafusion::scalar::ScalarValue;
 use once_cell::sync::Lazy;
@@ -19,9 +21,31 @@ macro_rules! invalid_query {
   };
 }
 
-pub const L2_DISTANCE: Lazy<ScalarUDF> = Lazy::new(|| {
+pub const L2_DISTANCE: Lazy<ScalarUDF> =
+  Lazy::new(|| create_vector_udf("l2", Arc::new(l2_distance)));
+
+pub const INNER_PRODUCT: Lazy<ScalarUDF> =
+  Lazy::new(|| create_vector_udf("inner_product", Arc::new(inner_product)));
+
+pub const COSINE_SIMILARITY: Lazy<ScalarUDF> = Lazy::new(|| {
+  create_vector_udf("cosine_similarity", Arc::new(cosine_similarity))
+});
+
+/// Same as pgvector's `<#>` operator
+pub const NEGATIVE_INNER_PRODUCT: Lazy<ScalarUDF> = Lazy::new(|| {
+  create_vector_udf("negative_inner_product", Arc::new(negative_inner_product))
+});
+
+/// Same as pgvector's `<=>` operator
+pub const COSINE_DISTANCE: Lazy<ScalarUDF> =
+  Lazy::new(|| create_vector_udf("cosine_distance", Arc::new(cosine_distance)));
+
+fn create_vector_udf(
+  name: &str,
+  fun: ScalarFunctionImplementation,
+) -> ScalarUDF {
   create_udf(
-    "l2",
+    name,
     vec![
       DataType::List(Field::new("item", DataType::Float32, true).into()),
       // JSON string of vector array
@@ -29,28 +53,67 @@ pub const L2_DISTANCE: Lazy<ScalarUDF> = Lazy::new(|| {
     ],
     Arc::new(DataType::Float32),
     Volatility::Immutable,
-    Arc::new(l2_distance),
+    fun,
   )
-});
+}
 
+/// Returns the euclidean distance between the vectors
 pub fn l2_distance(args: &[ColumnarValue]) -> Result<ColumnarValue> {
+  // L2 scorer returns negative of the squared distance
+  compute_scores("L2", args, SimilarityType::L2, |score| (-score).sqrt())
+}
+
+pub fn inner_product(args: &[ColumnarValue]) -> Result<ColumnarValue> {
+  compute_scores("INNER_PRODUCT", args, SimilarityType::Dot, |score| score)
+}
+
+pub fn cosine_similarity(args: &[ColumnarValue]) -> Result<ColumnarValue> {
+  compute_scores("COSINE_SIMILARITY", args, SimilarityType::Cosine, |score| {
+    score
+  })
+}
+
+pub fn negative_inner_product(args: &[ColumnarValue]) -> Result<ColumnarValue> {
+  compute_scores(
+    "NEGATIVE_INNER_PRODUCT",
+    args,
+    SimilarityType::Dot,
+    |score| -score,
+  )
+}
+
+pub fn cosine_distance(args: &[ColumnarValue]) -> Result<ColumnarValue> {
+  compute_scores("COSINE_DISTANCE", args, SimilarityType::Cosine, |score| {
+    1.0 - score
+  })
+}
+
+fn compute_scores(
+  name: &str,
+  args: &[ColumnarValue],
+  similarity_type: SimilarityType,
+  to_output: fn(f32) -> f32,
+) -> Result<ColumnarValue> {
   let ColumnarValue::Array(ref vector_column) = args[0] else {
     return Err(invalid_query!(
-      "First argument of \"L2\" should be a vector column"
+      "First argument of \"{}\" should be a vector column",
+      name
     ));
   };
   let ColumnarValue::Scalar(ScalarValue::Utf8(Some(ref raw_input_vector))) =
     args[1]
   else {
     return Err(invalid_query!(
-      "Second argument of \"L2\" should be a valid JSON string"
+      "Second argument of \"{}\" should be a valid JSON string",
+      name
     ));
   };
 
   let input_vector = serde_json::from_str::<Vec<f32>>(&raw_input_vector)
     .map_err(|_| {
       invalid_query!(
-        "Second argument of \"L2\" should be a valid JSON string of a vector"
+        "Second argument of \"{}\" should be a valid JSON string of a vector",
+        name
       )
     })?;
 
@@ -84,7 +147,7 @@ pub fn l2_distance(args: &[ColumnarValue]) -> Result<ColumnarValue> {
   }
 
   let vectors = vector_data.buffer::<f32>(0);
-  let scorer = SimilarityScorerFactory::get_default(SimilarityType::Dot);
+  let scorer = SimilarityScorerFactory::get_default(similarity_type);
 
   let mut similarity_scores = Float32Builder::with_capacity(vector_array.len());
   for i in 0..vector_array.len() {
@@ -92,7 +155,7 @@ pub fn l2_distance(args: &[ColumnarValue]) -> Result<ColumnarValue> {
       &vectors[i * vector_length..(i + 1) * vector_length],
       &input_vector,
     );
-    similarity_scores.append_value(score.0);
+    similarity_scores.append_value(to_output(score.0));
   }
 
   Ok(ColumnarValue::Array(Arc::<Float32Array>::new(
@@ -1,10 +1,33 @@
 use std::cmp::Ordering;
+use std::fmt;
 
-use datafusion::logical_expr::{Expr, Like, Operator};
+use datafusion::logical_expr::expr::{InList, ScalarUDF};
+use datafusion::logical_expr::{BinaryExpr, Expr, Like, Operator};
+use datafusion::optimizer::utils::{split_binary, split_conjunction};
+use datafusion::scalar::ScalarValue;
 
-use crate::schema::{OwnedSerializedCell, Table, TableIndex};
+use crate::ast::parse_array_elements;
+use crate::schema::{
+  compare_cells, parse_uuid, DataType, IndexStatistics, OwnedSerializedCell,
+  Table, TableIndex, TableStatistics, DEFAULT_ROW_COUNT,
+};
 use crate::{Error, Result};
 
+/// Cost of reading a row and applying the filter to it
+const ROW_FILTER_COST: f32 = 0.0025;
+
+/// Maximum number of key prefixes scanned for the `IN` lists on the
+/// index columns
+const MAX_INDEX_KEY_PREFIXES: usize = 1_000;
+
+/// Fraction of the rows assumed to match a `<`, `<=`, `>` or `>=` filter
+/// if the statistics of the column aren't known
+const DEFAULT_RANGE_SELECTIVITY: f64 = 1.0 / 3.0;
+
+/// Minimum fraction of the rows assumed to be between the lower and the
+/// upper bounds of a column
+const MIN_RANGE_SELECTIVITY: f64 = 0.005;
+
 #[derive(Debug, Clone)]
 pub enum Filter {
   BinaryExpr {
@@ -21,6 +44,12 @@ pub enum Filter {
     projected_columns: Vec<usize>,
     expr: Like,
   },
+  /// `column IN (...)`; `column = a OR column = b` is also converted to
+  /// this since datafusion rewrites the short IN lists that way
+  InList {
+    projected_columns: Vec<usize>,
+    expr: InList,
+  },
   IsNull {
     projected_columns: Vec<usize>,
     expr: Box<Expr>,
@@ -45,7 +74,7 @@ impl Filter {
       .columns
       .iter()
       .enumerate()
-      .filter(|tcol| filter_cols.contains(&tcol.1.name))
+      .filter(|tcol| !tcol.1.archived() && filter_cols.contains(&tcol.1.name))
       .map(|p| p.0)
       .collect();
 
@@ -57,12 +86,33 @@ impl Filter {
       )));
     }
 
+    let is_uuid = projected_columns
+      .first()
+      .map(|col| table.columns[*col].data_type == DataType::Uuid)
+      .unwrap_or(false);
     match expr {
+      Expr::BinaryExpr(e) if e.op == Operator::Or => {
+        let in_list = get_in_list_of_disjunction(expr)
+          .ok_or_else(|| Error::UnsupportedQueryFilter(expr.to_string()))?;
+        Ok(Self::InList {
+          projected_columns,
+          expr: cast_uuid_literals_in_list(&in_list, is_uuid)?,
+        })
+      }
+      Expr::InList(e)
+        if !e.negated
+          && e.list.iter().all(|value| matches!(value, Expr::Literal(_))) =>
+      {
+        Ok(Self::InList {
+          projected_columns,
+          expr: cast_uuid_literals_in_list(e, is_uuid)?,
+        })
+      }
       Expr::BinaryExpr(e) => Ok(Self::BinaryExpr {
-        projected_columns,
-        left: e.left.clone(),
+        left: cast_uuid_literal(&e.left, is_uuid)?,
         op: e.op.clone(),
-        right: e.right.clone(),
+        right: cast_uuid_literal(&e.right, is_uuid)?,
+        projected_columns,
       }),
       Expr::IsNotNull(e) => Ok(Self::IsNotNull {
         projected_columns,
@@ -88,6 +138,16 @@ impl Filter {
     }
   }
 
+  /// Returns the filters of each predicate of the conjunction such that
+  /// the predicates on different columns can be used to scan an index
+  /// on those columns
+  pub fn split_for_table(table: &Table, expr: &Expr) -> Result<Vec<Self>> {
+    split_conjunction(expr)
+      .into_iter()
+      .map(|expr| Self::for_table(table, expr))
+      .collect()
+  }
+
   #[inline]
   pub fn get_column_projection(&self) -> &Vec<usize> {
     match self {
@@ -100,6 +160,9 @@ impl Filter {
       | Self::Like {
         projected_columns, ..
       }
+      | Self::InList {
+        projected_columns, ..
+      }
       | Self::IsNull {
         projected_columns, ..
       }
@@ -143,93 +206,272 @@ impl Filter {
     }
   }
 
-  pub fn is_supported_by_index(&self, index: &TableIndex) -> bool {
-    self
-      .get_column_projection()
-      .iter()
-      .zip(index.columns().iter())
-      .fold(true, |agg, (filter_col, index_col)| {
-        agg && filter_col == index_col
-      })
+  /// Returns the operator and the literal if the filter compares the
+  /// column (or the value at a JSON path of the column) to a literal.
+  /// The operator is flipped if the literal is on the left side
+  fn get_column_comparison(&self) -> Option<(Operator, &ScalarValue)> {
+    let Self::BinaryExpr {
+      left, op, right, ..
+    } = self
+    else {
+      return None;
+    };
+    match (left.as_ref(), right.as_ref()) {
+      (column, Expr::Literal(value)) if is_column_or_json_path(column) => {
+        Some((*op, value))
+      }
+      (Expr::Literal(value), column) if is_column_or_json_path(column) => {
+        Some((op.swap()?, value))
+      }
+      _ => None,
+    }
   }
 
-  /// This is used to keep track of whether the filter will be properly
-  /// applied to the rows during scanning such that datafusion doesn't
-  /// have to re-apply the filter
-  pub fn is_filter_pushdown_suported(&self) -> bool {
+  /// Returns the values that the column is compared to if the filter
+  /// is '=' or 'IN' comparision
+  pub fn get_eq_literals(&self) -> Option<Vec<OwnedSerializedCell>> {
     match self {
-      Self::BinaryExpr { op, .. } => match op {
-        Operator::Eq => true,
-        _ => false,
+      Self::InList { expr, .. } if is_column_or_json_path(&expr.expr) => expr
+        .list
+        .iter()
+        .map(|value| match value {
+          Expr::Literal(value) => Some(OwnedSerializedCell::from_scalar(value)),
+          _ => None,
+        })
+        .collect(),
+      _ => match self.get_column_comparison()? {
+        (Operator::Eq, value) => {
+          Some(vec![OwnedSerializedCell::from_scalar(value)])
+        }
+        _ => None,
       },
+    }
+  }
+
+  /// Returns the operator and the value that the column is compared to
+  /// if the filter is '<', '<=', '>' or '>=' comparision
+  pub fn get_range_bound(&self) -> Option<(Operator, OwnedSerializedCell)> {
+    match self.get_column_comparison()? {
+      (
+        op @ (Operator::Lt | Operator::LtEq | Operator::Gt | Operator::GtEq),
+        value,
+      ) => Some((op, OwnedSerializedCell::from_scalar(value))),
+      _ => None,
+    }
+  }
+
+  /// Returns the namespace to search in the vector index if the filter is
+  /// '=' comparision on the namespace column of the index
+  pub fn get_vector_index_namespace(
+    &self,
+    index: &TableIndex,
+  ) -> Option<OwnedSerializedCell> {
+    match (
+      index.namespace_column(),
+      self.get_column_projection().as_slice(),
+    ) {
+      (Some(namespace_column), [column]) if namespace_column == *column => self
+        .get_binary_eq_literal()
+        .filter(|value| !value.is_null()),
+      _ => None,
+    }
+  }
+
+  /// Returns the JSON path if the filter is on the text value at the
+  /// path of a JSONB column, i.e. `data->'a'->>'b' = 'x'`
+  pub fn get_json_path(&self) -> Option<Vec<String>> {
+    match self {
+      Self::BinaryExpr { left, right, .. } => {
+        get_json_path(left, true).or_else(|| get_json_path(right, true))
+      }
+      Self::IsNotNull { expr, .. } | Self::IsNull { expr, .. } => {
+        get_json_path(expr, true)
+      }
+      Self::Like { expr, .. } => get_json_path(&expr.expr, true),
+      Self::InList { expr, .. } => get_json_path(&expr.expr, true),
+      _ => None,
+    }
+  }
+
+  /// Returns whether the filter is on the same JSON path as the JSON path
+  /// index or neither the filter nor the index is on a JSON path
+  pub fn matches_json_path(&self, index: &TableIndex) -> bool {
+    index.json_path() == self.get_json_path().as_ref()
+  }
+
+  pub fn is_supported_by_index(&self, index: &TableIndex) -> bool {
+    // Vector index can only be used for nearest neighbour search; the
+    // '=' filter on the namespace column is pushed down to the scanner
+    // so that the search can be limited to that namespace
+    if index.is_vector_index() {
+      return self.get_vector_index_namespace(index).is_some();
+    }
+    if !self.matches_json_path(index) {
+      return false;
+    }
+    // The filter on any column of the index is supported since the filters
+    // on the preceding columns might select the prefix of the index keys
+    match self.get_column_projection().as_slice() {
+      [column] => index.columns().contains(column),
       _ => false,
     }
   }
 
+  /// This is used to keep track of whether the filter can be used to
+  /// find the index keys to scan, i.e. '=', 'IN' and range comparisions
+  /// of the column to literals
+  pub fn is_filter_pushdown_suported(&self) -> bool {
+    self.get_eq_literals().is_some() || self.get_range_bound().is_some()
+  }
+
   #[inline]
   pub fn get_operator_cost(&self) -> f32 {
-    return 0.0025;
+    return ROW_FILTER_COST;
   }
 
   /// Cost is calcualted in the following way
   /// n = total number of rows
   /// m = average rows per unique column value
+  /// k = number of key prefixes selected by '=' and 'IN' filters
   ///
   /// unique index with exact columns:
-  /// cost = row_filter_cost
+  /// cost = k * row_filter_cost
+  ///
+  /// secondary index with exact columns:
+  /// cost = k * m * row_filter_cost
   ///
   /// secondary index:
-  /// first column cost = n/m * row_filter_cost
-  /// second column cost = n/2m * row_filter_cost
-  /// third column cost = n/3m * row_filter_cost
+  /// first column cost = k * n/1 * row_filter_cost
+  /// second column cost = k * n/2 * row_filter_cost
+  /// third column cost = k * n/3 * row_filter_cost
+  ///
+  /// The range filters on the column after the prefix columns count as
+  /// another matched column and the cost is multiplied by the fraction of
+  /// the rows that are estimated to be in the range
   ///
   /// if first column doesn't match,
   /// cost = n [because of entire index scan] * row_filter_cost
-  pub fn estimate_cost(&self, index: &TableIndex) -> f32 {
+  ///
+  /// The cost is doubled if the rows have to be looked up in the table
+  /// because the index doesn't have all the columns used by the query
+  pub fn estimate_index_scan_cost(
+    index: &TableIndex,
+    filters: &[Filter],
+    column_projection: &[usize],
+    statistics: Option<&TableStatistics>,
+  ) -> f32 {
+    let key_range = IndexKeyRange::new(index, filters);
+    let has_bounds = key_range.has_bounds();
+    // Index can't be used if no filter is on the first index column
+    if key_range.prefix_len == 0 && !has_bounds {
+      return f32::INFINITY;
+    }
     let index_columns = index.columns();
-    let matched_cols = self
-      .get_column_projection()
-      .iter()
-      .zip(index_columns.iter())
-      .take_while(|(filter_col, index_col)| filter_col == index_col)
-      .count();
+    let row_count = statistics
+      .map(|stats| stats.row_count)
+      .unwrap_or(DEFAULT_ROW_COUNT) as f64;
+    let index_stats =
+      statistics.and_then(|stats| stats.get_index_statistics(index.id));
+    let prefixes = key_range.prefixes.len() as f64;
 
-    // If the filter is '=', it doesn't require index scan,
-    // so, the cost is O(1)
-    if matched_cols == index_columns.len() && self.is_eq() {
-      return self.get_operator_cost() * 1.0;
-    }
-    // TODO: penalize the index if the index doesn't have all the
-    // columns used in the filter
+    let scanned_rows = match key_range.prefix_len == index_columns.len() {
+      // If all the index columns are compared with '=', it doesn't require
+      // index scan, so, only the rows with the same key are read
+      true => match index.is_unique() {
+        true => prefixes,
+        false => {
+          let rows_per_key = index_stats
+            .map(|stats| stats.get_rows_per_key(row_count as u64))
+            .unwrap_or(1.0)
+            .max(1.0);
+          prefixes * rows_per_key
+        }
+      },
+      false => {
+        let matched_cols = key_range.prefix_len + has_bounds as usize;
+        // Histogram is only of the first column of the index
+        let selectivity = key_range.estimate_bounds_selectivity(
+          index_stats.filter(|_| key_range.prefix_len == 0),
+        );
+        (prefixes * row_count * selectivity / matched_cols as f64)
+          .min(row_count)
+      }
+    };
 
-    // TODO: use estimated row count instead of 10_000
-    self.get_operator_cost() * 10_000.0 / matched_cols as f32
+    // JSON path index doesn't store the column
+    let requires_table_lookup = index.json_path().is_some()
+      || column_projection
+        .iter()
+        .any(|col| !index_columns.contains(col));
+    let lookup_cost = if requires_table_lookup { 2.0 } else { 1.0 };
+    ROW_FILTER_COST * scanned_rows as f32 * lookup_cost
   }
 
-  /// Returns the minimum cost of using the filters on the given index
+  /// Returns the estimated fraction of the rows of the table that match
+  /// the filter using the statistics of the index on the filter column
+  pub fn estimate_selectivity(
+    &self,
+    indexes: &[TableIndex],
+    statistics: &TableStatistics,
+  ) -> Option<f64> {
+    let projected_columns = self.get_column_projection();
+    indexes
+      .iter()
+      .filter(|index| {
+        !index.is_vector_index()
+          && self.matches_json_path(index)
+          && index.columns().first() == projected_columns.first()
+      })
+      .find_map(|index| {
+        let index_stats = statistics.get_index_statistics(index.id)?;
+        match self.get_eq_literals() {
+          // Distinct count is of the whole index key
+          Some(values) if index.columns().len() == 1 => Some(
+            (values.len() as f64 / index_stats.distinct_count.max(1) as f64)
+              .min(1.0),
+          ),
+          Some(_) => None,
+          None => {
+            let (op, value) = self.get_range_bound()?;
+            index_stats.estimate_range_selectivity(op, &value)
+          }
+        }
+      })
+  }
+
+  /// Returns the cost of scanning all the rows of the table
+  pub fn estimate_table_scan_cost(statistics: &TableStatistics) -> f32 {
+    ROW_FILTER_COST * statistics.row_count as f32
+  }
+
+  /// Returns the index with minimum cost of using the filters on it.
+  /// If the statistics of the table are known, returns `None` if scanning
+  /// the table is cheaper than using any index
   pub fn find_index_with_lowest_cost<'a>(
     indexes: &'a Vec<TableIndex>,
     filters: &'a Vec<Filter>,
+    column_projection: &[usize],
+    statistics: Option<&TableStatistics>,
   ) -> Option<&'a TableIndex> {
     if filters.is_empty() {
       return None;
     }
     indexes
       .iter()
+      .filter(|index| !index.is_vector_index())
       .map(|index| {
-        let lowest_cost = filters
-          .iter()
-          .map(|filter| filter.estimate_cost(index))
-          .min_by(|a, b| {
-            if a > b {
-              Ordering::Greater
-            } else {
-              Ordering::Less
-            }
-          })
-          .unwrap_or(f32::INFINITY);
-        (index, lowest_cost)
+        let cost = Self::estimate_index_scan_cost(
+          index,
+          filters,
+          column_projection,
+          statistics,
+        );
+        (index, cost)
       })
+      // Scanning the entire index isn't cheaper than scanning the table
+      // and the index that covers all the columns of the query is used
+      // by the scanner if no index can be used for the filters
+      .filter(|(_, cost)| cost.is_finite())
       .min_by(|index1, index2| {
         if index1.1 > index2.1 {
           Ordering::Greater
@@ -237,6 +479,282 @@ impl Filter {
           Ordering::Less
         }
       })
+      .filter(|(_, cost)| {
+        statistics
+          .map(|stats| *cost <= Self::estimate_table_scan_cost(stats))
+          .unwrap_or(true)
+      })
       .map(|(index, _)| index)
   }
 }
+
+impl fmt::Display for Filter {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    match self {
+      Self::BinaryExpr {
+        left, op, right, ..
+      } => write!(f, "{} {} {}", left, op, right),
+      Self::IsNotNull { expr, .. } => write!(f, "{} IS NOT NULL", expr),
+      Self::Like { expr, .. } => write!(f, "{}", Expr::Like(expr.clone())),
+      Self::InList { expr, .. } => {
+        write!(f, "{}", Expr::InList(expr.clone()))
+      }
+      Self::IsNull { expr, .. } => write!(f, "{} IS NULL", expr),
+      Self::IsTrue { expr, .. } => write!(f, "{} IS TRUE", expr),
+      Self::IsFalse { expr, .. } => write!(f, "{} IS FALSE", expr),
+    }
+  }
+}
+
+/// Keys of an index that can have the rows matching the filters
+#[derive(Debug, Clone)]
+pub struct IndexKeyRange {
+  /// Values of the leading index columns that are compared with '=' or
+  /// 'IN'; each combination of the values is a key prefix to scan
+  pub prefixes: Vec<Vec<OwnedSerializedCell>>,
+  /// Number of index columns in each prefix
+  pub prefix_len: usize,
+  /// Lower bound of the index column after the prefix columns and
+  /// whether the bound is inclusive
+  pub lower: Option<(OwnedSerializedCell, bool)>,
+  /// Upper bound of the index column after the prefix columns and
+  /// whether the bound is inclusive
+  pub upper: Option<(OwnedSerializedCell, bool)>,
+  /// Number of filters that are fully applied by scanning the range
+  pub applied_filters: usize,
+}
+
+impl IndexKeyRange {
+  pub fn new(index: &TableIndex, filters: &[Filter]) -> Self {
+    let mut range = Self {
+      prefixes: vec![vec![]],
+      prefix_len: 0,
+      lower: None,
+      upper: None,
+      applied_filters: 0,
+    };
+    let column_filters = |column: usize| {
+      filters.iter().filter(move |filter| {
+        filter.get_column_projection().as_slice() == [column]
+          && filter.matches_json_path(index)
+      })
+    };
+
+    for column in index.columns() {
+      let Some(values) =
+        column_filters(*column).find_map(|filter| filter.get_eq_literals())
+      else {
+        break;
+      };
+      if range.prefixes.len() * values.len() > MAX_INDEX_KEY_PREFIXES {
+        break;
+      }
+      // Comparision with NULL never matches, so the keys with NULL
+      // don't have to be scanned
+      let mut distinct_values: Vec<OwnedSerializedCell> = vec![];
+      for value in values {
+        if !value.is_null() && !distinct_values.contains(&value) {
+          distinct_values.push(value);
+        }
+      }
+      range.prefixes = range
+        .prefixes
+        .iter()
+        .flat_map(|prefix| {
+          distinct_values.iter().map(|value| {
+            let mut prefix = prefix.clone();
+            prefix.push(value.clone());
+            prefix
+          })
+        })
+        .collect();
+      range.prefix_len += 1;
+      range.applied_filters += 1;
+    }
+
+    let Some(column) = index.columns().get(range.prefix_len) else {
+      return range;
+    };
+    for (op, value) in
+      column_filters(*column).filter_map(|filter| filter.get_range_bound())
+    {
+      if value.is_null() {
+        range.prefixes.clear();
+        range.applied_filters += 1;
+        continue;
+      }
+      let is_lower = matches!(op, Operator::Gt | Operator::GtEq);
+      let inclusive = matches!(op, Operator::GtEq | Operator::LtEq);
+      let bound = match is_lower {
+        true => &mut range.lower,
+        false => &mut range.upper,
+      };
+      match bound {
+        None => *bound = Some((value, inclusive)),
+        Some((current, current_inclusive)) => {
+          match compare_cells(&value, current) {
+            Some(Ordering::Equal) => *current_inclusive &= inclusive,
+            // Use the narrower bound
+            Some(order) if (order == Ordering::Greater) == is_lower => {
+              *bound = Some((value, inclusive))
+            }
+            Some(_) => {}
+            None => continue,
+          }
+        }
+      }
+      range.applied_filters += 1;
+    }
+    range
+  }
+
+  #[inline]
+  pub fn has_bounds(&self) -> bool {
+    self.lower.is_some() || self.upper.is_some()
+  }
+
+  /// Returns `Less` if the value of the index column after the prefix
+  /// columns is below the lower bound, `Greater` if it's above the upper
+  /// bound and `Equal` if it's within the bounds. Since NULLs are before
+  /// the other values in the index, NULL is below the lower bound.
+  /// If the value can't be compared with a bound, it's considered to be
+  /// within the bound
+  pub fn compare_to_bounds(&self, value: &OwnedSerializedCell) -> Ordering {
+    if value.is_null() && self.has_bounds() {
+      return Ordering::Less;
+    }
+    if let Some((lower, inclusive)) = &self.lower {
+      match compare_cells(value, lower) {
+        Some(Ordering::Less) => return Ordering::Less,
+        Some(Ordering::Equal) if !inclusive => return Ordering::Less,
+        _ => {}
+      }
+    }
+    if let Some((upper, inclusive)) = &self.upper {
+      match compare_cells(value, upper) {
+        Some(Ordering::Greater) => return Ordering::Greater,
+        Some(Ordering::Equal) if !inclusive => return Ordering::Greater,
+        _ => {}
+      }
+    }
+    Ordering::Equal
+  }
+
+  /// Returns the estimated fraction of the rows with the same prefix that
+  /// are within the bounds
+  fn estimate_bounds_selectivity(
+    &self,
+    statistics: Option<&IndexStatistics>,
+  ) -> f64 {
+    let estimate = |bound: &Option<(OwnedSerializedCell, bool)>, op| {
+      bound.as_ref().map(|(value, _)| {
+        statistics
+          .and_then(|stats| stats.estimate_range_selectivity(op, value))
+          .unwrap_or(DEFAULT_RANGE_SELECTIVITY)
+      })
+    };
+    match (
+      estimate(&self.lower, Operator::GtEq),
+      estimate(&self.upper, Operator::LtEq),
+    ) {
+      (Some(lower), Some(upper)) => {
+        (lower + upper - 1.0).max(MIN_RANGE_SELECTIVITY)
+      }
+      (Some(selectivity), None) | (None, Some(selectivity)) => selectivity,
+      (None, None) => 1.0,
+    }
+  }
+}
+
+/// Returns `column IN (a, b)` for `column = a OR column = b`
+fn get_in_list_of_disjunction(expr: &Expr) -> Option<InList> {
+  let mut column: Option<&Expr> = None;
+  let mut list = vec![];
+  for disjunct in split_binary(expr, Operator::Or) {
+    let Expr::BinaryExpr(BinaryExpr {
+      left,
+      op: Operator::Eq,
+      right,
+    }) = disjunct
+    else {
+      return None;
+    };
+    let (lhs, value) = match (left.as_ref(), right.as_ref()) {
+      (lhs, value @ Expr::Literal(_)) | (value @ Expr::Literal(_), lhs) => {
+        (lhs, value)
+      }
+      _ => return None,
+    };
+    if *column.get_or_insert(lhs) != lhs {
+      return None;
+    }
+    list.push(value.clone());
+  }
+  Some(InList::new(Box::new(column?.clone()), list, false))
+}
+
+#[inline]
+fn is_column_or_json_path(expr: &Expr) -> bool {
+  matches!(expr, Expr::Column(_)) || get_json_path(expr, true).is_some()
+}
+
+/// UUIDs are passed to datafusion as strings but stored as bytes. So,
+/// convert the string literal compared to UUID column to bytes such that
+/// it matches the serialized cells in the index
+fn cast_uuid_literal(expr: &Expr, is_uuid: bool) -> Result<Box<Expr>> {
+  match expr {
+    Expr::Literal(ScalarValue::Utf8(Some(value))) if is_uuid => {
+      Ok(Box::new(Expr::Literal(ScalarValue::FixedSizeBinary(
+        16,
+        Some(parse_uuid(value)?.to_vec()),
+      ))))
+    }
+    _ => Ok(Box::new(expr.clone())),
+  }
+}
+
+fn cast_uuid_literals_in_list(
+  in_list: &InList,
+  is_uuid: bool,
+) -> Result<InList> {
+  Ok(InList::new(
+    in_list.expr.clone(),
+    in_list
+      .list
+      .iter()
+      .map(|value| cast_uuid_literal(value, is_uuid).map(|value| *value))
+      .collect::<Result<Vec<Expr>>>()?,
+    in_list.negated,
+  ))
+}
+
+/// Returns the path of the JSONB column accessed by `jsonb_get_text` and
+/// `jsonb_get_path_text` (or `jsonb_get` and `jsonb_get_path` if `text` is
+/// false) expressions on the column, i.e. `data->'a'->>'b'`
+fn get_json_path(expr: &Expr, text: bool) -> Option<Vec<String>> {
+  if !text && matches!(expr, Expr::Column(_)) {
+    return Some(vec![]);
+  }
+  let Expr::ScalarUDF(ScalarUDF { fun, args }) = expr else {
+    return None;
+  };
+  let (get, get_path) = match text {
+    true => ("jsonb_get_text", "jsonb_get_path_text"),
+    false => ("jsonb_get", "jsonb_get_path"),
+  };
+  let (json, keys) = match args.as_slice() {
+    [json, Expr::Literal(ScalarValue::Utf8(Some(key)))] if fun.name == get => {
+      (json, vec![key.clone()])
+    }
+    [json, Expr::Literal(ScalarValue::Utf8(Some(path)))]
+      if fun.name == get_path =>
+    {
+      let keys = parse_array_elements(path).ok()?;
+      (json, keys.into_iter().collect::<Option<Vec<String>>>()?)
+    }
+    _ => return None,
+  };
+  let mut path = get_json_path(json, false)?;
+  path.extend(keys);
+  Some(path)
+}
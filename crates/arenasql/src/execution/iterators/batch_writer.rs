@@ -0,0 +1,140 @@
+use datafusion::arrow::datatypes::SchemaRef;
+use datafusion::arrow::record_batch::RecordBatch;
+
+use crate::schema::{DataFrame, DataType, SerializedCell};
+use crate::Result;
+
+/// Destination of the rows read by the table iterators
+pub trait RowSink {
+  /// Appends the selected columns of the row and returns whether more
+  /// rows should be appended
+  fn append_row(
+    &mut self,
+    row_id: &[u8],
+    columns: &Vec<&SerializedCell<'_>>,
+  ) -> Result<bool>;
+}
+
+impl RowSink for DataFrame {
+  #[inline]
+  fn append_row(
+    &mut self,
+    row_id: &[u8],
+    columns: &Vec<&SerializedCell<'_>>,
+  ) -> Result<bool> {
+    DataFrame::append_row(self, row_id, columns);
+    Ok(true)
+  }
+}
+
+/// Collects the rows read by the table iterators into record batches of
+/// at most `batch_size` rows and passes each batch to the callback as
+/// soon as it's full, so that the rows don't have to be kept in memory
+/// until the whole table is scanned
+pub struct RecordBatchWriter<'a> {
+  schema: SchemaRef,
+  columns: Vec<(String, DataType)>,
+  batch_size: usize,
+  /// Number of rows that can still be written; `None` if there's no limit
+  remaining_rows: Option<usize>,
+  dataframe: DataFrame,
+  /// Returns false if no more batches are needed
+  on_batch: &'a mut dyn FnMut(RecordBatch) -> Result<bool>,
+  /// Returns true if the rest of the rows aren't needed anymore; checked
+  /// for every row so that the scan stops without waiting for the batch
+  /// to be full
+  is_cancelled: Option<&'a dyn Fn() -> bool>,
+  sent_batches: usize,
+  done: bool,
+}
+
+impl<'a> RecordBatchWriter<'a> {
+  pub fn new(
+    schema: SchemaRef,
+    columns: Vec<(
+      // column name
+      String,
+      DataType,
+    )>,
+    batch_size: usize,
+    limit: Option<usize>,
+    on_batch: &'a mut dyn FnMut(RecordBatch) -> Result<bool>,
+  ) -> Self {
+    let batch_size = batch_size.max(1);
+    let capacity = limit.unwrap_or(batch_size).min(batch_size);
+    Self {
+      schema,
+      dataframe: DataFrame::with_capacity(capacity, columns.clone()),
+      columns,
+      batch_size,
+      remaining_rows: limit,
+      on_batch,
+      is_cancelled: None,
+      sent_batches: 0,
+      done: limit == Some(0),
+    }
+  }
+
+  /// Stops writing the rows once `is_cancelled` returns true
+  pub fn with_cancellation(
+    mut self,
+    is_cancelled: &'a dyn Fn() -> bool,
+  ) -> Self {
+    self.is_cancelled = Some(is_cancelled);
+    self
+  }
+
+  /// Passes the rows that are written after the last batch to the
+  /// callback. If no batches were passed yet, an empty batch is passed
+  /// so that the scan always returns a batch with the schema
+  pub fn finish(&mut self) -> Result<()> {
+    match self.dataframe.row_count() > 0 || self.sent_batches == 0 {
+      true => self.send_batch(),
+      false => Ok(()),
+    }
+  }
+
+  fn send_batch(&mut self) -> Result<()> {
+    let capacity = self
+      .remaining_rows
+      .unwrap_or(self.batch_size)
+      .min(self.batch_size);
+    let dataframe = std::mem::replace(
+      &mut self.dataframe,
+      DataFrame::with_capacity(capacity, self.columns.clone()),
+    );
+    let batch = dataframe.to_record_batch(self.schema.clone())?;
+    self.sent_batches += 1;
+    if !(self.on_batch)(batch)? {
+      self.done = true;
+    }
+    Ok(())
+  }
+}
+
+impl<'a> RowSink for RecordBatchWriter<'a> {
+  fn append_row(
+    &mut self,
+    row_id: &[u8],
+    columns: &Vec<&SerializedCell<'_>>,
+  ) -> Result<bool> {
+    let is_cancelled = self.is_cancelled.is_some_and(|cancelled| cancelled());
+    if self.done || is_cancelled {
+      self.done = true;
+      return Ok(false);
+    }
+    self.dataframe.append_row(row_id, columns);
+    if let Some(remaining_rows) = self.remaining_rows.as_mut() {
+      *remaining_rows -= 1;
+      if *remaining_rows == 0 {
+        self.send_batch()?;
+        self.done = true;
+        return Ok(false);
+      }
+    }
+    if self.dataframe.row_count() >= self.batch_size {
+      self.send_batch()?;
+    }
+    Ok(!self.done)
+  }
+}
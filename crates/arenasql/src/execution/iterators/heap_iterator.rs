@@ -1,9 +1,11 @@
-use crate::schema::{DataFrame, Row, Table};
+use super::RowSink;
+use crate::schema::{Row, SerializedCell, Table};
 use crate::storage::{KeyValueGroup, KeyValueIterator, StorageHandler};
 use crate::{table_rows_prefix_key, Result};
 
 pub struct HeapIterator<'a> {
   storage: &'a StorageHandler,
+  table: &'a Table,
   column_projection: &'a Vec<usize>,
   row_prefix: Vec<u8>,
   rows_iter: Box<dyn KeyValueIterator>,
@@ -22,6 +24,7 @@ impl<'a> HeapIterator<'a> {
     let row_prefix = table_rows_prefix_key!(table.id);
     Self {
       storage,
+      table,
       column_projection,
       row_prefix,
       rows_iter,
@@ -32,7 +35,9 @@ impl<'a> HeapIterator<'a> {
   pub fn get(&'a self) -> Result<Option<(&'a [u8], Row<'a>)>> {
     if let Some((row_id_with_prefix, row_bytes)) = self.rows_iter.get() {
       let row_id = &row_id_with_prefix[self.row_prefix.len()..];
-      let row = self.storage.serializer.deserialize::<Row<'_>>(row_bytes)?;
+      let row = self
+        .storage
+        .deserialize_row(self.table, row_id, row_bytes)?;
       Ok(Some((row_id, row)))
     } else {
       Ok(None)
@@ -44,15 +49,21 @@ impl<'a> HeapIterator<'a> {
     self.rows_iter.next();
   }
 
-  pub fn fill_into(&mut self, dataframe: &mut DataFrame) -> Result<()> {
+  /// Appends the selected columns of the rows to the sink until all the
+  /// rows are appended or the sink doesn't need more rows
+  pub fn fill_into(&mut self, sink: &mut dyn RowSink) -> Result<()> {
     while let Some((row_id, row)) = self.get()? {
+      // Note: rows inserted before a column was added don't have
+      // the cells of the new columns
       let columns = self
         .column_projection
         .iter()
-        .map(|proj| &row[*proj])
+        .map(|proj| row.get(*proj).unwrap_or(&SerializedCell::Null))
         .collect();
 
-      dataframe.append_row(row_id, &columns);
+      if !sink.append_row(row_id, &columns)? {
+        break;
+      }
       self.next();
     }
     Ok(())
@@ -1,12 +1,46 @@
-use crate::execution::filter::Filter;
+use std::cmp::Ordering;
+
+use super::RowSink;
+use crate::execution::filter::{Filter, IndexKeyRange};
 use crate::schema::{
-  DataFrame, OwnedSerializedCell, Row, RowId, SerializedCell, Table, TableIndex,
+  compare_cells, DataType, OwnedSerializedCell, RowId, SerializedCell, Table,
+  TableIndex,
 };
-use crate::storage::{KeyValueGroup, KeyValueIterator, StorageHandler};
+use crate::storage::{KeyValueGroup, StorageHandler};
 use crate::{
   index_row_key, index_rows_prefix_key, table_row_key, Error, Result,
 };
 
+/// Order of the rows returned by the index scan
+#[derive(Debug, Clone, PartialEq)]
+pub struct IndexOrder {
+  pub descending: bool,
+  pub nulls_first: bool,
+  /// Number of rows to return
+  pub limit: usize,
+}
+
+impl IndexOrder {
+  pub fn compare(
+    &self,
+    a: &OwnedSerializedCell,
+    b: &OwnedSerializedCell,
+  ) -> Ordering {
+    match (a.is_null(), b.is_null(), self.nulls_first) {
+      (true, true, _) => Ordering::Equal,
+      (true, false, true) | (false, true, false) => Ordering::Less,
+      (true, false, false) | (false, true, true) => Ordering::Greater,
+      (false, false, _) => {
+        let order = compare_cells(a, b).unwrap_or(Ordering::Equal);
+        match self.descending {
+          true => order.reverse(),
+          false => order,
+        }
+      }
+    }
+  }
+}
+
 #[allow(unused)]
 pub struct IndexIterator<'a> {
   storage: &'a StorageHandler,
@@ -14,6 +48,9 @@ pub struct IndexIterator<'a> {
   index: &'a TableIndex,
   column_projection: &'a Vec<usize>,
   filters: &'a Vec<Filter>,
+  /// If set, the rows are returned in this order of the index column
+  /// after the prefix columns selected by the '=' filters
+  order: Option<&'a IndexOrder>,
 }
 
 impl<'a> IndexIterator<'a> {
@@ -30,172 +67,219 @@ impl<'a> IndexIterator<'a> {
       index,
       column_projection,
       filters,
+      order: None,
     }
   }
 
-  pub fn fill_into(&self, dataframe: &mut DataFrame) -> Result<()> {
-    let index_scan_prefix_row = self.select_eq_filters_for_prefix();
-    let index_scan_prefix =
-      self.generate_index_scan_prefix(&index_scan_prefix_row)?;
-    let _scan_filters = self
-      .filters
-      .split_at(index_scan_prefix_row.len())
-      .1
-      .to_vec();
-
-    let mut index_iter = self
-      .storage
-      .kv
-      .scan_with_prefix(KeyValueGroup::IndexRows, &index_scan_prefix)?;
+  pub fn with_order(mut self, order: Option<&'a IndexOrder>) -> Self {
+    self.order = order;
+    self
+  }
 
-    match (self.index.is_unique(), self.requires_table_lookup()) {
-      (true, false) => {
-        self.scan_unique_index_into_df(&mut index_iter, dataframe)
-      }
-      (true, true) => self.scan_unique_index_with_table_lookup_into_df(
-        &mut index_iter,
-        dataframe,
-      ),
-      (false, false) => {
-        self.scan_secondary_index_into_df(&mut index_iter, dataframe)
-      }
-      (false, true) => self.scan_secondary_index_with_table_lookup_nto_df(
-        &mut index_iter,
-        dataframe,
-      ),
+  /// Appends the selected columns of the rows that match the filters to
+  /// the sink until all the rows are appended or the sink doesn't need
+  /// more rows
+  pub fn fill_into(&self, sink: &mut dyn RowSink) -> Result<()> {
+    let key_range = IndexKeyRange::new(self.index, self.filters);
+    if let Some(order) = self.order {
+      return self.fill_ordered_into(&key_range, order, sink);
     }
-  }
 
-  /// This scans the unique index and fills the dataframe
-  /// This ONLY handles case where unique index is used and
-  /// all selected columns are present in the index
-  fn scan_unique_index_into_df(
-    &self,
-    index_iter: &mut Box<dyn KeyValueIterator>,
-    dataframe: &mut DataFrame,
-  ) -> Result<()> {
-    let index_prefix = index_rows_prefix_key!(self.index.id);
+    let requires_table_lookup = self.requires_table_lookup();
     let projection_on_index_columns =
       self.valid_index_columns_projection(self.column_projection);
-    while let Some((index_row_with_prefix, row_id)) = index_iter.get() {
-      let index_row_bytes = &index_row_with_prefix[index_prefix.len()..];
-      let index_columns = self
-        .storage
-        .serializer
-        .deserialize::<Vec<SerializedCell<'_>>>(index_row_bytes)?;
-
-      let selected_columns = projection_on_index_columns
-        .iter()
-        .map(|proj| &index_columns[*proj])
-        .collect();
-
-      dataframe.append_row(row_id, &selected_columns);
-      index_iter.next();
-    }
-    Ok(())
+    self.for_each_index_entry(&key_range, &mut |index_columns, row_id| {
+      match requires_table_lookup {
+        true => self.append_table_row(row_id, sink),
+        false => {
+          let selected_columns = projection_on_index_columns
+            .iter()
+            .map(|proj| &index_columns[*proj])
+            .collect();
+          sink.append_row(row_id, &selected_columns)
+        }
+      }
+    })
   }
 
-  /// This scans the non-unique index and fills the dataframe
-  /// This ONLY handles case where non-unique index is used and
-  /// all selected columns are present in the index
-  fn scan_secondary_index_into_df(
+  /// Fills the first rows in the order of the index column after the
+  /// prefix columns. If the index keys of the column are in the same
+  /// order as the values, the scan stops after the first rows. Otherwise,
+  /// the first rows are selected while scanning the index so that only
+  /// those rows have to be read from the table
+  fn fill_ordered_into(
     &self,
-    index_iter: &mut Box<dyn KeyValueIterator>,
-    dataframe: &mut DataFrame,
+    key_range: &IndexKeyRange,
+    order: &IndexOrder,
+    sink: &mut dyn RowSink,
   ) -> Result<()> {
-    let index_prefix = index_rows_prefix_key!(self.index.id);
-    let projection_on_index_columns =
-      self.valid_index_columns_projection(self.column_projection);
-    while let Some((index_row_with_prefix, _)) = index_iter.get() {
-      let index_row_bytes = &index_row_with_prefix[index_prefix.len()..];
-      let (index_columns, row_id) =
-        self
-          .storage
-          .serializer
-          .deserialize::<(Vec<SerializedCell<'_>>, &[u8])>(index_row_bytes)?;
-
-      let selected_columns = projection_on_index_columns
-        .iter()
-        .map(|proj| &index_columns[*proj])
-        .collect();
+    // NULLs are before the other values in the index
+    let stops_early = !order.descending
+      && key_range.prefixes.len() == 1
+      && self.has_ordered_keys(key_range);
+    let mut rows: Vec<(OwnedSerializedCell, Vec<u8>)> = vec![];
+    let mut rows_in_order = 0;
+    self.for_each_index_entry(key_range, &mut |index_columns, row_id| {
+      let value = index_columns
+        .get(key_range.prefix_len)
+        .map(OwnedSerializedCell::from)
+        .unwrap_or_default();
+      if order.nulls_first || !value.is_null() {
+        rows_in_order += 1;
+      }
+      rows.push((value, row_id.to_vec()));
+      if rows.len() >= 2 * order.limit.max(1) {
+        rows.sort_by(|a, b| order.compare(&a.0, &b.0));
+        rows.truncate(order.limit);
+      }
+      Ok(!stops_early || rows_in_order < order.limit)
+    })?;
 
-      dataframe.append_row(row_id, &selected_columns);
-      index_iter.next();
+    rows.sort_by(|a, b| order.compare(&a.0, &b.0));
+    rows.truncate(order.limit);
+    for (_, row_id) in rows {
+      if !self.append_table_row(&row_id, sink)? {
+        break;
+      }
     }
     Ok(())
   }
 
-  fn scan_secondary_index_with_table_lookup_nto_df(
+  /// Calls the callback with the index columns and the row id of each
+  /// index entry in the key range until the callback returns false
+  fn for_each_index_entry(
     &self,
-    index_iter: &mut Box<dyn KeyValueIterator>,
-    dataframe: &mut DataFrame,
+    key_range: &IndexKeyRange,
+    callback: &mut dyn FnMut(&[SerializedCell<'_>], &[u8]) -> Result<bool>,
   ) -> Result<()> {
     let index_prefix = index_rows_prefix_key!(self.index.id);
-    while let Some((index_row_with_prefix, _)) = index_iter.get() {
-      let index_row_bytes = &index_row_with_prefix[index_prefix.len()..];
-      let (_, row_id) = self
-        .storage
-        .serializer
-        .deserialize::<(Vec<SerializedCell<'_>>, &[u8])>(index_row_bytes)?;
+    let has_ordered_keys = self.has_ordered_keys(key_range);
+    for prefix in &key_range.prefixes {
+      let scan_prefix = self.generate_index_scan_prefix(prefix)?;
+      let mut index_iter = match &key_range.lower {
+        // Seek to the first key of the lower bound
+        Some((lower, _)) if has_ordered_keys => {
+          let mut start = prefix.clone();
+          start.push(lower.clone());
+          self.storage.kv.scan_with_prefix_from(
+            KeyValueGroup::IndexRows,
+            &scan_prefix,
+            &self.generate_index_scan_prefix(&start)?,
+          )?
+        }
+        _ => self
+          .storage
+          .kv
+          .scan_with_prefix(KeyValueGroup::IndexRows, &scan_prefix)?,
+      };
 
-      let row_bytes = self
-        .storage
-        .kv
-        .get(KeyValueGroup::Rows, &table_row_key!(self.table.id, &row_id))?
-        .ok_or_else(|| {
-          Error::IOError(format!(
-            "Couldn't find row data for rowid: {:?}",
-            RowId::deserialize(&row_id)
-          ))
-        })?;
+      while let Some((index_row_with_prefix, value)) = index_iter.get() {
+        let index_row_bytes = &index_row_with_prefix[index_prefix.len()..];
+        // Unique index has the row id as the value and the non-unique
+        // index has it at the end of the key
+        let (index_columns, row_id) = match self.index.is_unique() {
+          true => (
+            self
+              .storage
+              .serializer
+              .deserialize::<Vec<SerializedCell<'_>>>(index_row_bytes)?,
+            value,
+          ),
+          false => self
+            .storage
+            .serializer
+            .deserialize::<(Vec<SerializedCell<'_>>, &[u8])>(index_row_bytes)?,
+        };
 
-      let row = self.storage.serializer.deserialize::<Row<'_>>(&row_bytes)?;
-      let selected_columns = self
-        .column_projection
-        .iter()
-        .map(|proj| &row[*proj])
-        .collect();
+        if key_range.has_bounds() {
+          let value = index_columns
+            .get(key_range.prefix_len)
+            .map(OwnedSerializedCell::from)
+            .unwrap_or_default();
+          match key_range.compare_to_bounds(&value) {
+            Ordering::Equal => {}
+            // Rest of the keys with the prefix are above the upper bound
+            Ordering::Greater if has_ordered_keys => break,
+            _ => {
+              index_iter.next();
+              continue;
+            }
+          }
+        }
 
-      dataframe.append_row(&row_id, &selected_columns);
-      index_iter.next();
+        if !callback(&index_columns, row_id)? {
+          return Ok(());
+        }
+        index_iter.next();
+      }
     }
     Ok(())
   }
 
-  /// This scans the unique index and looks up the table rows to
-  /// get columns that are not present in the index. This handles
-  /// the case where unique index is used but required looking up
-  /// the table because the index doesn't have all the columns selected
-  /// by the query
-  fn scan_unique_index_with_table_lookup_into_df(
+  /// Looks up the row in the table and appends the selected columns;
+  /// returns whether the sink needs more rows
+  fn append_table_row(
     &self,
-    index_iter: &mut Box<dyn KeyValueIterator>,
-    dataframe: &mut DataFrame,
-  ) -> Result<()> {
-    while let Some((_, row_id)) = index_iter.get() {
-      let row_bytes = self
-        .storage
-        .kv
-        .get(KeyValueGroup::Rows, &table_row_key!(self.table.id, &row_id))?
-        .ok_or_else(|| {
-          Error::IOError(format!(
-            "Couldn't find row data for rowid: {:?}",
-            RowId::deserialize(&row_id)
-          ))
-        })?;
+    row_id: &[u8],
+    sink: &mut dyn RowSink,
+  ) -> Result<bool> {
+    let row_bytes = self
+      .storage
+      .kv
+      .get(KeyValueGroup::Rows, &table_row_key!(self.table.id, &row_id))?
+      .ok_or_else(|| {
+        Error::IOError(format!(
+          "Couldn't find row data for rowid: {:?}",
+          RowId::deserialize(&row_id)
+        ))
+      })?;
 
-      let row = self.storage.serializer.deserialize::<Row<'_>>(&row_bytes)?;
+    let row = self
+      .storage
+      .deserialize_row(self.table, &row_id, &row_bytes)?;
+    // Note: rows inserted before a column was added don't have
+    // the cells of the new columns
+    let selected_columns = self
+      .column_projection
+      .iter()
+      .map(|proj| row.get(*proj).unwrap_or(&SerializedCell::Null))
+      .collect();
 
-      let selected_columns = self
-        .column_projection
-        .iter()
-        .map(|proj| &row[*proj])
-        .collect();
+    sink.append_row(&row_id, &selected_columns)
+  }
 
-      dataframe.append_row(&row_id, &selected_columns);
-      index_iter.next();
+  /// Index keys are compared by their serialized bytes. Returns whether
+  /// the keys of the values of the index column after the prefix columns
+  /// that are within the bounds are in the same order as the values.
+  /// Integers are zigzag encoded and the sign is the first bit of floats,
+  /// so their keys are only ordered if the values are non-negative
+  fn has_ordered_keys(&self, key_range: &IndexKeyRange) -> bool {
+    if self.index.json_path().is_some() {
+      return false;
+    }
+    let Some(column) = self.index.columns().get(key_range.prefix_len) else {
+      return false;
+    };
+    match self.table.columns[*column].data_type {
+      DataType::Boolean
+      | DataType::UInt32
+      | DataType::UInt64
+      | DataType::Uuid => true,
+      DataType::Int16
+      | DataType::Int32
+      | DataType::Int64
+      | DataType::Float32
+      | DataType::Float64
+      | DataType::Date
+      | DataType::Time
+      | DataType::Timestamp
+      | DataType::TimestampTz => key_range
+        .lower
+        .as_ref()
+        .map(|(lower, _)| is_non_negative(lower))
+        .unwrap_or(false),
+      _ => false,
     }
-    Ok(())
   }
 
   #[inline]
@@ -217,6 +301,10 @@ impl<'a> IndexIterator<'a> {
     &self,
     column_projection: &Vec<usize>,
   ) -> Vec<usize> {
+    // JSON path index only has the value at the path, not the column
+    if self.index.json_path().is_some() {
+      return vec![];
+    }
     column_projection
       .iter()
       .filter_map(|col| {
@@ -229,39 +317,6 @@ impl<'a> IndexIterator<'a> {
       .collect()
   }
 
-  /// returns a row with columns that can be used to generate a prefix
-  /// key to iterate over the key value store. Only the columns with
-  /// exact match filter are returned and they are in the same order
-  /// as they appear in the index such that concatenating the returned
-  /// rows will make a valid prefix (as long as it's serialized properly
-  /// to match the prefix when if the returned row doesn't contain all the
-  /// columns used in the index. For example, bincode serialization adds
-  /// the vec length at the begining of the serialized value, so that should
-  /// be changed to match the number of index columns if the returned
-  /// row doesn't have all the columns in the index)
-  fn select_eq_filters_for_prefix(&'a self) -> Vec<OwnedSerializedCell> {
-    self
-      .index
-      .columns()
-      .iter()
-      .map(|col| {
-        self.filters.iter().find_map(|filter| {
-          if filter
-            .get_column_projection()
-            .iter()
-            .any(|col_proj| *col_proj == *col)
-          {
-            filter.get_binary_eq_literal()
-          } else {
-            None
-          }
-        })
-      })
-      .take_while(|lit| lit.is_some())
-      .map(|v| v.unwrap())
-      .collect::<Vec<OwnedSerializedCell>>()
-  }
-
   fn generate_index_scan_prefix<'b>(
     &self,
     eq_filter_for_prefix: &Vec<OwnedSerializedCell>,
@@ -298,3 +353,17 @@ impl<'a> IndexIterator<'a> {
     }
   }
 }
+
+fn is_non_negative(value: &OwnedSerializedCell) -> bool {
+  match value {
+    OwnedSerializedCell::Int16(v) => *v >= 0,
+    OwnedSerializedCell::Int32(v) | OwnedSerializedCell::Date(v) => *v >= 0,
+    OwnedSerializedCell::Int64(v)
+    | OwnedSerializedCell::Time(v)
+    | OwnedSerializedCell::Timestamp(v)
+    | OwnedSerializedCell::TimestampTz(v) => *v >= 0,
+    OwnedSerializedCell::Float32(v) => v.is_sign_positive(),
+    OwnedSerializedCell::Float64(v) => v.is_sign_positive(),
+    _ => false,
+  }
+}
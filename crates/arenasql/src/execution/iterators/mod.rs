@@ -1,5 +1,9 @@
+mod batch_writer;
 mod heap_iterator;
 mod index_iterator;
+mod vector_index_iterator;
 
+pub(crate) use batch_writer::{RecordBatchWriter, RowSink};
 pub use heap_iterator::HeapIterator;
-pub(crate) use index_iterator::IndexIterator;
+pub(crate) use index_iterator::{IndexIterator, IndexOrder};
+pub(crate) use vector_index_iterator::VectorIndexIterator;
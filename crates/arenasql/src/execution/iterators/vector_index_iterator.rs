@@ -0,0 +1,74 @@
+use super::RowSink;
+use crate::schema::{OwnedSerializedCell, RowId, Table, TableIndex};
+use crate::storage::{KeyValueGroup, StorageHandler};
+use crate::{table_row_key, Error, Result};
+
+/// Iterates over the rows closest to the query vector using
+/// the vector index
+pub(crate) struct VectorIndexIterator<'a> {
+  storage: &'a StorageHandler,
+  table: &'a Table,
+  index: &'a TableIndex,
+  namespace: Option<&'a OwnedSerializedCell>,
+  query: &'a [f32],
+  k: usize,
+  column_projection: &'a Vec<usize>,
+}
+
+impl<'a> VectorIndexIterator<'a> {
+  pub fn new(
+    storage: &'a StorageHandler,
+    table: &'a Table,
+    index: &'a TableIndex,
+    namespace: Option<&'a OwnedSerializedCell>,
+    query: &'a [f32],
+    k: usize,
+    column_projection: &'a Vec<usize>,
+  ) -> Self {
+    Self {
+      storage,
+      table,
+      index,
+      namespace,
+      query,
+      k,
+      column_projection,
+    }
+  }
+
+  pub fn fill_into(&self, sink: &mut dyn RowSink) -> Result<()> {
+    let nearest_rows = self.storage.search_vector_index(
+      self.index,
+      self.namespace,
+      self.query,
+      self.k,
+    )?;
+
+    for (_, row_id) in nearest_rows {
+      let row_bytes = self
+        .storage
+        .kv
+        .get(KeyValueGroup::Rows, &table_row_key!(self.table.id, &row_id))?
+        .ok_or_else(|| {
+          Error::IOError(format!(
+            "Couldn't find row data for rowid: {:?}",
+            RowId::deserialize(&row_id)
+          ))
+        })?;
+
+      let row = self
+        .storage
+        .deserialize_row(self.table, &row_id, &row_bytes)?;
+      let selected_columns = self
+        .column_projection
+        .iter()
+        .map(|proj| &row[*proj])
+        .collect();
+
+      if !sink.append_row(&row_id, &selected_columns)? {
+        break;
+      }
+    }
+    Ok(())
+  }
+}
@@ -1,13 +1,18 @@
 use std::sync::Arc;
 
 use async_trait::async_trait;
+use datafusion::arrow::datatypes::SchemaRef;
+use datafusion::common::display::{PlanType, ToStringifiedPlan};
 use datafusion::error::{DataFusionError, Result};
 use datafusion::execution::context::{QueryPlanner, SessionState};
-use datafusion::logical_expr::{DmlStatement, LogicalPlan, WriteOp};
-use datafusion::physical_plan::ExecutionPlan;
+use datafusion::logical_expr::{DmlStatement, Explain, LogicalPlan, WriteOp};
+use datafusion::physical_plan::analyze::AnalyzeExec;
+use datafusion::physical_plan::explain::ExplainExec;
+use datafusion::physical_plan::{displayable, ExecutionPlan};
 use datafusion::physical_planner::DefaultPhysicalPlanner;
 use datafusion::physical_planner::PhysicalPlanner;
 
+use crate::df::plans::{index_order, vector_search};
 use crate::df::providers::{self, get_schema_provider, get_table_ref};
 use crate::error::Error;
 
@@ -21,6 +26,33 @@ impl ArenaQueryPlanner {
       df_planner: DefaultPhysicalPlanner::default(),
     }
   }
+
+  /// Datafusion's planner creates the physical plan of the explained
+  /// query by itself, so the plans of DML queries and vector search
+  /// that are created here wouldn't be used. So, create the physical
+  /// plan of the explained query using this planner instead
+  async fn create_explain_plan(
+    &self,
+    explain: &Explain,
+    state: &SessionState,
+  ) -> Result<Arc<dyn ExecutionPlan>> {
+    let mut stringified_plans = explain.stringified_plans.clone();
+    if explain.logical_optimization_succeeded {
+      stringified_plans
+        .push(explain.plan.to_stringified(PlanType::FinalLogicalPlan));
+      let plan = self.create_physical_plan(&explain.plan, state).await?;
+      stringified_plans.push(
+        displayable(plan.as_ref())
+          .to_stringified(explain.verbose, PlanType::FinalPhysicalPlan),
+      );
+    }
+
+    Ok(Arc::new(ExplainExec::new(
+      SchemaRef::new(explain.schema.as_ref().clone().into()),
+      stringified_plans,
+      explain.verbose,
+    )))
+  }
 }
 
 #[async_trait]
@@ -59,9 +91,9 @@ impl QueryPlanner for ArenaQueryPlanner {
             let scanner_plan =
               self.df_planner.create_physical_plan(&input, &state).await?;
             if *op == WriteOp::Delete {
-              return table_provider.delete(scanner_plan).await;
+              return table_provider.delete(state, scanner_plan).await;
             } else if *op == WriteOp::Update {
-              return table_provider.update(scanner_plan).await;
+              return table_provider.update(state, scanner_plan).await;
             }
           }
           _ => {
@@ -71,14 +103,28 @@ impl QueryPlanner for ArenaQueryPlanner {
           }
         };
       }
+      LogicalPlan::Explain(explain) => {
+        return self.create_explain_plan(explain, state).await;
+      }
+      LogicalPlan::Analyze(analyze) => {
+        let input = self.create_physical_plan(&analyze.input, state).await?;
+        return Ok(Arc::new(AnalyzeExec::new(
+          analyze.verbose,
+          state.config_options().explain.show_statistics,
+          input,
+          SchemaRef::new(analyze.schema.as_ref().clone().into()),
+        )));
+      }
       LogicalPlan::Ddl(_stmt) => {
         panic!();
       }
       _ => {}
     }
-    self
+    let plan = self
       .df_planner
       .create_physical_plan(logical_plan, state)
-      .await
+      .await?;
+    let plan = vector_search::use_vector_index(plan)?;
+    index_order::use_index_order(plan)
   }
 }
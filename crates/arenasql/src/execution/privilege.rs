@@ -2,6 +2,8 @@ use bitflags::bitflags;
 use serde::{Deserialize, Serialize};
 use sqlparser::ast::{ObjectType, Statement as SQLStatement};
 
+use crate::ast::statement::{get_savepoint_name, DatabaseStatement};
+
 bitflags! {
   #[derive(
     Debug,
@@ -62,13 +64,24 @@ impl Privilege {
     match stmt {
       // Database
       SQLStatement::CreateDatabase { .. } => Self::CREATE_DATABASE,
-      // Table
-      SQLStatement::CreateTable { .. } | SQLStatement::CreateIndex { .. } => {
-        Self::CREATE_TABLE
-      }
-      SQLStatement::AlterTable { .. } | SQLStatement::AlterIndex { .. } => {
-        Self::ALTER_TABLE
+      SQLStatement::Execute { .. }
+        if DatabaseStatement::from_statement(stmt).is_some() =>
+      {
+        Self::CREATE_DATABASE
       }
+      // Table
+      SQLStatement::CreateTable { .. }
+      | SQLStatement::CreateIndex { .. }
+      | SQLStatement::CreateSequence { .. } => Self::CREATE_TABLE,
+      SQLStatement::AlterTable { .. }
+      | SQLStatement::AlterIndex { .. }
+      | SQLStatement::Analyze { .. } => Self::ALTER_TABLE,
+      // `EXPLAIN ANALYZE` executes the statement
+      SQLStatement::Explain {
+        analyze: true,
+        statement,
+        ..
+      } => Self::READ_TABLE_SCHEMA | Self::get_required_privilege(statement),
       SQLStatement::Explain { .. } => Self::READ_TABLE_SCHEMA,
       // Rows
       SQLStatement::Insert { .. } => Self::INSERT_ROWS,
@@ -78,10 +91,18 @@ impl Privilege {
       // Statements done need privilege
       SQLStatement::StartTransaction { .. }
       | SQLStatement::Commit { .. }
-      | SQLStatement::Rollback { .. } => Self::NONE,
+      | SQLStatement::Rollback { .. }
+      | SQLStatement::Savepoint { .. } => Self::NONE,
+      SQLStatement::SetVariable { .. }
+        if get_savepoint_name(stmt).is_some() =>
+      {
+        Self::NONE
+      }
       // Drop
       SQLStatement::Drop { object_type, .. } => match object_type {
-        ObjectType::Table | ObjectType::Index => Self::DROP_TABLE,
+        ObjectType::Table | ObjectType::Index | ObjectType::Sequence => {
+          Self::DROP_TABLE
+        }
         _ => Self::SUPER_USER,
       },
       SQLStatement::SetTimeZone { .. } => Self::SET_SESSION_PARAMS,
@@ -251,5 +272,28 @@ mod tests {
       !Privilege::INSERT_ROWS.can_execute(&select_query),
       "Expected INSERT_ROWS privileges to NOT execute SELECT query"
     );
+
+    let explain_query =
+      parse("EXPLAIN DELETE FROM users").unwrap().pop().unwrap();
+    let explain_analyze_query = parse("EXPLAIN ANALYZE DELETE FROM users")
+      .unwrap()
+      .pop()
+      .unwrap();
+
+    assert!(
+      Privilege::READ_ONLY.can_execute(&explain_query),
+      "Expected READ_ONLY privileges to execute EXPLAIN query"
+    );
+
+    assert!(
+      !Privilege::READ_ONLY.can_execute(&explain_analyze_query),
+      "Expected READ_ONLY privileges to NOT execute EXPLAIN ANALYZE DELETE"
+    );
+
+    assert!(
+      (Privilege::READ_ONLY | Privilege::DELETE_ROWS)
+        .can_execute(&explain_analyze_query),
+      "Expected DELETE_ROWS privileges to execute EXPLAIN ANALYZE DELETE query"
+    );
   }
 }
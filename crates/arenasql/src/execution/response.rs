@@ -8,8 +8,8 @@ use datafusion::arrow::record_batch::RecordBatch;
 use datafusion::error::{DataFusionError, Result as DataFusionResult};
 use datafusion::physical_plan::stream::RecordBatchStreamAdapter;
 use derivative::Derivative;
-use futures::Stream;
-use futures::StreamExt;
+use futures::future::BoxFuture;
+use futures::{Future, FutureExt, Stream, StreamExt};
 
 use crate::ast::statement::StatementType;
 use crate::datafusion::RecordBatchStream;
@@ -43,9 +43,10 @@ impl ExecutionResponse {
     stream: RecordBatchStream,
   ) -> ArenaResult<Self> {
     let (record_batches, stream) = match stmt_type {
-      StatementType::Query | StatementType::Execute | StatementType::Set => {
-        (None, Some(stream))
-      }
+      StatementType::Query
+      | StatementType::Execute
+      | StatementType::Set
+      | StatementType::Explain => (None, Some(stream)),
       _ => {
         let batches = stream
           .collect::<Vec<Result<RecordBatch, DataFusionError>>>()
@@ -66,7 +67,39 @@ impl ExecutionResponse {
     })
   }
 
-  pub fn set_stream_completion_hook(
+  /// DML queries with `RETURNING` clause return the modified rows. The
+  /// stream is polled here so that the query is executed even if the rows
+  /// aren't read, and the response has a new stream of the polled rows
+  pub async fn from_returning_stream(
+    stream: RecordBatchStream,
+  ) -> ArenaResult<Self> {
+    let schema = stream.schema();
+    let batches = stream
+      .collect::<Vec<Result<RecordBatch, DataFusionError>>>()
+      .await
+      .into_iter()
+      .map(|b| Ok(b?))
+      .collect::<ArenaResult<Vec<RecordBatch>>>()?;
+
+    let rows_stream =
+      futures::stream::iter(batches.clone().into_iter().map(Ok));
+    Ok(Self {
+      record_batches: Some(batches),
+      stream: Some(Box::pin(RecordBatchStreamAdapter::new(
+        schema,
+        rows_stream,
+      ))),
+      stream_completion_hook: None,
+    })
+  }
+
+  /// Returns whether the response has rows to return; i.e. it's a query
+  /// or a DML query with `RETURNING` clause
+  pub fn has_rows(&self) -> bool {
+    self.stream.is_some()
+  }
+
+  pub async fn set_stream_completion_hook(
     &mut self,
     hook: StreamCompletionHook,
   ) -> ArenaResult<()> {
@@ -76,10 +109,8 @@ impl ExecutionResponse {
       self.stream_completion_hook = Some(Box::pin(hook));
       Ok(())
     } else {
-      // If the stream was already collected, call the hook
-      let mut hook = hook.hook;
-      let cb = hook.take().unwrap();
-      cb()
+      // If the stream was already collected, run the hook
+      hook.hook.unwrap().await
     }
   }
 
@@ -130,6 +161,14 @@ impl ExecutionResponse {
   /// Returns total number of modified rows
   /// Panics if called on non DML queries
   pub fn get_modified_rows(&self) -> Option<usize> {
+    // Each returned row is a modified row if the query has `RETURNING`
+    // clause
+    if self.stream.is_some() {
+      return self
+        .record_batches
+        .as_ref()
+        .map(|batches| batches.iter().map(|b| b.num_rows()).sum());
+    }
     self.record_batches.as_ref().map(|batch| {
       batch
         .iter()
@@ -169,19 +208,23 @@ impl ExecutionResponse {
   }
 }
 
+/// Runs the future once the stream is completed. The future is run
+/// only once
 pub struct StreamCompletionHook {
-  hook: Option<Box<dyn (FnOnce() -> ArenaResult<()>) + Send + Sync>>,
+  hook: Option<BoxFuture<'static, ArenaResult<()>>>,
 }
 
 unsafe impl Send for StreamCompletionHook {}
 unsafe impl Sync for StreamCompletionHook {}
 
 impl StreamCompletionHook {
-  pub fn new<F>(hook: Box<F>) -> Self
+  pub fn new<F>(hook: F) -> Self
   where
-    F: (FnOnce() -> ArenaResult<()>) + Send + Sync + 'static,
+    F: Future<Output = ArenaResult<()>> + Send + 'static,
   {
-    Self { hook: Some(hook) }
+    Self {
+      hook: Some(hook.boxed()),
+    }
   }
 }
 
@@ -190,10 +233,17 @@ impl Stream for StreamCompletionHook {
 
   fn poll_next(
     mut self: Pin<&mut Self>,
-    _cx: &mut Context<'_>,
+    cx: &mut Context<'_>,
   ) -> Poll<Option<Self::Item>> {
-    let hook = self.hook.take().unwrap();
-    if let Err(err) = hook() {
+    let Some(hook) = self.hook.as_mut() else {
+      return Poll::Ready(None);
+    };
+    let res = match hook.poll_unpin(cx) {
+      Poll::Ready(res) => res,
+      Poll::Pending => return Poll::Pending,
+    };
+    self.hook = None;
+    if let Err(err) = res {
       Poll::Ready(Some(Err(err.into())))
     } else {
       Poll::Ready(None)
@@ -7,13 +7,15 @@ use derivative::Derivative;
 use getset::{Getters, Setters};
 use parking_lot::Mutex;
 use sqlparser::ast::Statement;
+use tokio::sync::oneshot;
 
 use super::lock::TransactionLock;
+use super::savepoint::{Savepoint, Savepoints};
 use crate::execution::factory::{SchemaFactory, StorageFactoryState};
 use crate::execution::TableSchemaWriteLock;
 use crate::schema::Table;
 use crate::storage::{KeyValueStore, Serializer, StorageHandler};
-use crate::Result;
+use crate::{Error, Result};
 
 /// Don't implement clone so that when this is dropped,
 /// we can guarantee that the transaction with this state
@@ -29,6 +31,7 @@ pub struct TransactionHandle {
   // List if tables locked by this transaction
   locked_tables: Arc<Mutex<Vec<Arc<Table>>>>,
   acquired_locks: Arc<Mutex<Vec<TableSchemaWriteLock>>>,
+  savepoints: Arc<Mutex<Savepoints>>,
   lock: TransactionLock,
   // NOTE: this is a hack to pass current query statement to the execution
   // plan so that execution plans can have access to sql data types instead
@@ -40,6 +43,11 @@ pub struct TransactionHandle {
   active_statement: Option<Arc<Statement>>,
   #[getset(get = "pub")]
   is_chained: Arc<AtomicBool>,
+  // Signals of the table scans whose streams were dropped before the
+  // scans were done. The scans release their read lock soon after the
+  // stream is dropped, so the transaction waits for them before the
+  // rows are modified instead of blocking when the stream is dropped
+  cancelled_scans: Arc<Mutex<Vec<oneshot::Receiver<()>>>>,
 }
 
 unsafe impl Send for TransactionHandle {}
@@ -67,11 +75,13 @@ impl TransactionHandle {
       storage_factory_state: storage_factory_state.clone(),
       locked_tables,
       acquired_locks,
+      savepoints: Arc::new(Mutex::new(Savepoints::default())),
       lock: TransactionLock {
         lock: Arc::new(AtomicUsize::new(1)),
       },
       active_statement: None,
       is_chained: Arc::new(AtomicBool::new(false)),
+      cancelled_scans: Arc::new(Mutex::new(vec![])),
     }
   }
 
@@ -94,6 +104,22 @@ impl TransactionHandle {
     self.lock.closed()
   }
 
+  /// Adds the signal of a table scan that was cancelled; the signal is
+  /// disconnected once the scan releases its lock
+  pub(crate) fn add_cancelled_scan(&self, scan_done: oneshot::Receiver<()>) {
+    self.cancelled_scans.lock().push(scan_done);
+  }
+
+  /// Waits for the cancelled table scans to release their read locks.
+  /// This should be called before the rows are modified or the
+  /// transaction is closed
+  pub async fn wait_for_cancelled_scans(&self) {
+    let scans = std::mem::take(&mut *self.cancelled_scans.lock());
+    for scan_done in scans {
+      let _ = scan_done.await;
+    }
+  }
+
   #[inline]
   pub fn commit(&self) -> Result<()> {
     self.release_lock()?;
@@ -118,6 +144,60 @@ impl TransactionHandle {
     Ok(())
   }
 
+  /// Sets a savepoint with the given name. If a savepoint with the same
+  /// name exists, the new savepoint hides it until the new one is released
+  #[tracing::instrument(skip(self), level = "TRACE")]
+  pub fn set_savepoint(&self, name: &str) -> Result<()> {
+    let _storage = self.lock(true)?;
+    let mut savepoints = self.savepoints.lock();
+    self.kvstore.set_savepoint()?;
+    savepoints.kv_savepoints += 1;
+    let kv_savepoint = savepoints.kv_savepoints;
+    savepoints.savepoints.push(Savepoint {
+      name: name.to_owned(),
+      kv_savepoint,
+      locked_tables: self.locked_tables.lock().clone(),
+    });
+    Ok(())
+  }
+
+  /// Removes the savepoint and the savepoints set after it. The changes
+  /// made after the savepoint are kept
+  #[tracing::instrument(skip(self), level = "TRACE")]
+  pub fn release_savepoint(&self, name: &str) -> Result<()> {
+    let mut savepoints = self.savepoints.lock();
+    let position = savepoints
+      .position(name)
+      .ok_or_else(|| Error::SavepointDoesntExist(name.to_owned()))?;
+    savepoints.savepoints.truncate(position);
+    Ok(())
+  }
+
+  /// Undoes the changes made after the savepoint and removes the
+  /// savepoints set after it. The savepoint is kept so that the
+  /// transaction can be rolled back to it again
+  #[tracing::instrument(skip(self), level = "TRACE")]
+  pub fn rollback_to_savepoint(&self, name: &str) -> Result<()> {
+    let _storage = self.lock(true)?;
+    let mut savepoints = self.savepoints.lock();
+    let position = savepoints
+      .position(name)
+      .ok_or_else(|| Error::SavepointDoesntExist(name.to_owned()))?;
+    let kv_savepoint = savepoints.savepoints[position].kv_savepoint;
+    while savepoints.kv_savepoints >= kv_savepoint {
+      self.kvstore.rollback_to_savepoint()?;
+      savepoints.kv_savepoints -= 1;
+    }
+    // Rolling back to the savepoint removes it from the key value store
+    self.kvstore.set_savepoint()?;
+    savepoints.kv_savepoints += 1;
+    savepoints.savepoints.truncate(position + 1);
+
+    let savepoint = &savepoints.savepoints[position];
+    *self.locked_tables.lock() = savepoint.locked_tables.clone();
+    Ok(())
+  }
+
   #[inline]
   pub async fn acquire_table_schema_write_lock(
     &self,
@@ -183,12 +263,20 @@ impl TransactionHandle {
       })
   }
 
+  /// Returns the names of the tables in the schema including the ones
+  /// created by this transaction that haven't been committed
   #[tracing::instrument(skip(self), level = "TRACE")]
   pub fn table_names(&self, schema: &str) -> Vec<String> {
-    self
+    let mut table_names = self
       .schema_factories
       .get(schema)
       .map(|sf| sf.table_names())
-      .unwrap_or_default()
+      .unwrap_or_default();
+    self.locked_tables.lock().iter().for_each(|locked_table| {
+      if !table_names.contains(&locked_table.name) {
+        table_names.push(locked_table.name.clone());
+      }
+    });
+    table_names
   }
 }
@@ -1,5 +1,6 @@
 mod handle;
 mod lock;
+mod savepoint;
 
 use std::borrow::BorrowMut;
 use std::sync::atomic::{AtomicUsize, Ordering};
@@ -27,7 +28,8 @@ use super::{custom_functions, ExecutionPlanExtension};
 use super::{SessionConfig, SessionState};
 use crate::ast::statement::StatementType;
 use crate::df::plans::{
-  self, alter_table, create_index, insert_rows, set_parameter,
+  self, alter_table, analyze, create_index, insert_rows, returning, savepoint,
+  sequence, set_parameter, unnest,
 };
 use crate::{ast, Error, Result};
 
@@ -42,8 +44,11 @@ pub const DEFAULT_EXTENSIONS: Lazy<Arc<Vec<ExecutionPlanExtension>>> =
     Arc::new(vec![
       Arc::new(create_index::extension),
       Arc::new(plans::advisory_lock::extension),
+      Arc::new(savepoint::extension),
       Arc::new(set_parameter::extension),
       Arc::new(alter_table::extension),
+      Arc::new(sequence::extension),
+      Arc::new(analyze::extension),
     ])
   });
 
@@ -107,6 +112,13 @@ impl Transaction {
 
     let datafusion_context = DfSessionContext::new_with_state(state);
     custom_functions::register_all(&datafusion_context);
+    custom_functions::register_transaction_functions(
+      &datafusion_context,
+      &handle,
+      session_config.catalog.clone(),
+      session_config.schemas.clone(),
+      session_state.clone(),
+    );
 
     let sql_options = SQLOptions::new();
 
@@ -163,15 +175,18 @@ impl Transaction {
       true => {
         let stmt: &mut SQLStatement = stmt.borrow_mut();
         insert_rows::set_explicit_columns_in_insert_query(&state, stmt).await?;
+        insert_rows::remove_on_conflict_clause(stmt);
       }
       _ => {}
     };
+    let returning_items = returning::remove_returning_clause(stmt.as_mut());
 
     let mut statement = stmt.borrow_mut();
     if stmt_type == StatementType::Create {
       // TODO: remove this when datafusion support custom data types
       // replace data type to anything that datafusion doesn't throw error for
       ast::cast_unsupported_data_types(&mut statement)?;
+      ast::remove_unsupported_constraints(&mut statement)?;
     }
 
     let custom_plan = DEFAULT_EXTENSIONS
@@ -196,12 +211,23 @@ impl Transaction {
       )));
     }
 
+    let unnest_column = match stmt_type.is_query() {
+      true => unnest::extract_unnest_column(stmt.as_mut())?,
+      false => None,
+    };
+
     // TODO: creating physical plan from SQL is expensive
     // look into caching physical plans
     tracing::trace!("creating logical plan from statement",);
-    let plan = state
+    let mut plan = state
       .statement_to_plan(datafusion::sql::parser::Statement::Statement(stmt))
       .await?;
+    if let Some(column) = unnest_column {
+      plan = unnest::unnest_column(plan, &column)?;
+    }
+    if let Some(items) = returning_items {
+      plan = returning::set_returning_schema(&state, plan, &items).await?;
+    }
     self.sql_options.verify_plan(&plan)?;
     Ok(plan)
   }
@@ -228,6 +254,7 @@ impl Transaction {
     plan: LogicalPlan,
   ) -> Result<ExecutionResponse> {
     tracing::trace!("transaction_id = {:?}", self.id);
+    self.handle.wait_for_cancelled_scans().await;
     if let LogicalPlan::Extension(extension) = plan {
       tracing::debug!("Using custom execution plan");
       return self
@@ -249,18 +276,26 @@ impl Transaction {
     let mut txn = self;
     #[allow(unused)]
     let mut handle_ref = None;
-    if *stmt_type == StatementType::Create {
+    let has_returning = returning::get_returning_clause(&statement).is_some();
+    if *stmt_type == StatementType::Create
+      || insert_rows::get_on_conflict_clause(&statement).is_some()
+      || has_returning
+    {
       // NOTE: this is a hack to pass current query statement to the execution
       // plan so that execution plans can have access to sql data types instead
       // of just datafusion data types; datafusion doesn't support all datatypes
       // and we need to access the query to support custom data types like VECTOR,
-      // JSONB, etc
+      // JSONB, etc. This is also used to pass `ON CONFLICT` and `RETURNING`
+      // clauses of DML queries since datafusion doesn't support them
       // TODO: remove this when datafusion support custom data types
       let mut txn_handle = txn.handle.clone();
       txn_handle.set_active_statement(Some(statement.clone().into()));
 
-      // replace data type to anything that datafusion doesn't throw error for
-      ast::cast_unsupported_data_types(&mut statement)?;
+      if *stmt_type == StatementType::Create {
+        // replace data type to anything that datafusion doesn't throw error for
+        ast::cast_unsupported_data_types(&mut statement)?;
+        ast::remove_unsupported_constraints(&mut statement)?;
+      }
       handle_ref = Some(Self::new_with_handle(
         self.id,
         txn_handle,
@@ -277,6 +312,13 @@ impl Transaction {
       .await?;
 
     let physical_plan = df.create_physical_plan().await?;
+    if has_returning {
+      let response = execute_stream(
+        physical_plan,
+        txn.datafusion_context.task_ctx().into(),
+      )?;
+      return ExecutionResponse::from_returning_stream(response).await;
+    }
     let result = txn.execute_stream(&stmt_type, physical_plan).await?;
     Ok(result)
   }
@@ -0,0 +1,29 @@
+use std::sync::Arc;
+
+use crate::schema::Table;
+
+/// Savepoints of a transaction in the order they were set
+#[derive(Default)]
+pub(super) struct Savepoints {
+  pub savepoints: Vec<Savepoint>,
+  /// Number of savepoints set in the key value store. Released savepoints
+  /// can't be removed from the key value store without undoing the changes
+  /// made after them, so this can be more than the number of savepoints
+  pub kv_savepoints: usize,
+}
+
+pub(super) struct Savepoint {
+  pub name: String,
+  /// Number of savepoints in the key value store after this savepoint
+  /// was set
+  pub kv_savepoint: usize,
+  /// Tables locked by the transaction when the savepoint was set
+  pub locked_tables: Vec<Arc<Table>>,
+}
+
+impl Savepoints {
+  /// Returns the position of the last savepoint with the given name
+  pub fn position(&self, name: &str) -> Option<usize> {
+    self.savepoints.iter().rposition(|s| s.name == name)
+  }
+}
@@ -18,8 +18,8 @@ pub type Error = error::Error;
 pub use sqlparser;
 
 pub use df::providers::{
-  CatalogListProvider, CatalogProvider, SchemaProviderBuilder,
-  SingleCatalogListProvider,
+  pg_database, CatalogListProvider, CatalogProvider, SchemaProviderBuilder,
+  SingleCatalogListProvider, INFORMATION_SCHEMA, PG_CATALOG_SCHEMA,
 };
 
 pub mod rocks {
@@ -41,12 +41,16 @@ pub mod datafusion {
   };
   pub use datafusion::arrow::record_batch::RecordBatch;
   pub use datafusion::catalog::{
+    schema::SchemaProvider as DatafusionSchemaProvider,
     CatalogList as DatafusionCatalogList,
     CatalogProvider as DatafusionCatalogProvider,
   };
   pub use datafusion::common::{
     config::ConfigOptions, DFSchema, ScalarType, ScalarValue, TableReference,
   };
+  pub use datafusion::datasource::{
+    MemTable, TableProvider as DatafusionTableProvider,
+  };
   pub use datafusion::error::{DataFusionError, Result};
   pub use datafusion::execution::{context::SessionState, TaskContext};
   pub use datafusion::logical_expr::expr::*;
@@ -76,8 +80,9 @@ pub mod arrow {
     StringBuilder, UInt16Array, UInt32Array, UInt64Array,
   };
   pub use datafusion::arrow::datatypes::{
-    Float32Type, Float64Type, Int32Type, TimeUnit, TimestampNanosecondType,
-    UInt32Type, UInt64Type,
+    Date32Type, Decimal128Type, Float32Type, Float64Type, Int16Type, Int32Type,
+    Int64Type, IntervalMonthDayNanoType, IntervalUnit, Time64NanosecondType,
+    TimeUnit, TimestampNanosecondType, UInt32Type, UInt64Type,
   };
   pub use datafusion::common::cast::as_binary_array;
 }
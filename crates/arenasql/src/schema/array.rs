@@ -1,11 +1,16 @@
 use std::sync::Arc;
 
 use datafusion::arrow::array::{
-  ArrayRef, BinaryBuilder, BooleanBuilder, Float32Builder, Float64Builder,
-  Int16Builder, Int32Builder, Int64Builder, ListBuilder, StringBuilder,
-  TimestampNanosecondBuilder, UInt32Builder, UInt64Builder,
+  ArrayRef, BinaryBuilder, BooleanBuilder, Date32Builder, Decimal128Builder,
+  Float32Builder, Float64Builder, Int16Builder, Int32Builder, Int64Builder,
+  IntervalMonthDayNanoBuilder, ListArray, ListBuilder, StringBuilder,
+  Time64NanosecondBuilder, TimestampNanosecondBuilder, UInt32Builder,
+  UInt64Builder,
 };
+use datafusion::arrow::buffer::{NullBuffer, OffsetBuffer};
+use datafusion::arrow::datatypes::{DataType as DfDataType, FieldRef};
 
+use super::datatype::TIMESTAMPTZ_TIMEZONE;
 use super::{DataType, SerializedCell};
 
 pub enum ColumnArrayBuilder {
@@ -24,6 +29,20 @@ pub enum ColumnArrayBuilder {
   // Need to store timestamp as string because DF can't cast
   // timestamp to u64 or i64
   Timestamp(TimestampNanosecondBuilder),
+  // UUIDs are stored as bytes but returned as strings
+  Uuid(StringBuilder),
+  Date(Date32Builder),
+  Time(Time64NanosecondBuilder),
+  Interval(IntervalMonthDayNanoBuilder),
+  Decimal(Decimal128Builder),
+  // Elements of all the arrays are appended to the same builder and
+  // split using the lengths when finished
+  Array {
+    field: FieldRef,
+    values: Box<ColumnArrayBuilder>,
+    lengths: Vec<usize>,
+    nulls: Vec<bool>,
+  },
 }
 
 impl ColumnArrayBuilder {
@@ -72,16 +91,49 @@ impl ColumnArrayBuilder {
       DataType::Timestamp => ColumnArrayBuilder::Timestamp(
         TimestampNanosecondBuilder::with_capacity(capacity),
       ),
+      DataType::TimestampTz => ColumnArrayBuilder::Timestamp(
+        TimestampNanosecondBuilder::with_capacity(capacity)
+          .with_timezone(TIMESTAMPTZ_TIMEZONE),
+      ),
+      DataType::Uuid => ColumnArrayBuilder::Uuid(StringBuilder::with_capacity(
+        capacity,
+        capacity * 36,
+      )),
+      DataType::Date => {
+        ColumnArrayBuilder::Date(Date32Builder::with_capacity(capacity))
+      }
+      DataType::Time => ColumnArrayBuilder::Time(
+        Time64NanosecondBuilder::with_capacity(capacity),
+      ),
+      DataType::Interval => ColumnArrayBuilder::Interval(
+        IntervalMonthDayNanoBuilder::with_capacity(capacity),
+      ),
+      DataType::Decimal { p, s } => ColumnArrayBuilder::Decimal(
+        Decimal128Builder::with_capacity(capacity)
+          .with_precision_and_scale(*p, *s)
+          .unwrap(),
+      ),
       DataType::Binary => {
         ColumnArrayBuilder::Binary(BinaryBuilder::with_capacity(capacity, 1000))
       }
-      DataType::Vector { len } => {
+      DataType::Array { element } => {
+        let field = match data_type.to_df_datatype().0 {
+          DfDataType::List(field) => field,
+          _ => unreachable!("Array should be converted to a list"),
+        };
+        ColumnArrayBuilder::Array {
+          field,
+          values: Box::new(ColumnArrayBuilder::from(element, capacity)),
+          lengths: Vec::with_capacity(capacity),
+          nulls: Vec::with_capacity(capacity),
+        }
+      }
+      DataType::Vector { len } | DataType::QuantizedVector { len, .. } => {
         ColumnArrayBuilder::Vector(ListBuilder::with_capacity(
           Float32Builder::with_capacity(*len),
           capacity,
         ))
       }
-      v => unimplemented!("Not implemented for data type: {:?}", v),
     }
   }
 
@@ -104,6 +156,29 @@ impl ColumnArrayBuilder {
         builder.append_option(Some(vector.clone().iter().map(|f| Some(*f))))
       }
       Self::Timestamp(ref mut builder) => builder.append_option(value.as_i64()),
+      Self::Uuid(ref mut builder) => {
+        builder.append_option(value.as_uuid().map(|uuid| uuid.to_string()))
+      }
+      Self::Date(ref mut builder) => builder.append_option(value.as_i32()),
+      Self::Time(ref mut builder) => builder.append_option(value.as_i64()),
+      Self::Interval(ref mut builder) => builder.append_option(value.as_i128()),
+      Self::Decimal(ref mut builder) => builder.append_option(value.as_i128()),
+      Self::Array {
+        ref mut values,
+        ref mut lengths,
+        ref mut nulls,
+        ..
+      } => match value.as_array() {
+        Some(elements) => {
+          elements.iter().for_each(|element| values.append(element));
+          lengths.push(elements.len());
+          nulls.push(true);
+        }
+        None => {
+          lengths.push(0);
+          nulls.push(false);
+        }
+      },
     }
   }
 
@@ -123,6 +198,22 @@ impl ColumnArrayBuilder {
       Self::Binary(mut v) => Arc::new(v.finish()) as ArrayRef,
       Self::Vector(mut v) => Arc::new(v.finish()) as ArrayRef,
       Self::Timestamp(mut v) => Arc::new(v.finish()) as ArrayRef,
+      Self::Uuid(mut v) => Arc::new(v.finish()) as ArrayRef,
+      Self::Date(mut v) => Arc::new(v.finish()) as ArrayRef,
+      Self::Time(mut v) => Arc::new(v.finish()) as ArrayRef,
+      Self::Interval(mut v) => Arc::new(v.finish()) as ArrayRef,
+      Self::Decimal(mut v) => Arc::new(v.finish()) as ArrayRef,
+      Self::Array {
+        field,
+        values,
+        lengths,
+        nulls,
+      } => Arc::new(ListArray::new(
+        field,
+        OffsetBuffer::from_lengths(lengths),
+        values.finish(),
+        Some(NullBuffer::from(nulls)),
+      )) as ArrayRef,
     }
   }
 }
@@ -5,7 +5,8 @@ use datafusion::arrow::array::{
   ArrayRef,
 };
 use datafusion::arrow::datatypes::{
-  Float32Type, Float64Type, Int16Type, Int32Type, Int64Type,
+  Date32Type, Decimal128Type, Float32Type, Float64Type, Int16Type, Int32Type,
+  Int64Type, IntervalMonthDayNanoType, Time64NanosecondType,
   TimestampNanosecondType, UInt32Type, UInt64Type,
 };
 use datafusion::common::cast::as_binary_array;
@@ -13,9 +14,11 @@ use datafusion::error::Result;
 use datafusion::scalar::ScalarValue;
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
+use uuid::Uuid;
 
-use super::{Column, DataType};
+use super::{Column, DataType, VectorElementType};
 use crate::error::null_constraint_violation;
+use crate::vectors::QuantizedVector;
 use crate::{bail, df_error, Error};
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
@@ -36,7 +39,6 @@ pub enum SerializedCell<'a> {
   // String JSON as string is good enough; tried storing as bytes but
   // was too much struggle making it work with datafusion
   Json(&'a str) = 11,
-  // TODO: convert f32 to u16 when storing in order to store bfloat16
   // Vec<f32> can't be deserialized to &'a [f32] because converting [u8]
   // to f32 requires allocation
   Vector(Arc<Vec<f32>>) = 12,
@@ -45,6 +47,22 @@ pub enum SerializedCell<'a> {
   // deserialization
   Blob(&'a [u8]) = 14,
   File(&'a str) = 15,
+  // Vectors of `VECTOR(len, bf16)` and `VECTOR(len, int8)` columns
+  QuantizedVector(Arc<QuantizedVector>) = 16,
+  Uuid([u8; 16]) = 17,
+  // Days since UNIX epoch
+  Date(i32) = 18,
+  // Nanoseconds since midnight
+  Time(i64) = 19,
+  // Nanoseconds since UNIX epoch in UTC
+  TimestampTz(i64) = 20,
+  // Months, days and nanoseconds packed in arrow's MonthDayNano format
+  Interval(i128) = 21,
+  // Unscaled value; the precision and scale are in the column type
+  Decimal(i128) = 22,
+  // Elements of one dimensional array; nested arrays aren't supported
+  #[serde(borrow)]
+  Array(Vec<SerializedCell<'a>>) = 23,
 }
 
 // Note: this should only be used when it's impossible to use
@@ -67,6 +85,14 @@ pub enum OwnedSerializedCell {
   Timestamp(i64) = 13,
   Blob(Arc<Vec<u8>>) = 14,
   File(Arc<str>) = 15,
+  QuantizedVector(Arc<QuantizedVector>) = 16,
+  Uuid([u8; 16]) = 17,
+  Date(i32) = 18,
+  Time(i64) = 19,
+  TimestampTz(i64) = 20,
+  Interval(i128) = 21,
+  Decimal(i128) = 22,
+  Array(Vec<OwnedSerializedCell>) = 23,
 }
 
 #[derive(Debug, Serialize, Deserialize, PartialEq)]
@@ -98,6 +124,7 @@ impl OwnedSerializedCell {
       ScalarValue::Boolean(v) => {
         v.map(|v| Self::Boolean(v)).unwrap_or_default()
       }
+      ScalarValue::Int16(v) => v.map(|v| Self::Int16(v)).unwrap_or_default(),
       ScalarValue::Int32(v) => v.map(|v| Self::Int32(v)).unwrap_or_default(),
       ScalarValue::Int64(v) => v.map(|v| Self::Int64(v)).unwrap_or_default(),
       ScalarValue::Float32(v) => {
@@ -110,11 +137,64 @@ impl OwnedSerializedCell {
         .as_ref()
         .map(|v| Self::String(v.as_str().into()))
         .unwrap_or_default(),
+      ScalarValue::FixedSizeBinary(16, v) => v
+        .as_ref()
+        .and_then(|v| v.as_slice().try_into().ok())
+        .map(|v| Self::Uuid(v))
+        .unwrap_or_default(),
+      ScalarValue::Date32(v) => v.map(|v| Self::Date(v)).unwrap_or_default(),
+      ScalarValue::Time64Nanosecond(v) => {
+        v.map(|v| Self::Time(v)).unwrap_or_default()
+      }
+      ScalarValue::TimestampNanosecond(v, None) => {
+        v.map(|v| Self::Timestamp(v)).unwrap_or_default()
+      }
+      ScalarValue::TimestampNanosecond(v, Some(_)) => {
+        v.map(|v| Self::TimestampTz(v)).unwrap_or_default()
+      }
+      ScalarValue::IntervalMonthDayNano(v) => {
+        v.map(|v| Self::Interval(v)).unwrap_or_default()
+      }
+      ScalarValue::Decimal128(v, _, _) => {
+        v.map(|v| Self::Decimal(v)).unwrap_or_default()
+      }
       _ => unimplemented!(),
     }
   }
 }
 
+impl<'a> From<&SerializedCell<'a>> for OwnedSerializedCell {
+  fn from(cell: &SerializedCell<'a>) -> Self {
+    match cell {
+      SerializedCell::Null => Self::Null,
+      SerializedCell::Boolean(v) => Self::Boolean(*v),
+      SerializedCell::Int16(v) => Self::Int16(*v),
+      SerializedCell::Int32(v) => Self::Int32(*v),
+      SerializedCell::UInt32(v) => Self::UInt32(*v),
+      SerializedCell::Int64(v) => Self::Int64(*v),
+      SerializedCell::UInt64(v) => Self::UInt64(*v),
+      SerializedCell::Float32(v) => Self::Float32(*v),
+      SerializedCell::Float64(v) => Self::Float64(*v),
+      SerializedCell::String(v) => Self::String((*v).into()),
+      SerializedCell::Json(v) => Self::Json((*v).into()),
+      SerializedCell::Vector(v) => Self::Vector(v.clone()),
+      SerializedCell::Timestamp(v) => Self::Timestamp(*v),
+      SerializedCell::Blob(v) => Self::Blob(Arc::new(v.to_vec())),
+      SerializedCell::File(v) => Self::File((*v).into()),
+      SerializedCell::QuantizedVector(v) => Self::QuantizedVector(v.clone()),
+      SerializedCell::Uuid(v) => Self::Uuid(*v),
+      SerializedCell::Date(v) => Self::Date(*v),
+      SerializedCell::Time(v) => Self::Time(*v),
+      SerializedCell::TimestampTz(v) => Self::TimestampTz(*v),
+      SerializedCell::Interval(v) => Self::Interval(*v),
+      SerializedCell::Decimal(v) => Self::Decimal(*v),
+      SerializedCell::Array(values) => {
+        Self::Array(values.iter().map(Self::from).collect())
+      }
+    }
+  }
+}
+
 impl OwnedSerializedCell {
   /// Converts arrow column array to Vec of SerializedCell
   pub fn column_array_to_vec<'b>(
@@ -125,8 +205,14 @@ impl OwnedSerializedCell {
     if !column.nullable() && array.null_count() > 0 {
       return Err(null_constraint_violation(table_name, &column.name));
     }
+    Self::array_to_vec(&column.data_type, array)
+  }
 
-    Ok(match &column.data_type {
+  fn array_to_vec(
+    data_type: &DataType,
+    array: &ArrayRef,
+  ) -> Result<Vec<OwnedSerializedCell>> {
+    Ok(match data_type {
       DataType::Boolean => as_boolean_array(array)
         .iter()
         .map(|v| v.map(|v| Self::Boolean(v)).unwrap_or_default())
@@ -177,8 +263,10 @@ impl OwnedSerializedCell {
           .map(|v| {
             v.map(|v| {
               // Convert to Value and back to make sure it's a valid JSON
-              let parsed_json: Value = serde_json::from_str(v)
-                .map_err(|_| Error::InvalidDataType("Invalid JSON".to_owned()))?;
+              let parsed_json: Value =
+                serde_json::from_str(v).map_err(|_| {
+                  Error::InvalidDataType("Invalid JSON".to_owned())
+                })?;
               Ok(Self::Json(
                 serde_json::to_string(&parsed_json).unwrap().into(),
               ))
@@ -186,7 +274,7 @@ impl OwnedSerializedCell {
             .unwrap_or(Ok(OwnedSerializedCell::Null))
           })
           .collect();
-      },
+      }
       DataType::File => {
         return as_string_array(array)
           .iter()
@@ -196,31 +284,40 @@ impl OwnedSerializedCell {
               let file: FileContent = serde_json::from_str(v).map_err(|e| {
                 Error::InvalidDataType(format!("Invalid File content: {:?}", e))
               })?;
-              Ok(Self::File(
-                serde_json::to_string(&file).unwrap().into(),
-              ))
+              Ok(Self::File(serde_json::to_string(&file).unwrap().into()))
             })
             .unwrap_or(Ok(OwnedSerializedCell::Null))
           })
           .collect();
-      },
-      DataType::Vector { len } => {
+      }
+      DataType::Vector { len } | DataType::QuantizedVector { len, .. } => {
+        let element = match data_type {
+          DataType::QuantizedVector { element, .. } => *element,
+          _ => VectorElementType::F32,
+        };
         let res: Result<Vec<Self>> =
           as_generic_list_array::<i32>(array)
             .iter()
             .map(|maybe_vector| {
-              let vector = Arc::new(
+              let vector =
                 as_primitive_array::<Float32Type>(&maybe_vector.unwrap())
                   .iter()
                   .map(|f| f.unwrap())
-                  .collect::<Vec<f32>>(),
-              );
+                  .collect::<Vec<f32>>();
               if vector.len() != *len {
                 bail!(df_error!(Error::InvalidQuery(format!(
                   "Expected vector of length \"{}\" but got vector of length \"{}\""
                 , len, vector.len()))));
               }
-              Ok(Self::Vector(vector))
+              Ok(match element {
+                VectorElementType::F32 => Self::Vector(Arc::new(vector)),
+                VectorElementType::BF16 => Self::QuantizedVector(Arc::new(
+                  QuantizedVector::bfloat16(&vector),
+                )),
+                VectorElementType::Int8 => Self::QuantizedVector(Arc::new(
+                  QuantizedVector::int8(&vector),
+                )),
+              })
             })
             .collect();
         res?
@@ -231,10 +328,50 @@ impl OwnedSerializedCell {
           .map(|v| v.map(|v| Self::Timestamp(v)).unwrap_or_default())
           .collect()
       }
-      dt => unimplemented!(
-        "ColumnArray to Vec<OwnedSerializedCell> not implemented for type: {:?}",
-        dt
-      ),
+      DataType::Uuid => {
+        return as_string_array(array)
+          .iter()
+          .map(|v| {
+            v.map(|v| Ok(Self::Uuid(parse_uuid(v)?)))
+              .unwrap_or(Ok(OwnedSerializedCell::Null))
+          })
+          .collect();
+      }
+      DataType::Date => as_primitive_array::<Date32Type>(array)
+        .iter()
+        .map(|v| v.map(|v| Self::Date(v)).unwrap_or_default())
+        .collect(),
+      DataType::Time => as_primitive_array::<Time64NanosecondType>(array)
+        .iter()
+        .map(|v| v.map(|v| Self::Time(v)).unwrap_or_default())
+        .collect(),
+      DataType::TimestampTz => {
+        as_primitive_array::<TimestampNanosecondType>(array)
+          .iter()
+          .map(|v| v.map(|v| Self::TimestampTz(v)).unwrap_or_default())
+          .collect()
+      }
+      DataType::Interval => {
+        as_primitive_array::<IntervalMonthDayNanoType>(array)
+          .iter()
+          .map(|v| v.map(|v| Self::Interval(v)).unwrap_or_default())
+          .collect()
+      }
+      DataType::Decimal { .. } => as_primitive_array::<Decimal128Type>(array)
+        .iter()
+        .map(|v| v.map(|v| Self::Decimal(v)).unwrap_or_default())
+        .collect(),
+      DataType::Array { element } => {
+        return as_generic_list_array::<i32>(array)
+          .iter()
+          .map(|v| {
+            v.map(|values| {
+              Ok(Self::Array(Self::array_to_vec(element, &values)?))
+            })
+            .unwrap_or(Ok(OwnedSerializedCell::Null))
+          })
+          .collect();
+      }
     })
   }
 
@@ -292,6 +429,7 @@ impl<'a> SerializedCell<'a> {
     match self {
       Self::Null => None,
       Self::Int32(value) => Some(*value),
+      Self::Date(value) => Some(*value),
       _ => self.error_converting_to("i32"),
     }
   }
@@ -310,11 +448,32 @@ impl<'a> SerializedCell<'a> {
     match self {
       Self::Null => None,
       Self::Timestamp(value) => Some(*value),
+      Self::TimestampTz(value) => Some(*value),
+      Self::Time(value) => Some(*value),
       Self::Int64(value) => Some(*value),
       _ => self.error_converting_to("i64"),
     }
   }
 
+  #[inline]
+  pub fn as_i128(&self) -> Option<i128> {
+    match self {
+      Self::Null => None,
+      Self::Interval(value) => Some(*value),
+      Self::Decimal(value) => Some(*value),
+      _ => self.error_converting_to("i128"),
+    }
+  }
+
+  #[inline]
+  pub fn as_uuid(&self) -> Option<Uuid> {
+    match self {
+      Self::Null => None,
+      Self::Uuid(value) => Some(Uuid::from_bytes(*value)),
+      _ => self.error_converting_to("uuid"),
+    }
+  }
+
   #[inline]
   pub fn as_u64(&self) -> Option<u64> {
     match self {
@@ -362,11 +521,21 @@ impl<'a> SerializedCell<'a> {
     }
   }
 
+  #[inline]
+  pub fn as_array(&self) -> Option<&[SerializedCell<'a>]> {
+    match self {
+      Self::Null => None,
+      Self::Array(values) => Some(values),
+      _ => self.error_converting_to("array"),
+    }
+  }
+
   #[inline]
   pub fn as_vector(&self) -> Option<Arc<Vec<f32>>> {
     match self {
       Self::Null => None,
       Self::Vector(v) => Some(v.clone()),
+      Self::QuantizedVector(v) => Some(Arc::new(v.to_f32())),
       _ => self.error_converting_to("float vector"),
     }
   }
@@ -375,3 +544,15 @@ impl<'a> SerializedCell<'a> {
     unreachable!("Trying to convert {:?} to {}", &self, &ty);
   }
 }
+
+/// Parses the UUID in its text format to the bytes that are stored
+pub fn parse_uuid(value: &str) -> crate::Result<[u8; 16]> {
+  Uuid::parse_str(value)
+    .map(|uuid| *uuid.as_bytes())
+    .map_err(|_| {
+      Error::InvalidDataType(format!(
+        "invalid input syntax for type uuid: \"{}\"",
+        value
+      ))
+    })
+}
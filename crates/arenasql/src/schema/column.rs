@@ -3,7 +3,7 @@ use datafusion::arrow::datatypes::Field;
 use derive_new::new;
 use serde::{Deserialize, Serialize};
 
-use super::{proto, DataType, OwnedSerializedCell, Table};
+use super::{proto, DataType, Table};
 use crate::storage::Serializer;
 use crate::Result;
 
@@ -24,6 +24,8 @@ bitflags! {
     const ARCHIVED = 1 << 1;
     const NOT_NULL = 1 << 2;
     const UNIQUE = 1 << 3;
+    // Identity column whose values are always generated by its sequence
+    const GENERATED_ALWAYS = 1 << 4;
   }
 }
 
@@ -39,7 +41,8 @@ pub struct Column {
   pub name: String,
   pub data_type: DataType,
   pub properties: ColumnProperty,
-  pub default_value: Option<OwnedSerializedCell>,
+  /// SQL expression of the default value, e.g. `now()`
+  pub default_expr: Option<String>,
 }
 
 impl Column {
@@ -53,7 +56,7 @@ impl Column {
       name: field.name().to_owned(),
       data_type: DataType::from_field(field)?,
       properties,
-      default_value: None,
+      default_expr: None,
     })
   }
 
@@ -64,11 +67,7 @@ impl Column {
       data_type: Serializer::FixedInt
         .deserialize::<DataType>(&col.data_type)?,
       properties: ColumnProperty::from_bits(col.properties).unwrap(),
-      default_value: col
-        .default_value
-        .as_ref()
-        .map(|v| Serializer::FixedInt.deserialize::<OwnedSerializedCell>(&v))
-        .transpose()?,
+      default_expr: col.default_expr.clone(),
     })
   }
 
@@ -78,11 +77,7 @@ impl Column {
       name: self.name.clone(),
       data_type: Serializer::FixedInt.serialize::<DataType>(&self.data_type)?,
       properties: self.properties.bits(),
-      default_value: self
-        .default_value
-        .as_ref()
-        .map(|v| Serializer::FixedInt.serialize::<OwnedSerializedCell>(&v))
-        .transpose()?,
+      default_expr: self.default_expr.clone(),
     })
   }
 
@@ -90,10 +85,21 @@ impl Column {
     !self.properties.intersects(ColumnProperty::NOT_NULL)
   }
 
+  /// Archived columns are the dropped columns. They are kept in the
+  /// table so that the cells of the existing rows don't need to be
+  /// rewritten but they aren't visible in the queries
+  pub fn archived(&self) -> bool {
+    self.properties.intersects(ColumnProperty::ARCHIVED)
+  }
+
   pub fn unique(&self) -> bool {
     self.properties.intersects(ColumnProperty::UNIQUE)
   }
 
+  pub fn generated_always(&self) -> bool {
+    self.properties.intersects(ColumnProperty::GENERATED_ALWAYS)
+  }
+
   pub fn to_field(&self, table: &Table) -> Field {
     let (data_type, mut metadata) = self.data_type.to_df_datatype();
     metadata.insert("TABLE_NAME".to_owned(), table.name.to_owned());
@@ -102,3 +108,34 @@ impl Column {
       .with_metadata(metadata)
   }
 }
+
+#[cfg(test)]
+mod tests {
+  use super::Column;
+  use crate::schema::{proto, DataType, VectorElementType};
+
+  #[test]
+  fn test_decode_vector_column_in_original_format() {
+    // `DataType::Vector { len: 4 }` serialized before the quantized vectors
+    // were supported; i.e. variant index and length as big endian ints
+    let column = Column::from_proto(&proto::Column {
+      id: 0,
+      name: "embeddings".to_owned(),
+      data_type: vec![0, 0, 0, 13, 0, 0, 0, 0, 0, 0, 0, 4],
+      properties: 0,
+      ..Default::default()
+    })
+    .unwrap();
+    assert_eq!(column.data_type, DataType::Vector { len: 4 });
+
+    let column = Column {
+      data_type: DataType::QuantizedVector {
+        len: 4,
+        element: VectorElementType::Int8,
+      },
+      ..column
+    };
+    let decoded = Column::from_proto(&column.to_proto().unwrap()).unwrap();
+    assert_eq!(decoded.data_type, column.data_type);
+  }
+}
@@ -1,5 +1,6 @@
 use datafusion::common::Constraint as DfConstraint;
 use serde::{Deserialize, Serialize};
+use strum_macros::FromRepr;
 
 use super::proto;
 
@@ -11,6 +12,49 @@ pub enum Constraint {
   PrimaryKey(Vec<usize>) = 1,
   /// Columns with the given indices form a composite unique key:
   Unique(Vec<usize>) = 2,
+  /// Columns reference the unique columns of another table
+  ForeignKey(ForeignKey) = 3,
+  /// Expression that must not be false for any row
+  Check(CheckConstraint) = 4,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct ForeignKey {
+  /// Name of the constraint
+  pub name: String,
+  /// Indices of the referencing columns
+  pub columns: Vec<usize>,
+  /// Name of the referenced table; it must be in the same schema
+  pub foreign_table: String,
+  /// Indices of the referenced columns. The referenced table must have
+  /// a unique index on exactly these columns
+  pub referred_columns: Vec<usize>,
+  pub on_delete: ReferentialAction,
+  pub on_update: ReferentialAction,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct CheckConstraint {
+  /// Name of the constraint
+  pub name: String,
+  /// Indices of the columns used in the expression
+  pub columns: Vec<usize>,
+  /// SQL expression of the constraint
+  pub expr: String,
+}
+
+/// Action taken on the referencing rows when the referenced row is
+/// deleted or its key is updated
+#[derive(
+  Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, FromRepr,
+)]
+pub enum ReferentialAction {
+  /// Same as `Restrict` since constraints can't be deferred
+  #[default]
+  NoAction = 0,
+  Restrict = 1,
+  Cascade = 2,
+  SetNull = 3,
 }
 
 impl Constraint {
@@ -19,6 +63,35 @@ impl Constraint {
     match proto.r#type {
       1 => Self::PrimaryKey(columns),
       2 => Self::Unique(columns),
+      3 => {
+        let foreign_key = proto.foreign_key.as_ref().unwrap();
+        Self::ForeignKey(ForeignKey {
+          name: foreign_key.name.clone(),
+          columns,
+          foreign_table: foreign_key.foreign_table.clone(),
+          referred_columns: foreign_key
+            .referred_columns
+            .iter()
+            .map(|col| *col as usize)
+            .collect(),
+          on_delete: ReferentialAction::from_repr(
+            foreign_key.on_delete as usize,
+          )
+          .unwrap_or_default(),
+          on_update: ReferentialAction::from_repr(
+            foreign_key.on_update as usize,
+          )
+          .unwrap_or_default(),
+        })
+      }
+      4 => {
+        let check = proto.check.as_ref().unwrap();
+        Self::Check(CheckConstraint {
+          name: check.name.clone(),
+          columns,
+          expr: check.expr.clone(),
+        })
+      }
       _ => unreachable!(),
     }
   }
@@ -27,6 +100,27 @@ impl Constraint {
     proto::Constraint {
       r#type: self.repr(),
       columns: self.columns().iter().map(|idx| *idx as u32).collect(),
+      foreign_key: match self {
+        Self::ForeignKey(foreign_key) => Some(proto::ForeignKey {
+          name: foreign_key.name.clone(),
+          foreign_table: foreign_key.foreign_table.clone(),
+          referred_columns: foreign_key
+            .referred_columns
+            .iter()
+            .map(|idx| *idx as u32)
+            .collect(),
+          on_delete: foreign_key.on_delete as i32,
+          on_update: foreign_key.on_update as i32,
+        }),
+        _ => None,
+      },
+      check: match self {
+        Self::Check(check) => Some(proto::CheckConstraint {
+          name: check.name.clone(),
+          expr: check.expr.clone(),
+        }),
+        _ => None,
+      },
     }
   }
 
@@ -34,12 +128,24 @@ impl Constraint {
     match self {
       Self::PrimaryKey(_) => 1,
       Self::Unique(_) => 2,
+      Self::ForeignKey(_) => 3,
+      Self::Check(_) => 4,
     }
   }
 
   pub fn columns(&self) -> &Vec<usize> {
     match self {
       Self::PrimaryKey(cols) | Self::Unique(cols) => cols,
+      Self::ForeignKey(foreign_key) => &foreign_key.columns,
+      Self::Check(check) => &check.columns,
+    }
+  }
+
+  /// Returns true if the constraint makes the columns unique
+  pub fn is_unique(&self) -> bool {
+    match self {
+      Self::PrimaryKey(_) | Self::Unique(_) => true,
+      Self::ForeignKey(_) | Self::Check(_) => false,
     }
   }
 
@@ -47,6 +153,10 @@ impl Constraint {
     match self {
       Self::PrimaryKey(_) => true,
       Self::Unique(_) => true,
+      // Index is needed to find the referencing rows when the
+      // referenced rows are updated or deleted
+      Self::ForeignKey(_) => true,
+      Self::Check(_) => false,
     }
   }
 }
@@ -62,15 +172,18 @@ impl From<&DfConstraint> for Constraint {
   }
 }
 
-impl Into<DfConstraint> for &Constraint {
-  fn into(self) -> DfConstraint {
+impl Constraint {
+  /// Returns the corresponding datafusion constraint; datafusion
+  /// doesn't have foreign key and check constraints
+  pub fn to_df_constraint(&self) -> Option<DfConstraint> {
     match self {
       Constraint::PrimaryKey(projection) => {
-        DfConstraint::PrimaryKey(projection.clone())
+        Some(DfConstraint::PrimaryKey(projection.clone()))
       }
       Constraint::Unique(projection) => {
-        DfConstraint::Unique(projection.clone())
+        Some(DfConstraint::Unique(projection.clone()))
       }
+      Constraint::ForeignKey(_) | Constraint::Check(_) => None,
     }
   }
 }
@@ -2,14 +2,35 @@ use std::collections::HashMap;
 use std::str::FromStr;
 use std::sync::Arc;
 
-use datafusion::arrow::datatypes::{DataType as DfDataType, Field, TimeUnit};
-use postgres_types::Type;
+use datafusion::arrow::datatypes::{
+  DataType as DfDataType, Field, IntervalUnit, TimeUnit,
+};
+use once_cell::sync::Lazy;
+use postgres_types::{Kind, Oid, Type};
 use serde::{Deserialize, Serialize};
-use sqlparser::ast::{ColumnDef, DataType as SQLDataType};
+use sqlparser::ast::{
+  ArrayElemTypeDef, ColumnDef, DataType as SQLDataType, ExactNumberInfo,
+  TimezoneInfo,
+};
 use strum_macros::{Display, EnumString};
 
 use crate::{Error, Result};
 
+/// Since pgvector's `vector` is an extension type, its oid isn't fixed in
+/// Postgres and clients look it up by name. So, use the first oid that's
+/// available for user defined objects
+pub const VECTOR_OID: Oid = 16385;
+
+/// Postgres type of pgvector's `vector`
+pub static VECTOR_PG_TYPE: Lazy<Type> = Lazy::new(|| {
+  Type::new(
+    "vector".to_owned(),
+    VECTOR_OID,
+    Kind::Simple,
+    "public".to_owned(),
+  )
+});
+
 #[derive(
   Debug, Display, Clone, Serialize, Deserialize, EnumString, PartialEq,
 )]
@@ -49,6 +70,9 @@ pub enum DataType {
   #[strum(serialize = "FLOAT8")]
   Float64 = 11,
   // Posgres Type::NUMERIC
+  // Unlike Postgres, NUMERIC without precision and scale isn't arbitrary
+  // precision; it's NUMERIC(38, 10), so its values are rounded to 10
+  // decimal places
   #[strum(serialize = "NUMERIC")]
   Decimal {
     p: u8,
@@ -57,7 +81,7 @@ pub enum DataType {
   // Posgres Type::JSONB
   #[strum(serialize = "JSONB")]
   Jsonb = 13,
-  // Posgres Type::JSONB
+  // pgvector's `vector`
   #[strum(serialize = "FLOAT4_ARRAY")]
   Vector {
     len: usize,
@@ -67,6 +91,70 @@ pub enum DataType {
   Timestamp = 15,
   // Postgres Type::JSONB
   File = 16,
+  // Posgres Type::UUID
+  #[strum(serialize = "UUID")]
+  Uuid = 17,
+  // Posgres Type::DATE
+  #[strum(serialize = "DATE")]
+  Date = 18,
+  // Posgres Type::TIME
+  #[strum(serialize = "TIME")]
+  Time = 19,
+  // Posgres Type::TIMESTAMPTZ; stored in UTC
+  #[strum(serialize = "TIMESTAMPTZ")]
+  TimestampTz = 20,
+  // Posgres Type::INTERVAL
+  #[strum(serialize = "INTERVAL")]
+  Interval = 21,
+  // One dimensional Postgres array; e.g. Type::TEXT_ARRAY
+  #[strum(serialize = "ARRAY")]
+  Array {
+    element: Box<DataType>,
+  } = 22,
+  // pgvector's `vector` stored as bf16 or int8; set with `VECTOR(len, type)`.
+  // This is a separate variant so that the schemas of the existing `Vector`
+  // columns can still be deserialized
+  #[strum(serialize = "QUANTIZED_VECTOR")]
+  QuantizedVector {
+    len: usize,
+    element: VectorElementType,
+  } = 23,
+}
+
+/// Only used by strum to parse data types whose element type isn't part
+/// of the string; the element type is derived from the field instead
+impl Default for DataType {
+  fn default() -> Self {
+    Self::Text
+  }
+}
+
+/// Timezone of the `TIMESTAMPTZ` values; the values are always stored
+/// and returned in UTC
+pub(crate) const TIMESTAMPTZ_TIMEZONE: &str = "+00:00";
+
+/// Type that the vector values are stored as; set with `VECTOR(len, type)`
+#[derive(
+  Debug,
+  Display,
+  Clone,
+  Copy,
+  Default,
+  Serialize,
+  Deserialize,
+  EnumString,
+  PartialEq,
+)]
+pub enum VectorElementType {
+  #[default]
+  #[strum(serialize = "f32", ascii_case_insensitive)]
+  F32,
+  // bfloat16; half the size of f32 with the same range
+  #[strum(serialize = "bf16", ascii_case_insensitive)]
+  BF16,
+  // scalar quantized to i8 with a scale per vector; quarter the size of f32
+  #[strum(serialize = "int8", ascii_case_insensitive)]
+  Int8,
 }
 
 impl DataType {
@@ -74,14 +162,58 @@ impl DataType {
     column_def: &ColumnDef,
     df_field: Option<&Field>,
   ) -> Result<Self> {
-    match &column_def.data_type {
-      SQLDataType::Bool => Ok(Self::Boolean),
+    Self::from_sql_data_type(&column_def.data_type, df_field)
+  }
+
+  fn from_sql_data_type(
+    data_type: &SQLDataType,
+    df_field: Option<&Field>,
+  ) -> Result<Self> {
+    match data_type {
+      SQLDataType::Bool | SQLDataType::Boolean => Ok(Self::Boolean),
+      SQLDataType::Bytea => Ok(Self::Binary),
       SQLDataType::Text => Ok(Self::Text),
-      SQLDataType::Int4(_) => Ok(Self::Int32),
-      SQLDataType::Int8(_) => Ok(Self::Int64),
-      SQLDataType::Float4 => Ok(Self::Float32),
-      SQLDataType::Float8 => Ok(Self::Float64),
+      SQLDataType::Int2(_) | SQLDataType::SmallInt(_) => Ok(Self::Int16),
+      SQLDataType::Int4(_) | SQLDataType::Int(_) | SQLDataType::Integer(_) => {
+        Ok(Self::Int32)
+      }
+      SQLDataType::Int8(_) | SQLDataType::BigInt(_) => Ok(Self::Int64),
+      SQLDataType::Float4 | SQLDataType::Real => Ok(Self::Float32),
+      SQLDataType::Float8
+      | SQLDataType::Double
+      | SQLDataType::DoublePrecision => Ok(Self::Float64),
+      SQLDataType::Timestamp(
+        _,
+        TimezoneInfo::Tz | TimezoneInfo::WithTimeZone,
+      ) => Ok(Self::TimestampTz),
       SQLDataType::Timestamp(_, _) => Ok(Self::Timestamp),
+      SQLDataType::Date => Ok(Self::Date),
+      SQLDataType::Time(
+        _,
+        TimezoneInfo::None | TimezoneInfo::WithoutTimeZone,
+      ) => Ok(Self::Time),
+      SQLDataType::Interval => Ok(Self::Interval),
+      SQLDataType::Uuid => Ok(Self::Uuid),
+      SQLDataType::Numeric(info) | SQLDataType::Decimal(info) => {
+        // Same defaults as datafusion since the values are stored as 128
+        // bit decimals; unconstrained NUMERIC is rounded to the scale
+        let (p, s) = match info {
+          ExactNumberInfo::None => (38, 10),
+          ExactNumberInfo::Precision(p) => (*p, 0),
+          ExactNumberInfo::PrecisionAndScale(p, s) => (*p, *s),
+        };
+        if p == 0 || p > 38 || s > p {
+          return Err(Error::InvalidDataType(format!(
+            "NUMERIC precision {} must be between 1 and 38 and scale {} \
+            must not be greater than the precision",
+            p, s
+          )));
+        }
+        Ok(Self::Decimal {
+          p: p as u8,
+          s: s as i8,
+        })
+      }
       SQLDataType::Varchar(len) => {
         let len = len.map(|l| l.length as usize);
         Ok(DataType::Varchar { len })
@@ -90,6 +222,10 @@ impl DataType {
         let data_type_str = object_name.0[0].value.to_uppercase();
         match data_type_str.as_str() {
           "JSONB" => Ok(DataType::Jsonb),
+          // Serial columns use sequences for their default values
+          "SMALLSERIAL" | "SERIAL2" => Ok(DataType::Int16),
+          "SERIAL" | "SERIAL4" => Ok(DataType::Int32),
+          "BIGSERIAL" | "SERIAL8" => Ok(DataType::Int64),
           "FILE" => Ok(DataType::File),
           "VECTOR" => {
             let len = data
@@ -106,13 +242,41 @@ impl DataType {
                 "VECTOR must be a multiple of 4"
               )));
             }
-            Ok(DataType::Vector { len })
-          }
-          _ => {
-            Err(Error::UnsupportedDataType(column_def.data_type.to_string()))
+            let element = data
+              .get(1)
+              .map(|element| {
+                VectorElementType::from_str(element).map_err(|_| {
+                  Error::InvalidDataType(format!(
+                    "Unsupported vector element type \"{}\"; \
+                    expected one of f32, bf16 or int8",
+                    element
+                  ))
+                })
+              })
+              .transpose()?
+              .unwrap_or_default();
+            Ok(match element {
+              VectorElementType::F32 => DataType::Vector { len },
+              element => DataType::QuantizedVector { len, element },
+            })
           }
+          _ => Err(Error::UnsupportedDataType(data_type.to_string())),
         }
       }
+      SQLDataType::Array(
+        ArrayElemTypeDef::SquareBracket(element)
+        | ArrayElemTypeDef::AngleBracket(element),
+      ) => match Self::from_sql_data_type(element, None)? {
+        Self::Vector { .. }
+        | Self::QuantizedVector { .. }
+        | Self::File
+        | Self::Array { .. } => {
+          Err(Error::UnsupportedDataType(data_type.to_string()))
+        }
+        element => Ok(Self::Array {
+          element: Box::new(element),
+        }),
+      },
       _ if df_field.is_some() => DataType::from_field(df_field.unwrap()),
       dt => Err(Error::UnsupportedDataType(dt.to_string())),
     }
@@ -126,10 +290,35 @@ impl DataType {
         let dt = Self::from_str(ty).unwrap();
         return Ok(match dt {
           Self::Varchar { .. } => Self::Varchar { len },
-          Self::Vector { .. } => {
+          Self::Vector { .. } => Self::Vector { len: len.unwrap() },
+          Self::QuantizedVector { .. } => {
             let len = len.unwrap();
-            Self::Vector { len }
+            let element = metadata
+              .get("ELEMENT")
+              .and_then(|e| VectorElementType::from_str(e).ok())
+              .unwrap_or_default();
+            Self::QuantizedVector { len, element }
           }
+          Self::Array { .. } => match field.data_type() {
+            DfDataType::List(item) => Self::Array {
+              element: Box::new(Self::from_field(item)?),
+            },
+            dt => {
+              return Err(Error::InvalidDataType(format!(
+                "Expected list field but got {:?}",
+                dt
+              )))
+            }
+          },
+          Self::Decimal { .. } => match field.data_type() {
+            DfDataType::Decimal128(p, s) => Self::Decimal { p: *p, s: *s },
+            dt => {
+              return Err(Error::InvalidDataType(format!(
+                "Expected decimal field but got {:?}",
+                dt
+              )))
+            }
+          },
           dt => dt,
         });
       }
@@ -142,7 +331,19 @@ impl DataType {
         DfDataType::Utf8 => Ok(Self::Text),
         DfDataType::Float32 => Ok(Self::Float32),
         DfDataType::Float64 => Ok(Self::Float64),
-        DfDataType::Timestamp(TimeUnit::Nanosecond, _) => Ok(Self::Timestamp),
+        DfDataType::Timestamp(TimeUnit::Nanosecond, None) => {
+          Ok(Self::Timestamp)
+        }
+        DfDataType::Timestamp(TimeUnit::Nanosecond, Some(_)) => {
+          Ok(Self::TimestampTz)
+        }
+        DfDataType::Date32 => Ok(Self::Date),
+        DfDataType::Time64(TimeUnit::Nanosecond) => Ok(Self::Time),
+        DfDataType::Interval(IntervalUnit::MonthDayNano) => Ok(Self::Interval),
+        DfDataType::Decimal128(p, s) => Ok(Self::Decimal { p: *p, s: *s }),
+        DfDataType::List(item) => Ok(Self::Array {
+          element: Box::new(Self::from_field(item)?),
+        }),
         DfDataType::Binary => Ok(Self::Binary),
         dt => Err(Error::UnsupportedDataType(format!(
           "Data type {:?} not supported",
@@ -167,9 +368,18 @@ impl DataType {
       Self::Float64 => Type::FLOAT8,
       Self::Decimal { p: _, s: _ } => Type::NUMERIC,
       Self::Jsonb => Type::JSONB,
-      Self::Vector { .. } => Type::FLOAT4_ARRAY,
+      Self::Vector { .. } | Self::QuantizedVector { .. } => {
+        VECTOR_PG_TYPE.clone()
+      }
       Self::Timestamp => Type::TIMESTAMP,
       Self::File => Type::JSONB,
+      Self::Uuid => Type::UUID,
+      Self::Date => Type::DATE,
+      Self::Time => Type::TIME,
+      Self::TimestampTz => Type::TIMESTAMPTZ,
+      Self::Interval => Type::INTERVAL,
+      Self::Array { element } => array_pg_type(&element.pg_type())
+        .expect("Array of the element type should have been rejected"),
     }
   }
 
@@ -204,10 +414,60 @@ impl DataType {
           true,
         )))
       }
+      Self::QuantizedVector { len, element } => {
+        metadata.insert("LENGTH".to_owned(), len.to_string());
+        metadata.insert("ELEMENT".to_owned(), element.to_string());
+        DfDataType::List(Arc::new(Field::new(
+          "item",
+          DfDataType::Float32,
+          true,
+        )))
+      }
       Self::Timestamp => DfDataType::Timestamp(TimeUnit::Nanosecond, None),
       Self::File => DfDataType::Utf8,
+      // UUIDs are stored as 16 bytes but datafusion can't cast strings to
+      // fixed size binary, so they are passed around as strings
+      Self::Uuid => DfDataType::Utf8,
+      Self::Date => DfDataType::Date32,
+      Self::Time => DfDataType::Time64(TimeUnit::Nanosecond),
+      Self::TimestampTz => DfDataType::Timestamp(
+        TimeUnit::Nanosecond,
+        Some(TIMESTAMPTZ_TIMEZONE.into()),
+      ),
+      Self::Interval => DfDataType::Interval(IntervalUnit::MonthDayNano),
+      Self::Array { element } => {
+        let (element_type, element_metadata) = element.to_df_datatype();
+        DfDataType::List(Arc::new(
+          Field::new("item", element_type, true)
+            .with_metadata(element_metadata),
+        ))
+      }
     };
 
     (df_data_type, metadata)
   }
 }
+
+/// Returns the Postgres array type of the given element type
+pub fn array_pg_type(element: &Type) -> Option<Type> {
+  Some(match *element {
+    Type::BOOL => Type::BOOL_ARRAY,
+    Type::BYTEA => Type::BYTEA_ARRAY,
+    Type::INT2 => Type::INT2_ARRAY,
+    Type::INT4 => Type::INT4_ARRAY,
+    Type::INT8 => Type::INT8_ARRAY,
+    Type::VARCHAR => Type::VARCHAR_ARRAY,
+    Type::TEXT => Type::TEXT_ARRAY,
+    Type::FLOAT4 => Type::FLOAT4_ARRAY,
+    Type::FLOAT8 => Type::FLOAT8_ARRAY,
+    Type::NUMERIC => Type::NUMERIC_ARRAY,
+    Type::JSONB => Type::JSONB_ARRAY,
+    Type::TIMESTAMP => Type::TIMESTAMP_ARRAY,
+    Type::UUID => Type::UUID_ARRAY,
+    Type::DATE => Type::DATE_ARRAY,
+    Type::TIME => Type::TIME_ARRAY,
+    Type::TIMESTAMPTZ => Type::TIMESTAMPTZ_ARRAY,
+    Type::INTERVAL => Type::INTERVAL_ARRAY,
+    _ => return None,
+  })
+}
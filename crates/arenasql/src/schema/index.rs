@@ -33,7 +33,15 @@ impl TableIndex {
           dim: provider.dim as usize,
           retain_vectors: provider.retain_vectors.unwrap_or(false),
           namespace_column: provider.namespace_column.map(|idx| idx as usize),
+          rerank: provider.rerank.unwrap_or(false),
         },
+        proto::TableIndexProvider::JsonPath(provider) => {
+          IndexProvider::JsonPathIndex {
+            columns: provider.columns.iter().map(|col| *col as usize).collect(),
+            path: provider.path.clone(),
+            unique: provider.unique,
+          }
+        }
       },
     }
   }
@@ -58,6 +66,7 @@ impl TableIndex {
           dim,
           retain_vectors,
           namespace_column,
+          rerank,
         } => proto::TableIndexProvider::Hnsw(proto::HnswIndexProvider {
           columns: columns.iter().map(|c| *c as u32).collect(),
           metric: metric.clone() as i32,
@@ -67,7 +76,19 @@ impl TableIndex {
           dim: *dim as u32,
           retain_vectors: Some(*retain_vectors),
           namespace_column: namespace_column.map(|idx| idx as u32),
+          rerank: Some(*rerank),
         }),
+        IndexProvider::JsonPathIndex {
+          columns,
+          path,
+          unique,
+        } => {
+          proto::TableIndexProvider::JsonPath(proto::JsonPathIndexProvider {
+            columns: columns.iter().map(|c| *c as u32).collect(),
+            path: path.clone(),
+            unique: *unique,
+          })
+        }
       }),
     }
   }
@@ -81,6 +102,26 @@ impl TableIndex {
   pub fn is_unique(&self) -> bool {
     self.provider.is_unique()
   }
+
+  #[inline]
+  pub fn is_vector_index(&self) -> bool {
+    self.provider.is_vector_index()
+  }
+
+  #[inline]
+  pub fn namespace_column(&self) -> Option<usize> {
+    self.provider.namespace_column()
+  }
+
+  #[inline]
+  pub fn retains_vectors(&self) -> bool {
+    self.provider.retains_vectors()
+  }
+
+  #[inline]
+  pub fn json_path(&self) -> Option<&Vec<String>> {
+    self.provider.json_path()
+  }
 }
 
 #[derive(Debug, Clone, PartialEq)]
@@ -98,7 +139,7 @@ pub enum IndexProvider {
     ef_construction: usize,
     ef: usize,
     dim: usize,
-    // by default, set this to false
+    // `true` by default; set with `WITH (retain_vectors = false)`
     // if set to false, flat vectors of the columns will be cleared out
     // for the rows that are already indexed; this is to avoid having
     // duplicate vectors in index as well as row data and save space.
@@ -106,6 +147,17 @@ pub enum IndexProvider {
     retain_vectors: bool,
     // column to split the indexing by
     namespace_column: Option<usize>,
+    // `false` by default; set with `WITH (rerank = true)`
+    // if set to true, the nearest vectors found using the compact form of
+    // bf16/int8 vectors are sorted again with the full precision query
+    rerank: bool,
+  },
+  JsonPathIndex {
+    // Must have only one JSONB column
+    columns: Vec<usize>,
+    // keys of the objects or indexes of the arrays
+    path: Vec<String>,
+    unique: bool,
   },
 }
 
@@ -131,22 +183,70 @@ impl IndexProvider {
           unique: true,
         }
       }
+      Constraint::ForeignKey(foreign_key) => Self::BasicIndex {
+        columns: foreign_key.columns.to_vec(),
+        unique: false,
+      },
+      Constraint::Check(_) => {
+        unreachable!("Check constraint doesn't need index")
+      }
     }
   }
 
   #[inline]
   pub fn is_unique(&self) -> bool {
     match self {
-      Self::BasicIndex { unique, .. } => *unique,
+      Self::BasicIndex { unique, .. } | Self::JsonPathIndex { unique, .. } => {
+        *unique
+      }
       Self::HNSWIndex { .. } => false,
     }
   }
 
+  #[inline]
+  pub fn is_vector_index(&self) -> bool {
+    match self {
+      Self::HNSWIndex { .. } => true,
+      _ => false,
+    }
+  }
+
+  /// Returns false if the vectors indexed by this index are removed
+  /// from the row data
+  #[inline]
+  pub fn retains_vectors(&self) -> bool {
+    match self {
+      Self::HNSWIndex { retain_vectors, .. } => *retain_vectors,
+      _ => true,
+    }
+  }
+
+  /// Returns the column that the vector index is partitioned by
+  #[inline]
+  pub fn namespace_column(&self) -> Option<usize> {
+    match self {
+      Self::HNSWIndex {
+        namespace_column, ..
+      } => *namespace_column,
+      _ => None,
+    }
+  }
+
+  /// Returns the path of the JSONB column that is indexed
+  #[inline]
+  pub fn json_path(&self) -> Option<&Vec<String>> {
+    match self {
+      Self::JsonPathIndex { path, .. } => Some(path),
+      _ => None,
+    }
+  }
+
   #[inline]
   pub fn columns(&self) -> &Vec<usize> {
     match self {
       Self::BasicIndex { columns, .. } => columns,
       Self::HNSWIndex { columns, .. } => columns,
+      Self::JsonPathIndex { columns, .. } => columns,
     }
   }
 }
@@ -0,0 +1,219 @@
+use std::sync::Arc;
+
+use serde_json::Value;
+
+use super::{OwnedSerializedCell, SerializedCell};
+
+/// Returns the value at the given path of the JSON. The path elements are
+/// object keys or array indexes; negative index counts from the end of
+/// the array
+pub fn get_json_value<'a, K: AsRef<str>>(
+  json: &'a Value,
+  path: &[K],
+) -> Option<&'a Value> {
+  path.iter().try_fold(json, |value, key| match value {
+    Value::Object(map) => map.get(key.as_ref()),
+    Value::Array(elements) => key
+      .as_ref()
+      .parse()
+      .ok()
+      .and_then(|index| array_index(elements.len(), index))
+      .map(|index| &elements[index]),
+    _ => None,
+  })
+}
+
+/// Returns the text representation of the JSON value like Postgres'
+/// `->>` operator; strings are returned without quotes and JSON null
+/// is returned as SQL NULL
+pub fn json_value_to_text(value: &Value) -> Option<String> {
+  match value {
+    Value::Null => None,
+    Value::String(value) => Some(value.clone()),
+    value => Some(value.to_string()),
+  }
+}
+
+/// Returns whether the JSON contains the other JSON like Postgres' `@>`
+/// operator
+pub fn json_contains(json: &Value, other: &Value) -> bool {
+  match (json, other) {
+    (Value::Object(json), Value::Object(other)) => {
+      other.iter().all(|(key, other)| {
+        json
+          .get(key)
+          .map_or(false, |json| json_contains(json, other))
+      })
+    }
+    (Value::Array(json), Value::Array(other)) => other.iter().all(|other| {
+      json.iter().any(|json| match (json, other) {
+        // nested arrays and objects must match the structure
+        (Value::Array(_), Value::Array(_))
+        | (Value::Object(_), Value::Object(_)) => json_contains(json, other),
+        _ => json_scalar_eq(json, other),
+      })
+    }),
+    // array contains a primitive value if any of the elements is equal
+    (Value::Array(json), other) if !other.is_object() => {
+      json.iter().any(|json| json_scalar_eq(json, other))
+    }
+    _ => json_scalar_eq(json, other),
+  }
+}
+
+fn json_scalar_eq(json: &Value, other: &Value) -> bool {
+  match (json, other) {
+    (Value::Number(json), Value::Number(other)) => {
+      json.as_f64() == other.as_f64()
+    }
+    (Value::Array(_), _) | (Value::Object(_), _) => false,
+    _ => json == other,
+  }
+}
+
+/// Returns whether the key is a top level key of the object or a string
+/// element of the array like Postgres' `?` operator
+pub fn json_key_exists(json: &Value, key: &str) -> bool {
+  match json {
+    Value::Object(map) => map.contains_key(key),
+    Value::Array(elements) => elements
+      .iter()
+      .any(|e| e.as_str().map_or(false, |e| e == key)),
+    Value::String(value) => value == key,
+    _ => false,
+  }
+}
+
+/// Replaces the value at the given path like Postgres' `jsonb_set`. If
+/// `create_missing` is true, the last key of the path is added if it
+/// doesn't exist; out of range array index prepends or appends the value.
+/// The JSON isn't changed if any other element of the path doesn't exist
+pub fn set_json_value<K: AsRef<str>>(
+  json: &mut Value,
+  path: &[K],
+  new_value: Value,
+  create_missing: bool,
+) {
+  let Some((key, parent_path)) = path.split_last() else {
+    return;
+  };
+  let Some(parent) = get_json_value_mut(json, parent_path) else {
+    return;
+  };
+  let key = key.as_ref();
+  match parent {
+    Value::Object(map) => {
+      if create_missing || map.contains_key(key) {
+        map.insert(key.to_owned(), new_value);
+      }
+    }
+    Value::Array(elements) => {
+      let Ok(index) = key.parse::<i64>() else {
+        return;
+      };
+      match array_index(elements.len(), index) {
+        Some(index) => elements[index] = new_value,
+        None if create_missing && index < 0 => elements.insert(0, new_value),
+        None if create_missing => elements.push(new_value),
+        None => {}
+      }
+    }
+    _ => {}
+  }
+}
+
+fn get_json_value_mut<'a, K: AsRef<str>>(
+  json: &'a mut Value,
+  path: &[K],
+) -> Option<&'a mut Value> {
+  path.iter().try_fold(json, |value, key| match value {
+    Value::Object(map) => map.get_mut(key.as_ref()),
+    Value::Array(elements) => {
+      let index = array_index(elements.len(), key.as_ref().parse().ok()?)?;
+      Some(&mut elements[index])
+    }
+    _ => None,
+  })
+}
+
+fn array_index(len: usize, index: i64) -> Option<usize> {
+  let index = match index < 0 {
+    true => len as i64 + index,
+    false => index,
+  };
+  (0..len as i64).contains(&index).then_some(index as usize)
+}
+
+/// Returns the index key of the JSON path index for the given JSON; the
+/// key is the text value at the path, same as `json #>> path`
+pub fn get_json_path_index_key<K: AsRef<str>>(
+  json: Option<&str>,
+  path: &[K],
+) -> OwnedSerializedCell {
+  json
+    .and_then(|json| serde_json::from_str::<Value>(json).ok())
+    .and_then(|json| get_json_value(&json, path).and_then(json_value_to_text))
+    .map(|value| OwnedSerializedCell::String(Arc::from(value)))
+    .unwrap_or_default()
+}
+
+/// Cell of the JSONB column indexed by the JSON path index
+pub trait JsonCell {
+  fn json(&self) -> Option<&str>;
+}
+
+impl<'a> JsonCell for SerializedCell<'a> {
+  #[inline]
+  fn json(&self) -> Option<&str> {
+    self.as_str()
+  }
+}
+
+impl JsonCell for OwnedSerializedCell {
+  #[inline]
+  fn json(&self) -> Option<&str> {
+    match self {
+      Self::Json(json) => Some(json),
+      _ => None,
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use serde_json::{json, Value};
+
+  use super::{get_json_value, json_contains, set_json_value};
+
+  #[test]
+  fn test_json_contains() {
+    let json = json!({"a": 1, "b": {"c": [1, 2, {"d": true}]}, "e": "x"});
+    for (other, expected) in [
+      (json!({}), true),
+      (json!({"a": 1.0}), true),
+      (json!({"b": {"c": [2]}}), true),
+      (json!({"b": {"c": [{"d": true}, 1]}}), true),
+      (json!({"b": {"c": 2}}), false),
+      (json!({"a": 1, "e": "y"}), false),
+      (json!({"f": null}), false),
+    ] {
+      assert_eq!(json_contains(&json, &other), expected, "{}", other);
+    }
+    assert!(json_contains(&json!(["a", "b"]), &json!("a")));
+    assert!(!json_contains(&json!([["a"]]), &json!(["a"])));
+  }
+
+  #[test]
+  fn test_set_json_value() {
+    let mut json = json!({"a": [1, 2], "b": {}});
+    set_json_value(&mut json, &["a", "-1"], json!(3), true);
+    set_json_value(&mut json, &["a", "5"], json!(4), true);
+    set_json_value(&mut json, &["b", "c"], json!("x"), true);
+    set_json_value(&mut json, &["b", "d"], json!("y"), false);
+    set_json_value(&mut json, &["x", "y"], json!("z"), true);
+    assert_eq!(json, json!({"a": [1, 3, 4], "b": {"c": "x"}}));
+    assert_eq!(get_json_value(&json, &["a", "-2"]), Some(&json!(3)));
+    assert_eq!(get_json_value::<&str>(&json, &[]), Some(&json));
+    assert_eq!(get_json_value(&json, &["a", "x"]), None::<&Value>);
+  }
+}
@@ -5,7 +5,10 @@ mod constraint;
 mod dataframe;
 mod datatype;
 mod index;
+mod json;
 mod row;
+mod sequence;
+mod statistics;
 mod table;
 
 pub(self) mod proto {
@@ -14,11 +17,27 @@ pub(self) mod proto {
 }
 
 pub use array::ColumnArrayBuilder;
+pub(crate) use cell::parse_uuid;
 pub use cell::{OwnedSerializedCell, SerializedCell};
 pub use column::{Column, ColumnId, ColumnProperty, CTID_COLUMN};
-pub use constraint::Constraint;
+pub use constraint::{
+  CheckConstraint, Constraint, ForeignKey, ReferentialAction,
+};
 pub use dataframe::DataFrame;
-pub use datatype::DataType;
+pub use datatype::{
+  array_pg_type, DataType, VectorElementType, VECTOR_OID, VECTOR_PG_TYPE,
+};
 pub use index::{IndexProvider, TableIndex, TableIndexId, VectorMetric};
+pub use json::JsonCell;
+pub(crate) use json::{
+  get_json_path_index_key, get_json_value, json_contains, json_key_exists,
+  json_value_to_text, set_json_value,
+};
 pub use row::{OwnedRow, Row, RowId, RowTrait};
+pub use sequence::Sequence;
+pub use statistics::{
+  compare_cells, IndexStatistics, IndexStatisticsCollector, TableStatistics,
+  DEFAULT_ROW_COUNT,
+};
+pub(crate) use table::{get_default_expr, set_column_sequence};
 pub use table::{Table, TableId};
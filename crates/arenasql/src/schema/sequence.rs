@@ -0,0 +1,266 @@
+use std::io::Cursor;
+
+use prost::Message;
+use sqlparser::ast::{
+  ColumnDef, ColumnOption, DataType as SQLDataType, Expr as SQLExpr,
+  GeneratedAs, MinMaxValue, SequenceOptions, UnaryOperator, Value,
+};
+
+use crate::{Error, Result};
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Sequence {
+  pub name: String,
+  /// First value returned by `nextval`
+  pub start: i64,
+  pub increment: i64,
+  pub min_value: i64,
+  pub max_value: i64,
+  /// Whether the sequence wraps around after reaching the min/max value
+  pub cycle: bool,
+  /// Name of the table that uses this sequence for its `SERIAL` or
+  /// identity column. The sequence is dropped with the table
+  pub owned_by: Option<String>,
+}
+
+impl Sequence {
+  /// Creates a sequence from the options of `CREATE SEQUENCE` query or
+  /// an identity column
+  pub fn new(
+    name: &str,
+    data_type: Option<&SQLDataType>,
+    options: &[SequenceOptions],
+  ) -> Result<Self> {
+    let (type_min, type_max) = match data_type {
+      Some(SQLDataType::Int2(_) | SQLDataType::SmallInt(_)) => {
+        (i16::MIN as i64, i16::MAX as i64)
+      }
+      Some(
+        SQLDataType::Int4(_) | SQLDataType::Int(_) | SQLDataType::Integer(_),
+      ) => (i32::MIN as i64, i32::MAX as i64),
+      Some(SQLDataType::Int8(_) | SQLDataType::BigInt(_)) | None => {
+        (i64::MIN, i64::MAX)
+      }
+      Some(_) => {
+        return Err(Error::InvalidQuery(
+          "sequence type must be smallint, integer, or bigint".to_owned(),
+        ))
+      }
+    };
+
+    let mut increment = 1;
+    let (mut min_value, mut max_value, mut start) = (None, None, None);
+    let mut cycle = false;
+    for option in options {
+      match option {
+        SequenceOptions::IncrementBy(expr, _) => increment = to_i64(expr)?,
+        SequenceOptions::MinValue(MinMaxValue::Some(expr)) => {
+          min_value = Some(to_i64(expr)?)
+        }
+        SequenceOptions::MaxValue(MinMaxValue::Some(expr)) => {
+          max_value = Some(to_i64(expr)?)
+        }
+        SequenceOptions::MinValue(_) | SequenceOptions::MaxValue(_) => {}
+        SequenceOptions::StartWith(expr, _) => start = Some(to_i64(expr)?),
+        SequenceOptions::Cycle(no_cycle) => cycle = !no_cycle,
+        // Values aren't cached since each `nextval` updates the storage
+        SequenceOptions::Cache(_) => {}
+      }
+    }
+
+    if increment == 0 {
+      return Err(Error::InvalidQuery("INCREMENT must not be zero".to_owned()));
+    }
+    // Descending sequences start from the max value
+    let min_value =
+      min_value.unwrap_or(if increment > 0 { 1 } else { type_min });
+    let max_value =
+      max_value.unwrap_or(if increment > 0 { type_max } else { -1 });
+    if min_value < type_min || max_value > type_max {
+      return Err(Error::InvalidQuery(format!(
+        "MINVALUE ({}) and MAXVALUE ({}) must be within the range of \
+        the sequence type",
+        min_value, max_value
+      )));
+    }
+    if min_value >= max_value {
+      return Err(Error::InvalidQuery(format!(
+        "MINVALUE ({}) must be less than MAXVALUE ({})",
+        min_value, max_value
+      )));
+    }
+    let start =
+      start.unwrap_or(if increment > 0 { min_value } else { max_value });
+    if start < min_value {
+      return Err(Error::InvalidQuery(format!(
+        "START value ({}) cannot be less than MINVALUE ({})",
+        start, min_value
+      )));
+    } else if start > max_value {
+      return Err(Error::InvalidQuery(format!(
+        "START value ({}) cannot be greater than MAXVALUE ({})",
+        start, max_value
+      )));
+    }
+
+    Ok(Self {
+      name: name.to_owned(),
+      start,
+      increment,
+      min_value,
+      max_value,
+      cycle,
+      owned_by: None,
+    })
+  }
+
+  /// Returns the sequence used by the `SERIAL` or `GENERATED AS IDENTITY`
+  /// column, if the column is one. The sequence is named
+  /// `<table>_<column>_seq` like in Postgres
+  pub fn from_column_def(
+    table: &str,
+    column_def: &ColumnDef,
+  ) -> Result<Option<Self>> {
+    let name = format!("{}_{}_seq", table, column_def.name.value);
+    let has_default = column_def
+      .options
+      .iter()
+      .any(|opt| matches!(opt.option, ColumnOption::Default(_)));
+    let sequence = match get_serial_type(&column_def.data_type) {
+      Some(data_type) => Some(Self::new(&name, Some(&data_type), &[])?),
+      None => column_def
+        .options
+        .iter()
+        .find_map(|opt| match &opt.option {
+          ColumnOption::Generated {
+            generated_as,
+            sequence_options,
+            ..
+          } => Some((generated_as, sequence_options)),
+          _ => None,
+        })
+        .map(|(generated_as, options)| match generated_as {
+          GeneratedAs::Always | GeneratedAs::ByDefault => Self::new(
+            &name,
+            Some(&column_def.data_type),
+            options.as_deref().unwrap_or_default(),
+          ),
+          GeneratedAs::ExpStored => Err(Error::UnsupportedQuery(
+            "Generated columns are not supported yet".to_owned(),
+          )),
+        })
+        .transpose()?,
+    };
+    if sequence.is_some() && has_default {
+      return Err(Error::InvalidQuery(format!(
+        r#"multiple default values specified for column "{}" of table "{}""#,
+        column_def.name.value, table
+      )));
+    }
+    Ok(sequence.map(|sequence| Self {
+      owned_by: Some(table.to_owned()),
+      ..sequence
+    }))
+  }
+
+  /// Returns the SQL of the column default that uses this sequence
+  pub fn default_expr(&self) -> String {
+    format!("nextval('{}')", self.name)
+  }
+
+  /// Returns the value after the given value. Returns error if the
+  /// sequence reached its limit and it can't cycle
+  pub fn next_value(&self, value: i64) -> Result<i64> {
+    match value
+      .checked_add(self.increment)
+      .filter(|next| (self.min_value..=self.max_value).contains(next))
+    {
+      Some(next) => Ok(next),
+      None if self.cycle => Ok(match self.increment > 0 {
+        true => self.min_value,
+        false => self.max_value,
+      }),
+      None => Err(Error::InvalidQuery(match self.increment > 0 {
+        true => format!(
+          r#"nextval: reached maximum value of sequence "{}" ({})"#,
+          self.name, self.max_value
+        ),
+        false => format!(
+          r#"nextval: reached minimum value of sequence "{}" ({})"#,
+          self.name, self.min_value
+        ),
+      })),
+    }
+  }
+
+  /// Returns error if the value is out of the bounds of the sequence
+  pub fn check_bounds(&self, value: i64) -> Result<()> {
+    if !(self.min_value..=self.max_value).contains(&value) {
+      return Err(Error::InvalidQuery(format!(
+        r#"setval: value {} is out of bounds for sequence "{}" ({}..{})"#,
+        value, self.name, self.min_value, self.max_value
+      )));
+    }
+    Ok(())
+  }
+
+  pub fn from_protobuf(buf: &[u8]) -> Result<Self> {
+    let sequence = super::proto::Sequence::decode(&mut Cursor::new(buf))?;
+    Ok(Self {
+      name: sequence.name,
+      start: sequence.start,
+      increment: sequence.increment,
+      min_value: sequence.min_value,
+      max_value: sequence.max_value,
+      cycle: sequence.cycle,
+      owned_by: sequence.owned_by,
+    })
+  }
+
+  pub fn to_protobuf(&self) -> Result<Vec<u8>> {
+    let sequence = super::proto::Sequence {
+      name: self.name.clone(),
+      start: self.start,
+      increment: self.increment,
+      min_value: self.min_value,
+      max_value: self.max_value,
+      cycle: self.cycle,
+      owned_by: self.owned_by.clone(),
+    };
+
+    let mut buf = Vec::new();
+    buf.reserve(sequence.encoded_len());
+    sequence.encode(&mut buf)?;
+    Ok(buf)
+  }
+}
+
+/// Returns the integer type of the `SMALLSERIAL`, `SERIAL` and `BIGSERIAL`
+/// types
+fn get_serial_type(data_type: &SQLDataType) -> Option<SQLDataType> {
+  match data_type {
+    SQLDataType::Custom(object_name, _) => {
+      match object_name.0[0].value.to_uppercase().as_str() {
+        "SMALLSERIAL" | "SERIAL2" => Some(SQLDataType::SmallInt(None)),
+        "SERIAL" | "SERIAL4" => Some(SQLDataType::Integer(None)),
+        "BIGSERIAL" | "SERIAL8" => Some(SQLDataType::BigInt(None)),
+        _ => None,
+      }
+    }
+    _ => None,
+  }
+}
+
+fn to_i64(expr: &SQLExpr) -> Result<i64> {
+  match expr {
+    SQLExpr::Value(Value::Number(num, _)) => num.parse::<i64>().ok(),
+    SQLExpr::UnaryOp {
+      op: UnaryOperator::Minus,
+      expr,
+    } => return to_i64(expr).map(|num| -num),
+    _ => None,
+  }
+  .ok_or_else(|| {
+    Error::InvalidQuery(format!("invalid sequence option value: {}", expr))
+  })
+}
@@ -0,0 +1,199 @@
+use std::cmp::Ordering;
+use std::collections::hash_map::DefaultHasher;
+use std::collections::HashSet;
+use std::hash::{Hash, Hasher};
+
+use datafusion::logical_expr::Operator;
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+
+use super::{OwnedSerializedCell, TableIndex, TableIndexId};
+
+/// Number of rows assumed for the tables whose rows aren't counted
+pub const DEFAULT_ROW_COUNT: u64 = 10_000;
+
+/// Maximum number of buckets in the histogram of an index
+const HISTOGRAM_BUCKETS: usize = 100;
+
+/// Maximum number of values sampled to build the histogram of an index
+const HISTOGRAM_SAMPLE_SIZE: usize = 30_000;
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct TableStatistics {
+  /// Number of rows in the table. This is updated by DML queries outside
+  /// of the transaction, so it's approximate until the table is analyzed
+  pub row_count: u64,
+  /// Statistics of the indexes collected by `ANALYZE`
+  pub indexes: Vec<IndexStatistics>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct IndexStatistics {
+  pub index_id: TableIndexId,
+  /// Number of distinct keys in the index excluding the keys with NULL
+  pub distinct_count: u64,
+  /// Boundaries of the equi-depth histogram of the first column of the
+  /// index. The number of rows between two consecutive boundaries is
+  /// roughly the same. Empty if the values of the column can't be ordered
+  pub histogram: Vec<OwnedSerializedCell>,
+}
+
+impl TableStatistics {
+  pub fn get_index_statistics(
+    &self,
+    index_id: TableIndexId,
+  ) -> Option<&IndexStatistics> {
+    self.indexes.iter().find(|stats| stats.index_id == index_id)
+  }
+
+  /// Returns the number of distinct values of the column if there's an
+  /// analyzed index on just that column
+  pub fn get_column_distinct_count(
+    &self,
+    indexes: &[TableIndex],
+    column: usize,
+  ) -> Option<u64> {
+    indexes
+      .iter()
+      .filter(|index| {
+        !index.is_vector_index()
+          && index.json_path().is_none()
+          && index.columns().as_slice() == [column]
+      })
+      .find_map(|index| self.get_index_statistics(index.id))
+      .map(|stats| stats.distinct_count)
+  }
+}
+
+impl IndexStatistics {
+  /// Returns the average number of rows that have the same index key
+  pub fn get_rows_per_key(&self, row_count: u64) -> f64 {
+    row_count as f64 / self.distinct_count.max(1) as f64
+  }
+
+  /// Returns the estimated fraction of the rows whose first index column
+  /// satisfies `column <op> value` using the histogram
+  pub fn estimate_range_selectivity(
+    &self,
+    op: Operator,
+    value: &OwnedSerializedCell,
+  ) -> Option<f64> {
+    if self.histogram.len() < 2 {
+      return None;
+    }
+    // Number of boundaries that are less than (or equal to) the value
+    let mut boundaries_below = 0;
+    for boundary in &self.histogram {
+      match (compare_cells(boundary, value)?, op) {
+        (Ordering::Less, _)
+        | (Ordering::Equal, Operator::LtEq | Operator::Gt) => {
+          boundaries_below += 1
+        }
+        _ => break,
+      }
+    }
+    let buckets = (self.histogram.len() - 1) as f64;
+    let fraction_below =
+      (boundaries_below as f64 - 0.5).clamp(0.0, buckets) / buckets;
+    match op {
+      Operator::Lt | Operator::LtEq => Some(fraction_below),
+      Operator::Gt | Operator::GtEq => Some(1.0 - fraction_below),
+      _ => None,
+    }
+  }
+}
+
+/// Collects the statistics of an index from the rows of the table
+pub struct IndexStatisticsCollector {
+  index_id: TableIndexId,
+  key_hashes: HashSet<u64>,
+  /// Reservoir sample of the values of the first column of the index
+  sample: Vec<OwnedSerializedCell>,
+  sampled_keys: usize,
+  /// Whether the values of the first index column can be ordered
+  is_ordered: bool,
+}
+
+impl IndexStatisticsCollector {
+  pub fn new(index_id: TableIndexId) -> Self {
+    Self {
+      index_id,
+      key_hashes: HashSet::new(),
+      sample: vec![],
+      sampled_keys: 0,
+      is_ordered: true,
+    }
+  }
+
+  /// Adds the index key of a row; the keys with NULL are skipped
+  pub fn add(&mut self, key: &[&OwnedSerializedCell], key_bytes: &[u8]) {
+    if key.iter().any(|cell| cell.is_null()) {
+      return;
+    }
+    let mut hasher = DefaultHasher::new();
+    key_bytes.hash(&mut hasher);
+    self.key_hashes.insert(hasher.finish());
+
+    let value = key[0];
+    self.is_ordered = self.is_ordered && compare_cells(value, value).is_some();
+    if !self.is_ordered {
+      return;
+    }
+    self.sampled_keys += 1;
+    if self.sample.len() < HISTOGRAM_SAMPLE_SIZE {
+      self.sample.push(value.clone());
+    } else {
+      let position = rand::thread_rng().gen_range(0..self.sampled_keys);
+      if position < HISTOGRAM_SAMPLE_SIZE {
+        self.sample[position] = value.clone();
+      }
+    }
+  }
+
+  pub fn finish(mut self) -> IndexStatistics {
+    let histogram = match self.is_ordered && !self.sample.is_empty() {
+      true => {
+        self
+          .sample
+          .sort_by(|a, b| compare_cells(a, b).unwrap_or(Ordering::Equal));
+        let last = self.sample.len() - 1;
+        let buckets = HISTOGRAM_BUCKETS.min(last.max(1));
+        (0..=buckets)
+          .map(|bucket| self.sample[bucket * last / buckets].clone())
+          .collect()
+      }
+      false => vec![],
+    };
+    IndexStatistics {
+      index_id: self.index_id,
+      distinct_count: self.key_hashes.len() as u64,
+      histogram,
+    }
+  }
+}
+
+/// Compares the cells of the same scalar type; returns `None` if the
+/// cells can't be ordered
+pub fn compare_cells(
+  a: &OwnedSerializedCell,
+  b: &OwnedSerializedCell,
+) -> Option<Ordering> {
+  use OwnedSerializedCell::*;
+  match (a, b) {
+    (Boolean(a), Boolean(b)) => a.partial_cmp(b),
+    (Int16(a), Int16(b)) => a.partial_cmp(b),
+    (Int32(a), Int32(b)) | (Date(a), Date(b)) => a.partial_cmp(b),
+    (UInt32(a), UInt32(b)) => a.partial_cmp(b),
+    (Int64(a), Int64(b))
+    | (Timestamp(a), Timestamp(b))
+    | (TimestampTz(a), TimestampTz(b))
+    | (Time(a), Time(b)) => a.partial_cmp(b),
+    (UInt64(a), UInt64(b)) => a.partial_cmp(b),
+    (Float32(a), Float32(b)) => a.partial_cmp(b),
+    (Float64(a), Float64(b)) => a.partial_cmp(b),
+    (Decimal(a), Decimal(b)) => a.partial_cmp(b),
+    (String(a), String(b)) => a.partial_cmp(b),
+    (Uuid(a), Uuid(b)) => a.partial_cmp(b),
+    _ => None,
+  }
+}
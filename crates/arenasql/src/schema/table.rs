@@ -1,24 +1,33 @@
 use std::collections::HashMap;
 use std::io::Cursor;
+use std::ops::ControlFlow;
 use std::sync::Arc;
 
 use datafusion::arrow::datatypes::{
   DataType as DfDataType, Field as DfField, Schema as DfSchema,
   SchemaRef as DfSchemaRef,
 };
-use datafusion::common::Constraints as DfConstraints;
+use datafusion::common::{
+  Constraint as DfConstraint, Constraints as DfConstraints,
+};
 use datafusion::datasource::TableProvider as DfTableProvider;
 use inflector::Inflector;
 use prost::Message;
-use sqlparser::ast::{ColumnOption, Statement};
+use sqlparser::ast::{
+  visit_expressions, visit_expressions_mut, ColumnDef, ColumnOption,
+  Expr as SQLExpr, GeneratedAs, Ident, ObjectName,
+  ReferentialAction as SQLReferentialAction, Statement, TableConstraint,
+};
+use sqlparser::dialect::PostgreSqlDialect;
+use sqlparser::parser::Parser;
 
 use super::column::CTID_COLUMN;
 use super::index::IndexProvider;
 use super::{
-  Column, ColumnId, ColumnProperty, Constraint, DataType, TableIndex,
-  TableIndexId,
+  CheckConstraint, Column, ColumnId, ColumnProperty, Constraint, DataType,
+  ForeignKey, ReferentialAction, Sequence, TableIndex, TableIndexId,
 };
-use crate::Result;
+use crate::{Error, Result};
 
 pub type TableId = u16;
 
@@ -38,7 +47,7 @@ impl Table {
     provider: Arc<dyn DfTableProvider>,
     stmt: &Statement,
   ) -> Result<Self> {
-    let columns = get_columns_from_query_stmt(stmt, provider.schema())?;
+    let columns = get_columns_from_query_stmt(stmt, name, provider.schema())?;
     let mut constraints: Vec<Constraint> = provider
       .constraints()
       .map(|constraints| {
@@ -56,6 +65,13 @@ impl Table {
         constraints.push(Constraint::Unique(vec![col.id as usize]));
       }
     });
+    // Datafusion doesn't support check constraints, so read them
+    // from the query directly
+    constraints.extend(
+      get_check_constraints_from_query_stmt(stmt, name, &columns)?
+        .into_iter()
+        .map(Constraint::Check),
+    );
     Ok(Table {
       id,
       name: name.to_owned(),
@@ -65,11 +81,265 @@ impl Table {
     })
   }
 
-  pub fn get_df_schema(&self) -> DfSchemaRef {
-    let fields: Vec<DfField> = self
+  /// Adds the foreign key constraints of the `CREATE TABLE` query.
+  /// Datafusion doesn't support foreign keys, so they are read from the
+  /// query directly. `get_table` should return the table with the given
+  /// name from the same schema as this table
+  pub fn add_foreign_keys_from_query_stmt(
+    &mut self,
+    stmt: &Statement,
+    get_table: impl Fn(&str) -> Option<Arc<Table>>,
+  ) -> Result<()> {
+    let foreign_keys = match stmt {
+      Statement::CreateTable {
+        columns,
+        constraints,
+        ..
+      } => {
+        let column_foreign_keys = columns.iter().flat_map(|col| {
+          col.options.iter().filter_map(|opt| match &opt.option {
+            ColumnOption::ForeignKey {
+              foreign_table,
+              referred_columns,
+              on_delete,
+              on_update,
+            } => Some((
+              opt.name.as_ref(),
+              vec![col.name.clone()],
+              foreign_table,
+              referred_columns,
+              on_delete,
+              on_update,
+            )),
+            _ => None,
+          })
+        });
+        let table_foreign_keys =
+          constraints
+            .iter()
+            .filter_map(|constraint| match constraint {
+              TableConstraint::ForeignKey {
+                name,
+                columns,
+                foreign_table,
+                referred_columns,
+                on_delete,
+                on_update,
+              } => Some((
+                name.as_ref(),
+                columns.clone(),
+                foreign_table,
+                referred_columns,
+                on_delete,
+                on_update,
+              )),
+              _ => None,
+            });
+        column_foreign_keys
+          .chain(table_foreign_keys)
+          .collect::<Vec<_>>()
+      }
+      _ => return Ok(()),
+    };
+
+    for (
+      name,
+      columns,
+      foreign_table,
+      referred_columns,
+      on_delete,
+      on_update,
+    ) in foreign_keys
+    {
+      let foreign_key = self.resolve_foreign_key(
+        name,
+        &columns,
+        foreign_table,
+        referred_columns,
+        (on_delete, on_update),
+        &get_table,
+      )?;
+      self.constraints.push(Constraint::ForeignKey(foreign_key));
+    }
+    Ok(())
+  }
+
+  fn resolve_foreign_key(
+    &self,
+    name: Option<&Ident>,
+    columns: &[Ident],
+    foreign_table: &ObjectName,
+    referred_columns: &[Ident],
+    (on_delete, on_update): (
+      &Option<SQLReferentialAction>,
+      &Option<SQLReferentialAction>,
+    ),
+    get_table: &impl Fn(&str) -> Option<Arc<Table>>,
+  ) -> Result<ForeignKey> {
+    let foreign_table_name = &foreign_table.0.last().unwrap().value;
+    // Table can reference itself
+    let foreign_table = match foreign_table_name == &self.name {
+      true => Arc::new(self.clone()),
+      false => get_table(foreign_table_name).ok_or_else(|| {
+        Error::RelationDoesntExist(foreign_table_name.clone())
+      })?,
+    };
+
+    let columns = find_column_indices(self, columns)?;
+    let referred_columns = match referred_columns.is_empty() {
+      // Primary key of the foreign table is used by default
+      true => foreign_table
+        .constraints
+        .iter()
+        .find_map(|constraint| match constraint {
+          Constraint::PrimaryKey(columns) => Some(columns.clone()),
+          _ => None,
+        })
+        .ok_or_else(|| {
+          Error::InvalidQuery(format!(
+            r#"there is no primary key for referenced table "{}""#,
+            foreign_table.name
+          ))
+        })?,
+      false => find_column_indices(&foreign_table, referred_columns)?,
+    };
+
+    if columns.len() != referred_columns.len() {
+      return Err(Error::InvalidQuery(
+        "number of referencing and referenced columns for foreign key disagree"
+          .to_owned(),
+      ));
+    }
+
+    let has_unique_key = foreign_table
+      .constraints
+      .iter()
+      .any(|c| c.is_unique() && c.columns() == &referred_columns)
+      || foreign_table.indexes.iter().any(|index| {
+        index.is_unique()
+          && index.json_path().is_none()
+          && index.columns() == &referred_columns
+      });
+    if !has_unique_key {
+      return Err(Error::InvalidQuery(format!(
+        r#"there is no unique constraint matching given keys for referenced table "{}""#,
+        foreign_table.name
+      )));
+    }
+
+    for (col, referred_col) in columns.iter().zip(referred_columns.iter()) {
+      let column = &self.columns[*col];
+      let referred_column = &foreign_table.columns[*referred_col];
+      if column.data_type != referred_column.data_type {
+        return Err(Error::InvalidQuery(format!(
+          r#"foreign key columns "{}" and "{}" are of incompatible types: {} and {}"#,
+          column.name,
+          referred_column.name,
+          column.data_type,
+          referred_column.data_type
+        )));
+      }
+    }
+
+    let name = name.map(|name| name.value.clone()).unwrap_or_else(|| {
+      columns.iter().fold(self.name.clone(), |agg, col| {
+        agg + "_" + &self.columns[*col].name.to_snake_case()
+      }) + "_fkey"
+    });
+    Ok(ForeignKey {
+      name,
+      columns,
+      foreign_table: foreign_table.name.clone(),
+      referred_columns,
+      on_delete: to_referential_action(on_delete)?,
+      on_update: to_referential_action(on_update)?,
+    })
+  }
+
+  /// Returns the foreign key constraints of this table
+  pub fn foreign_keys(&self) -> impl Iterator<Item = &ForeignKey> {
+    self.constraints.iter().filter_map(|c| match c {
+      Constraint::ForeignKey(foreign_key) => Some(foreign_key),
+      _ => None,
+    })
+  }
+
+  /// Archives the column and removes the constraints and indexes that
+  /// use the column. Returns the removed indexes so that the index rows
+  /// can be deleted
+  pub fn drop_column(&mut self, column: usize) -> Vec<TableIndex> {
+    let col = &mut self.columns[column];
+    col.properties = ColumnProperty::ARCHIVED;
+    col.default_expr = None;
+
+    self
+      .constraints
+      .retain(|constraint| !constraint.columns().contains(&column));
+    let (dropped_indexes, indexes) =
+      self.indexes.drain(..).partition(|index| {
+        index.columns().contains(&column)
+          || index.namespace_column() == Some(column)
+      });
+    self.indexes = indexes;
+    dropped_indexes
+  }
+
+  /// Renames the column and updates the check constraints that use
+  /// the column
+  pub fn rename_column(&mut self, column: usize, new_name: &str) -> Result<()> {
+    let old_name = self.columns[column].name.clone();
+    for constraint in self.constraints.iter_mut() {
+      if let Constraint::Check(check) = constraint {
+        if !check.columns.contains(&column) {
+          continue;
+        }
+        let mut expr = Parser::new(&PostgreSqlDialect {})
+          .try_with_sql(&check.expr)?
+          .parse_expr()?;
+        let _ = visit_expressions_mut(&mut expr, |e| {
+          match e {
+            SQLExpr::Identifier(ident) if ident.value == old_name => {
+              ident.value = new_name.to_owned();
+            }
+            SQLExpr::CompoundIdentifier(idents) => {
+              let ident = idents.last_mut().unwrap();
+              if ident.value == old_name {
+                ident.value = new_name.to_owned();
+              }
+            }
+            _ => {}
+          }
+          ControlFlow::<()>::Continue(())
+        });
+        check.expr = expr.to_string();
+      }
+    }
+    self.columns[column].name = new_name.to_owned();
+    Ok(())
+  }
+
+  /// Returns the (column index, column) of the columns that aren't
+  /// archived. Only these columns are included in the Datafusion schema
+  pub fn active_columns(&self) -> impl Iterator<Item = (usize, &Column)> {
+    self
       .columns
       .iter()
-      .map(|col| col.to_field(&self))
+      .enumerate()
+      .filter(|(_, col)| !col.archived())
+  }
+
+  /// Returns the index of the active column with the given name
+  pub fn find_column(&self, name: &str) -> Option<usize> {
+    self
+      .active_columns()
+      .find(|(_, col)| col.name == name)
+      .map(|(idx, _)| idx)
+  }
+
+  pub fn get_df_schema(&self) -> DfSchemaRef {
+    let fields: Vec<DfField> = self
+      .active_columns()
+      .map(|(_, col)| col.to_field(&self))
       .chain(vec![DfField::new(CTID_COLUMN, DfDataType::UInt64, false)
         .with_metadata(HashMap::from([(
           "TYPE".to_owned(),
@@ -83,8 +353,30 @@ impl Table {
     if self.constraints.is_empty() {
       return None;
     }
+    // Archived columns aren't in the Datafusion schema, so the column
+    // indices need to be mapped to the indices of the schema fields
+    let df_columns: Vec<usize> =
+      self.active_columns().map(|(idx, _)| idx).collect();
+    let to_df_columns = |columns: Vec<usize>| -> Vec<usize> {
+      columns
+        .iter()
+        .filter_map(|col| df_columns.iter().position(|c| c == col))
+        .collect()
+    };
     Some(DfConstraints::new_unverified(
-      self.constraints.iter().map(|c| c.into()).collect(),
+      self
+        .constraints
+        .iter()
+        .filter_map(|c| c.to_df_constraint())
+        .map(|c| match c {
+          DfConstraint::PrimaryKey(columns) => {
+            DfConstraint::PrimaryKey(to_df_columns(columns))
+          }
+          DfConstraint::Unique(columns) => {
+            DfConstraint::Unique(to_df_columns(columns))
+          }
+        })
+        .collect(),
     ))
   }
 
@@ -100,8 +392,13 @@ impl Table {
         .iter()
         .fold(self.name.clone(), |agg, col| {
           agg + "_" + &self.columns[*col].name.to_snake_case()
-        })
-        + "_key";
+        });
+      if let Some(path) = provider.json_path() {
+        index_name = path
+          .iter()
+          .fold(index_name, |agg, key| agg + "_" + &key.to_snake_case());
+      }
+      index_name += "_key";
 
       let index_name_overlap_count = self
         .indexes
@@ -180,8 +477,163 @@ impl Table {
   }
 }
 
+fn find_column_indices(table: &Table, columns: &[Ident]) -> Result<Vec<usize>> {
+  columns
+    .iter()
+    .map(|col| {
+      table
+        .find_column(&col.value)
+        .ok_or_else(|| Error::ColumnDoesntExist(col.value.clone()))
+    })
+    .collect()
+}
+
+fn to_referential_action(
+  action: &Option<SQLReferentialAction>,
+) -> Result<ReferentialAction> {
+  match action {
+    None | Some(SQLReferentialAction::NoAction) => {
+      Ok(ReferentialAction::NoAction)
+    }
+    Some(SQLReferentialAction::Restrict) => Ok(ReferentialAction::Restrict),
+    Some(SQLReferentialAction::Cascade) => Ok(ReferentialAction::Cascade),
+    Some(SQLReferentialAction::SetNull) => Ok(ReferentialAction::SetNull),
+    Some(SQLReferentialAction::SetDefault) => Err(Error::UnsupportedOperation(
+      "ON DELETE/UPDATE SET DEFAULT is not supported".to_owned(),
+    )),
+  }
+}
+
+fn get_check_constraints_from_query_stmt(
+  stmt: &Statement,
+  table_name: &str,
+  columns: &[Column],
+) -> Result<Vec<CheckConstraint>> {
+  let checks: Vec<(Option<&Ident>, &SQLExpr)> = match stmt {
+    Statement::CreateTable {
+      columns: column_defs,
+      constraints,
+      ..
+    } => column_defs
+      .iter()
+      .flat_map(|col| {
+        col.options.iter().filter_map(|opt| match &opt.option {
+          ColumnOption::Check(expr) => Some((opt.name.as_ref(), expr)),
+          _ => None,
+        })
+      })
+      .chain(
+        constraints
+          .iter()
+          .filter_map(|constraint| match constraint {
+            TableConstraint::Check { name, expr } => {
+              Some((name.as_ref(), expr.as_ref()))
+            }
+            _ => None,
+          }),
+      )
+      .collect(),
+    _ => return Ok(vec![]),
+  };
+
+  let mut check_constraints: Vec<CheckConstraint> = vec![];
+  for (name, expr) in checks {
+    let mut check_columns = vec![];
+    for identifier in get_expr_identifiers(expr) {
+      let column = columns
+        .iter()
+        .position(|col| col.name == identifier)
+        .ok_or_else(|| Error::ColumnDoesntExist(identifier.clone()))?;
+      if !check_columns.contains(&column) {
+        check_columns.push(column);
+      }
+    }
+
+    let name = name.map(|name| name.value.clone()).unwrap_or_else(|| {
+      let name = match check_columns.len() {
+        1 => format!(
+          "{}_{}_check",
+          table_name,
+          columns[check_columns[0]].name.to_snake_case()
+        ),
+        _ => format!("{}_check", table_name),
+      };
+      let overlap_count = check_constraints
+        .iter()
+        .filter(|check| check.name.starts_with(&name))
+        .count();
+      match overlap_count {
+        0 => name,
+        count => format!("{}{}", name, count),
+      }
+    });
+    check_constraints.push(CheckConstraint {
+      name,
+      columns: check_columns,
+      expr: expr.to_string(),
+    });
+  }
+  Ok(check_constraints)
+}
+
+/// Returns the SQL of the column default expression. Returns error if
+/// the expression uses any columns
+pub(crate) fn get_default_expr(expr: &SQLExpr) -> Result<String> {
+  if !get_expr_identifiers(expr).is_empty() {
+    return Err(Error::InvalidQuery(
+      "cannot use column reference in DEFAULT expression".to_owned(),
+    ));
+  }
+  Ok(expr.to_string())
+}
+
+/// Returns the names of the columns used in the expression
+fn get_expr_identifiers(expr: &SQLExpr) -> Vec<String> {
+  let mut identifiers = vec![];
+  let _ = visit_expressions(expr, |e| {
+    match e {
+      SQLExpr::Identifier(ident) => identifiers.push(ident.value.clone()),
+      SQLExpr::CompoundIdentifier(idents) => {
+        identifiers.push(idents.last().unwrap().value.clone())
+      }
+      _ => {}
+    }
+    ControlFlow::<()>::Continue(())
+  });
+  identifiers
+}
+
+/// Sets the default value of the `SERIAL` or identity column to the
+/// next value of its sequence and returns the sequence. Returns None
+/// if the column isn't a `SERIAL` or identity column
+pub(crate) fn set_column_sequence(
+  table: &str,
+  column: &mut Column,
+  column_def: &ColumnDef,
+) -> Result<Option<Sequence>> {
+  let sequence = Sequence::from_column_def(table, column_def)?;
+  if let Some(sequence) = &sequence {
+    column.default_expr = Some(sequence.default_expr());
+    column.properties.insert(ColumnProperty::NOT_NULL);
+    let generated_always = column_def.options.iter().any(|opt| {
+      matches!(
+        opt.option,
+        ColumnOption::Generated {
+          generated_as: GeneratedAs::Always,
+          ..
+        }
+      )
+    });
+    if generated_always {
+      column.properties.insert(ColumnProperty::GENERATED_ALWAYS);
+    }
+  }
+  Ok(sequence)
+}
+
 fn get_columns_from_query_stmt(
   stmt: &Statement,
+  table: &str,
   schema: DfSchemaRef,
 ) -> Result<Vec<Column>> {
   match stmt {
@@ -210,13 +662,25 @@ fn get_columns_from_query_stmt(
           properties.insert(ColumnProperty::UNIQUE);
         }
 
-        Ok(Column {
+        let default_expr = col
+          .options
+          .iter()
+          .find_map(|opt| match &opt.option {
+            ColumnOption::Default(expr) => Some(expr),
+            _ => None,
+          })
+          .map(get_default_expr)
+          .transpose()?;
+
+        let mut column = Column {
           id: index as ColumnId,
           name: col.name.value.clone(),
           data_type: DataType::from_column_def(&col, Some(field.as_ref()))?,
           properties,
-          default_value: None,
-        })
+          default_expr,
+        };
+        set_column_sequence(table, &mut column, col)?;
+        Ok(column)
       })
       .collect::<Result<Vec<Column>>>(),
     _ => unimplemented!(),
@@ -0,0 +1,41 @@
+use super::StorageHandler;
+use crate::storage::KeyValueGroup;
+use crate::Result;
+
+impl StorageHandler {
+  /// Moves the metadata of the catalog that's keyed by the catalog name;
+  /// i.e. table schemas, sequences and their values, to the new name.
+  /// Rows and indexes are keyed by the table/index ids, so they don't
+  /// need to be moved
+  #[tracing::instrument(skip(self), level = "TRACE")]
+  pub fn rename_catalog(&self, catalog: &str, new_catalog: &str) -> Result<()> {
+    let prefixes = [
+      (
+        KeyValueGroup::Schemas,
+        format!("m_schema_c{}_s", catalog),
+        format!("m_schema_c{}_s", new_catalog),
+      ),
+      (
+        KeyValueGroup::Locks,
+        format!("m_c{}_s", catalog),
+        format!("m_c{}_s", new_catalog),
+      ),
+    ];
+    for (group, prefix, new_prefix) in prefixes {
+      let mut iter = self.kv.scan_with_prefix(group, prefix.as_bytes())?;
+      let mut rows = Vec::new();
+      while let Some((key, value)) = iter.get() {
+        rows.push((key.to_vec(), value.to_vec()));
+        iter.next();
+      }
+      drop(iter);
+
+      for (key, value) in rows {
+        let new_key = [new_prefix.as_bytes(), &key[prefix.len()..]].concat();
+        self.kv.put(group, &new_key, &value)?;
+        self.kv.delete(group, &key)?;
+      }
+    }
+    Ok(())
+  }
+}
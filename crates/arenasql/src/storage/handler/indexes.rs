@@ -1,9 +1,12 @@
 use super::StorageHandler;
 use crate::schema::{
-  OwnedRow, OwnedSerializedCell, RowTrait, Table, TableIndex, TableIndexId,
+  get_json_path_index_key, JsonCell, OwnedRow, OwnedSerializedCell, RowTrait,
+  SerializedCell, Table, TableIndex, TableIndexId,
 };
 use crate::storage::{KeyValueGroup, Serializer};
-use crate::{index_row_key, last_table_index_id_key, Error, Result};
+use crate::{
+  index_row_key, index_rows_prefix_key, last_table_index_id_key, Error, Result,
+};
 
 impl StorageHandler {
   /// Table index id is unique to the database
@@ -36,7 +39,20 @@ impl StorageHandler {
     row_id_bytes: &[u8],
     row: &OwnedRow,
   ) -> Result<()> {
-    let projected_cells = row.project(&table_index.columns());
+    if table_index.is_vector_index() {
+      return self.add_row_to_vector_index(table_index, row_id_bytes, row);
+    }
+
+    // JSON path index is keyed by the value at the path of the JSON
+    let json_key;
+    let projected_cells = match table_index.json_path() {
+      Some(path) => {
+        json_key =
+          get_json_path_index_key(row[table_index.columns()[0]].json(), path);
+        vec![&json_key]
+      }
+      None => row.project(&table_index.columns()),
+    };
     let projected_cells_has_null = projected_cells.iter().any(|c| c.is_null());
     // Note(sagar): if there's any index column with NULL value,
     // don't check unique constraint
@@ -75,21 +91,127 @@ impl StorageHandler {
     Ok(())
   }
 
+  /// Returns the ids of the rows whose indexed columns are equal to
+  /// the given values
+  pub fn get_row_ids_from_index(
+    &self,
+    table_index: &TableIndex,
+    values: &[&OwnedSerializedCell],
+  ) -> Result<Vec<Vec<u8>>> {
+    let serialized_values = self.serializer.serialize(values)?;
+    let index_key = index_row_key!(table_index.id, &serialized_values);
+    if table_index.is_unique() {
+      return Ok(
+        self
+          .kv
+          .get(KeyValueGroup::IndexRows, &index_key)?
+          .into_iter()
+          .collect(),
+      );
+    }
+
+    // Since row_id is the last part of the key when index allows
+    // duplicates, the index values form the prefix of the key
+    let prefix_len = index_key.len() - serialized_values.len();
+    let mut iter = self
+      .kv
+      .scan_with_prefix(KeyValueGroup::IndexRows, &index_key)?;
+    let mut row_ids = vec![];
+    while let Some((key, _)) = iter.get() {
+      let (_, row_id) = self
+        .serializer
+        .deserialize::<(Vec<SerializedCell<'_>>, &[u8])>(&key[prefix_len..])?;
+      row_ids.push(row_id.to_vec());
+      iter.next();
+    }
+    Ok(row_ids)
+  }
+
+  /// Returns the id of the row that has the same key as the given row
+  /// in the unique index. Returns None if the index isn't unique or if
+  /// any of the index columns of the row is NULL
+  pub fn get_conflicting_row_id(
+    &self,
+    table_index: &TableIndex,
+    row: &OwnedRow,
+  ) -> Result<Option<Vec<u8>>> {
+    let json_key;
+    let projected_cells = match table_index.json_path() {
+      Some(path) => {
+        json_key =
+          get_json_path_index_key(row[table_index.columns()[0]].json(), path);
+        vec![&json_key]
+      }
+      None => row.project(&table_index.columns()),
+    };
+    if !table_index.is_unique() || projected_cells.iter().any(|c| c.is_null()) {
+      return Ok(None);
+    }
+    Ok(
+      self
+        .get_row_ids_from_index(table_index, &projected_cells)?
+        .pop(),
+    )
+  }
+
   pub fn delete_row_from_index<'a, O, S>(
     &self,
     table_index: &TableIndex,
+    row_id_bytes: &[u8],
     row: &'a S,
   ) -> Result<()>
   where
-    O: serde::Serialize + 'a,
+    O: serde::Serialize + JsonCell + 'a,
     S: ?Sized + serde::Serialize + RowTrait<'a, O>,
   {
+    if table_index.is_vector_index() {
+      return self.delete_row_from_vector_index(table_index, row_id_bytes, row);
+    }
+
     let projected_cells = row.project(&table_index.columns());
-    let serialized_index_key_columns =
-      self.serializer.serialize(&projected_cells)?;
+    let serialized_index_key_columns = match table_index.json_path() {
+      Some(path) => {
+        let json_key = get_json_path_index_key(projected_cells[0].json(), path);
+        self.serialize_index_key(table_index, vec![&json_key], row_id_bytes)?
+      }
+      None => {
+        self.serialize_index_key(table_index, projected_cells, row_id_bytes)?
+      }
+    };
     let index_key =
       index_row_key!(table_index.id, &serialized_index_key_columns);
 
     self.kv.delete(KeyValueGroup::IndexRows, &index_key)
   }
+
+  fn serialize_index_key<C: serde::Serialize>(
+    &self,
+    table_index: &TableIndex,
+    cells: Vec<&C>,
+    row_id_bytes: &[u8],
+  ) -> Result<Vec<u8>> {
+    if table_index.is_unique() {
+      self.serializer.serialize(&cells)
+    } else {
+      // row_id is part of the key when index allows duplicates
+      self
+        .serializer
+        .serialize::<(Vec<&C>, &[u8])>(&(cells, row_id_bytes))
+    }
+  }
+
+  /// Deletes all the rows of the index
+  pub fn delete_index_rows(&self, table_index: &TableIndex) -> Result<()> {
+    let mut index_rows_iter = self.kv.scan_with_prefix(
+      KeyValueGroup::IndexRows,
+      &index_rows_prefix_key!(table_index.id),
+    )?;
+
+    // TODO: is there a way to do bulk delete?
+    while let Some((index_row_key, _)) = index_rows_iter.get() {
+      self.kv.delete(KeyValueGroup::IndexRows, index_row_key)?;
+      index_rows_iter.next();
+    }
+    Ok(())
+  }
 }
@@ -1,7 +1,11 @@
+mod catalog;
 mod indexes;
 mod rowid;
 mod rows;
+mod sequence;
+mod statistics;
 mod table;
+mod vectors;
 
 use std::sync::Arc;
 
@@ -1,5 +1,5 @@
 use super::StorageHandler;
-use crate::schema::{OwnedRow, Table};
+use crate::schema::{OwnedRow, Row, Table};
 use crate::storage::KeyValueGroup;
 use crate::{table_rows_prefix_key, Result};
 
@@ -14,19 +14,47 @@ impl StorageHandler {
       .get(
         KeyValueGroup::Rows,
         &vec![table_rows_prefix_key!(table.id).as_slice(), &row_id].concat(),
-      )
-      .transpose()
-      .map(|bytes| bytes.and_then(|b| self.serializer.deserialize(&b)))
+      )?
+      .map(|bytes| self.deserialize_owned_row(table, row_id, &bytes))
       .transpose()
   }
 
+  /// Deserializes the row data of the table. The vectors that were removed
+  /// from the row data because they are stored in vector index are put back
+  /// into the row.
+  pub fn deserialize_row<'a>(
+    &self,
+    table: &Table,
+    row_id: &[u8],
+    row_bytes: &'a [u8],
+  ) -> Result<Row<'a>> {
+    let mut row = self.serializer.deserialize::<Row<'a>>(row_bytes)?;
+    self.restore_stripped_vectors(table, row_id, &mut row)?;
+    Ok(row)
+  }
+
+  /// Same as [`StorageHandler::deserialize_row`] but returns owned row
+  pub fn deserialize_owned_row(
+    &self,
+    table: &Table,
+    row_id: &[u8],
+    row_bytes: &[u8],
+  ) -> Result<OwnedRow> {
+    let mut row = self.serializer.deserialize::<OwnedRow>(row_bytes)?;
+    self.restore_stripped_vectors(table, row_id, &mut row)?;
+    Ok(row)
+  }
+
   pub fn insert_row(
     &self,
     table: &Table,
     row_id: &[u8],
     row: &OwnedRow,
   ) -> Result<()> {
-    let row_bytes = self.serializer.serialize(&row)?;
+    let row_bytes = match self.strip_indexed_vectors(table, row) {
+      Some(stripped_row) => self.serializer.serialize(&stripped_row)?,
+      None => self.serializer.serialize(&row)?,
+    };
     self.kv.put(
       KeyValueGroup::Rows,
       &vec![table_rows_prefix_key!(table.id).as_slice(), &row_id].concat(),
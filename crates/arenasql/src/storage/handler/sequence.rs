@@ -0,0 +1,156 @@
+use std::cell::RefCell;
+
+use super::StorageHandler;
+use crate::schema::Sequence;
+use crate::storage::{KeyValueGroup, Serializer};
+use crate::{
+  sequence_key, sequence_value_key, sequences_prefix_key, Error, Result,
+};
+
+/// (last value, whether the last value was returned by `nextval`)
+type SequenceValue = (i64, bool);
+
+impl StorageHandler {
+  #[tracing::instrument(skip(self), level = "TRACE")]
+  pub fn get_sequence(
+    &self,
+    catalog: &str,
+    schema: &str,
+    name: &str,
+  ) -> Result<Option<Sequence>> {
+    self
+      .kv
+      .get(KeyValueGroup::Schemas, sequence_key!(catalog, schema, name))?
+      .map(|bytes| Sequence::from_protobuf(&bytes))
+      .transpose()
+  }
+
+  #[tracing::instrument(skip(self), level = "TRACE")]
+  pub fn get_all_sequences(
+    &self,
+    catalog: &str,
+    schema: &str,
+  ) -> Result<Vec<Sequence>> {
+    let mut iter = self.kv.scan_with_prefix(
+      KeyValueGroup::Schemas,
+      sequences_prefix_key!(catalog, schema),
+    )?;
+
+    let mut sequences = Vec::new();
+    while let Some((_key, value)) = iter.get() {
+      sequences.push(Sequence::from_protobuf(&value)?);
+      iter.next();
+    }
+    Ok(sequences)
+  }
+
+  /// Stores the new sequence and resets its value. Returns error if a
+  /// sequence with the same name already exists
+  #[tracing::instrument(skip(self, sequence), level = "TRACE")]
+  pub fn create_sequence(
+    &self,
+    catalog: &str,
+    schema: &str,
+    sequence: &Sequence,
+  ) -> Result<()> {
+    if self
+      .get_sequence(catalog, schema, &sequence.name)?
+      .is_some()
+    {
+      return Err(Error::RelationAlreadyExists(sequence.name.clone()));
+    }
+    self.put_sequence(catalog, schema, sequence)?;
+    // The value of the dropped sequence with the same name might still
+    // be in the storage
+    self.set_sequence_value(catalog, schema, sequence, sequence.start, false)
+  }
+
+  #[tracing::instrument(skip(self, sequence), level = "TRACE")]
+  pub fn put_sequence(
+    &self,
+    catalog: &str,
+    schema: &str,
+    sequence: &Sequence,
+  ) -> Result<()> {
+    self.kv.put(
+      KeyValueGroup::Schemas,
+      sequence_key!(catalog, schema, &sequence.name),
+      &sequence.to_protobuf()?,
+    )
+  }
+
+  /// Deletes the sequence definition. The value of the sequence is left
+  /// as is since it's updated outside of the transaction; it's reset
+  /// when a sequence with the same name is created
+  #[tracing::instrument(skip(self), level = "TRACE")]
+  pub fn delete_sequence(
+    &self,
+    catalog: &str,
+    schema: &str,
+    name: &str,
+  ) -> Result<()> {
+    self
+      .kv
+      .delete(KeyValueGroup::Schemas, sequence_key!(catalog, schema, name))
+  }
+
+  /// Returns the next `count` values of the sequence.
+  ///
+  /// The values are updated atomically outside of the transaction, so
+  /// they aren't reused even if the transaction is rolled back
+  #[tracing::instrument(skip(self, sequence), level = "TRACE")]
+  pub fn next_sequence_values(
+    &self,
+    catalog: &str,
+    schema: &str,
+    sequence: &Sequence,
+    count: usize,
+  ) -> Result<Vec<i64>> {
+    let serializer = Serializer::FixedInt;
+    let values = RefCell::new(Vec::with_capacity(count));
+    self.kv.atomic_update(
+      KeyValueGroup::Locks,
+      &sequence_value_key!(catalog, schema, &sequence.name),
+      &|prev: Option<Vec<u8>>| {
+        let (mut last_value, mut is_called) = prev
+          .map(|bytes| serializer.deserialize::<SequenceValue>(&bytes))
+          .unwrap_or(Ok((sequence.start, false)))?;
+
+        let mut values = values.borrow_mut();
+        // Clear the values in case the update is retried
+        values.clear();
+        for _ in 0..count {
+          if is_called {
+            last_value = sequence.next_value(last_value)?;
+          }
+          is_called = true;
+          values.push(last_value);
+        }
+        serializer.serialize::<SequenceValue>(&(last_value, is_called))
+      },
+    )?;
+    Ok(values.into_inner())
+  }
+
+  /// Sets the value of the sequence. If `is_called` is true, next
+  /// `nextval` returns the value after the given value, else it returns
+  /// the given value
+  #[tracing::instrument(skip(self, sequence), level = "TRACE")]
+  pub fn set_sequence_value(
+    &self,
+    catalog: &str,
+    schema: &str,
+    sequence: &Sequence,
+    value: i64,
+    is_called: bool,
+  ) -> Result<()> {
+    sequence.check_bounds(value)?;
+    let serializer = Serializer::FixedInt;
+    self.kv.atomic_update(
+      KeyValueGroup::Locks,
+      &sequence_value_key!(catalog, schema, &sequence.name),
+      &|_| serializer.serialize::<SequenceValue>(&(value, is_called)),
+    )?;
+    Ok(())
+  }
+}
@@ -0,0 +1,168 @@
+use super::StorageHandler;
+use crate::schema::{
+  get_json_path_index_key, IndexStatisticsCollector, JsonCell,
+  OwnedSerializedCell, RowTrait, Table, TableIndex, TableStatistics,
+};
+use crate::storage::{KeyValueGroup, Serializer};
+use crate::{
+  row_count_of_table_key, table_rows_prefix_key, table_statistics_key, Result,
+};
+
+/// Number of rows in the table; `None` if the rows aren't counted
+type RowCount = Option<u64>;
+
+impl StorageHandler {
+  /// Returns the statistics of the table. Returns `None` if the rows of
+  /// the table aren't counted, i.e. if the table was created before the
+  /// rows were counted and it hasn't been analyzed yet
+  #[tracing::instrument(skip(self, table), level = "TRACE")]
+  pub fn get_table_statistics(
+    &self,
+    table: &Table,
+  ) -> Result<Option<TableStatistics>> {
+    let serializer = Serializer::FixedInt;
+    let row_count = self
+      .kv
+      .get(KeyValueGroup::Locks, &row_count_of_table_key!(table.id))?
+      .map(|bytes| serializer.deserialize::<RowCount>(&bytes))
+      .transpose()?
+      .flatten();
+    let Some(row_count) = row_count else {
+      return Ok(None);
+    };
+
+    let mut statistics = self
+      .kv
+      .get(KeyValueGroup::Schemas, &table_statistics_key!(table.id))?
+      .map(|bytes| self.serializer.deserialize::<TableStatistics>(&bytes))
+      .transpose()?
+      .unwrap_or_default();
+    statistics.row_count = row_count;
+    Ok(Some(statistics))
+  }
+
+  /// Stores the statistics collected by `ANALYZE` and resets the row
+  /// count of the table
+  #[tracing::instrument(skip(self, table, statistics), level = "TRACE")]
+  pub fn put_table_statistics(
+    &self,
+    table: &Table,
+    statistics: &TableStatistics,
+  ) -> Result<()> {
+    self.set_table_row_count(table, Some(statistics.row_count))?;
+    self.kv.put(
+      KeyValueGroup::Schemas,
+      &table_statistics_key!(table.id),
+      &self.serializer.serialize(statistics)?,
+    )
+  }
+
+  /// Sets the number of rows in the table. Like the row id, the row count
+  /// is updated outside of the transaction so that the transactions that
+  /// modify the same table don't conflict with each other
+  pub fn set_table_row_count(
+    &self,
+    table: &Table,
+    row_count: RowCount,
+  ) -> Result<()> {
+    let serializer = Serializer::FixedInt;
+    self.kv.atomic_update(
+      KeyValueGroup::Locks,
+      &row_count_of_table_key!(table.id),
+      &|_| serializer.serialize::<RowCount>(&row_count),
+    )?;
+    Ok(())
+  }
+
+  /// Adds the number of rows inserted by a query to the row count of the
+  /// table; `delta` is negative for the deleted rows
+  pub fn update_table_row_count(
+    &self,
+    table: &Table,
+    delta: i64,
+  ) -> Result<()> {
+    if delta == 0 {
+      return Ok(());
+    }
+    let serializer = Serializer::FixedInt;
+    self.kv.atomic_update(
+      KeyValueGroup::Locks,
+      &row_count_of_table_key!(table.id),
+      &|prev: Option<Vec<u8>>| {
+        let row_count = prev
+          .map(|bytes| serializer.deserialize::<RowCount>(&bytes))
+          .transpose()?
+          .flatten()
+          .map(|count| count.saturating_add_signed(delta));
+        serializer.serialize::<RowCount>(&row_count)
+      },
+    )?;
+    Ok(())
+  }
+
+  #[tracing::instrument(skip(self, table), level = "TRACE")]
+  pub fn delete_table_statistics(&self, table: &Table) -> Result<()> {
+    self
+      .kv
+      .delete(KeyValueGroup::Locks, &row_count_of_table_key!(table.id))?;
+    self
+      .kv
+      .delete(KeyValueGroup::Schemas, &table_statistics_key!(table.id))
+  }
+
+  /// Scans all the rows of the table to count the rows and to collect
+  /// the statistics of the indexes
+  #[tracing::instrument(skip(self, table), level = "TRACE")]
+  pub fn compute_table_statistics(
+    &self,
+    table: &Table,
+  ) -> Result<TableStatistics> {
+    // Vector index can only be used for nearest neighbour search
+    let indexes: Vec<&TableIndex> = table
+      .indexes
+      .iter()
+      .filter(|index| !index.is_vector_index())
+      .collect();
+    let mut collectors: Vec<IndexStatisticsCollector> = indexes
+      .iter()
+      .map(|index| IndexStatisticsCollector::new(index.id))
+      .collect();
+
+    let table_row_prefix = table_rows_prefix_key!(table.id);
+    let mut rows_iter = self
+      .kv
+      .scan_with_prefix(KeyValueGroup::Rows, &table_row_prefix)?;
+    let mut row_count = 0;
+    while let Some((row_key, row_bytes)) = rows_iter.get() {
+      let row_id = &row_key[table_row_prefix.len()..];
+      // Restore the stripped vectors so that the keys match the keys of
+      // the index rows
+      let row = self.deserialize_owned_row(table, row_id, row_bytes)?;
+      for (index, collector) in indexes.iter().zip(collectors.iter_mut()) {
+        let json_key;
+        let key = match index.json_path() {
+          Some(path) => {
+            json_key =
+              get_json_path_index_key(row[index.columns()[0]].json(), path);
+            vec![&json_key]
+          }
+          None => row.project(&index.columns()),
+        };
+        let key_bytes = self
+          .serializer
+          .serialize::<Vec<&OwnedSerializedCell>>(&key)?;
+        collector.add(&key, &key_bytes);
+      }
+      row_count += 1;
+      rows_iter.next();
+    }
+
+    Ok(TableStatistics {
+      row_count,
+      indexes: collectors
+        .into_iter()
+        .map(|collector| collector.finish())
+        .collect(),
+    })
+  }
+}
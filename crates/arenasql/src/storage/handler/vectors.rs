@@ -0,0 +1,352 @@
+use std::sync::Arc;
+
+use super::StorageHandler;
+use crate::schema::{
+  IndexProvider, OwnedRow, OwnedSerializedCell, RowId, RowTrait,
+  SerializedCell, Table, TableIndex, TableIndexId,
+};
+use crate::storage::KeyValueGroup;
+use crate::vectors::{
+  EntryPoint, HnswGraph, HnswGraphStore, HnswNode, HnswParams, HnswVector,
+  NodeId, Score,
+};
+use crate::{index_row_key, Error, Result};
+
+/// Key of the entry point of the HNSW graph; prefixed by the namespace
+const HNSW_ENTRY_POINT_KEY: &[u8] = "e".as_bytes();
+/// Prefix of the HNSW graph nodes; node key = namespace + prefix + row id
+const HNSW_NODE_KEY_PREFIX: &[u8] = "n".as_bytes();
+
+impl StorageHandler {
+  pub fn add_row_to_vector_index(
+    &self,
+    table_index: &TableIndex,
+    row_id_bytes: &[u8],
+    row: &OwnedRow,
+  ) -> Result<()> {
+    let vector = match &row[table_index.columns()[0]] {
+      OwnedSerializedCell::Vector(vector) => {
+        HnswVector::Float32(vector.as_ref().clone())
+      }
+      OwnedSerializedCell::QuantizedVector(vector) => {
+        HnswVector::Quantized(vector.as_ref().clone())
+      }
+      // NULL vectors aren't indexed
+      OwnedSerializedCell::Null => return Ok(()),
+      cell => {
+        return Err(Error::InvalidDataType(format!(
+          "Expected vector but got {:?}",
+          cell
+        )))
+      }
+    };
+    // Rows with NULL namespace can never be searched, so don't index them
+    if let Some(namespace_column) = table_index.namespace_column() {
+      if row[namespace_column].is_null() {
+        return Ok(());
+      }
+    }
+
+    let namespace = self.serialize_vector_index_namespace(table_index, row)?;
+    let store = HnswIndexStore::new(self, table_index.id, &namespace);
+    HnswGraph::new(&store, hnsw_params(table_index)?)
+      .insert(RowId::deserialize(row_id_bytes).value(), vector)
+  }
+
+  pub fn delete_row_from_vector_index<'a, O, S>(
+    &self,
+    table_index: &TableIndex,
+    row_id_bytes: &[u8],
+    row: &'a S,
+  ) -> Result<()>
+  where
+    O: serde::Serialize + 'a,
+    S: ?Sized + serde::Serialize + RowTrait<'a, O>,
+  {
+    let namespace = self.serialize_vector_index_namespace(table_index, row)?;
+    let store = HnswIndexStore::new(self, table_index.id, &namespace);
+    HnswGraph::new(&store, hnsw_params(table_index)?)
+      .delete(RowId::deserialize(row_id_bytes).value())
+  }
+
+  /// Returns the serialized row ids of (approximate) k nearest vectors
+  /// to the query along with the distance, sorted by the distance.
+  ///
+  /// If the index is partitioned by a namespace column, only the vectors
+  /// in the given namespace are searched.
+  pub fn search_vector_index(
+    &self,
+    table_index: &TableIndex,
+    namespace: Option<&OwnedSerializedCell>,
+    query: &[f32],
+    k: usize,
+  ) -> Result<Vec<(Score, Vec<u8>)>> {
+    let namespace = match (table_index.namespace_column(), namespace) {
+      (Some(_), Some(namespace)) => self.serializer.serialize(namespace)?,
+      (None, None) => vec![],
+      _ => {
+        return Err(Error::InternalError(format!(
+          "Namespace must be provided to search index \"{}\" iff the index \
+          is partitioned by namespace",
+          table_index.name
+        )))
+      }
+    };
+    let store = HnswIndexStore::new(self, table_index.id, &namespace);
+    Ok(
+      HnswGraph::new(&store, hnsw_params(table_index)?)
+        .search(query, k)?
+        .into_iter()
+        .map(|(score, id)| (score, RowId::serialize_u64(id)))
+        .collect(),
+    )
+  }
+
+  /// Returns a copy of the row without the vectors that are stored in the
+  /// vector indexes that don't retain vectors. Returns `None` if nothing
+  /// needs to be removed from the row.
+  ///
+  /// Note: the row must be added to the indexes before storing the
+  /// stripped row
+  pub(super) fn strip_indexed_vectors(
+    &self,
+    table: &Table,
+    row: &OwnedRow,
+  ) -> Option<OwnedRow> {
+    let mut stripped_row: Option<OwnedRow> = None;
+    for index in table.indexes.iter().filter(|i| !i.retains_vectors()) {
+      let column = index.columns()[0];
+      // Only the rows that were added to the index can be stripped
+      let is_indexed = match &row[column] {
+        OwnedSerializedCell::Vector(_)
+        | OwnedSerializedCell::QuantizedVector(_) => index
+          .namespace_column()
+          .map(|namespace_column| !row[namespace_column].is_null())
+          .unwrap_or(true),
+        _ => false,
+      };
+      if is_indexed {
+        stripped_row.get_or_insert_with(|| row.clone())[column] =
+          OwnedSerializedCell::Null;
+      }
+    }
+    stripped_row
+  }
+
+  /// Puts back the vectors that were removed from the row data when the
+  /// row was stored, using the vector indexes that don't retain vectors
+  pub(super) fn restore_stripped_vectors<C: VectorCell>(
+    &self,
+    table: &Table,
+    row_id_bytes: &[u8],
+    row: &mut Vec<C>,
+  ) -> Result<()> {
+    for index in table.indexes.iter().filter(|i| !i.retains_vectors()) {
+      let column = index.columns()[0];
+      if !row[column].is_null() {
+        continue;
+      }
+      let namespace = match index.namespace_column() {
+        Some(namespace_column) if row[namespace_column].is_null() => continue,
+        Some(namespace_column) => {
+          self.serializer.serialize(&row[namespace_column])?
+        }
+        None => vec![],
+      };
+      let store = HnswIndexStore::new(self, index.id, &namespace);
+      // If the node doesn't exist, the vector was NULL
+      if let Some(node) =
+        store.get_node(RowId::deserialize(row_id_bytes).value())?
+      {
+        row[column] = C::vector(node.vector);
+      }
+    }
+    Ok(())
+  }
+
+  /// Returns the key prefix of the vector index partition the row belongs
+  /// to; empty if the index isn't partitioned by namespace
+  fn serialize_vector_index_namespace<'a, O, S>(
+    &self,
+    table_index: &TableIndex,
+    row: &'a S,
+  ) -> Result<Vec<u8>>
+  where
+    O: serde::Serialize + 'a,
+    S: ?Sized + RowTrait<'a, O>,
+  {
+    match table_index.namespace_column() {
+      Some(column) => self.serializer.serialize(row.project(&[column])[0]),
+      None => Ok(vec![]),
+    }
+  }
+}
+
+/// Cell that can hold a vector restored from the vector index
+pub(super) trait VectorCell: serde::Serialize {
+  fn is_null(&self) -> bool;
+
+  fn vector(vector: HnswVector) -> Self;
+}
+
+impl<'a> VectorCell for SerializedCell<'a> {
+  #[inline]
+  fn is_null(&self) -> bool {
+    SerializedCell::is_null(self)
+  }
+
+  #[inline]
+  fn vector(vector: HnswVector) -> Self {
+    match vector {
+      HnswVector::Float32(vector) => Self::Vector(Arc::new(vector)),
+      HnswVector::Quantized(vector) => Self::QuantizedVector(Arc::new(vector)),
+    }
+  }
+}
+
+impl VectorCell for OwnedSerializedCell {
+  #[inline]
+  fn is_null(&self) -> bool {
+    OwnedSerializedCell::is_null(self)
+  }
+
+  #[inline]
+  fn vector(vector: HnswVector) -> Self {
+    match vector {
+      HnswVector::Float32(vector) => Self::Vector(Arc::new(vector)),
+      HnswVector::Quantized(vector) => Self::QuantizedVector(Arc::new(vector)),
+    }
+  }
+}
+
+fn hnsw_params(table_index: &TableIndex) -> Result<HnswParams> {
+  match &table_index.provider {
+    IndexProvider::HNSWIndex {
+      metric,
+      m,
+      ef_construction,
+      ef,
+      rerank,
+      ..
+    } => Ok(HnswParams {
+      metric: metric.clone(),
+      m: *m,
+      ef_construction: *ef_construction,
+      ef: *ef,
+      rerank: *rerank,
+    }),
+    _ => Err(Error::InternalError(format!(
+      "Index \"{}\" is not a vector index",
+      table_index.name
+    ))),
+  }
+}
+
+/// Stores the HNSW graph in the `IndexRows` key group under
+/// the index prefix. If the index is partitioned by a namespace column,
+/// each namespace has a separate graph with keys prefixed by the
+/// serialized namespace value.
+struct HnswIndexStore<'a> {
+  storage: &'a StorageHandler,
+  index_id: TableIndexId,
+  namespace: &'a [u8],
+}
+
+impl<'a> HnswIndexStore<'a> {
+  fn new(
+    storage: &'a StorageHandler,
+    index_id: TableIndexId,
+    namespace: &'a [u8],
+  ) -> Self {
+    Self {
+      storage,
+      index_id,
+      namespace,
+    }
+  }
+
+  #[inline]
+  fn entry_point_key(&self) -> Vec<u8> {
+    index_row_key!(
+      self.index_id,
+      &[self.namespace, HNSW_ENTRY_POINT_KEY].concat()
+    )
+  }
+
+  #[inline]
+  fn nodes_prefix(&self) -> Vec<u8> {
+    index_row_key!(
+      self.index_id,
+      &[self.namespace, HNSW_NODE_KEY_PREFIX].concat()
+    )
+  }
+
+  #[inline]
+  fn node_key(&self, id: NodeId) -> Vec<u8> {
+    [self.nodes_prefix(), RowId::serialize_u64(id)].concat()
+  }
+}
+
+impl<'a> HnswGraphStore for HnswIndexStore<'a> {
+  fn get_entry_point(&self) -> Result<Option<EntryPoint>> {
+    self
+      .storage
+      .kv
+      .get(KeyValueGroup::IndexRows, &self.entry_point_key())?
+      .map(|bytes| self.storage.serializer.deserialize::<EntryPoint>(&bytes))
+      .transpose()
+  }
+
+  fn put_entry_point(&self, entry_point: Option<&EntryPoint>) -> Result<()> {
+    let key = self.entry_point_key();
+    match entry_point {
+      Some(entry_point) => self.storage.kv.put(
+        KeyValueGroup::IndexRows,
+        &key,
+        &self.storage.serializer.serialize(entry_point)?,
+      ),
+      None => self.storage.kv.delete(KeyValueGroup::IndexRows, &key),
+    }
+  }
+
+  fn get_node(&self, id: NodeId) -> Result<Option<HnswNode>> {
+    self
+      .storage
+      .kv
+      .get(KeyValueGroup::IndexRows, &self.node_key(id))?
+      .map(|bytes| self.storage.serializer.deserialize::<HnswNode>(&bytes))
+      .transpose()
+  }
+
+  fn put_node(&self, id: NodeId, node: &HnswNode) -> Result<()> {
+    self.storage.kv.put(
+      KeyValueGroup::IndexRows,
+      &self.node_key(id),
+      &self.storage.serializer.serialize(node)?,
+    )
+  }
+
+  fn delete_node(&self, id: NodeId) -> Result<()> {
+    self
+      .storage
+      .kv
+      .delete(KeyValueGroup::IndexRows, &self.node_key(id))
+  }
+
+  fn find_any_node(&self) -> Result<Option<(NodeId, HnswNode)>> {
+    let prefix = self.nodes_prefix();
+    let iter = self
+      .storage
+      .kv
+      .scan_with_prefix(KeyValueGroup::IndexRows, &prefix)?;
+    iter
+      .get()
+      .map(|(key, bytes)| {
+        Ok((
+          RowId::deserialize(&key[prefix.len()..]).value(),
+          self.storage.serializer.deserialize::<HnswNode>(bytes)?,
+        ))
+      })
+      .transpose()
+  }
+}
@@ -84,6 +84,18 @@ pub trait KeyValueStore {
     unimplemented!()
   }
 
+  /// Same as [`KeyValueStore::scan_with_prefix`] but the iterator starts
+  /// from the first key with the prefix that's greater than or equal to
+  /// the `start` key
+  fn scan_with_prefix_from(
+    &self,
+    _group: KeyValueGroup,
+    _prefix: &[u8],
+    _start: &[u8],
+  ) -> Result<Box<dyn KeyValueIterator>> {
+    unimplemented!()
+  }
+
   fn put(&self, group: KeyValueGroup, key: &[u8], value: &[u8]) -> Result<()> {
     self.put_all(group, &vec![(key, value)])
   }
@@ -96,6 +108,15 @@ pub trait KeyValueStore {
 
   fn delete(&self, group: KeyValueGroup, key: &[u8]) -> Result<()>;
 
+  /// Sets a savepoint in the transaction. Savepoints are stacked; so,
+  /// [`KeyValueStore::rollback_to_savepoint`] undoes the changes made
+  /// after the savepoint that was set last
+  fn set_savepoint(&self) -> Result<()>;
+
+  /// Undoes the changes made after the last savepoint and removes the
+  /// savepoint. Returns error if there's no savepoint
+  fn rollback_to_savepoint(&self) -> Result<()>;
+
   fn commit(&self) -> Result<()>;
 
   fn rollback(&self) -> Result<()>;
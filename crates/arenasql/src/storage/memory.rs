@@ -1,10 +1,15 @@
+use std::collections::HashMap;
+
 use dashmap::DashMap;
 use derive_new::new;
+use parking_lot::Mutex;
 
 use super::{
   KeyValueGroup, KeyValueIterator, KeyValueStore, KeyValueStoreProvider,
 };
-use crate::Result;
+use crate::{Error, Result};
+
+type Key = (KeyValueGroup, Vec<u8>);
 
 pub struct MemoryKeyValueStoreProvider {}
 
@@ -27,7 +32,12 @@ impl KeyValueStoreProvider for MemoryKeyValueStoreProvider {
 #[derive(new)]
 pub struct MemoryKeyValueStore {
   #[new(default)]
-  map: DashMap<(KeyValueGroup, Vec<u8>), Vec<u8>>,
+  map: DashMap<Key, Vec<u8>>,
+  /// A layer for each savepoint with the values of the keys before they
+  /// were first modified after the savepoint; `None` if the key didn't
+  /// exist
+  #[new(default)]
+  savepoints: Mutex<Vec<HashMap<Key, Option<Vec<u8>>>>>,
 }
 
 impl KeyValueStore for MemoryKeyValueStore {
@@ -70,13 +80,29 @@ impl KeyValueStore for MemoryKeyValueStore {
     Ok(Box::new(EmptyIterator {}))
   }
 
+  fn scan_with_prefix_from(
+    &self,
+    _group: super::KeyValueGroup,
+    _prefix: &[u8],
+    _start: &[u8],
+  ) -> Result<Box<dyn super::KeyValueIterator>> {
+    Ok(Box::new(EmptyIterator {}))
+  }
+
   fn put(
     &self,
     group: super::KeyValueGroup,
     key: &[u8],
     value: &[u8],
   ) -> Result<()> {
-    self.map.insert((group, key.to_vec()), value.to_vec());
+    let key = (group, key.to_vec());
+    if let Some(layer) = self.savepoints.lock().last_mut() {
+      if !layer.contains_key(&key) {
+        layer
+          .insert(key.clone(), self.map.get(&key).map(|v| v.value().clone()));
+      }
+    }
+    self.map.insert(key, value.to_vec());
     Ok(())
   }
 
@@ -92,6 +118,24 @@ impl KeyValueStore for MemoryKeyValueStore {
     unimplemented!()
   }
 
+  fn set_savepoint(&self) -> Result<()> {
+    self.savepoints.lock().push(HashMap::new());
+    Ok(())
+  }
+
+  fn rollback_to_savepoint(&self) -> Result<()> {
+    let layer = self.savepoints.lock().pop().ok_or_else(|| {
+      Error::InvalidTransactionState("No savepoint to rollback to".to_owned())
+    })?;
+    for (key, value) in layer {
+      match value {
+        Some(value) => self.map.insert(key, value),
+        None => self.map.remove(&key).map(|(_, value)| value),
+      };
+    }
+    Ok(())
+  }
+
   fn commit(&self) -> Result<()> {
     Ok(())
   }
@@ -33,6 +33,20 @@ macro_rules! last_row_id_of_table_key {
   };
 }
 
+#[macro_export]
+macro_rules! row_count_of_table_key {
+  ($table_id:expr) => {
+    format!("m_t{}_row_count", $table_id).into_bytes()
+  };
+}
+
+#[macro_export]
+macro_rules! table_statistics_key {
+  ($table_id:expr) => {
+    format!("m_t{}_statistics", $table_id).into_bytes()
+  };
+}
+
 #[macro_export]
 macro_rules! table_schemas_prefix_key {
   ($catalog:expr, $schema:expr) => {
@@ -47,6 +61,28 @@ macro_rules! table_schema_key {
   };
 }
 
+#[macro_export]
+macro_rules! sequences_prefix_key {
+  ($catalog:expr, $schema:expr) => {
+    format!("m_schema_c{}_s{}_q", $catalog, $schema).as_bytes()
+  };
+}
+
+#[macro_export]
+macro_rules! sequence_key {
+  ($catalog:expr, $schema:expr, $sequence:expr) => {
+    format!("m_schema_c{}_s{}_q{}", $catalog, $schema, $sequence).as_bytes()
+  };
+}
+
+#[macro_export]
+macro_rules! sequence_value_key {
+  ($catalog:expr, $schema:expr, $sequence:expr) => {
+    format!("m_c{}_s{}_q{}_last_value", $catalog, $schema, $sequence)
+      .into_bytes()
+  };
+}
+
 #[macro_export]
 macro_rules! index_rows_prefix_key {
   ($index_id:expr) => {
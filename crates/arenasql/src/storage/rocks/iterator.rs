@@ -18,6 +18,8 @@ impl<'a> PrefixIterator<'a> {
     txn: &UnsafeCell<Option<RocksTransaction<'static, RocksDatabase>>>,
     cf: &Arc<BoundColumnFamily<'static>>,
     prefix: Vec<u8>,
+    // key to start the iteration from
+    start: &[u8],
   ) -> Self {
     let txn = unsafe { txn.get().as_ref() }
       .as_ref()
@@ -32,7 +34,7 @@ impl<'a> PrefixIterator<'a> {
     // TODO: pass this as option
     opts.fill_cache(true);
     let mut rocks_iter = txn.raw_iterator_cf_opt(cf, opts);
-    rocks_iter.seek(&prefix);
+    rocks_iter.seek(start);
 
     let mut iter = Self {
       prefix,
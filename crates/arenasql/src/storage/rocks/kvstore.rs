@@ -119,6 +119,21 @@ impl crate::storage::KeyValueStore for KeyValueStore {
       &self.transaction,
       &self.cfs[group as usize],
       prefix.to_vec(),
+      prefix,
+    )))
+  }
+
+  fn scan_with_prefix_from(
+    &self,
+    group: KeyValueGroup,
+    prefix: &[u8],
+    start: &[u8],
+  ) -> DatabaseResult<Box<dyn KeyValueIterator>> {
+    Ok(Box::new(RocksRawIterator::new(
+      &self.transaction,
+      &self.cfs[group as usize],
+      prefix.to_vec(),
+      start,
     )))
   }
 
@@ -153,6 +168,15 @@ impl crate::storage::KeyValueStore for KeyValueStore {
     Ok(txn.delete_cf(group_cf, key)?)
   }
 
+  fn set_savepoint(&self) -> DatabaseResult<()> {
+    self.get_txn().set_savepoint();
+    Ok(())
+  }
+
+  fn rollback_to_savepoint(&self) -> DatabaseResult<()> {
+    Ok(self.get_txn().rollback_to_savepoint()?)
+  }
+
   /// Once a rocksdb transaction is committed, it shouldn't be used
   /// again. If used again, it will panic
   fn commit(&self) -> DatabaseResult<()> {
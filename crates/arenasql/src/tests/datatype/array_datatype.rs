@@ -0,0 +1,138 @@
+use datafusion::arrow::array::{
+  as_generic_list_array, as_primitive_array, as_string_array,
+};
+use datafusion::arrow::datatypes::{Int32Type, Int64Type};
+
+use crate::execute_query;
+use crate::tests::create_session_context;
+
+#[tokio::test(flavor = "multi_thread")]
+async fn array_datatype_test_insert_and_select() {
+  let session = create_session_context();
+  let txn = session.new_active_transaction().unwrap();
+
+  execute_query!(
+    txn,
+    r#"CREATE TABLE posts (
+      id INT,
+      tags TEXT[],
+      scores INT8[]
+    )"#
+  )
+  .unwrap();
+  execute_query!(
+    txn,
+    r#"INSERT INTO posts VALUES
+      (1, ARRAY['rust', 'db'], ARRAY[1, 2]),
+      (2, '{{"web app",NULL}}'::TEXT[], '{{3}}'::INT8[]),
+      (3, NULL, ARRAY[]::INT8[])"#
+  )
+  .unwrap();
+
+  let batch =
+    execute_query!(txn, r#"SELECT tags, scores FROM posts ORDER BY id"#)
+      .unwrap()
+      .collect_batches()
+      .await
+      .unwrap()
+      .pop()
+      .unwrap();
+  assert_eq!(batch.num_rows(), 3);
+
+  let tags = as_generic_list_array::<i32>(batch.column(0));
+  let first = tags.value(0);
+  let first = as_string_array(&first);
+  assert_eq!(
+    first.iter().collect::<Vec<_>>(),
+    vec![Some("rust"), Some("db")]
+  );
+  let second = tags.value(1);
+  let second = as_string_array(&second);
+  assert_eq!(
+    second.iter().collect::<Vec<_>>(),
+    vec![Some("web app"), None]
+  );
+  assert!(tags.is_null(2));
+
+  let scores = as_generic_list_array::<i32>(batch.column(1));
+  let first = scores.value(0);
+  assert_eq!(as_primitive_array::<Int64Type>(&first).values(), &[1, 2]);
+  assert!(!scores.is_null(2));
+  assert_eq!(scores.value(2).len(), 0);
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn array_datatype_test_array_operators() {
+  let session = create_session_context();
+  let txn = session.new_active_transaction().unwrap();
+
+  execute_query!(txn, r#"CREATE TABLE posts (id INT, tags TEXT[])"#).unwrap();
+  execute_query!(
+    txn,
+    r#"INSERT INTO posts VALUES
+      (1, ARRAY['rust', 'db']),
+      (2, ARRAY['rust', 'web']),
+      (3, ARRAY['go'])"#
+  )
+  .unwrap();
+
+  for (filter, expected) in [
+    ("tags @> ARRAY['rust', 'db']", 1),
+    ("tags && ARRAY['db', 'web']", 2),
+    ("'rust' = ANY(tags)", 2),
+    ("'rust' <> ALL(tags)", 1),
+  ] {
+    let count = txn
+      .execute_sql(&format!("SELECT count(*) FROM posts WHERE {}", filter))
+      .await
+      .unwrap()
+      .get_count()
+      .await
+      .unwrap();
+    assert_eq!(count, expected, "{}", filter);
+  }
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn array_datatype_test_unnest_and_array_agg() {
+  let session = create_session_context();
+  let txn = session.new_active_transaction().unwrap();
+
+  execute_query!(txn, r#"CREATE TABLE posts (id INT, tags TEXT[])"#).unwrap();
+  execute_query!(
+    txn,
+    r#"INSERT INTO posts VALUES
+      (1, ARRAY['rust', 'db']),
+      (2, ARRAY['web']),
+      (3, NULL)"#
+  )
+  .unwrap();
+
+  let batch = execute_query!(
+    txn,
+    r#"SELECT unnest(tags) AS tag FROM posts ORDER BY tag"#
+  )
+  .unwrap()
+  .collect_batches()
+  .await
+  .unwrap()
+  .pop()
+  .unwrap();
+  assert_eq!(
+    as_string_array(batch.column(0)).iter().collect::<Vec<_>>(),
+    vec![Some("db"), Some("rust"), Some("web")]
+  );
+
+  let batch =
+    execute_query!(txn, r#"SELECT array_agg(id) FROM posts WHERE id < 3"#)
+      .unwrap()
+      .collect_batches()
+      .await
+      .unwrap()
+      .pop()
+      .unwrap();
+  let ids = as_generic_list_array::<i32>(batch.column(0)).value(0);
+  let mut ids = as_primitive_array::<Int32Type>(&ids).values().to_vec();
+  ids.sort();
+  assert_eq!(ids, vec![1, 2]);
+}
@@ -0,0 +1,64 @@
+use datafusion::arrow::array::as_string_array;
+use datafusion::arrow::compute::cast;
+use datafusion::arrow::datatypes::DataType;
+
+use crate::execute_query;
+use crate::tests::create_session_context;
+
+#[tokio::test(flavor = "multi_thread")]
+async fn datetime_datatype_test_insert_and_select() {
+  let session = create_session_context();
+  let txn = session.new_active_transaction().unwrap();
+
+  execute_query!(
+    txn,
+    r#"CREATE TABLE events (
+      day DATE,
+      starts_at TIME,
+      created_at TIMESTAMPTZ,
+      duration INTERVAL
+    )"#
+  )
+  .unwrap();
+  execute_query!(
+    txn,
+    r#"INSERT INTO events VALUES
+      ('2024-02-29', '10:30:00', '2024-02-29T10:30:00+05:00', '1 day 2 hours'),
+      ('2023-12-31', '08:00:00', '2023-12-31T08:00:00Z', '30 minutes')"#
+  )
+  .unwrap();
+
+  let batch = execute_query!(
+    txn,
+    r#"SELECT day, starts_at, created_at FROM events
+      WHERE day > '2024-01-01'"#
+  )
+  .unwrap()
+  .collect_batches()
+  .await
+  .unwrap()
+  .pop()
+  .unwrap();
+  assert_eq!(batch.num_rows(), 1);
+
+  let values = batch
+    .columns()
+    .iter()
+    .map(|column| {
+      let column = cast(column, &DataType::Utf8).unwrap();
+      as_string_array(&column).value(0).to_owned()
+    })
+    .collect::<Vec<String>>();
+  assert_eq!(
+    values,
+    vec!["2024-02-29", "10:30:00", "2024-02-29T05:30:00Z"],
+    "TIMESTAMPTZ should be stored in UTC"
+  );
+
+  let res = execute_query!(
+    txn,
+    r#"SELECT count(*) FROM events WHERE duration = INTERVAL '30 minutes'"#
+  )
+  .unwrap();
+  assert_eq!(res.get_count().await.unwrap(), 1);
+}
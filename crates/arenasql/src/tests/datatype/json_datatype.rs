@@ -1,3 +1,6 @@
+use datafusion::arrow::array::{as_primitive_array, as_string_array};
+use datafusion::arrow::datatypes::Int32Type;
+
 use crate::execute_query;
 use crate::tests::create_session_context;
 
@@ -30,3 +33,176 @@ async fn crate_table_with_json_column() {
     "Number of rows didn't match"
   );
 }
+
+#[tokio::test(flavor = "multi_thread")]
+async fn json_datatype_test_operators_and_functions() {
+  let session = create_session_context();
+  let txn = session.new_active_transaction().unwrap();
+
+  execute_query!(txn, r#"CREATE TABLE events (id INT, data JSONB)"#).unwrap();
+  execute_query!(
+    txn,
+    r#"INSERT INTO events VALUES
+      (1, '{{"kind": "click", "tags": ["a", "b"], "meta": {{"x": 1}}}}'),
+      (2, '{{"kind": "view", "tags": ["b"], "meta": {{"x": 2}}}}'),
+      (3, NULL)"#
+  )
+  .unwrap();
+
+  let batch = execute_query!(
+    txn,
+    r#"SELECT data->>'kind', data->'meta', data#>>'{{tags,0}}',
+      data->'tags'->>1
+      FROM events ORDER BY id"#
+  )
+  .unwrap()
+  .collect_batches()
+  .await
+  .unwrap()
+  .pop()
+  .unwrap();
+  let column = |idx| as_string_array(batch.column(idx)).iter().collect();
+  let kind: Vec<_> = column(0);
+  assert_eq!(kind, vec![Some("click"), Some("view"), None]);
+  let meta: Vec<_> = column(1);
+  assert_eq!(meta, vec![Some(r#"{"x":1}"#), Some(r#"{"x":2}"#), None]);
+  let first_tag: Vec<_> = column(2);
+  assert_eq!(first_tag, vec![Some("a"), Some("b"), None]);
+  let second_tag: Vec<_> = column(3);
+  assert_eq!(second_tag, vec![Some("b"), None, None]);
+
+  for (filter, expected) in [
+    ("data->>'kind' = 'click' AND id > 0", vec![1]),
+    (r#"data @> '{"tags": ["b"]}'::jsonb"#, vec![1, 2]),
+    (r#"'{"kind": "view"}' <@ data"#, vec![2]),
+    ("data->'meta' ? 'x' AND data->'tags' ? 'a'", vec![1]),
+    ("data ?| ARRAY['kind', 'y']", vec![1, 2]),
+    ("data ?& ARRAY['kind', 'y']", vec![]),
+  ] {
+    let batch = txn
+      .execute_sql(&format!(
+        "SELECT id FROM events WHERE {} ORDER BY id",
+        filter
+      ))
+      .await
+      .unwrap()
+      .collect_batches()
+      .await
+      .unwrap();
+    let ids: Vec<i32> = batch
+      .iter()
+      .flat_map(|b| as_primitive_array::<Int32Type>(b.column(0)).values())
+      .copied()
+      .collect();
+    assert_eq!(ids, expected, "{}", filter);
+  }
+
+  execute_query!(
+    txn,
+    r#"UPDATE events
+      SET data = jsonb_set(data, '{{meta,y}}', '"new"')
+      WHERE id = 1"#
+  )
+  .unwrap();
+  let batch =
+    execute_query!(txn, r#"SELECT data->'meta' FROM events WHERE id = 1"#)
+      .unwrap()
+      .collect_batches()
+      .await
+      .unwrap()
+      .pop()
+      .unwrap();
+  assert_eq!(
+    as_string_array(batch.column(0)).value(0),
+    r#"{"x":1,"y":"new"}"#
+  );
+
+  let batch = execute_query!(
+    txn,
+    r#"SELECT jsonb_array_elements_text(data->'tags') FROM events"#
+  )
+  .unwrap()
+  .collect_batches()
+  .await
+  .unwrap();
+  assert_eq!(batch[0].schema().field(0).name(), "value");
+  let mut tags: Vec<&str> = batch
+    .iter()
+    .flat_map(|b| as_string_array(b.column(0)).iter().flatten())
+    .collect();
+  tags.sort();
+  assert_eq!(tags, vec!["a", "b", "b"]);
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn json_datatype_test_json_path_index() {
+  let session = create_session_context();
+  let txn = session.new_active_transaction().unwrap();
+
+  execute_query!(txn, r#"CREATE TABLE users (id INT, profile JSONB)"#).unwrap();
+  execute_query!(
+    txn,
+    r#"INSERT INTO users VALUES
+      (1, '{{"email": "a@x.com", "team": {{"name": "db"}}}}'),
+      (2, '{{"email": "b@x.com", "team": {{"name": "web"}}}}'),
+      (3, '{{"team": {{"name": "db"}}}}')"#
+  )
+  .unwrap();
+  execute_query!(
+    txn,
+    r#"CREATE UNIQUE INDEX users_email ON users ((profile->>'email'))"#
+  )
+  .unwrap();
+  execute_query!(
+    txn,
+    r#"CREATE INDEX users_team ON users ((profile#>>'{{team,name}}'))"#
+  )
+  .unwrap();
+
+  // Rows without email aren't unique constrained
+  execute_query!(txn, r#"INSERT INTO users VALUES (4, '{{}}')"#).unwrap();
+  assert!(execute_query!(
+    txn,
+    r#"INSERT INTO users VALUES (5, '{{"email": "a@x.com"}}')"#
+  )
+  .is_err());
+
+  execute_query!(
+    txn,
+    r#"UPDATE users SET profile = '{{"email": "c@x.com"}}' WHERE id = 2"#
+  )
+  .unwrap();
+  execute_query!(
+    txn,
+    r#"INSERT INTO users VALUES (5, '{{"email": "b@x.com"}}')"#
+  )
+  .unwrap();
+
+  for (filter, expected) in [
+    ("profile->>'email' = 'b@x.com'", vec![5]),
+    ("profile->>'email' = 'c@x.com'", vec![2]),
+    ("profile->'team'->>'name' = 'db'", vec![1, 3]),
+    ("profile->'team'->>'name' = 'web'", vec![]),
+  ] {
+    let batch = txn
+      .execute_sql(&format!(
+        "SELECT id FROM users WHERE {} ORDER BY id",
+        filter
+      ))
+      .await
+      .unwrap()
+      .collect_batches()
+      .await
+      .unwrap();
+    let ids: Vec<i32> = batch
+      .iter()
+      .flat_map(|b| as_primitive_array::<Int32Type>(b.column(0)).values())
+      .copied()
+      .collect();
+    assert_eq!(ids, expected, "{}", filter);
+  }
+
+  // JSON path index must be on a JSONB column
+  let res = execute_query!(txn, r#"CREATE INDEX ON users ((id->>'a'))"#);
+  assert!(res.is_err());
+}
@@ -1,2 +1,6 @@
+mod array_datatype;
+mod datetime_datatype;
 mod file_datatype;
 mod json_datatype;
+mod numeric_datatype;
+mod uuid_datatype;
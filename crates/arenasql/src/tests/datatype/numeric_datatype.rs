@@ -0,0 +1,82 @@
+use datafusion::arrow::array::as_string_array;
+use datafusion::arrow::compute::cast;
+use datafusion::arrow::datatypes::DataType;
+
+use crate::execute_query;
+use crate::tests::create_session_context;
+
+#[tokio::test(flavor = "multi_thread")]
+async fn numeric_datatype_test_insert_and_select() {
+  let session = create_session_context();
+  let txn = session.new_active_transaction().unwrap();
+
+  execute_query!(
+    txn,
+    r#"CREATE TABLE prices (
+      price NUMERIC(10, 2)
+    )"#
+  )
+  .unwrap();
+  execute_query!(
+    txn,
+    r#"INSERT INTO prices VALUES (10.5), (-0.25), (12345678.99)"#
+  )
+  .unwrap();
+
+  let batch = execute_query!(txn, r#"SELECT price FROM prices ORDER BY price"#)
+    .unwrap()
+    .collect_batches()
+    .await
+    .unwrap()
+    .pop()
+    .unwrap();
+  assert_eq!(batch.column(0).data_type(), &DataType::Decimal128(10, 2));
+
+  let prices = cast(batch.column(0), &DataType::Utf8).unwrap();
+  assert_eq!(
+    as_string_array(&prices)
+      .iter()
+      .flatten()
+      .collect::<Vec<&str>>(),
+    vec!["-0.25", "10.50", "12345678.99"]
+  );
+
+  let res = execute_query!(
+    txn,
+    r#"CREATE TABLE invalid_prices (price NUMERIC(40, 2))"#
+  );
+  assert!(res.is_err(), "NUMERIC precision can't be more than 38");
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn numeric_datatype_test_unconstrained_numeric_is_rounded() {
+  let session = create_session_context();
+  let txn = session.new_active_transaction().unwrap();
+
+  execute_query!(txn, r#"CREATE TABLE amounts (amount NUMERIC)"#).unwrap();
+  execute_query!(
+    txn,
+    r#"INSERT INTO amounts VALUES (0.12345678901234), (0.12345678906), (1)"#
+  )
+  .unwrap();
+
+  let batch =
+    execute_query!(txn, r#"SELECT amount FROM amounts ORDER BY amount"#)
+      .unwrap()
+      .collect_batches()
+      .await
+      .unwrap()
+      .pop()
+      .unwrap();
+  // Unconstrained NUMERIC is NUMERIC(38, 10)
+  assert_eq!(batch.column(0).data_type(), &DataType::Decimal128(38, 10));
+
+  let amounts = cast(batch.column(0), &DataType::Utf8).unwrap();
+  assert_eq!(
+    as_string_array(&amounts)
+      .iter()
+      .flatten()
+      .collect::<Vec<&str>>(),
+    vec!["0.1234567890", "0.1234567891", "1.0000000000"]
+  );
+}
@@ -0,0 +1,57 @@
+use datafusion::arrow::array::as_string_array;
+
+use crate::execute_query;
+use crate::tests::create_session_context;
+
+#[tokio::test(flavor = "multi_thread")]
+async fn uuid_datatype_test_insert_and_filter_using_index() {
+  let session = create_session_context();
+  let txn = session.new_active_transaction().unwrap();
+
+  execute_query!(
+    txn,
+    r#"CREATE TABLE users (
+      id UUID PRIMARY KEY,
+      name TEXT
+    )"#
+  )
+  .unwrap();
+  execute_query!(
+    txn,
+    r#"INSERT INTO users
+      VALUES('a0eebc99-9c0b-4ef8-bb6d-6bb9bd380a11', 'name 1'),
+      ('A0EEBC99-9C0B-4EF8-BB6D-6BB9BD380A12', 'name 2')"#
+  )
+  .unwrap();
+
+  let batch = execute_query!(
+    txn,
+    r#"SELECT id, name FROM users
+      WHERE id = 'a0eebc99-9c0b-4ef8-bb6d-6bb9bd380a12'"#
+  )
+  .unwrap()
+  .collect_batches()
+  .await
+  .unwrap()
+  .pop()
+  .unwrap();
+  assert_eq!(batch.num_rows(), 1);
+  // UUIDs are returned in lower case
+  assert_eq!(
+    as_string_array(batch.column(0)).value(0),
+    "a0eebc99-9c0b-4ef8-bb6d-6bb9bd380a12"
+  );
+  assert_eq!(as_string_array(batch.column(1)).value(0), "name 2");
+
+  let res = execute_query!(
+    txn,
+    r#"INSERT INTO users VALUES('a0eebc99-9c0b-4ef8-bb6d-6bb9bd380a12', 'x')"#
+  );
+  assert!(res.is_err(), "Expected unique constraint violation");
+
+  let res = execute_query!(txn, r#"INSERT INTO users VALUES('invalid', 'x')"#);
+  assert_eq!(
+    res.unwrap_err().message(),
+    r#"invalid input syntax for type uuid: "invalid""#
+  );
+}
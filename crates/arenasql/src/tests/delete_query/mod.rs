@@ -1,3 +1,5 @@
+use datafusion::arrow::array::as_string_array;
+
 use crate::execute_query;
 use crate::tests::create_session_context;
 
@@ -127,3 +129,41 @@ async fn delete_test_delete_using_two_columns_filter() {
     "Select query expected to return 2 rows"
   );
 }
+
+#[tokio::test(flavor = "multi_thread")]
+async fn delete_test_returning_deleted_rows() {
+  let session = create_session_context();
+  let txn = session.new_active_transaction().unwrap();
+
+  execute_query!(
+    txn,
+    r#"CREATE TABLE IF NOT EXISTS test_table (
+      id VARCHAR(50),
+      name TEXT
+    )"#
+  )
+  .unwrap();
+
+  execute_query!(
+    txn,
+    r#"INSERT INTO test_table(id, name)
+      VALUES('id_1', 'name 1'),
+      ('id_2', 'name'),
+      ('id_3', 'name 3')"#
+  )
+  .unwrap();
+
+  let res = execute_query!(
+    txn,
+    r#"DELETE FROM test_table where id = 'id_1' RETURNING name"#
+  )
+  .unwrap();
+  assert_eq!(res.get_modified_rows(), Some(1));
+
+  let batches = res.collect_batches().await.unwrap();
+  assert_eq!(batches[0].schema().field(0).name(), "name");
+  assert_eq!(as_string_array(batches[0].column(0)).value(0), "name 1");
+
+  let res = execute_query!(txn, r#"SELECT * FROM test_table"#).unwrap();
+  assert_eq!(res.num_rows().await.unwrap(), 2);
+}
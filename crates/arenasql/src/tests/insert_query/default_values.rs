@@ -0,0 +1,62 @@
+use crate::execute_query;
+use crate::tests::create_session_context;
+
+#[tokio::test(flavor = "multi_thread")]
+async fn insert_test_column_default_values() {
+  let session = create_session_context();
+  let txn = session.new_active_transaction().unwrap();
+
+  execute_query!(
+    txn,
+    r#"CREATE TABLE IF NOT EXISTS test_table (
+      id VARCHAR(50) DEFAULT gen_random_uuid(),
+      name TEXT DEFAULT 'unnamed',
+      age INTEGER DEFAULT 10 + 8,
+      created_at TIMESTAMP DEFAULT now()
+    )"#
+  )
+  .unwrap();
+
+  execute_query!(txn, r#"INSERT INTO test_table(age) VALUES(1), (2), (3)"#)
+    .unwrap();
+  execute_query!(txn, r#"INSERT INTO test_table(name) VALUES('name 1')"#)
+    .unwrap();
+
+  let res = execute_query!(
+    txn,
+    r#"SELECT count(DISTINCT id) FROM test_table WHERE created_at IS NOT NULL"#
+  )
+  .unwrap();
+  assert_eq!(
+    res.get_count().await.unwrap(),
+    4,
+    "Default expression should be evaluated for each row"
+  );
+
+  let res = execute_query!(
+    txn,
+    r#"SELECT count(*) FROM test_table WHERE name = 'unnamed'"#
+  )
+  .unwrap();
+  assert_eq!(res.get_count().await.unwrap(), 3);
+
+  let res =
+    execute_query!(txn, r#"SELECT count(*) FROM test_table WHERE age = 18"#)
+      .unwrap();
+  assert_eq!(res.get_count().await.unwrap(), 1);
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn insert_test_column_default_with_column_reference() {
+  let session = create_session_context();
+  let txn = session.new_active_transaction().unwrap();
+
+  let res = execute_query!(
+    txn,
+    r#"CREATE TABLE IF NOT EXISTS test_table (
+      id INTEGER,
+      age INTEGER DEFAULT id + 1
+    )"#
+  );
+  assert!(res.is_err());
+}
@@ -1 +1,4 @@
+mod default_values;
+mod returning;
 mod unique_rows;
+mod upsert;
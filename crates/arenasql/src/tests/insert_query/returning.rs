@@ -0,0 +1,47 @@
+use datafusion::arrow::array::{as_primitive_array, as_string_array};
+use datafusion::arrow::datatypes::Int32Type;
+
+use crate::execute_query;
+use crate::tests::create_session_context;
+
+#[tokio::test(flavor = "multi_thread")]
+async fn insert_test_returning_inserted_rows() {
+  let session = create_session_context();
+  let txn = session.new_active_transaction().unwrap();
+
+  execute_query!(
+    txn,
+    r#"CREATE TABLE test_table (id SERIAL PRIMARY KEY, name TEXT)"#
+  )
+  .unwrap();
+
+  let res = execute_query!(
+    txn,
+    r#"INSERT INTO test_table(name) VALUES('name 1'), ('name 2')
+      RETURNING id, upper(name) AS upper_name"#
+  )
+  .unwrap();
+  assert_eq!(res.get_modified_rows(), Some(2));
+
+  let batches = res.collect_batches().await.unwrap();
+  let schema = batches[0].schema();
+  assert_eq!(schema.field(0).name(), "id");
+  assert_eq!(schema.field(1).name(), "upper_name");
+  let ids = as_primitive_array::<Int32Type>(batches[0].column(0));
+  assert_eq!(ids.values().to_vec(), vec![1, 2]);
+  let names = as_string_array(batches[0].column(1));
+  assert_eq!(names.value(1), "NAME 2");
+
+  let res = execute_query!(
+    txn,
+    r#"INSERT INTO test_table VALUES(1, 'new name'), (3, 'name 3')
+      ON CONFLICT (id) DO UPDATE SET name = EXCLUDED.name
+      RETURNING *"#
+  )
+  .unwrap();
+  let batches = res.collect_batches().await.unwrap();
+  assert_eq!(batches[0].num_columns(), 2);
+  let names = as_string_array(batches[0].column(1));
+  assert_eq!(names.value(0), "new name");
+  assert_eq!(names.value(1), "name 3");
+}
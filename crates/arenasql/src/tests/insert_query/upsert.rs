@@ -0,0 +1,208 @@
+use datafusion::arrow::array::{as_primitive_array, as_string_array};
+use datafusion::arrow::datatypes::Int32Type;
+
+use crate::execute_query;
+use crate::tests::create_session_context;
+
+#[tokio::test(flavor = "multi_thread")]
+async fn upsert_test_on_conflict_do_nothing() {
+  let session = create_session_context();
+  let txn = session.new_active_transaction().unwrap();
+
+  execute_query!(
+    txn,
+    r#"CREATE TABLE test_table (
+      id VARCHAR(50) PRIMARY KEY,
+      email TEXT UNIQUE,
+      name TEXT
+    )"#
+  )
+  .unwrap();
+  execute_query!(
+    txn,
+    r#"INSERT INTO test_table VALUES('id1', 'a@x.com', 'name 1')"#
+  )
+  .unwrap();
+
+  let res = execute_query!(
+    txn,
+    r#"INSERT INTO test_table VALUES
+      ('id1', 'b@x.com', 'name 2'), ('id2', 'c@x.com', 'name 3')
+      ON CONFLICT (id) DO NOTHING"#
+  )
+  .unwrap();
+  assert_eq!(res.get_modified_rows(), Some(1));
+
+  // conflict on any unique index is ignored when target isn't specified
+  let res = execute_query!(
+    txn,
+    r#"INSERT INTO test_table VALUES('id3', 'a@x.com', 'name 4')
+      ON CONFLICT DO NOTHING"#
+  )
+  .unwrap();
+  assert_eq!(res.get_modified_rows(), Some(0));
+
+  // conflict on the index that isn't the target should fail
+  let res = execute_query!(
+    txn,
+    r#"INSERT INTO test_table VALUES('id3', 'a@x.com', 'name 4')
+      ON CONFLICT (id) DO NOTHING"#
+  );
+  assert!(res.is_err());
+
+  let res = execute_query!(
+    txn,
+    r#"SELECT count(*) FROM test_table WHERE name IN ('name 1', 'name 3')"#
+  )
+  .unwrap();
+  assert_eq!(res.get_count().await.unwrap(), 2);
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn upsert_test_on_conflict_do_update() {
+  let session = create_session_context();
+  let txn = session.new_active_transaction().unwrap();
+
+  execute_query!(
+    txn,
+    r#"CREATE TABLE test_table (
+      id INTEGER PRIMARY KEY,
+      name TEXT,
+      visits INTEGER CHECK (visits < 10)
+    )"#
+  )
+  .unwrap();
+  execute_query!(txn, r#"INSERT INTO test_table VALUES(1, 'name 1', 1)"#)
+    .unwrap();
+
+  let res = execute_query!(
+    txn,
+    r#"INSERT INTO test_table VALUES(1, 'new name', 1), (2, 'name 2', 1)
+      ON CONFLICT (id) DO UPDATE
+      SET name = EXCLUDED.name, visits = visits + EXCLUDED.visits"#
+  )
+  .unwrap();
+  assert_eq!(res.get_modified_rows(), Some(2));
+
+  let res = execute_query!(
+    txn,
+    r#"SELECT count(*) FROM test_table
+      WHERE id = 1 AND name = 'new name' AND visits = 2"#
+  )
+  .unwrap();
+  assert_eq!(res.get_count().await.unwrap(), 1);
+
+  // rows that don't match the condition aren't updated
+  let res = execute_query!(
+    txn,
+    r#"INSERT INTO test_table VALUES(2, 'name 3', 1)
+      ON CONFLICT (id) DO UPDATE SET name = EXCLUDED.name
+      WHERE test_table.visits > 1"#
+  )
+  .unwrap();
+  assert_eq!(res.get_modified_rows(), Some(0));
+
+  let res = execute_query!(
+    txn,
+    r#"SELECT count(*) FROM test_table WHERE name = 'name 2'"#
+  )
+  .unwrap();
+  assert_eq!(res.get_count().await.unwrap(), 1);
+
+  // updated row should be checked for the constraints
+  let res = execute_query!(
+    txn,
+    r#"INSERT INTO test_table VALUES(1, 'name 1', 1)
+      ON CONFLICT (id) DO UPDATE SET visits = 10"#
+  );
+  assert!(res.is_err());
+
+  // same row can't be updated twice by the same query
+  let res = execute_query!(
+    txn,
+    r#"INSERT INTO test_table VALUES(3, 'name 3', 1), (3, 'name 4', 1)
+      ON CONFLICT (id) DO UPDATE SET name = EXCLUDED.name"#
+  );
+  assert!(res.is_err());
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn upsert_test_on_conflict_do_update_returning() {
+  let session = create_session_context();
+  let txn = session.new_active_transaction().unwrap();
+
+  execute_query!(
+    txn,
+    r#"CREATE TABLE test_table (
+      id INTEGER PRIMARY KEY,
+      name TEXT,
+      visits INTEGER
+    )"#
+  )
+  .unwrap();
+  execute_query!(
+    txn,
+    r#"INSERT INTO test_table VALUES(1, 'name 1', 1), (3, 'name 3', 5)"#
+  )
+  .unwrap();
+
+  // Inserted and updated rows are returned in the order of the values;
+  // the row that doesn't match the condition isn't returned
+  let res = execute_query!(
+    txn,
+    r#"INSERT INTO test_table VALUES
+      (1, 'new name 1', 1), (2, 'name 2', 1), (3, 'new name 3', 1),
+      (4, 'name 4', 1)
+      ON CONFLICT (id) DO UPDATE
+      SET name = EXCLUDED.name, visits = visits + EXCLUDED.visits
+      WHERE test_table.visits < 5
+      RETURNING id, name, visits"#
+  )
+  .unwrap();
+  assert_eq!(res.get_modified_rows(), Some(3));
+
+  let batches = res.collect_batches().await.unwrap();
+  assert_eq!(batches.len(), 1);
+  let ids = as_primitive_array::<Int32Type>(batches[0].column(0));
+  assert_eq!(ids.values().to_vec(), vec![1, 2, 4]);
+  let names = as_string_array(batches[0].column(1))
+    .iter()
+    .map(|name| name.unwrap().to_owned())
+    .collect::<Vec<String>>();
+  assert_eq!(names, vec!["new name 1", "name 2", "name 4"]);
+  let visits = as_primitive_array::<Int32Type>(batches[0].column(2));
+  assert_eq!(visits.values().to_vec(), vec![2, 1, 1]);
+
+  let res = execute_query!(
+    txn,
+    r#"SELECT count(*) FROM test_table WHERE id = 3 AND name = 'name 3'"#
+  )
+  .unwrap();
+  assert_eq!(res.get_count().await.unwrap(), 1);
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn upsert_test_on_conflict_without_unique_index() {
+  let session = create_session_context();
+  let txn = session.new_active_transaction().unwrap();
+
+  execute_query!(
+    txn,
+    r#"CREATE TABLE test_table (id INTEGER PRIMARY KEY, name TEXT)"#
+  )
+  .unwrap();
+
+  let res = execute_query!(
+    txn,
+    r#"INSERT INTO test_table VALUES(1, 'name 1')
+      ON CONFLICT (name) DO NOTHING"#
+  );
+  assert!(res.is_err());
+
+  let res = execute_query!(
+    txn,
+    r#"INSERT INTO test_table VALUES(1, 'name 1')
+      ON CONFLICT DO UPDATE SET name = EXCLUDED.name"#
+  );
+  assert!(res.is_err());
+}
@@ -18,6 +18,7 @@ mod datatype;
 mod delete_query;
 mod drop_table;
 mod insert_query;
+mod pg_catalog;
 mod schema;
 mod select_query;
 mod statement;
@@ -0,0 +1,168 @@
+use datafusion::arrow::array::as_string_array;
+
+use crate::execution::SessionContext;
+use crate::tests::create_session_context;
+
+async fn create_users_table(session: &SessionContext) {
+  session
+    .execute_sql(
+      r#"CREATE TABLE users (
+      id INT PRIMARY KEY,
+      name VARCHAR(50) NOT NULL,
+      email TEXT
+    )"#,
+    )
+    .await
+    .unwrap();
+  session
+    .execute_sql(r#"CREATE UNIQUE INDEX users_email ON users(email)"#)
+    .await
+    .unwrap();
+}
+
+/// Returns the values of the first column of the query's result
+async fn query_strings(
+  session: &SessionContext,
+  query: &str,
+) -> Vec<Option<String>> {
+  session
+    .execute_sql(query)
+    .await
+    .unwrap()
+    .pop()
+    .unwrap()
+    .collect_batches()
+    .await
+    .unwrap()
+    .iter()
+    .flat_map(|batch| {
+      as_string_array(batch.column(0))
+        .iter()
+        .map(|value| value.map(|v| v.to_owned()))
+        .collect::<Vec<Option<String>>>()
+    })
+    .collect()
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn pg_catalog_test_pg_class_and_pg_attribute() {
+  let session = create_session_context();
+  create_users_table(&session).await;
+
+  let tables = query_strings(
+    &session,
+    r#"SELECT c.relname FROM pg_catalog.pg_class c
+      JOIN pg_catalog.pg_namespace n ON c.relnamespace = n.oid
+      WHERE n.nspname = 'public' AND c.relkind = 'r'"#,
+  )
+  .await;
+  assert_eq!(tables, vec![Some("users".to_owned())]);
+
+  let columns = query_strings(
+    &session,
+    r#"SELECT a.attname || ' '
+        || pg_catalog.format_type(a.atttypid, a.atttypmod)
+      FROM pg_catalog.pg_attribute a
+      JOIN pg_catalog.pg_class c ON a.attrelid = c.oid
+      WHERE c.relname = 'users' AND a.attnum > 0 AND NOT a.attisdropped
+      ORDER BY a.attnum"#,
+  )
+  .await;
+  assert_eq!(
+    columns,
+    vec![
+      Some("id integer".to_owned()),
+      Some("name character varying(50)".to_owned()),
+      Some("email text".to_owned()),
+    ]
+  );
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn pg_catalog_test_information_schema_columns() {
+  let session = create_session_context();
+  create_users_table(&session).await;
+
+  let tables = query_strings(
+    &session,
+    r#"SELECT table_name FROM information_schema.tables
+      WHERE table_schema = 'public'"#,
+  )
+  .await;
+  assert_eq!(tables, vec![Some("users".to_owned())]);
+
+  let columns = query_strings(
+    &session,
+    r#"SELECT column_name || ' ' || data_type || ' ' || is_nullable
+      FROM information_schema.columns
+      WHERE table_name = 'users' ORDER BY ordinal_position"#,
+  )
+  .await;
+  assert_eq!(
+    columns,
+    vec![
+      Some("id integer NO".to_owned()),
+      Some("name character varying NO".to_owned()),
+      Some("email text YES".to_owned()),
+    ]
+  );
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn pg_catalog_test_functions() {
+  let session = create_session_context();
+  create_users_table(&session).await;
+
+  assert_eq!(
+    query_strings(&session, "SELECT current_database()").await,
+    vec![Some("test".to_owned())]
+  );
+
+  let version = query_strings(&session, "SELECT version()").await;
+  assert!(version[0].as_ref().unwrap().starts_with("PostgreSQL"));
+
+  let definitions = query_strings(
+    &session,
+    r#"SELECT pg_get_indexdef(i.indexrelid) FROM pg_catalog.pg_index i
+      JOIN pg_catalog.pg_class c ON i.indexrelid = c.oid
+      WHERE c.relname = 'users_email'"#,
+  )
+  .await;
+  assert_eq!(
+    definitions,
+    vec![Some(
+      "CREATE UNIQUE INDEX users_email ON public.users USING btree (email)"
+        .to_owned()
+    )]
+  );
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn pg_catalog_test_index_access_methods() {
+  let session = create_session_context();
+  create_users_table(&session).await;
+  session
+    .execute_sql(
+      r#"CREATE TABLE vectors (id INT, embeddings VECTOR(4));
+      CREATE INDEX vectors_index ON vectors USING hnsw (embeddings)
+      WITH (metric = 'l2', m = 4, ef_construction = 16, ef = 8, dim = 4)"#,
+    )
+    .await
+    .unwrap();
+
+  let access_methods = query_strings(
+    &session,
+    r#"SELECT c.relname || ' ' || a.amname FROM pg_catalog.pg_class c
+      JOIN pg_catalog.pg_am a ON c.relam = a.oid
+      WHERE c.relname IN ('users_email', 'vectors_index')
+      ORDER BY c.relname"#,
+  )
+  .await;
+  assert_eq!(
+    access_methods,
+    vec![
+      Some("users_email btree".to_owned()),
+      Some("vectors_index hnsw".to_owned()),
+    ]
+  );
+}
@@ -103,3 +103,315 @@ async fn table_schema_alter_table_add_timestamp_column_on_empty_table() {
     .await;
   assert!(res.is_ok());
 }
+
+#[tokio::test(flavor = "multi_thread")]
+async fn table_schema_alter_table_drop_column() {
+  let session = create_session_context();
+
+  let _ = session
+    .execute_sql(
+      r#"CREATE TABLE IF NOT EXISTS test_table (
+        id VARCHAR(50),
+        name TEXT UNIQUE,
+        age INTEGER
+      )"#,
+    )
+    .await
+    .unwrap();
+  session
+    .execute_sql(r#"INSERT INTO test_table VALUES('id1', 'name 1', 10)"#)
+    .await
+    .unwrap();
+
+  let res = session
+    .execute_sql(r#"ALTER TABLE test_table DROP COLUMN name"#)
+    .await;
+  assert!(res.is_ok());
+
+  let res = session
+    .execute_sql(r#"SELECT * FROM test_table"#)
+    .await
+    .unwrap()
+    .pop()
+    .unwrap()
+    .collect_batches()
+    .await
+    .unwrap();
+  assert_eq!(
+    res[0].num_columns(),
+    2,
+    "Dropped column shouldn't be visible"
+  );
+  assert_eq!(res[0].num_rows(), 1);
+
+  let res = session.execute_sql(r#"SELECT name FROM test_table"#).await;
+  assert!(res.is_err());
+
+  // inserting without the dropped column should succeed
+  let res = session
+    .execute_sql(r#"INSERT INTO test_table VALUES('id2', 20)"#)
+    .await;
+  assert!(res.is_ok());
+
+  // column with the same name as the dropped column can be added
+  let res = session
+    .execute_sql(r#"ALTER TABLE test_table ADD COLUMN name TEXT"#)
+    .await;
+  assert!(res.is_ok());
+
+  let res = session
+    .execute_sql(r#"SELECT id FROM test_table WHERE name IS NULL"#)
+    .await
+    .unwrap()
+    .pop()
+    .unwrap();
+  assert_eq!(res.num_rows().await.unwrap(), 2);
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn table_schema_alter_table_rename_column() {
+  let session = create_session_context();
+
+  let _ = session
+    .execute_sql(
+      r#"CREATE TABLE IF NOT EXISTS test_table (
+        id VARCHAR(50),
+        age INTEGER CHECK (age > 0)
+      )"#,
+    )
+    .await
+    .unwrap();
+
+  let res = session
+    .execute_sql(r#"ALTER TABLE test_table RENAME COLUMN age TO years"#)
+    .await;
+  assert!(res.is_ok());
+
+  let res = session
+    .execute_sql(r#"INSERT INTO test_table(id, years) VALUES('id1', 10)"#)
+    .await;
+  assert!(res.is_ok());
+
+  // check constraint should use the renamed column
+  let res = session
+    .execute_sql(r#"INSERT INTO test_table(id, years) VALUES('id2', -1)"#)
+    .await;
+  assert!(res.is_err());
+
+  let res = session
+    .execute_sql(r#"ALTER TABLE test_table RENAME COLUMN years TO id"#)
+    .await;
+  assert_eq!(
+    res.unwrap_err().message(),
+    r#"column "id" of relation "test_table" already exists"#
+  );
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn table_schema_alter_table_rename_table() {
+  let session = create_session_context();
+
+  let _ = session
+    .execute_sql(r#"CREATE TABLE users (id VARCHAR(50) PRIMARY KEY)"#)
+    .await
+    .unwrap();
+  let _ = session
+    .execute_sql(
+      r#"CREATE TABLE posts (
+        id VARCHAR(50),
+        user_id VARCHAR(50) REFERENCES users(id)
+      )"#,
+    )
+    .await
+    .unwrap();
+  session
+    .execute_sql(r#"INSERT INTO users VALUES('user_1')"#)
+    .await
+    .unwrap();
+
+  let res = session
+    .execute_sql(r#"ALTER TABLE users RENAME TO members"#)
+    .await;
+  assert!(res.is_ok());
+
+  let res = session.execute_sql(r#"SELECT * FROM users"#).await;
+  assert!(res.is_err());
+
+  let res = session
+    .execute_sql(r#"SELECT id FROM members"#)
+    .await
+    .unwrap()
+    .pop()
+    .unwrap();
+  assert_eq!(res.num_rows().await.unwrap(), 1);
+
+  // foreign key should reference the renamed table
+  let res = session
+    .execute_sql(r#"INSERT INTO posts VALUES('post_1', 'user_1')"#)
+    .await;
+  assert!(res.is_ok());
+  let res = session
+    .execute_sql(r#"INSERT INTO posts VALUES('post_2', 'user_2')"#)
+    .await;
+  assert!(res.is_err());
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn table_schema_alter_table_set_and_drop_not_null() {
+  let session = create_session_context();
+
+  let _ = session
+    .execute_sql(
+      r#"CREATE TABLE IF NOT EXISTS test_table (
+        id VARCHAR(50),
+        name TEXT
+      )"#,
+    )
+    .await
+    .unwrap();
+  session
+    .execute_sql(r#"INSERT INTO test_table(id) VALUES('id1')"#)
+    .await
+    .unwrap();
+
+  // existing row has NULL name
+  let res = session
+    .execute_sql(r#"ALTER TABLE test_table ALTER COLUMN name SET NOT NULL"#)
+    .await;
+  assert!(res.is_err());
+
+  let res = session
+    .execute_sql(r#"ALTER TABLE test_table ALTER COLUMN id SET NOT NULL"#)
+    .await;
+  assert!(res.is_ok());
+  let res = session
+    .execute_sql(r#"INSERT INTO test_table(name) VALUES('name 2')"#)
+    .await;
+  assert!(res.is_err());
+
+  let res = session
+    .execute_sql(r#"ALTER TABLE test_table ALTER COLUMN id DROP NOT NULL"#)
+    .await;
+  assert!(res.is_ok());
+  let res = session
+    .execute_sql(r#"INSERT INTO test_table(name) VALUES('name 2')"#)
+    .await;
+  assert!(res.is_ok());
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn table_schema_alter_table_set_and_drop_default() {
+  let session = create_session_context();
+
+  let _ = session
+    .execute_sql(
+      r#"CREATE TABLE IF NOT EXISTS test_table (
+        id VARCHAR(50),
+        name TEXT
+      )"#,
+    )
+    .await
+    .unwrap();
+
+  let res = session
+    .execute_sql(
+      r#"ALTER TABLE test_table ALTER COLUMN name SET DEFAULT 'unnamed'"#,
+    )
+    .await;
+  assert!(res.is_ok());
+  session
+    .execute_sql(r#"INSERT INTO test_table(id) VALUES('id1')"#)
+    .await
+    .unwrap();
+
+  let res = session
+    .execute_sql(r#"ALTER TABLE test_table ALTER COLUMN name DROP DEFAULT"#)
+    .await;
+  assert!(res.is_ok());
+  session
+    .execute_sql(r#"INSERT INTO test_table(id) VALUES('id2')"#)
+    .await
+    .unwrap();
+
+  let res = session
+    .execute_sql(r#"SELECT id FROM test_table WHERE name = 'unnamed'"#)
+    .await
+    .unwrap()
+    .pop()
+    .unwrap();
+  assert_eq!(res.num_rows().await.unwrap(), 1);
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn table_schema_alter_table_alter_column_type() {
+  let session = create_session_context();
+
+  let _ = session
+    .execute_sql(
+      r#"CREATE TABLE IF NOT EXISTS test_table (
+        id VARCHAR(50),
+        count INTEGER,
+        label TEXT
+      )"#,
+    )
+    .await
+    .unwrap();
+  session
+    .execute_sql(
+      r#"INSERT INTO test_table VALUES('id1', 5, '10'), ('id2', 6, 'label')"#,
+    )
+    .await
+    .unwrap();
+
+  let res = session
+    .execute_sql(r#"ALTER TABLE test_table ALTER COLUMN count TYPE BIGINT"#)
+    .await;
+  assert!(res.is_ok());
+
+  let res = session
+    .execute_sql(r#"SELECT id FROM test_table WHERE count = 5"#)
+    .await
+    .unwrap()
+    .pop()
+    .unwrap();
+  assert_eq!(res.num_rows().await.unwrap(), 1);
+
+  // 'label' can't be cast to integer
+  let res = session
+    .execute_sql(r#"ALTER TABLE test_table ALTER COLUMN label TYPE INTEGER"#)
+    .await;
+  assert!(res.is_err());
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn table_schema_alter_table_add_column_with_default() {
+  let session = create_session_context();
+
+  let _ = session
+    .execute_sql(r#"CREATE TABLE IF NOT EXISTS test_table (id VARCHAR(50))"#)
+    .await
+    .unwrap();
+  session
+    .execute_sql(r#"INSERT INTO test_table VALUES('id1'), ('id2')"#)
+    .await
+    .unwrap();
+
+  let res = session
+    .execute_sql(
+      r#"ALTER TABLE test_table ADD COLUMN status TEXT DEFAULT 'active'"#,
+    )
+    .await;
+  assert!(res.is_ok());
+
+  let res = session
+    .execute_sql(r#"SELECT id FROM test_table WHERE status = 'active'"#)
+    .await
+    .unwrap()
+    .pop()
+    .unwrap();
+  assert_eq!(
+    res.num_rows().await.unwrap(),
+    2,
+    "Existing rows should have the default value"
+  );
+}
@@ -0,0 +1,91 @@
+use crate::execute_query;
+use crate::tests::create_session_context;
+
+#[tokio::test(flavor = "multi_thread")]
+async fn check_constraint_test_insert_valid_rows() {
+  let session = create_session_context();
+  let txn = session.new_active_transaction().unwrap();
+
+  execute_query!(
+    txn,
+    r#"CREATE TABLE IF NOT EXISTS test_table (
+      id VARCHAR(50),
+      age INTEGER CHECK (age >= 0),
+      min_price INTEGER,
+      max_price INTEGER,
+      CHECK (min_price <= max_price)
+    )"#
+  )
+  .unwrap();
+
+  let res = execute_query!(
+    txn,
+    r#"INSERT INTO test_table(id, age, min_price, max_price)
+      VALUES('id_1', 10, 1, 2),
+      ('id_2', NULL, 2, 2),
+      ('id_3', 0, NULL, 1)"#
+  );
+  assert!(
+    res.is_ok(),
+    "NULL values shouldn't violate check constraints"
+  );
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn check_constraint_test_insert_invalid_row() {
+  let session = create_session_context();
+  let txn = session.new_active_transaction().unwrap();
+
+  execute_query!(
+    txn,
+    r#"CREATE TABLE IF NOT EXISTS test_table (
+      id VARCHAR(50),
+      age INTEGER CHECK (age >= 0)
+    )"#
+  )
+  .unwrap();
+
+  let err = execute_query!(
+    txn,
+    r#"INSERT INTO test_table(id, age) VALUES('id_1', 10), ('id_2', -1)"#
+  )
+  .unwrap_err();
+  assert_eq!(err.code(), "23514");
+  assert_eq!(
+    err.message(),
+    r#"new row for relation "test_table" violates check constraint "test_table_age_check""#
+  );
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn check_constraint_test_update_invalid_row() {
+  let session = create_session_context();
+  let txn = session.new_active_transaction().unwrap();
+
+  execute_query!(
+    txn,
+    r#"CREATE TABLE IF NOT EXISTS test_table (
+      id VARCHAR(50),
+      min_price INTEGER,
+      max_price INTEGER,
+      CONSTRAINT valid_price CHECK (min_price <= max_price)
+    )"#
+  )
+  .unwrap();
+
+  execute_query!(
+    txn,
+    r#"INSERT INTO test_table(id, min_price, max_price) VALUES('id_1', 1, 5)"#
+  )
+  .unwrap();
+
+  let err = execute_query!(
+    txn,
+    r#"UPDATE test_table SET min_price = 10 WHERE id = 'id_1'"#
+  )
+  .unwrap_err();
+  assert_eq!(
+    err.message(),
+    r#"new row for relation "test_table" violates check constraint "valid_price""#
+  );
+}
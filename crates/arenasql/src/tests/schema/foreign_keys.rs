@@ -0,0 +1,334 @@
+use crate::execute_query;
+use crate::execution::Transaction;
+use crate::tests::create_session_context;
+
+async fn create_tables(txn: &Transaction, on_delete: &str) {
+  execute_query!(
+    txn,
+    r#"CREATE TABLE IF NOT EXISTS users (
+      id VARCHAR(50) PRIMARY KEY,
+      name TEXT
+    )"#
+  )
+  .unwrap();
+
+  execute_query!(
+    txn,
+    r#"CREATE TABLE IF NOT EXISTS posts (
+      id VARCHAR(50) PRIMARY KEY,
+      user_id VARCHAR(50) REFERENCES users(id) ON DELETE {on_delete},
+      title TEXT
+    )"#
+  )
+  .unwrap();
+
+  execute_query!(
+    txn,
+    r#"INSERT INTO users(id, name)
+      VALUES('user_1', 'name 1'),
+      ('user_2', 'name 2')"#
+  )
+  .unwrap();
+
+  execute_query!(
+    txn,
+    r#"INSERT INTO posts(id, user_id, title)
+      VALUES('post_1', 'user_1', 'title 1'),
+      ('post_2', 'user_1', 'title 2'),
+      ('post_3', 'user_2', 'title 3')"#
+  )
+  .unwrap();
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn foreign_key_test_insert_row_with_missing_reference() {
+  let session = create_session_context();
+  let txn = session.new_active_transaction().unwrap();
+  create_tables(&txn, "CASCADE").await;
+
+  let res = execute_query!(
+    txn,
+    r#"INSERT INTO posts(id, user_id, title)
+      VALUES('post_4', 'user_3', 'title 4')"#
+  );
+  assert_eq!(
+    res.unwrap_err().message(),
+    r#"insert or update on table "posts" violates foreign key constraint "posts_user_id_fkey""#
+  );
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn foreign_key_test_insert_rows_referencing_later_row() {
+  let session = create_session_context();
+  let txn = session.new_active_transaction().unwrap();
+
+  execute_query!(
+    txn,
+    r#"CREATE TABLE IF NOT EXISTS categories (
+      id VARCHAR(50) PRIMARY KEY,
+      parent_id VARCHAR(50) REFERENCES categories(id)
+    )"#
+  )
+  .unwrap();
+
+  execute_query!(
+    txn,
+    r#"INSERT INTO categories(id, parent_id)
+      VALUES('category_1', 'category_2'),
+      ('category_2', NULL)"#
+  )
+  .unwrap();
+
+  let res = execute_query!(txn, r#"SELECT count(*) FROM categories"#).unwrap();
+  assert_eq!(res.get_count().await.unwrap(), 2);
+
+  let res = execute_query!(
+    txn,
+    r#"INSERT INTO categories(id, parent_id)
+      VALUES('category_3', 'category_4'),
+      ('category_5', 'category_3')"#
+  );
+  assert!(res.is_err());
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn foreign_key_test_insert_row_with_null_reference() {
+  let session = create_session_context();
+  let txn = session.new_active_transaction().unwrap();
+  create_tables(&txn, "CASCADE").await;
+
+  let res = execute_query!(
+    txn,
+    r#"INSERT INTO posts(id, title) VALUES('post_4', 'title 4')"#
+  );
+  assert!(res.is_ok());
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn foreign_key_test_update_row_with_missing_reference() {
+  let session = create_session_context();
+  let txn = session.new_active_transaction().unwrap();
+  create_tables(&txn, "CASCADE").await;
+
+  let res = execute_query!(
+    txn,
+    r#"UPDATE posts SET user_id = 'user_3' WHERE id = 'post_1'"#
+  );
+  assert!(res.is_err());
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn foreign_key_test_on_delete_cascade() {
+  let session = create_session_context();
+  let txn = session.new_active_transaction().unwrap();
+  create_tables(&txn, "CASCADE").await;
+
+  execute_query!(txn, r#"DELETE FROM users WHERE id = 'user_1'"#).unwrap();
+
+  let res = execute_query!(txn, r#"SELECT * FROM posts"#).unwrap();
+  assert_eq!(
+    res.num_rows().await.unwrap(),
+    1,
+    "Posts of the deleted user should be deleted"
+  );
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn foreign_key_test_on_delete_cascade_through_referencing_tables() {
+  let session = create_session_context();
+  let txn = session.new_active_transaction().unwrap();
+  create_tables(&txn, "CASCADE").await;
+
+  execute_query!(
+    txn,
+    r#"CREATE TABLE IF NOT EXISTS comments (
+      id VARCHAR(50) PRIMARY KEY,
+      post_id VARCHAR(50) REFERENCES posts(id) ON DELETE CASCADE
+    )"#
+  )
+  .unwrap();
+  execute_query!(
+    txn,
+    r#"INSERT INTO comments(id, post_id)
+      VALUES('comment_1', 'post_1'),
+      ('comment_2', 'post_3')"#
+  )
+  .unwrap();
+
+  execute_query!(txn, r#"DELETE FROM users WHERE id = 'user_1'"#).unwrap();
+
+  let res = execute_query!(txn, r#"SELECT count(*) FROM comments"#).unwrap();
+  assert_eq!(
+    res.get_count().await.unwrap(),
+    1,
+    "Comments of the posts of the deleted user should be deleted"
+  );
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn foreign_key_test_on_delete_set_null() {
+  let session = create_session_context();
+  let txn = session.new_active_transaction().unwrap();
+  create_tables(&txn, "SET NULL").await;
+
+  execute_query!(txn, r#"DELETE FROM users WHERE id = 'user_1'"#).unwrap();
+
+  let res =
+    execute_query!(txn, r#"SELECT count(*) FROM posts WHERE user_id IS NULL"#)
+      .unwrap();
+  assert_eq!(res.get_count().await.unwrap(), 2);
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn foreign_key_test_on_delete_restrict() {
+  let session = create_session_context();
+  let txn = session.new_active_transaction().unwrap();
+  create_tables(&txn, "RESTRICT").await;
+
+  let res = execute_query!(txn, r#"DELETE FROM users WHERE id = 'user_1'"#);
+  assert_eq!(
+    res.unwrap_err().message(),
+    r#"update or delete on table "users" violates foreign key constraint "posts_user_id_fkey" on table "posts""#
+  );
+}
+
+async fn create_tree(txn: &Transaction) {
+  execute_query!(
+    txn,
+    r#"CREATE TABLE IF NOT EXISTS nodes (
+      id INT PRIMARY KEY,
+      parent_id INT REFERENCES nodes(id)
+    )"#
+  )
+  .unwrap();
+
+  execute_query!(
+    txn,
+    r#"INSERT INTO nodes(id, parent_id) VALUES(1, NULL), (2, 1), (3, 2)"#
+  )
+  .unwrap();
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn foreign_key_test_on_delete_no_action_checked_after_statement() {
+  let session = create_session_context();
+  let txn = session.new_active_transaction().unwrap();
+  create_tree(&txn).await;
+
+  let res = execute_query!(txn, r#"DELETE FROM nodes WHERE id = 1"#);
+  assert_eq!(
+    res.unwrap_err().message(),
+    r#"update or delete on table "nodes" violates foreign key constraint "nodes_parent_id_fkey" on table "nodes""#
+  );
+
+  // The referencing rows are deleted by the same statement
+  let session = create_session_context();
+  let txn = session.new_active_transaction().unwrap();
+  create_tree(&txn).await;
+  let res = execute_query!(txn, r#"DELETE FROM nodes"#).unwrap();
+  assert_eq!(res.get_modified_rows(), Some(3));
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn foreign_key_test_on_update_no_action_checked_after_statement() {
+  let session = create_session_context();
+  let txn = session.new_active_transaction().unwrap();
+  create_tree(&txn).await;
+
+  let res = execute_query!(txn, r#"UPDATE nodes SET id = 10 WHERE id = 1"#);
+  assert!(res.is_err());
+
+  // The referencing rows are updated by the same statement
+  let session = create_session_context();
+  let txn = session.new_active_transaction().unwrap();
+  create_tree(&txn).await;
+  execute_query!(
+    txn,
+    r#"UPDATE nodes SET id = id + 10, parent_id = parent_id + 10"#
+  )
+  .unwrap();
+
+  let res = execute_query!(
+    txn,
+    r#"SELECT count(*) FROM nodes WHERE parent_id IN (11, 12)"#
+  )
+  .unwrap();
+  assert_eq!(res.get_count().await.unwrap(), 2);
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn foreign_key_test_on_update_cascade() {
+  let session = create_session_context();
+  let txn = session.new_active_transaction().unwrap();
+
+  execute_query!(
+    txn,
+    r#"CREATE TABLE IF NOT EXISTS users (
+      id VARCHAR(50) UNIQUE,
+      name TEXT
+    )"#
+  )
+  .unwrap();
+
+  execute_query!(
+    txn,
+    r#"CREATE TABLE IF NOT EXISTS posts (
+      id VARCHAR(50),
+      user_id VARCHAR(50),
+      CONSTRAINT posts_user_fkey FOREIGN KEY (user_id)
+        REFERENCES users(id) ON UPDATE CASCADE
+    )"#
+  )
+  .unwrap();
+
+  execute_query!(txn, r#"INSERT INTO users(id) VALUES('user_1')"#).unwrap();
+  execute_query!(
+    txn,
+    r#"INSERT INTO posts(id, user_id) VALUES('post_1', 'user_1')"#
+  )
+  .unwrap();
+
+  execute_query!(txn, r#"UPDATE users SET id = 'user_2' WHERE id = 'user_1'"#)
+    .unwrap();
+
+  let res = execute_query!(
+    txn,
+    r#"SELECT count(*) FROM posts WHERE user_id = 'user_2'"#
+  )
+  .unwrap();
+  assert_eq!(res.get_count().await.unwrap(), 1);
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn foreign_key_test_reference_without_unique_constraint() {
+  let session = create_session_context();
+  let txn = session.new_active_transaction().unwrap();
+
+  execute_query!(
+    txn,
+    r#"CREATE TABLE IF NOT EXISTS users (
+      id VARCHAR(50),
+      name TEXT
+    )"#
+  )
+  .unwrap();
+
+  let res = execute_query!(
+    txn,
+    r#"CREATE TABLE IF NOT EXISTS posts (
+      id VARCHAR(50),
+      user_id VARCHAR(50) REFERENCES users(id)
+    )"#
+  );
+  assert!(res.is_err());
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn foreign_key_test_drop_referenced_table() {
+  let session = create_session_context();
+  let txn = session.new_active_transaction().unwrap();
+  create_tables(&txn, "CASCADE").await;
+
+  let res = execute_query!(txn, r#"DROP TABLE users"#);
+  assert!(res.is_err());
+}
@@ -1,4 +1,8 @@
 mod alter_table;
+mod check_constraints;
+mod foreign_keys;
 mod indexes;
+mod sequences;
+mod statistics;
 mod table;
 mod table_lock;
@@ -0,0 +1,220 @@
+use crate::execution::SessionContext;
+use crate::tests::create_session_context;
+use crate::Result;
+
+/// Returns the first value of the query result
+async fn get_value(session: &SessionContext, sql: &str) -> Result<i64> {
+  session
+    .execute_sql(sql)
+    .await?
+    .pop()
+    .unwrap()
+    .get_count()
+    .await
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn sequence_test_nextval_and_currval() {
+  let session = create_session_context();
+  session
+    .execute_sql(r#"CREATE SEQUENCE order_seq START WITH 10 INCREMENT BY 5"#)
+    .await
+    .unwrap();
+
+  let res = get_value(&session, r#"SELECT currval('order_seq')"#).await;
+  assert_eq!(
+    res.unwrap_err().message(),
+    r#"currval of sequence "order_seq" is not yet defined in this session"#
+  );
+
+  for expected in [10, 15] {
+    assert_eq!(
+      get_value(&session, r#"SELECT nextval('order_seq')"#)
+        .await
+        .unwrap(),
+      expected
+    );
+  }
+
+  assert_eq!(
+    get_value(&session, r#"SELECT currval('order_seq')"#)
+      .await
+      .unwrap(),
+    15
+  );
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn sequence_test_setval() {
+  let session = create_session_context();
+  session
+    .execute_sql(r#"CREATE SEQUENCE order_seq"#)
+    .await
+    .unwrap();
+
+  get_value(&session, r#"SELECT setval('order_seq', 100)"#)
+    .await
+    .unwrap();
+  assert_eq!(
+    get_value(&session, r#"SELECT nextval('order_seq')"#)
+      .await
+      .unwrap(),
+    101
+  );
+
+  get_value(&session, r#"SELECT setval('order_seq', 50, false)"#)
+    .await
+    .unwrap();
+  assert_eq!(
+    get_value(&session, r#"SELECT nextval('order_seq')"#)
+      .await
+      .unwrap(),
+    50
+  );
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn sequence_test_max_value() {
+  let session = create_session_context();
+  session
+    .execute_sql(r#"CREATE SEQUENCE small_seq MAXVALUE 2"#)
+    .await
+    .unwrap();
+  session
+    .execute_sql(r#"CREATE SEQUENCE cyclic_seq MAXVALUE 2 CYCLE"#)
+    .await
+    .unwrap();
+
+  for _ in 0..2 {
+    get_value(&session, r#"SELECT nextval('small_seq')"#)
+      .await
+      .unwrap();
+    get_value(&session, r#"SELECT nextval('cyclic_seq')"#)
+      .await
+      .unwrap();
+  }
+
+  let res = get_value(&session, r#"SELECT nextval('small_seq')"#).await;
+  assert_eq!(
+    res.unwrap_err().message(),
+    r#"nextval: reached maximum value of sequence "small_seq" (2)"#
+  );
+
+  assert_eq!(
+    get_value(&session, r#"SELECT nextval('cyclic_seq')"#)
+      .await
+      .unwrap(),
+    1
+  );
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn sequence_test_serial_column() {
+  let session = create_session_context();
+  session
+    .execute_sql(
+      r#"CREATE TABLE users (
+        id SERIAL PRIMARY KEY,
+        name TEXT
+      )"#,
+    )
+    .await
+    .unwrap();
+
+  session
+    .execute_sql(r#"INSERT INTO users(name) VALUES('name 1'), ('name 2')"#)
+    .await
+    .unwrap();
+  session
+    .execute_sql(r#"INSERT INTO users(name) VALUES('name 3')"#)
+    .await
+    .unwrap();
+
+  assert_eq!(
+    get_value(
+      &session,
+      r#"SELECT count(*) FROM users WHERE id IN (1, 2, 3)"#
+    )
+    .await
+    .unwrap(),
+    3
+  );
+
+  assert_eq!(
+    get_value(&session, r#"SELECT nextval('users_id_seq')"#)
+      .await
+      .unwrap(),
+    4
+  );
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn sequence_test_identity_columns() {
+  let session = create_session_context();
+  session
+    .execute_sql(
+      r#"CREATE TABLE users (
+        id BIGINT GENERATED ALWAYS AS IDENTITY (START WITH 100),
+        code INT GENERATED BY DEFAULT AS IDENTITY,
+        name TEXT
+      )"#,
+    )
+    .await
+    .unwrap();
+
+  let res = session
+    .execute_sql(r#"INSERT INTO users(id, name) VALUES(1, 'name 1')"#)
+    .await;
+  assert_eq!(
+    res.unwrap_err().message(),
+    r#"cannot insert a non-DEFAULT value into column "id""#
+  );
+
+  session
+    .execute_sql(r#"INSERT INTO users(code, name) VALUES(10, 'name 1')"#)
+    .await
+    .unwrap();
+  session
+    .execute_sql(r#"INSERT INTO users(name) VALUES('name 2')"#)
+    .await
+    .unwrap();
+
+  let res = get_value(
+    &session,
+    r#"SELECT count(*) FROM users
+      WHERE (id = 100 AND code = 10) OR (id = 101 AND code = 1)"#,
+  )
+  .await;
+  assert_eq!(res.unwrap(), 2);
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn sequence_test_drop_sequence() {
+  let session = create_session_context();
+  session
+    .execute_sql(r#"CREATE TABLE users (id SERIAL, name TEXT)"#)
+    .await
+    .unwrap();
+  session
+    .execute_sql(r#"CREATE SEQUENCE order_seq"#)
+    .await
+    .unwrap();
+
+  let res = session.execute_sql(r#"DROP SEQUENCE users_id_seq"#).await;
+  assert!(res.is_err(), "Sequence used by a table can't be dropped");
+
+  session
+    .execute_sql(r#"DROP SEQUENCE order_seq"#)
+    .await
+    .unwrap();
+  let res = get_value(&session, r#"SELECT nextval('order_seq')"#).await;
+  assert_eq!(
+    res.unwrap_err().message(),
+    r#"relation "order_seq" does not exist"#
+  );
+
+  // Sequences of the table are dropped with the table
+  session.execute_sql(r#"DROP TABLE users"#).await.unwrap();
+  let res = get_value(&session, r#"SELECT nextval('users_id_seq')"#).await;
+  assert!(res.is_err());
+}
@@ -0,0 +1,120 @@
+use crate::execution::{SessionContext, DEFAULT_SCHEMA_NAME};
+use crate::schema::{compare_cells, OwnedSerializedCell, TableStatistics};
+use crate::tests::create_session_context;
+
+async fn execute(session: &SessionContext, sql: &str) {
+  session.execute_sql(sql).await.unwrap();
+}
+
+fn get_statistics(
+  session: &SessionContext,
+  table_name: &str,
+) -> (Vec<u64>, Option<TableStatistics>) {
+  let txn = session.new_active_transaction().unwrap();
+  let storage = txn.handle().lock(false).unwrap();
+  let table = storage
+    .get_table_schema(&session.config.catalog, DEFAULT_SCHEMA_NAME, table_name)
+    .unwrap()
+    .unwrap();
+  let index_ids = table.indexes.iter().map(|index| index.id).collect();
+  let statistics = storage.get_table_statistics(&table).unwrap();
+  drop(storage);
+  session.rollback_active_transaction().unwrap();
+  (index_ids, statistics)
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn statistics_test_row_count_updated_by_dml() {
+  let session = create_session_context();
+  execute(&session, r#"CREATE TABLE items (id INT, name TEXT)"#).await;
+  let (_, statistics) = get_statistics(&session, "items");
+  assert_eq!(statistics.unwrap().row_count, 0);
+
+  execute(
+    &session,
+    r#"INSERT INTO items VALUES (1, 'one'), (2, 'two'), (3, 'three')"#,
+  )
+  .await;
+  execute(&session, r#"DELETE FROM items WHERE id = 2"#).await;
+  execute(&session, r#"UPDATE items SET name = 'first' WHERE id = 1"#).await;
+
+  let (_, statistics) = get_statistics(&session, "items");
+  assert_eq!(statistics.unwrap().row_count, 2);
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn statistics_test_analyze_collects_index_statistics() {
+  let session = create_session_context();
+  execute(
+    &session,
+    r#"CREATE TABLE items (id INT UNIQUE, category TEXT)"#,
+  )
+  .await;
+  execute(
+    &session,
+    r#"CREATE INDEX items_category ON items(category)"#,
+  )
+  .await;
+  execute(
+    &session,
+    r#"INSERT INTO items VALUES
+      (4, 'b'), (1, 'a'), (3, 'a'), (2, NULL), (5, 'c')"#,
+  )
+  .await;
+  execute(&session, r#"ANALYZE items"#).await;
+
+  let (index_ids, statistics) = get_statistics(&session, "items");
+  let statistics = statistics.unwrap();
+  assert_eq!(statistics.row_count, 5);
+
+  let id_stats = statistics.get_index_statistics(index_ids[0]).unwrap();
+  assert_eq!(id_stats.distinct_count, 5);
+  assert_eq!(
+    id_stats.histogram.first(),
+    Some(&OwnedSerializedCell::Int32(1))
+  );
+  assert_eq!(
+    id_stats.histogram.last(),
+    Some(&OwnedSerializedCell::Int32(5))
+  );
+  assert!(id_stats
+    .histogram
+    .windows(2)
+    .all(|w| compare_cells(&w[0], &w[1]).unwrap().is_le()));
+
+  // NULL values aren't counted
+  let category_stats = statistics.get_index_statistics(index_ids[1]).unwrap();
+  assert_eq!(category_stats.distinct_count, 3);
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn statistics_test_analyze_all_tables() {
+  let session = create_session_context();
+  execute(&session, r#"CREATE TABLE items (id INT)"#).await;
+  execute(&session, r#"CREATE TABLE orders (id INT)"#).await;
+  execute(&session, r#"INSERT INTO items VALUES (1), (2)"#).await;
+  execute(&session, r#"INSERT INTO orders VALUES (1)"#).await;
+
+  // Rolled back rows are counted until the table is analyzed
+  let txn = session.new_active_transaction().unwrap();
+  txn
+    .execute_sql(r#"INSERT INTO items VALUES (3)"#)
+    .await
+    .unwrap();
+  session.rollback_active_transaction().unwrap();
+  let (_, statistics) = get_statistics(&session, "items");
+  assert_eq!(statistics.unwrap().row_count, 3);
+
+  execute(&session, r#"ANALYZE"#).await;
+  let (_, statistics) = get_statistics(&session, "items");
+  assert_eq!(statistics.unwrap().row_count, 2);
+  let (_, statistics) = get_statistics(&session, "orders");
+  assert_eq!(statistics.unwrap().row_count, 1);
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn statistics_test_analyze_unknown_table() {
+  let session = create_session_context();
+  let res = session.execute_sql(r#"ANALYZE items"#).await;
+  assert!(res.is_err(), "Expected error analyzing unknown table");
+}
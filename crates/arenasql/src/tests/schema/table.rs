@@ -1,6 +1,6 @@
 use crate::schema::{
-  Column, ColumnProperty, Constraint, DataType, IndexProvider, Table,
-  TableIndex, VectorMetric,
+  Column, ColumnProperty, Constraint, DataType, ForeignKey, IndexProvider,
+  ReferentialAction, Table, TableIndex, VectorMetric,
 };
 use crate::tests::create_session_context;
 
@@ -35,9 +35,19 @@ async fn table_schema_protobuf_encoding_decoding() {
       name: "column_1".to_owned(),
       data_type: DataType::Jsonb,
       properties: ColumnProperty::NOT_NULL,
-      default_value: None,
+      default_expr: Some("now()".to_owned()),
     }],
-    constraints: vec![Constraint::Unique(vec![1])],
+    constraints: vec![
+      Constraint::Unique(vec![1]),
+      Constraint::ForeignKey(ForeignKey {
+        name: "test_table_column_1_fkey".to_owned(),
+        columns: vec![0],
+        foreign_table: "other_table".to_owned(),
+        referred_columns: vec![2],
+        on_delete: ReferentialAction::Cascade,
+        on_update: ReferentialAction::SetNull,
+      }),
+    ],
     indexes: vec![
       TableIndex {
         id: 12,
@@ -59,6 +69,16 @@ async fn table_schema_protobuf_encoding_decoding() {
           dim: 10,
           retain_vectors: false,
           namespace_column: Some(1),
+          rerank: true,
+        },
+      },
+      TableIndex {
+        id: 13,
+        name: "index_3".to_owned(),
+        provider: IndexProvider::JsonPathIndex {
+          columns: vec![2],
+          path: vec!["team".to_owned(), "0".to_owned()],
+          unique: true,
         },
       },
     ],
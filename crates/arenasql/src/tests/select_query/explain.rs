@@ -0,0 +1,197 @@
+use datafusion::arrow::array::as_string_array;
+
+use crate::execute_query;
+use crate::execution::Transaction;
+use crate::tests::create_session_context;
+
+async fn create_users_table(txn: &Transaction) {
+  execute_query!(
+    txn,
+    r#"CREATE TABLE users (
+      id INT,
+      name TEXT,
+      email TEXT
+    )"#
+  )
+  .unwrap();
+
+  execute_query!(txn, r#"CREATE INDEX users_email_idx ON users(email)"#)
+    .unwrap();
+
+  execute_query!(
+    txn,
+    r#"INSERT INTO users VALUES
+      (1, 'name 1', 'one@arenasql.dev'),
+      (2, 'name 2', 'two@arenasql.dev'),
+      (3, 'name 3', 'three@arenasql.dev')"#
+  )
+  .unwrap();
+}
+
+/// Returns the plan of the given type from the output of the EXPLAIN query
+async fn get_plan(txn: &Transaction, query: &str, plan_type: &str) -> String {
+  let batches = txn
+    .execute_sql(query)
+    .await
+    .unwrap()
+    .collect_batches()
+    .await
+    .unwrap();
+
+  batches
+    .iter()
+    .find_map(|batch| {
+      let plan_types = as_string_array(batch.column(0));
+      let plans = as_string_array(batch.column(1));
+      (0..batch.num_rows())
+        .find(|row| plan_types.value(*row) == plan_type)
+        .map(|row| plans.value(row).to_owned())
+    })
+    .expect("Expected plan to be in EXPLAIN output")
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn explain_test_shows_index_scan() {
+  let session = create_session_context();
+  let txn = session.new_active_transaction().unwrap();
+  create_users_table(&txn).await;
+
+  let plan = get_plan(
+    &txn,
+    "EXPLAIN SELECT email FROM users WHERE email = 'two@arenasql.dev'",
+    "physical_plan",
+  )
+  .await;
+
+  assert!(
+    plan
+      .contains("TableScaner: table=users, scan=index, index=users_email_idx"),
+    "Expected index scan in plan: {}",
+    plan
+  );
+  assert!(
+    plan.contains("filters=[") && plan.contains("two@arenasql.dev"),
+    "Expected pushed down filter in plan: {}",
+    plan
+  );
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn explain_test_shows_heap_scan() {
+  let session = create_session_context();
+  let txn = session.new_active_transaction().unwrap();
+  create_users_table(&txn).await;
+
+  let plan = get_plan(
+    &txn,
+    "EXPLAIN SELECT * FROM users WHERE name = 'name 1'",
+    "physical_plan",
+  )
+  .await;
+
+  assert!(
+    plan.contains("TableScaner: table=users, scan=heap"),
+    "Expected heap scan in plan: {}",
+    plan
+  );
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn explain_test_analyze_shows_metrics() {
+  let session = create_session_context();
+  let txn = session.new_active_transaction().unwrap();
+  create_users_table(&txn).await;
+
+  let plan = get_plan(
+    &txn,
+    "EXPLAIN ANALYZE SELECT email FROM users WHERE email = 'two@arenasql.dev'",
+    "Plan with Metrics",
+  )
+  .await;
+
+  let scan = plan
+    .lines()
+    .find(|line| line.contains("TableScaner"))
+    .expect("Expected table scan in plan");
+  assert!(
+    scan.contains("scan=index, index=users_email_idx"),
+    "Expected index scan in plan: {}",
+    plan
+  );
+  assert!(
+    scan.contains("output_rows=1") && scan.contains("elapsed_compute="),
+    "Expected row count and timing of the scan: {}",
+    plan
+  );
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn explain_test_delete_query_doesnt_delete_rows() {
+  let session = create_session_context();
+  let txn = session.new_active_transaction().unwrap();
+  create_users_table(&txn).await;
+
+  let plan = get_plan(
+    &txn,
+    "EXPLAIN DELETE FROM users WHERE email = 'two@arenasql.dev'",
+    "physical_plan",
+  )
+  .await;
+
+  assert!(
+    plan
+      .contains("TableScaner: table=users, scan=index, index=users_email_idx"),
+    "Expected index scan in plan: {}",
+    plan
+  );
+
+  let res = execute_query!(txn, r#"SELECT * FROM users"#).unwrap();
+  assert_eq!(res.num_rows().await.unwrap(), 3);
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn explain_test_uses_statistics_to_choose_scan() {
+  let session = create_session_context();
+  let txn = session.new_active_transaction().unwrap();
+  execute_query!(txn, r#"CREATE TABLE items (id INT, category TEXT)"#).unwrap();
+  execute_query!(txn, r#"CREATE INDEX items_category ON items(category)"#)
+    .unwrap();
+  execute_query!(
+    txn,
+    r#"INSERT INTO items VALUES (1, 'a'), (2, 'a'), (3, 'a'), (4, 'a')"#
+  )
+  .unwrap();
+  execute_query!(txn, r#"ANALYZE items"#).unwrap();
+
+  // Most of the rows have to be looked up in the table if the index is used
+  let plan = get_plan(
+    &txn,
+    "EXPLAIN SELECT * FROM items WHERE category = 'a'",
+    "physical_plan",
+  )
+  .await;
+  assert!(
+    plan.contains("TableScaner: table=items, scan=heap"),
+    "Expected heap scan in plan: {}",
+    plan
+  );
+
+  execute_query!(
+    txn,
+    r#"INSERT INTO items VALUES (5, 'c'), (6, 'd'), (7, 'e'), (8, 'f')"#
+  )
+  .unwrap();
+  execute_query!(txn, r#"ANALYZE items"#).unwrap();
+
+  let plan = get_plan(
+    &txn,
+    "EXPLAIN SELECT * FROM items WHERE category = 'c'",
+    "physical_plan",
+  )
+  .await;
+  assert!(
+    plan.contains("TableScaner: table=items, scan=index, index=items_category"),
+    "Expected index scan in plan: {}",
+    plan
+  );
+}
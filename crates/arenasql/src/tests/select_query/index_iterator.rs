@@ -116,9 +116,8 @@ async fn le_filter_returns_multi_row_iterator() {
 
   rows_iterator.fill_into(&mut dataframe).unwrap();
 
-  // Since only '=' filter is applied during scanning right now,
-  // other operator will return all rows
-  assert_eq!(dataframe.row_count(), 3)
+  // Range filters are applied during scanning
+  assert_eq!(dataframe.row_count(), 2)
 }
 
 #[tokio::test(flavor = "multi_thread")]
@@ -0,0 +1,232 @@
+use datafusion::arrow::array::{as_primitive_array, as_string_array};
+use datafusion::arrow::datatypes::Int32Type;
+
+use crate::execute_query;
+use crate::execution::Transaction;
+use crate::tests::create_session_context;
+
+async fn create_items_table(txn: &Transaction) {
+  execute_query!(
+    txn,
+    r#"CREATE TABLE items (
+      id INT,
+      category TEXT,
+      name TEXT
+    )"#
+  )
+  .unwrap();
+  execute_query!(txn, r#"CREATE INDEX items_id ON items(id)"#).unwrap();
+  execute_query!(
+    txn,
+    r#"CREATE INDEX items_category_id ON items(category, id)"#
+  )
+  .unwrap();
+
+  execute_query!(
+    txn,
+    r#"INSERT INTO items VALUES
+      (-5, 'a', 'item -5'),
+      (-3, 'b', 'item -3'),
+      (-2, 'a', 'item -2'),
+      (0, 'b', 'item 0'),
+      (1, 'a', 'item 1'),
+      (3, 'b', 'item 3'),
+      (4, 'a', 'item 4'),
+      (5, 'c', 'item 5'),
+      (NULL, 'c', 'item null')"#
+  )
+  .unwrap();
+}
+
+/// Returns the ids returned by the query in the same order
+async fn query_ids(txn: &Transaction, query: &str) -> Vec<Option<i32>> {
+  txn
+    .execute_sql(query)
+    .await
+    .unwrap()
+    .collect_batches()
+    .await
+    .unwrap()
+    .iter()
+    .flat_map(|batch| {
+      as_primitive_array::<Int32Type>(batch.column(0))
+        .iter()
+        .collect::<Vec<_>>()
+    })
+    .collect()
+}
+
+async fn query_sorted_ids(txn: &Transaction, query: &str) -> Vec<Option<i32>> {
+  let mut ids = query_ids(txn, query).await;
+  ids.sort();
+  ids
+}
+
+async fn get_physical_plan(txn: &Transaction, query: &str) -> String {
+  let batches = txn
+    .execute_sql(&format!("EXPLAIN {}", query))
+    .await
+    .unwrap()
+    .collect_batches()
+    .await
+    .unwrap();
+
+  batches
+    .iter()
+    .find_map(|batch| {
+      let plan_types = as_string_array(batch.column(0));
+      let plans = as_string_array(batch.column(1));
+      (0..batch.num_rows())
+        .find(|row| plan_types.value(*row) == "physical_plan")
+        .map(|row| plans.value(row).to_owned())
+    })
+    .expect("Expected physical plan to be in EXPLAIN output")
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn index_scan_test_range_filters_with_negative_values() {
+  let session = create_session_context();
+  let txn = session.new_active_transaction().unwrap();
+  create_items_table(&txn).await;
+
+  let ids =
+    query_sorted_ids(&txn, "SELECT id FROM items WHERE id BETWEEN -3 AND 3")
+      .await;
+  assert_eq!(ids, vec![Some(-3), Some(-2), Some(0), Some(1), Some(3)]);
+
+  let ids = query_sorted_ids(&txn, "SELECT id FROM items WHERE id > -3").await;
+  assert_eq!(
+    ids,
+    vec![Some(-2), Some(0), Some(1), Some(3), Some(4), Some(5)]
+  );
+
+  let ids = query_sorted_ids(&txn, "SELECT id FROM items WHERE id >= 3").await;
+  assert_eq!(ids, vec![Some(3), Some(4), Some(5)]);
+
+  let ids = query_sorted_ids(&txn, "SELECT id FROM items WHERE id < 0").await;
+  assert_eq!(ids, vec![Some(-5), Some(-3), Some(-2)]);
+
+  let ids =
+    query_sorted_ids(&txn, "SELECT id FROM items WHERE id > 4 AND id < 1")
+      .await;
+  assert!(ids.is_empty());
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn index_scan_test_multi_column_filters() {
+  let session = create_session_context();
+  let txn = session.new_active_transaction().unwrap();
+  create_items_table(&txn).await;
+
+  let query = "SELECT id FROM items WHERE category = 'a' AND id > -2";
+  let ids = query_sorted_ids(&txn, query).await;
+  assert_eq!(ids, vec![Some(1), Some(4)]);
+
+  let plan = get_physical_plan(&txn, query).await;
+  assert!(
+    plan.contains("scan=index, index=items_category_id"),
+    "Expected composite index scan in plan: {}",
+    plan
+  );
+
+  let ids = query_sorted_ids(
+    &txn,
+    "SELECT id FROM items WHERE category = 'b' AND id = 3",
+  )
+  .await;
+  assert_eq!(ids, vec![Some(3)]);
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn index_scan_test_in_list_filters() {
+  let session = create_session_context();
+  let txn = session.new_active_transaction().unwrap();
+  create_items_table(&txn).await;
+
+  let ids = query_sorted_ids(
+    &txn,
+    "SELECT id FROM items WHERE id IN (-5, 0, 4, 5, 10)",
+  )
+  .await;
+  assert_eq!(ids, vec![Some(-5), Some(0), Some(4), Some(5)]);
+
+  let ids =
+    query_sorted_ids(&txn, "SELECT id FROM items WHERE id IN (1, 3)").await;
+  assert_eq!(ids, vec![Some(1), Some(3)]);
+
+  let ids = query_sorted_ids(
+    &txn,
+    "SELECT id FROM items WHERE category IN ('a', 'c') AND id >= 1",
+  )
+  .await;
+  assert_eq!(ids, vec![Some(1), Some(4), Some(5)]);
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn index_scan_test_order_by_limit_uses_index_order() {
+  let session = create_session_context();
+  let txn = session.new_active_transaction().unwrap();
+  create_items_table(&txn).await;
+
+  let query = "SELECT id, name FROM items ORDER BY id LIMIT 3";
+  let ids = query_ids(&txn, query).await;
+  assert_eq!(ids, vec![Some(-5), Some(-3), Some(-2)]);
+
+  let plan = get_physical_plan(&txn, query).await;
+  assert!(
+    plan.contains("index=items_id, order=asc"),
+    "Expected ordered index scan in plan: {}",
+    plan
+  );
+  assert!(
+    !plan.contains("SortExec"),
+    "Expected no sort in plan: {}",
+    plan
+  );
+
+  let ids =
+    query_ids(&txn, "SELECT id FROM items ORDER BY id DESC LIMIT 2").await;
+  assert_eq!(ids, vec![None, Some(5)]);
+
+  let ids = query_ids(
+    &txn,
+    "SELECT id FROM items ORDER BY id DESC NULLS LAST LIMIT 2",
+  )
+  .await;
+  assert_eq!(ids, vec![Some(5), Some(4)]);
+
+  let ids = query_ids(
+    &txn,
+    "SELECT id FROM items WHERE id >= 0 ORDER BY id LIMIT 2",
+  )
+  .await;
+  assert_eq!(ids, vec![Some(0), Some(1)]);
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn index_scan_test_order_by_limit_with_index_prefix_filter() {
+  let session = create_session_context();
+  let txn = session.new_active_transaction().unwrap();
+  create_items_table(&txn).await;
+
+  let query =
+    "SELECT id FROM items WHERE category = 'a' ORDER BY id DESC LIMIT 2";
+  let ids = query_ids(&txn, query).await;
+  assert_eq!(ids, vec![Some(4), Some(1)]);
+
+  let plan = get_physical_plan(&txn, query).await;
+  assert!(
+    plan.contains("index=items_category_id, order=desc"),
+    "Expected ordered index scan in plan: {}",
+    plan
+  );
+
+  // The filter on a column that's not in the index can't be applied
+  // when scanning the index, so all the rows need to be sorted
+  let query = "SELECT id FROM items WHERE name = 'item 1' ORDER BY id LIMIT 2";
+  let ids = query_ids(&txn, query).await;
+  assert_eq!(ids, vec![Some(1)]);
+
+  let plan = get_physical_plan(&txn, query).await;
+  assert!(plan.contains("SortExec"), "Expected sort in plan: {}", plan);
+}
@@ -1,2 +1,5 @@
+mod explain;
 mod index_iterator;
+mod index_scan;
 mod select;
+mod table_scan;
@@ -0,0 +1,139 @@
+use crate::execute_query;
+use crate::execution::Transaction;
+use crate::tests::create_session_context;
+
+/// More rows than the default batch size of the scan
+const ROW_COUNT: usize = 10_000;
+
+async fn create_items_table(txn: &Transaction, name: &str) {
+  txn
+    .execute_sql(&format!("CREATE TABLE {} (id INT, name TEXT)", name))
+    .await
+    .unwrap();
+
+  for start in (0..ROW_COUNT).step_by(1_000) {
+    let values = (start..start + 1_000)
+      .map(|id| format!("({}, 'item {}')", id, id))
+      .collect::<Vec<String>>()
+      .join(", ");
+    txn
+      .execute_sql(&format!("INSERT INTO {} VALUES {}", name, values))
+      .await
+      .unwrap();
+  }
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn table_scan_test_returns_rows_in_batches() {
+  let session = create_session_context();
+  let txn = session.new_active_transaction().unwrap();
+  create_items_table(&txn, "items").await;
+
+  let batches = execute_query!(txn, r#"SELECT * FROM items"#)
+    .unwrap()
+    .collect_batches()
+    .await
+    .unwrap();
+
+  assert!(batches.len() > 1, "Expected rows to be scanned in batches");
+  assert_eq!(
+    batches.iter().map(|batch| batch.num_rows()).sum::<usize>(),
+    ROW_COUNT
+  );
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn table_scan_test_stops_scan_after_limit() {
+  let session = create_session_context();
+  let txn = session.new_active_transaction().unwrap();
+  create_items_table(&txn, "items").await;
+
+  let res =
+    execute_query!(txn, r#"SELECT * FROM items WHERE name <> 'x' LIMIT 5"#)
+      .unwrap();
+  assert_eq!(res.num_rows().await.unwrap(), 5);
+
+  // The scan shouldn't hold the lock of the transaction after the rows
+  // are returned
+  let res = execute_query!(txn, r#"DELETE FROM items WHERE id < 10"#);
+  assert!(res.is_ok(), "{:?}", res.err());
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn table_scan_test_commits_after_limit() {
+  let session = create_session_context();
+  session.execute_sql("BEGIN").await.unwrap();
+  let txn = unsafe { session.get_or_create_active_transaction() };
+  create_items_table(&txn, "items").await;
+
+  let res = session
+    .execute_sql(r#"SELECT * FROM items LIMIT 5"#)
+    .await
+    .unwrap()
+    .pop()
+    .unwrap();
+  assert_eq!(res.num_rows().await.unwrap(), 5);
+
+  // The transaction should be committed once the cancelled scan
+  // releases its lock
+  let res = session.execute_sql("COMMIT").await;
+  assert!(res.is_ok(), "{:?}", res.err());
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn table_scan_test_autocommits_after_limit() {
+  let session = create_session_context();
+  let txn = session.new_active_transaction().unwrap();
+  create_items_table(&txn, "items").await;
+  session.commit_active_transaction().unwrap();
+
+  let res = session
+    .execute_sql(r#"SELECT * FROM items LIMIT 1"#)
+    .await
+    .unwrap()
+    .pop()
+    .unwrap();
+  assert_eq!(res.num_rows().await.unwrap(), 1);
+
+  // The statement should be committed once the cancelled scan releases
+  // its lock
+  let res = session
+    .execute_sql(r#"DELETE FROM items WHERE id < 10"#)
+    .await;
+  assert!(res.is_ok(), "{:?}", res.err());
+
+  let count = session
+    .execute_sql(r#"SELECT COUNT(*) FROM items"#)
+    .await
+    .unwrap()
+    .pop()
+    .unwrap()
+    .get_count()
+    .await
+    .unwrap();
+  assert_eq!(count as usize, ROW_COUNT - 10);
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn table_scan_test_modifies_all_scanned_rows() {
+  let session = create_session_context();
+  let txn = session.new_active_transaction().unwrap();
+  create_items_table(&txn, "items").await;
+  execute_query!(txn, r#"CREATE TABLE items_copy (id INT, name TEXT)"#)
+    .unwrap();
+
+  let res =
+    execute_query!(txn, r#"INSERT INTO items_copy SELECT * FROM items"#)
+      .unwrap();
+  assert_eq!(res.get_modified_rows(), Some(ROW_COUNT));
+
+  let res =
+    execute_query!(txn, r#"UPDATE items_copy SET id = id + 1"#).unwrap();
+  assert_eq!(res.get_modified_rows(), Some(ROW_COUNT));
+
+  let res = execute_query!(txn, r#"DELETE FROM items_copy"#).unwrap();
+  assert_eq!(res.get_modified_rows(), Some(ROW_COUNT));
+
+  let res = execute_query!(txn, r#"SELECT * FROM items_copy"#).unwrap();
+  assert_eq!(res.num_rows().await.unwrap(), 0);
+}
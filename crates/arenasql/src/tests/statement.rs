@@ -1,3 +1,6 @@
+use crate::ast::statement::{
+  get_savepoint_name, DatabaseStatement, StatementType,
+};
 use crate::tests::create_session_context;
 
 #[tokio::test(flavor = "multi_thread")]
@@ -9,3 +12,107 @@ async fn statement_test_begin_transaction() {
   let plan = txn.create_verified_logical_plan(stmt).await;
   assert!(plan.is_ok());
 }
+
+#[test]
+fn statement_test_parse_savepoint_statements() {
+  let statement_type = |sql: &str| {
+    let stmts = crate::ast::parse(sql).unwrap();
+    (
+      StatementType::from(&stmts[0]),
+      get_savepoint_name(&stmts[0]),
+    )
+  };
+
+  assert_eq!(
+    statement_type("SAVEPOINT sp"),
+    (StatementType::Savepoint, Some("sp".to_owned()))
+  );
+  assert_eq!(
+    statement_type("RELEASE SAVEPOINT sp"),
+    (StatementType::ReleaseSavepoint, Some("sp".to_owned()))
+  );
+  assert_eq!(
+    statement_type("RELEASE sp"),
+    (StatementType::ReleaseSavepoint, Some("sp".to_owned()))
+  );
+  assert_eq!(
+    statement_type("ROLLBACK TO SAVEPOINT sp"),
+    (StatementType::RollbackToSavepoint, Some("sp".to_owned()))
+  );
+  assert_eq!(
+    statement_type("ROLLBACK WORK TO sp"),
+    (StatementType::RollbackToSavepoint, Some("sp".to_owned()))
+  );
+  assert_eq!(statement_type("ROLLBACK"), (StatementType::Rollback, None));
+  assert_eq!(
+    statement_type(r#"RELEASE SAVEPOINT "Sp""#),
+    (StatementType::ReleaseSavepoint, Some("Sp".to_owned()))
+  );
+  assert_eq!(
+    statement_type("SAVEPOINT Sp"),
+    (StatementType::Savepoint, Some("sp".to_owned()))
+  );
+  // The variables that the savepoint statements are parsed into can't be
+  // set by users
+  assert_eq!(
+    statement_type(r#"SET LOCAL "arenasql.release_savepoint" = sp"#),
+    (StatementType::Set, None)
+  );
+  assert_eq!(
+    statement_type(r#"SET LOCAL "arenasql.rollback_to_savepoint" = sp"#),
+    (StatementType::Set, None)
+  );
+}
+
+#[test]
+fn statement_test_parse_database_statements() {
+  let parse = |sql: &str| {
+    let stmts = crate::ast::parse(sql).unwrap();
+    (
+      StatementType::from(&stmts[0]),
+      DatabaseStatement::from_statement(&stmts[0]),
+    )
+  };
+
+  assert_eq!(
+    parse("DROP DATABASE test_db"),
+    (
+      StatementType::Drop,
+      Some(DatabaseStatement::Drop {
+        name: "test_db".to_owned(),
+        if_exists: false
+      })
+    )
+  );
+  assert_eq!(
+    parse("DROP DATABASE IF EXISTS Test_DB"),
+    (
+      StatementType::Drop,
+      Some(DatabaseStatement::Drop {
+        name: "test_db".to_owned(),
+        if_exists: true
+      })
+    )
+  );
+  assert_eq!(
+    parse(r#"ALTER DATABASE test_db RENAME TO "New_DB""#),
+    (
+      StatementType::Alter,
+      Some(DatabaseStatement::Rename {
+        name: "test_db".to_owned(),
+        new_name: "New_DB".to_owned()
+      })
+    )
+  );
+  assert_eq!(parse("DROP TABLE test_db"), (StatementType::Drop, None));
+  // The statements that the database statements are parsed into can't be
+  // executed by users
+  assert_eq!(
+    parse(r#"EXECUTE "arenasql.drop_database"(test_db, true)"#),
+    (StatementType::Execute, None)
+  );
+  assert_eq!(
+    parse(r#"EXECUTE "arenasql.rename_database"(test_db, new_db)"#),
+    (StatementType::Execute, None)
+  );
+}
@@ -1,4 +1,6 @@
+use crate::execution::SessionContext;
 use crate::tests::create_session_context;
+use crate::Error;
 
 #[tokio::test(flavor = "multi_thread")]
 async fn transaction_autocommit_unchained_transaction() {
@@ -147,3 +149,165 @@ async fn transaction_create_index_after_committing_a_transaction() {
     .await;
   assert!(res.is_ok());
 }
+
+async fn count_rows(session: &SessionContext, query: &str) -> usize {
+  session
+    .execute_sql(query)
+    .await
+    .unwrap()
+    .pop()
+    .unwrap()
+    .num_rows()
+    .await
+    .unwrap()
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn transaction_rollback_to_savepoint_undoes_changes_after_savepoint() {
+  let session = create_session_context();
+
+  let _ = session.execute_sql(r#"BEGIN"#).await.unwrap();
+  let _ = session
+    .execute_sql(r#"CREATE TABLE test_table (id INT, name TEXT)"#)
+    .await
+    .unwrap();
+  let _ = session
+    .execute_sql(r#"INSERT INTO test_table VALUES (1, 'one')"#)
+    .await
+    .unwrap();
+  let _ = session.execute_sql(r#"SAVEPOINT first"#).await.unwrap();
+  let _ = session
+    .execute_sql(r#"INSERT INTO test_table VALUES (2, 'two')"#)
+    .await
+    .unwrap();
+  let _ = session
+    .execute_sql(r#"ROLLBACK TO SAVEPOINT first"#)
+    .await
+    .unwrap();
+  let _ = session
+    .execute_sql(r#"INSERT INTO test_table VALUES (3, 'three')"#)
+    .await
+    .unwrap();
+  let _ = session.execute_sql(r#"COMMIT"#).await.unwrap();
+
+  assert_eq!(count_rows(&session, "SELECT * FROM test_table").await, 2);
+  assert_eq!(
+    count_rows(&session, "SELECT * FROM test_table WHERE id = 2").await,
+    0
+  );
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn transaction_rollback_to_savepoint_undoes_created_table() {
+  let session = create_session_context();
+
+  let _ = session.execute_sql(r#"BEGIN"#).await.unwrap();
+  let _ = session.execute_sql(r#"SAVEPOINT first"#).await.unwrap();
+  let _ = session
+    .execute_sql(r#"CREATE TABLE test_table (id INT, name TEXT)"#)
+    .await
+    .unwrap();
+  let _ = session.execute_sql(r#"ROLLBACK TO first"#).await.unwrap();
+
+  let res = session.execute_sql("SELECT * FROM test_table").await;
+  assert!(
+    res.is_err(),
+    "Table created after savepoint shouldn't exist"
+  );
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn transaction_rollback_to_outer_savepoint_removes_inner_savepoints() {
+  let session = create_session_context();
+
+  let _ = session.execute_sql(r#"BEGIN"#).await.unwrap();
+  let _ = session
+    .execute_sql(r#"CREATE TABLE test_table (id INT, name TEXT)"#)
+    .await
+    .unwrap();
+  let _ = session.execute_sql(r#"SAVEPOINT outer_sp"#).await.unwrap();
+  let _ = session
+    .execute_sql(r#"INSERT INTO test_table VALUES (1, 'one')"#)
+    .await
+    .unwrap();
+  let _ = session.execute_sql(r#"SAVEPOINT inner_sp"#).await.unwrap();
+  let _ = session
+    .execute_sql(r#"INSERT INTO test_table VALUES (2, 'two')"#)
+    .await
+    .unwrap();
+
+  let _ = session
+    .execute_sql(r#"ROLLBACK TO SAVEPOINT inner_sp"#)
+    .await
+    .unwrap();
+  assert_eq!(count_rows(&session, "SELECT * FROM test_table").await, 1);
+
+  let _ = session
+    .execute_sql(r#"ROLLBACK TO SAVEPOINT outer_sp"#)
+    .await
+    .unwrap();
+  assert_eq!(count_rows(&session, "SELECT * FROM test_table").await, 0);
+
+  let res = session
+    .execute_sql(r#"ROLLBACK TO SAVEPOINT inner_sp"#)
+    .await;
+  assert!(
+    matches!(res, Err(Error::SavepointDoesntExist(_))),
+    "Inner savepoint should be removed after rolling back to outer savepoint"
+  );
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn transaction_release_savepoint_keeps_changes() {
+  let session = create_session_context();
+
+  let _ = session.execute_sql(r#"BEGIN"#).await.unwrap();
+  let _ = session
+    .execute_sql(r#"CREATE TABLE test_table (id INT, name TEXT)"#)
+    .await
+    .unwrap();
+  let _ = session.execute_sql(r#"SAVEPOINT first"#).await.unwrap();
+  let _ = session
+    .execute_sql(r#"INSERT INTO test_table VALUES (1, 'one')"#)
+    .await
+    .unwrap();
+  let _ = session
+    .execute_sql(r#"RELEASE SAVEPOINT first"#)
+    .await
+    .unwrap();
+
+  let res = session.execute_sql(r#"ROLLBACK TO SAVEPOINT first"#).await;
+  assert!(matches!(res, Err(Error::SavepointDoesntExist(_))));
+
+  let _ = session.execute_sql(r#"COMMIT"#).await.unwrap();
+  assert_eq!(count_rows(&session, "SELECT * FROM test_table").await, 1);
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn transaction_savepoint_names_are_case_insensitive() {
+  let session = create_session_context();
+
+  let _ = session.execute_sql(r#"BEGIN"#).await.unwrap();
+  let _ = session.execute_sql(r#"SAVEPOINT First"#).await.unwrap();
+  let _ = session.execute_sql(r#"SAVEPOINT "Second""#).await.unwrap();
+
+  let res = session.execute_sql(r#"RELEASE SAVEPOINT second"#).await;
+  assert!(matches!(res, Err(Error::SavepointDoesntExist(_))));
+  let _ = session
+    .execute_sql(r#"RELEASE SAVEPOINT "Second""#)
+    .await
+    .unwrap();
+  let _ = session
+    .execute_sql(r#"RELEASE SAVEPOINT first"#)
+    .await
+    .unwrap();
+  let _ = session.execute_sql(r#"COMMIT"#).await.unwrap();
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn transaction_savepoint_requires_transaction_block() {
+  let session = create_session_context();
+
+  let res = session.execute_sql(r#"SAVEPOINT first"#).await;
+  assert!(matches!(res, Err(Error::InvalidTransactionState(_))));
+}
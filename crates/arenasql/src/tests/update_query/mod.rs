@@ -1,3 +1,6 @@
+use datafusion::arrow::array::as_primitive_array;
+use datafusion::arrow::datatypes::Int32Type;
+
 use crate::execute_query;
 use crate::tests::create_session_context;
 
@@ -132,3 +135,47 @@ async fn update_test_update_multiple_columns_and_multiple_rows() {
     "Select returned more than one row"
   );
 }
+
+#[tokio::test(flavor = "multi_thread")]
+async fn update_test_returning_updated_rows() {
+  let session = create_session_context();
+  let txn = session.new_active_transaction().unwrap();
+
+  execute_query!(
+    txn,
+    r#"CREATE TABLE IF NOT EXISTS test_table (
+      id VARCHAR(50),
+      name TEXT,
+      age INTEGER
+    )"#
+  )
+  .unwrap();
+
+  execute_query!(
+    txn,
+    r#"INSERT INTO test_table(id, name, age)
+      VALUES('id_1', 'name 1', 10),
+      ('id_2', 'name', 20),
+      ('id_3', 'name 3', 30)"#
+  )
+  .unwrap();
+
+  let res = execute_query!(
+    txn,
+    r#"UPDATE test_table SET age = age + 1 WHERE id > 'id_1'
+      RETURNING test_table.id, age"#
+  )
+  .unwrap();
+  assert_eq!(res.get_modified_rows(), Some(2));
+
+  let batches = res.collect_batches().await.unwrap();
+  let ages = batches
+    .iter()
+    .flat_map(|batch| {
+      as_primitive_array::<Int32Type>(batch.column(1))
+        .values()
+        .to_vec()
+    })
+    .collect::<Vec<i32>>();
+  assert_eq!(ages, vec![21, 31], "Updated values should be returned");
+}
@@ -1,3 +1,9 @@
+use datafusion::arrow::array::{
+  as_primitive_array, as_string_array, Array, ListArray,
+};
+use datafusion::arrow::datatypes::Float32Type;
+
+use crate::execution::SessionContext;
 use crate::tests::create_session_context;
 
 #[tokio::test(flavor = "multi_thread")]
@@ -40,3 +46,418 @@ async fn vector_create_hnsw_index() {
     .await;
   assert!(res.is_ok());
 }
+
+#[tokio::test(flavor = "multi_thread")]
+async fn vector_hnsw_index_nearest_neighbour_search() {
+  let session = create_session_context();
+
+  session
+    .execute_sql(
+      r#"CREATE TABLE IF NOT EXISTS vectors (
+      id VARCHAR(50),
+      embeddings VECTOR(4)
+    )"#,
+    )
+    .await
+    .unwrap();
+
+  session
+    .execute_sql(
+      "INSERT INTO vectors VALUES
+      ('id1', [0.1, 0.1, 0.1, 0.1]),
+      ('id2', [1.0, 1.0, 1.0, 1.0])",
+    )
+    .await
+    .unwrap();
+
+  session
+    .execute_sql(
+      r#"CREATE INDEX vectors_index ON vectors
+      USING hnsw (embeddings)
+      WITH (metric = 'l2', m = 4, ef_construction = 16, ef = 8, dim = 4)"#,
+    )
+    .await
+    .unwrap();
+
+  // Insert after creating the index to make sure new rows are indexed
+  session
+    .execute_sql("INSERT INTO vectors VALUES ('id3', [2.0, 2.0, 2.0, 2.0])")
+    .await
+    .unwrap();
+
+  assert_eq!(get_nearest_id(&session).await, "id1");
+
+  session
+    .execute_sql("DELETE FROM vectors WHERE id = 'id1'")
+    .await
+    .unwrap();
+  assert_eq!(get_nearest_id(&session).await, "id2");
+
+  session
+    .execute_sql(
+      "UPDATE vectors SET embeddings = [5.0, 5.0, 5.0, 5.0] WHERE id = 'id2'",
+    )
+    .await
+    .unwrap();
+  assert_eq!(get_nearest_id(&session).await, "id3");
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn vector_hnsw_index_namespaced_search() {
+  let session = create_session_context();
+
+  session
+    .execute_sql(
+      r#"CREATE TABLE IF NOT EXISTS vectors (
+      id VARCHAR(50),
+      parent_id VARCHAR(50),
+      embeddings VECTOR(4)
+    )"#,
+    )
+    .await
+    .unwrap();
+
+  session
+    .execute_sql(
+      r#"CREATE INDEX vectors_index ON vectors
+      USING hnsw (embeddings)
+      WITH (
+        metric = 'l2',
+        namespace = 'parent_id',
+        m = 4,
+        ef_construction = 16,
+        ef = 8,
+        dim = 4
+      )"#,
+    )
+    .await
+    .unwrap();
+
+  session
+    .execute_sql(
+      "INSERT INTO vectors VALUES
+      ('id1', 'p1', [0.1, 0.1, 0.1, 0.1]),
+      ('id2', 'p1', [1.0, 1.0, 1.0, 1.0]),
+      ('id3', 'p2', [5.0, 5.0, 5.0, 5.0]),
+      ('id4', 'p2', [2.0, 2.0, 2.0, 2.0])",
+    )
+    .await
+    .unwrap();
+
+  let query = r#"SELECT id FROM vectors
+    WHERE parent_id = 'p2'
+    ORDER BY embeddings <-> '[0.1, 0.1, 0.1, 0.1]'
+    LIMIT 1"#;
+  assert_eq!(get_first_id(&session, query).await, "id4");
+  // Without the namespace filter, all the rows are searched
+  assert_eq!(get_nearest_id(&session).await, "id1");
+
+  // Moving the row to another namespace should move it in the index too
+  session
+    .execute_sql("UPDATE vectors SET parent_id = 'p2' WHERE id = 'id1'")
+    .await
+    .unwrap();
+  assert_eq!(get_first_id(&session, query).await, "id1");
+
+  session
+    .execute_sql("DELETE FROM vectors WHERE id = 'id1'")
+    .await
+    .unwrap();
+  assert_eq!(get_first_id(&session, query).await, "id4");
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn vector_hnsw_index_without_retaining_vectors() {
+  let session = create_session_context();
+
+  session
+    .execute_sql(
+      r#"CREATE TABLE IF NOT EXISTS vectors (
+      id VARCHAR(50),
+      embeddings VECTOR(4)
+    )"#,
+    )
+    .await
+    .unwrap();
+
+  session
+    .execute_sql(
+      "INSERT INTO vectors VALUES
+      ('id1', [0.1, 0.1, 0.1, 0.1]),
+      ('id2', NULL)",
+    )
+    .await
+    .unwrap();
+
+  session
+    .execute_sql(
+      r#"CREATE INDEX vectors_index ON vectors
+      USING hnsw (embeddings)
+      WITH (
+        metric = 'l2',
+        m = 4,
+        ef_construction = 16,
+        ef = 8,
+        dim = 4,
+        retain_vectors = false
+      )"#,
+    )
+    .await
+    .unwrap();
+
+  session
+    .execute_sql("INSERT INTO vectors VALUES ('id3', [2.0, 2.0, 2.0, 2.0])")
+    .await
+    .unwrap();
+  session
+    .execute_sql("UPDATE vectors SET id = 'id4' WHERE id = 'id3'")
+    .await
+    .unwrap();
+
+  let mut res = session
+    .execute_sql("SELECT embeddings FROM vectors ORDER BY id")
+    .await
+    .unwrap();
+  let batch = res
+    .pop()
+    .unwrap()
+    .collect_batches()
+    .await
+    .unwrap()
+    .pop()
+    .unwrap();
+
+  let embeddings = batch
+    .column(0)
+    .as_any()
+    .downcast_ref::<ListArray>()
+    .unwrap();
+  assert_eq!(
+    embeddings,
+    &ListArray::from_iter_primitive::<Float32Type, _, _>(vec![
+      Some(vec![Some(0.1), Some(0.1), Some(0.1), Some(0.1)]),
+      None,
+      Some(vec![Some(2.0), Some(2.0), Some(2.0), Some(2.0)]),
+    ])
+  );
+  assert_eq!(embeddings.null_count(), 1);
+  assert_eq!(get_nearest_id(&session).await, "id1");
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn vector_hnsw_index_on_quantized_vectors() {
+  let session = create_session_context();
+
+  session
+    .execute_sql(
+      r#"CREATE TABLE IF NOT EXISTS vectors (
+      id VARCHAR(50),
+      embeddings VECTOR(4, int8)
+    )"#,
+    )
+    .await
+    .unwrap();
+
+  session
+    .execute_sql(
+      r#"CREATE INDEX vectors_index ON vectors
+      USING hnsw (embeddings)
+      WITH (
+        metric = 'l2',
+        m = 4,
+        ef_construction = 16,
+        ef = 8,
+        dim = 4,
+        retain_vectors = false,
+        rerank = true
+      )"#,
+    )
+    .await
+    .unwrap();
+
+  session
+    .execute_sql(
+      "INSERT INTO vectors VALUES
+      ('id1', [0.1, 0.1, 0.1, 0.1]),
+      ('id2', [1.0, 1.0, 1.0, 1.0]),
+      ('id3', [2.0, 2.0, 2.0, 2.0])",
+    )
+    .await
+    .unwrap();
+  assert_eq!(get_nearest_id(&session).await, "id1");
+
+  // Stripped vectors must be restored from the index in the compact form
+  let mut res = session
+    .execute_sql("SELECT embeddings FROM vectors WHERE id = 'id3'")
+    .await
+    .unwrap();
+  let batch = res
+    .pop()
+    .unwrap()
+    .collect_batches()
+    .await
+    .unwrap()
+    .pop()
+    .unwrap();
+  let embeddings = batch
+    .column(0)
+    .as_any()
+    .downcast_ref::<ListArray>()
+    .unwrap()
+    .value(0);
+  let embeddings = as_primitive_array::<Float32Type>(&embeddings);
+  assert_eq!(embeddings.len(), 4);
+  assert!(embeddings.iter().all(|v| (v.unwrap() - 2.0).abs() < 0.01));
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn vector_hnsw_index_drop_namespace_column_without_retaining_vectors() {
+  let session = create_session_context();
+
+  session
+    .execute_sql(
+      r#"CREATE TABLE IF NOT EXISTS vectors (
+      id VARCHAR(50),
+      parent_id VARCHAR(50),
+      embeddings VECTOR(4)
+    )"#,
+    )
+    .await
+    .unwrap();
+
+  session
+    .execute_sql(
+      r#"CREATE INDEX vectors_index ON vectors
+      USING hnsw (embeddings)
+      WITH (
+        metric = 'l2',
+        namespace = 'parent_id',
+        m = 4,
+        ef_construction = 16,
+        ef = 8,
+        dim = 4,
+        retain_vectors = false
+      )"#,
+    )
+    .await
+    .unwrap();
+
+  session
+    .execute_sql(
+      "INSERT INTO vectors VALUES
+      ('id1', 'p1', [0.1, 0.1, 0.1, 0.1]),
+      ('id2', NULL, [1.0, 1.0, 1.0, 1.0]),
+      ('id3', 'p2', [2.0, 2.0, 2.0, 2.0])",
+    )
+    .await
+    .unwrap();
+
+  // Dropping the namespace column drops the index, so the vectors that
+  // were only stored in the index must be put back into the rows
+  session
+    .execute_sql("ALTER TABLE vectors DROP COLUMN parent_id")
+    .await
+    .unwrap();
+
+  let mut res = session
+    .execute_sql("SELECT embeddings FROM vectors ORDER BY id")
+    .await
+    .unwrap();
+  let batch = res
+    .pop()
+    .unwrap()
+    .collect_batches()
+    .await
+    .unwrap()
+    .pop()
+    .unwrap();
+  let embeddings = batch
+    .column(0)
+    .as_any()
+    .downcast_ref::<ListArray>()
+    .unwrap();
+  assert_eq!(
+    embeddings,
+    &ListArray::from_iter_primitive::<Float32Type, _, _>(vec![
+      Some(vec![Some(0.1), Some(0.1), Some(0.1), Some(0.1)]),
+      Some(vec![Some(1.0), Some(1.0), Some(1.0), Some(1.0)]),
+      Some(vec![Some(2.0), Some(2.0), Some(2.0), Some(2.0)]),
+    ])
+  );
+  assert_eq!(get_nearest_id(&session).await, "id1");
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn vector_hnsw_index_alter_namespace_column_type() {
+  let session = create_session_context();
+
+  session
+    .execute_sql(
+      r#"CREATE TABLE IF NOT EXISTS vectors (
+      id VARCHAR(50),
+      parent_id INT,
+      embeddings VECTOR(4)
+    )"#,
+    )
+    .await
+    .unwrap();
+
+  session
+    .execute_sql(
+      r#"CREATE INDEX vectors_index ON vectors
+      USING hnsw (embeddings)
+      WITH (
+        metric = 'l2',
+        namespace = 'parent_id',
+        m = 4,
+        ef_construction = 16,
+        ef = 8,
+        dim = 4,
+        retain_vectors = false
+      )"#,
+    )
+    .await
+    .unwrap();
+
+  session
+    .execute_sql("INSERT INTO vectors VALUES ('id1', 1, [0.1, 0.1, 0.1, 0.1])")
+    .await
+    .unwrap();
+
+  // Changing the type changes the serialized namespace of the rows in
+  // the index, so it isn't allowed
+  let res = session
+    .execute_sql("ALTER TABLE vectors ALTER COLUMN parent_id TYPE BIGINT")
+    .await;
+  assert!(res.is_err());
+
+  let query = r#"SELECT id FROM vectors
+    WHERE parent_id = 1
+    ORDER BY embeddings <-> '[0.1, 0.1, 0.1, 0.1]'
+    LIMIT 1"#;
+  assert_eq!(get_first_id(&session, query).await, "id1");
+}
+
+async fn get_nearest_id(session: &SessionContext) -> String {
+  get_first_id(
+    session,
+    r#"SELECT id FROM vectors
+      ORDER BY l2(embeddings, '[0.1, 0.1, 0.1, 0.1]')
+      LIMIT 1"#,
+  )
+  .await
+}
+
+async fn get_first_id(session: &SessionContext, query: &str) -> String {
+  let mut res = session.execute_sql(query).await.unwrap();
+  let batch = res
+    .pop()
+    .unwrap()
+    .collect_batches()
+    .await
+    .unwrap()
+    .pop()
+    .unwrap();
+  assert_eq!(batch.num_rows(), 1, "Expected only one row");
+  as_string_array(batch.column(0)).value(0).to_owned()
+}
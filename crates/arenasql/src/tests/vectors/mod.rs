@@ -1,6 +1,8 @@
 use std::sync::Arc;
 
-use datafusion::arrow::array::{as_string_array, ListArray};
+use datafusion::arrow::array::{
+  as_primitive_array, as_string_array, ListArray,
+};
 use datafusion::arrow::datatypes::Float32Type;
 use datafusion::scalar::ScalarValue;
 
@@ -53,7 +55,7 @@ async fn vector_column_type_test_l2_distance() {
 
   assert_eq!(batch.num_rows(), 1, "Expected count(id) to be 1");
   let id = as_string_array(batch.column(0)).value(0);
-  assert_eq!(id, "id1");
+  assert_eq!(id, "id2");
 }
 
 #[tokio::test(flavor = "multi_thread")]
@@ -100,7 +102,116 @@ async fn vector_column_type_test_l2_distance_sorting() {
 
   assert_eq!(batch.num_rows(), 1, "Expected count(id) to be 1");
   let id = as_string_array(batch.column(0)).value(0);
-  assert_eq!(id, "id1");
+  assert_eq!(id, "id2");
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn vector_column_type_test_inner_product_and_cosine_similarity() {
+  let session = create_session_context();
+
+  session
+    .execute_sql(
+      r#"CREATE TABLE IF NOT EXISTS vectors (
+      id VARCHAR(50),
+      embeddings VECTOR(4)
+    )"#,
+    )
+    .await
+    .unwrap();
+
+  session
+    .execute_sql(&format!(
+      "INSERT INTO vectors VALUES
+      ('id1', [0.1, 0.2, 0.3,0.4]),
+      ('id2', [1.4, 1.3, 1.2, 1.1])"
+    ))
+    .await
+    .unwrap();
+
+  for (query, expected_id) in [
+    (
+      r#"SELECT id FROM vectors
+        ORDER BY inner_product(embeddings, '[-1.0, -1.0, -1.0, -1.0]') DESC
+        LIMIT 1"#,
+      "id1",
+    ),
+    (
+      r#"SELECT id FROM vectors
+        ORDER BY cosine_similarity(embeddings, '[1.0, 1.0, 1.0, 1.0]') DESC
+        LIMIT 1"#,
+      "id2",
+    ),
+  ] {
+    let mut res = session.execute_sql(query).await.unwrap();
+    let batch = res
+      .pop()
+      .unwrap()
+      .collect_batches()
+      .await
+      .unwrap()
+      .pop()
+      .unwrap();
+
+    assert_eq!(batch.num_rows(), 1, "Expected count(id) to be 1");
+    let id = as_string_array(batch.column(0)).value(0);
+    assert_eq!(id, expected_id);
+  }
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn vector_column_type_test_pgvector_operators() {
+  let session = create_session_context();
+
+  session
+    .execute_sql(
+      r#"CREATE TABLE IF NOT EXISTS vectors (
+      id VARCHAR(50),
+      embeddings VECTOR(4)
+    )"#,
+    )
+    .await
+    .unwrap();
+
+  session
+    .execute_sql(&format!(
+      "INSERT INTO vectors VALUES
+      ('id1', '[0.1, 0.2, 0.3,0.4]'::vector),
+      ('id2', '[1.4, 1.3, 1.2, 1.1]'::vector(4))"
+    ))
+    .await
+    .unwrap();
+
+  for (query, expected_id) in [
+    (
+      r#"SELECT id FROM vectors
+        ORDER BY embeddings <-> '[1.0, 1.0, 1.0, 1.0]' LIMIT 1"#,
+      "id2",
+    ),
+    (
+      r#"SELECT id FROM vectors
+        ORDER BY embeddings <#> '[-1, -1, -1, -1]'::vector LIMIT 1"#,
+      "id1",
+    ),
+    (
+      r#"SELECT id FROM vectors
+        ORDER BY '[1, 1, 1, 1]'::vector <=> embeddings LIMIT 1"#,
+      "id2",
+    ),
+  ] {
+    let mut res = session.execute_sql(query).await.unwrap();
+    let batch = res
+      .pop()
+      .unwrap()
+      .collect_batches()
+      .await
+      .unwrap()
+      .pop()
+      .unwrap();
+
+    assert_eq!(batch.num_rows(), 1, "Expected count(id) to be 1");
+    let id = as_string_array(batch.column(0)).value(0);
+    assert_eq!(id, expected_id);
+  }
 }
 
 #[tokio::test(flavor = "multi_thread")]
@@ -118,6 +229,76 @@ async fn vector_column_type_test_create_table_with_odd_length_vector_column() {
   assert!(res.is_err());
 }
 
+#[tokio::test(flavor = "multi_thread")]
+async fn vector_column_type_test_quantized_vectors() {
+  let session = create_session_context();
+
+  session
+    .execute_sql(
+      r#"CREATE TABLE IF NOT EXISTS vectors (
+      id VARCHAR(50),
+      bf16_embeddings VECTOR(4, bf16),
+      int8_embeddings VECTOR(4, INT8)
+    )"#,
+    )
+    .await
+    .unwrap();
+
+  session
+    .execute_sql(&format!(
+      "INSERT INTO vectors VALUES
+      ('id1', [0.1, 0.2, 0.3, 0.4], [0.1, 0.2, 0.3, 0.4]),
+      ('id2', [1.4, 1.3, 1.2, 1.1], [1.4, 1.3, 1.2, 1.1])"
+    ))
+    .await
+    .unwrap();
+
+  for column in ["bf16_embeddings", "int8_embeddings"] {
+    let mut res = session
+      .execute_sql(&format!(
+        "SELECT id, {column} FROM vectors
+        ORDER BY l2({column}, '[1.0, 1.0, 1.0, 1.0]') LIMIT 1"
+      ))
+      .await
+      .unwrap();
+    let batch = res
+      .pop()
+      .unwrap()
+      .collect_batches()
+      .await
+      .unwrap()
+      .pop()
+      .unwrap();
+
+    assert_eq!(as_string_array(batch.column(0)).value(0), "id2");
+    let embeddings = batch
+      .column(1)
+      .as_any()
+      .downcast_ref::<ListArray>()
+      .unwrap()
+      .value(0);
+    let embeddings = as_primitive_array::<Float32Type>(&embeddings);
+    for (value, expected) in embeddings.iter().zip([1.4, 1.3, 1.2, 1.1]) {
+      assert!((value.unwrap() - expected).abs() < 0.01);
+    }
+  }
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn vector_column_type_test_invalid_vector_element_type() {
+  let session = create_session_context();
+
+  let res = session
+    .execute_sql(
+      r#"CREATE TABLE IF NOT EXISTS vectors (
+      id VARCHAR(50),
+      embeddings VECTOR(4, int4)
+    )"#,
+    )
+    .await;
+  assert!(res.is_err());
+}
+
 #[tokio::test(flavor = "multi_thread")]
 async fn vector_column_type_test_params() {
   let session = create_session_context();
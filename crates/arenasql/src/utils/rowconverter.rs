@@ -29,6 +29,11 @@ pub fn convert_to_rows<'a>(
   let mut serialized_col_vecs = columns
     .iter()
     .map(|col| {
+      // Archived columns aren't in the batch and their values are
+      // always set to NULL
+      if col.archived() {
+        return Ok(vec![OwnedSerializedCell::Null; row_count]);
+      }
       let values = batch.column_by_name(&col.name).map(|columns_data| {
         let cell = OwnedSerializedCell::column_array_to_vec(
           &table.name,
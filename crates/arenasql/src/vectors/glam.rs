@@ -3,7 +3,7 @@ use std::ops::AddAssign;
 use glam::Vec4;
 use ordered_float::OrderedFloat;
 
-use super::scoring::{Score, SimilarityScorer};
+use super::scoring::{cosine_similarity, Score, SimilarityScorer};
 
 #[derive(Clone)]
 pub struct GlamDotSimilarity;
@@ -42,3 +42,57 @@ impl SimilarityScorer for GlamDotSimilarity {
     )
   }
 }
+
+#[derive(Clone)]
+pub struct GlamCosineSimilarity;
+
+impl SimilarityScorer for GlamCosineSimilarity {
+  fn similarity_score(&self, vector: &[f32], query: &[f32]) -> Score {
+    let zero = Vec4::from_slice(&[0.0, 0.0, 0.0, 0.0]);
+    let (dot, vector_norm, query_norm) = query
+      .chunks_exact(4)
+      .map(Vec4::from_slice)
+      .zip(vector.chunks_exact(4).map(Vec4::from_slice))
+      .fold(
+        (zero, zero, zero),
+        |(mut dot, mut vector_norm, mut query_norm), (a, b)| {
+          dot.add_assign(a * b);
+          vector_norm.add_assign(b * b);
+          query_norm.add_assign(a * a);
+          (dot, vector_norm, query_norm)
+        },
+      );
+    cosine_similarity(
+      dot.to_array().iter().sum(),
+      vector_norm.to_array().iter().sum(),
+      query_norm.to_array().iter().sum(),
+    )
+  }
+}
+
+/// Negative of the squared euclidean distance is used as the similarity
+/// score so that the closer vectors have higher score
+#[derive(Clone)]
+pub struct GlamL2Similarity;
+
+impl SimilarityScorer for GlamL2Similarity {
+  fn similarity_score(&self, vector: &[f32], query: &[f32]) -> Score {
+    OrderedFloat(
+      -query
+        .chunks_exact(4)
+        .map(Vec4::from_slice)
+        .zip(vector.chunks_exact(4).map(Vec4::from_slice))
+        .fold(
+          Vec4::from_slice(&[0.0, 0.0, 0.0, 0.0]),
+          |mut agg, (a, b)| {
+            let diff = a - b;
+            agg.add_assign(diff * diff);
+            agg
+          },
+        )
+        .to_array()
+        .iter()
+        .sum::<f32>(),
+    )
+  }
+}
@@ -0,0 +1,561 @@
+use std::borrow::Cow;
+use std::cmp::Reverse;
+use std::collections::{BinaryHeap, HashSet};
+
+use ordered_float::OrderedFloat;
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+
+use crate::schema::VectorMetric;
+use crate::vectors::{
+  QuantizedVector, Score, SimilarityScorer, SimilarityScorerFactory,
+  SimilarityType,
+};
+use crate::Result;
+
+/// Id of the node in the graph. This is the row id of the row
+/// that the vector belongs to
+pub type NodeId = u64;
+
+/// Max number of layers of the graph. With `m >= 2`, the probability
+/// of a node being assigned a level higher than this is negligible
+const MAX_LEVEL: usize = 16;
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct HnswNode {
+  pub vector: HnswVector,
+  /// Neighbours of the node in each layer starting from layer 0.
+  /// So, the level of the node is `neighbours.len() - 1`
+  pub neighbours: Vec<Vec<NodeId>>,
+}
+
+impl HnswNode {
+  #[inline]
+  pub fn level(&self) -> usize {
+    self.neighbours.len() - 1
+  }
+}
+
+/// Vector of a node. Quantized vectors are kept in the compact form and
+/// the graph is traversed using the scores of the compact vectors
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub enum HnswVector {
+  Float32(Vec<f32>),
+  Quantized(QuantizedVector),
+}
+
+impl HnswVector {
+  pub fn to_f32(&self) -> Cow<[f32]> {
+    match self {
+      Self::Float32(vector) => Cow::Borrowed(vector),
+      Self::Quantized(vector) => Cow::Owned(vector.to_f32()),
+    }
+  }
+
+  /// Converts the f32 vector to the same format as this vector
+  pub fn convert_like(&self, vector: &[f32]) -> Self {
+    match self {
+      Self::Float32(_) => Self::Float32(vector.to_vec()),
+      Self::Quantized(quantized) => {
+        Self::Quantized(quantized.quantize_like(vector))
+      }
+    }
+  }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct EntryPoint {
+  pub id: NodeId,
+  pub level: usize,
+}
+
+/// Storage used by the HNSW graph. The graph itself doesn't keep any
+/// state in memory, so every node is loaded from the store when needed
+pub trait HnswGraphStore {
+  fn get_entry_point(&self) -> Result<Option<EntryPoint>>;
+
+  fn put_entry_point(&self, entry_point: Option<&EntryPoint>) -> Result<()>;
+
+  fn get_node(&self, id: NodeId) -> Result<Option<HnswNode>>;
+
+  fn put_node(&self, id: NodeId, node: &HnswNode) -> Result<()>;
+
+  fn delete_node(&self, id: NodeId) -> Result<()>;
+
+  /// Returns any node of the graph. This is used to find a new entry
+  /// point when the entry point is deleted and it doesn't have any
+  /// neighbours left
+  fn find_any_node(&self) -> Result<Option<(NodeId, HnswNode)>>;
+}
+
+#[derive(Debug, Clone)]
+pub struct HnswParams {
+  pub metric: VectorMetric,
+  /// Max number of neighbours per node in the layers above 0.
+  /// Layer 0 allows `2 * m` neighbours
+  pub m: usize,
+  pub ef_construction: usize,
+  pub ef: usize,
+  /// If set, the `ef` nearest nodes found using the quantized vectors are
+  /// sorted again using the full precision query before taking the top k.
+  /// This has no effect if the vectors aren't quantized
+  pub rerank: bool,
+}
+
+/// Hierarchical Navigable Small World graph
+/// https://arxiv.org/abs/1603.09320
+///
+/// Note: the graph links aren't guaranteed to be symmetric, so a node might
+/// still be referenced by other nodes after it's deleted. Those dangling
+/// links are skipped when traversing the graph.
+pub struct HnswGraph<'a, S: HnswGraphStore + ?Sized> {
+  store: &'a S,
+  params: HnswParams,
+  scorer: &'a dyn SimilarityScorer,
+}
+
+impl<'a, S: HnswGraphStore + ?Sized> HnswGraph<'a, S> {
+  pub fn new(store: &'a S, params: HnswParams) -> Self {
+    let scorer = SimilarityScorerFactory::get_default(SimilarityType::from(
+      &params.metric,
+    ));
+    Self {
+      store,
+      params,
+      scorer,
+    }
+  }
+
+  pub fn insert(&self, id: NodeId, vector: HnswVector) -> Result<()> {
+    let level = self.random_level();
+    let mut node = HnswNode {
+      vector,
+      neighbours: vec![vec![]; level + 1],
+    };
+
+    let entry_point = self.store.get_entry_point()?;
+    let mut entry_points = match &entry_point {
+      Some(entry_point) => {
+        self.distance_to_node(&node.vector, entry_point.id)?
+      }
+      None => vec![],
+    };
+    let Some(entry_point) = entry_point.filter(|_| !entry_points.is_empty())
+    else {
+      self.store.put_node(id, &node)?;
+      return self.store.put_entry_point(Some(&EntryPoint { id, level }));
+    };
+
+    for layer in (level + 1..=entry_point.level).rev() {
+      entry_points = self.search_layer(&node.vector, entry_points, 1, layer)?;
+    }
+
+    for layer in (0..=level.min(entry_point.level)).rev() {
+      let candidates = self.search_layer(
+        &node.vector,
+        entry_points,
+        self.params.ef_construction,
+        layer,
+      )?;
+      node.neighbours[layer] = candidates
+        .iter()
+        .filter(|(_, candidate)| *candidate != id)
+        .take(self.max_connections(layer))
+        .map(|(_, candidate)| *candidate)
+        .collect();
+      for neighbour in &node.neighbours[layer] {
+        self.connect(*neighbour, id, &node.vector, layer)?;
+      }
+      entry_points = candidates;
+    }
+
+    self.store.put_node(id, &node)?;
+    if level > entry_point.level {
+      self
+        .store
+        .put_entry_point(Some(&EntryPoint { id, level }))?;
+    }
+    Ok(())
+  }
+
+  pub fn delete(&self, id: NodeId) -> Result<()> {
+    let Some(node) = self.store.get_node(id)? else {
+      return Ok(());
+    };
+    self.store.delete_node(id)?;
+
+    // Repair the neighbourhood by linking the neighbours of the deleted
+    // node to each other
+    for (layer, neighbours) in node.neighbours.iter().enumerate() {
+      for neighbour_id in neighbours {
+        let Some(mut neighbour) = self.store.get_node(*neighbour_id)? else {
+          continue;
+        };
+        if neighbour.neighbours.len() <= layer {
+          continue;
+        }
+        neighbour.neighbours[layer].retain(|n| *n != id);
+
+        let mut replacements = Vec::new();
+        for candidate_id in neighbours {
+          if candidate_id == neighbour_id
+            || neighbour.neighbours[layer].contains(candidate_id)
+          {
+            continue;
+          }
+          if let Some(candidate) = self.store.get_node(*candidate_id)? {
+            replacements.push((
+              self.distance(&neighbour.vector, &candidate.vector),
+              *candidate_id,
+            ));
+          }
+        }
+        replacements.sort();
+
+        let max_connections = self.max_connections(layer);
+        for (_, replacement) in replacements {
+          if neighbour.neighbours[layer].len() >= max_connections {
+            break;
+          }
+          neighbour.neighbours[layer].push(replacement);
+        }
+        self.store.put_node(*neighbour_id, &neighbour)?;
+      }
+    }
+
+    match self.store.get_entry_point()? {
+      Some(entry_point) if entry_point.id == id => {
+        // Use the neighbour from the highest layer as the new entry point
+        let mut new_entry_point = None;
+        for neighbour_id in node.neighbours.iter().rev().flatten() {
+          if let Some(neighbour) = self.store.get_node(*neighbour_id)? {
+            new_entry_point = Some(EntryPoint {
+              id: *neighbour_id,
+              level: neighbour.level(),
+            });
+            break;
+          }
+        }
+        if new_entry_point.is_none() {
+          new_entry_point =
+            self.store.find_any_node()?.map(|(id, node)| EntryPoint {
+              id,
+              level: node.level(),
+            });
+        }
+        self.store.put_entry_point(new_entry_point.as_ref())
+      }
+      _ => Ok(()),
+    }
+  }
+
+  /// Returns the (approximate) k nearest nodes to the query sorted by
+  /// the distance in ascending order
+  pub fn search(
+    &self,
+    query: &[f32],
+    k: usize,
+  ) -> Result<Vec<(Score, NodeId)>> {
+    let Some(entry_point) = self.store.get_entry_point()? else {
+      return Ok(vec![]);
+    };
+    let Some(entry_node) = self.store.get_node(entry_point.id)? else {
+      return Ok(vec![]);
+    };
+    // All the vectors of the graph have the same format, so convert the
+    // query to the format of the entry point
+    let converted_query = entry_node.vector.convert_like(query);
+    let mut entry_points = vec![(
+      self.distance(&converted_query, &entry_node.vector),
+      entry_point.id,
+    )];
+
+    for layer in (1..=entry_point.level).rev() {
+      entry_points =
+        self.search_layer(&converted_query, entry_points, 1, layer)?;
+    }
+    let mut nearest = self.search_layer(
+      &converted_query,
+      entry_points,
+      self.params.ef.max(k),
+      0,
+    )?;
+    if self.params.rerank {
+      nearest = self.rerank(query, nearest)?;
+    }
+    nearest.truncate(k);
+    Ok(nearest)
+  }
+
+  /// Sorts the nodes by the distance calculated using the full precision
+  /// query and the dequantized vectors
+  fn rerank(
+    &self,
+    query: &[f32],
+    nodes: Vec<(Score, NodeId)>,
+  ) -> Result<Vec<(Score, NodeId)>> {
+    let mut reranked = Vec::with_capacity(nodes.len());
+    for (_, id) in nodes {
+      if let Some(node) = self.store.get_node(id)? {
+        let score = self.scorer.similarity_score(&node.vector.to_f32(), query);
+        reranked.push((self.score_to_distance(score), id));
+      }
+    }
+    reranked.sort();
+    Ok(reranked)
+  }
+
+  /// Returns upto `ef` nodes closest to the query in the given layer
+  /// sorted by the distance in ascending order
+  fn search_layer(
+    &self,
+    query: &HnswVector,
+    entry_points: Vec<(Score, NodeId)>,
+    ef: usize,
+    layer: usize,
+  ) -> Result<Vec<(Score, NodeId)>> {
+    let mut visited: HashSet<NodeId> =
+      entry_points.iter().map(|(_, id)| *id).collect();
+    let mut candidates: BinaryHeap<Reverse<(Score, NodeId)>> =
+      entry_points.iter().map(|c| Reverse(*c)).collect();
+    // max-heap so that the furthest node can be removed
+    let mut nearest: BinaryHeap<(Score, NodeId)> =
+      entry_points.into_iter().collect();
+
+    while let Some(Reverse((distance, id))) = candidates.pop() {
+      let furthest = nearest.peek().map(|n| n.0);
+      if nearest.len() >= ef && Some(distance) > furthest {
+        break;
+      }
+
+      let Some(node) = self.store.get_node(id)? else {
+        continue;
+      };
+      let Some(neighbours) = node.neighbours.get(layer) else {
+        continue;
+      };
+      for neighbour_id in neighbours {
+        if !visited.insert(*neighbour_id) {
+          continue;
+        }
+        let Some(neighbour) = self.store.get_node(*neighbour_id)? else {
+          continue;
+        };
+        let distance = self.distance(query, &neighbour.vector);
+        if nearest.len() < ef || Some(&distance) < nearest.peek().map(|n| &n.0)
+        {
+          candidates.push(Reverse((distance, *neighbour_id)));
+          nearest.push((distance, *neighbour_id));
+          if nearest.len() > ef {
+            nearest.pop();
+          }
+        }
+      }
+    }
+    Ok(nearest.into_sorted_vec())
+  }
+
+  /// Adds `new_id` to the neighbours of the given node and drops the
+  /// furthest neighbour if the node has more than max allowed neighbours
+  fn connect(
+    &self,
+    id: NodeId,
+    new_id: NodeId,
+    new_vector: &HnswVector,
+    layer: usize,
+  ) -> Result<()> {
+    let Some(mut node) = self.store.get_node(id)? else {
+      return Ok(());
+    };
+    if node.neighbours.len() <= layer {
+      return Ok(());
+    }
+    node.neighbours[layer].push(new_id);
+
+    let max_connections = self.max_connections(layer);
+    if node.neighbours[layer].len() > max_connections {
+      let mut neighbours = Vec::with_capacity(node.neighbours[layer].len());
+      for neighbour_id in &node.neighbours[layer] {
+        if *neighbour_id == new_id {
+          neighbours.push((self.distance(&node.vector, new_vector), new_id));
+        } else if let Some(neighbour) = self.store.get_node(*neighbour_id)? {
+          neighbours.push((
+            self.distance(&node.vector, &neighbour.vector),
+            *neighbour_id,
+          ));
+        }
+      }
+      neighbours.sort();
+      node.neighbours[layer] = neighbours
+        .into_iter()
+        .take(max_connections)
+        .map(|(_, id)| id)
+        .collect();
+    }
+    self.store.put_node(id, &node)
+  }
+
+  fn distance_to_node(
+    &self,
+    query: &HnswVector,
+    id: NodeId,
+  ) -> Result<Vec<(Score, NodeId)>> {
+    Ok(
+      self
+        .store
+        .get_node(id)?
+        .map(|node| vec![(self.distance(query, &node.vector), id)])
+        .unwrap_or_default(),
+    )
+  }
+
+  #[inline]
+  fn max_connections(&self, layer: usize) -> usize {
+    if layer == 0 {
+      self.params.m * 2
+    } else {
+      self.params.m
+    }
+  }
+
+  fn random_level(&self) -> usize {
+    let level_multiplier = 1.0 / (self.params.m.max(2) as f64).ln();
+    let uniform: f64 = rand::thread_rng().gen_range(f64::EPSILON..1.0);
+    ((-uniform.ln() * level_multiplier).floor() as usize).min(MAX_LEVEL)
+  }
+
+  /// Distance between two vectors; lower distance means the vectors
+  /// are more similar
+  #[inline]
+  fn distance(&self, a: &HnswVector, b: &HnswVector) -> Score {
+    let score = match (a, b) {
+      (HnswVector::Float32(a), HnswVector::Float32(b)) => {
+        self.scorer.similarity_score(a, b)
+      }
+      (HnswVector::Quantized(a), HnswVector::Quantized(b)) => {
+        a.similarity_score(&SimilarityType::from(&self.params.metric), b)
+      }
+      (a, b) => self.scorer.similarity_score(&a.to_f32(), &b.to_f32()),
+    };
+    self.score_to_distance(score)
+  }
+
+  #[inline]
+  fn score_to_distance(&self, score: Score) -> Score {
+    match self.params.metric {
+      VectorMetric::Cos => OrderedFloat(1.0 - score.0),
+      // L2 scorer already returns the negative of the distance
+      VectorMetric::Dot | VectorMetric::L2 => -score,
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use std::cell::RefCell;
+  use std::collections::BTreeMap;
+
+  use super::{
+    EntryPoint, HnswGraph, HnswGraphStore, HnswNode, HnswParams, HnswVector,
+    NodeId,
+  };
+  use crate::schema::VectorMetric;
+  use crate::vectors::QuantizedVector;
+  use crate::Result;
+
+  #[derive(Default)]
+  struct MemoryGraphStore {
+    entry_point: RefCell<Option<EntryPoint>>,
+    nodes: RefCell<BTreeMap<NodeId, HnswNode>>,
+  }
+
+  impl HnswGraphStore for MemoryGraphStore {
+    fn get_entry_point(&self) -> Result<Option<EntryPoint>> {
+      Ok(self.entry_point.borrow().clone())
+    }
+
+    fn put_entry_point(&self, entry_point: Option<&EntryPoint>) -> Result<()> {
+      *self.entry_point.borrow_mut() = entry_point.cloned();
+      Ok(())
+    }
+
+    fn get_node(&self, id: NodeId) -> Result<Option<HnswNode>> {
+      Ok(self.nodes.borrow().get(&id).cloned())
+    }
+
+    fn put_node(&self, id: NodeId, node: &HnswNode) -> Result<()> {
+      self.nodes.borrow_mut().insert(id, node.clone());
+      Ok(())
+    }
+
+    fn delete_node(&self, id: NodeId) -> Result<()> {
+      self.nodes.borrow_mut().remove(&id);
+      Ok(())
+    }
+
+    fn find_any_node(&self) -> Result<Option<(NodeId, HnswNode)>> {
+      Ok(
+        self
+          .nodes
+          .borrow()
+          .iter()
+          .next()
+          .map(|(id, node)| (*id, node.clone())),
+      )
+    }
+  }
+
+  fn params() -> HnswParams {
+    HnswParams {
+      metric: VectorMetric::L2,
+      m: 4,
+      ef_construction: 32,
+      ef: 16,
+      rerank: false,
+    }
+  }
+
+  #[test]
+  fn test_hnsw_search_and_delete() -> Result<()> {
+    let store = MemoryGraphStore::default();
+    let graph = HnswGraph::new(&store, params());
+    for i in 0..200 {
+      let x = i as f32;
+      graph.insert(i, HnswVector::Float32(vec![x, x * 0.5, 1.0, 0.0]))?;
+    }
+
+    let nearest = graph.search(&[42.1, 21.0, 1.0, 0.0], 3)?;
+    let ids: Vec<NodeId> = nearest.iter().map(|(_, id)| *id).collect();
+    assert_eq!(ids, vec![42, 43, 41]);
+
+    graph.delete(42)?;
+    let nearest = graph.search(&[42.1, 21.0, 1.0, 0.0], 2)?;
+    let ids: Vec<NodeId> = nearest.iter().map(|(_, id)| *id).collect();
+    assert_eq!(ids, vec![43, 41]);
+
+    for i in 0..200 {
+      graph.delete(i)?;
+    }
+    assert_eq!(store.get_entry_point()?, None);
+    assert!(graph.search(&[1.0, 1.0, 1.0, 0.0], 2)?.is_empty());
+    Ok(())
+  }
+
+  #[test]
+  fn test_hnsw_search_quantized_vectors() -> Result<()> {
+    for rerank in [false, true] {
+      let store = MemoryGraphStore::default();
+      let graph = HnswGraph::new(&store, HnswParams { rerank, ..params() });
+      for i in 0..100 {
+        let x = i as f32;
+        let vector = QuantizedVector::int8(&[x, 1.0, 0.0, 0.0]);
+        graph.insert(i, HnswVector::Quantized(vector))?;
+      }
+
+      let nearest = graph.search(&[42.1, 1.0, 0.0, 0.0], 3)?;
+      let ids: Vec<NodeId> = nearest.iter().map(|(_, id)| *id).collect();
+      assert_eq!(ids, vec![42, 43, 41]);
+    }
+    Ok(())
+  }
+}
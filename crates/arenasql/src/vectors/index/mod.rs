@@ -1,4 +1,5 @@
 pub mod flatindex;
+pub mod hnsw;
 
 use anyhow::Result;
 
@@ -1,9 +1,15 @@
 mod glam;
 mod index;
+mod quantization;
 mod scoring;
 mod ultraviolet;
 
+pub use index::hnsw::{
+  EntryPoint, HnswGraph, HnswGraphStore, HnswNode, HnswParams, HnswVector,
+  NodeId,
+};
 pub use index::{flatindex::FlatVectorIndex, VectorIndex};
+pub use quantization::QuantizedVector;
 #[allow(unused)]
 pub use scoring::{
   Score, SimilarityScorer, SimilarityScorerFactory, SimilarityType,
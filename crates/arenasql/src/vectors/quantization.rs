@@ -0,0 +1,204 @@
+use ordered_float::OrderedFloat;
+use serde::{Deserialize, Serialize};
+
+use super::scoring::{cosine_similarity, Score, SimilarityType};
+
+/// Vector stored in a compact form to reduce the storage size.
+///
+/// The similarity scores of quantized vectors are calculated directly on
+/// the compact values, so they are approximate. Use the dequantized vector
+/// with the full precision query to get more accurate scores.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub enum QuantizedVector {
+  /// bfloat16 values; i.e. upper 16 bits of f32 values. This keeps
+  /// the range of f32 but only has ~3 significant decimal digits
+  BFloat16(Vec<u16>),
+  /// Symmetric scalar quantization where each value is
+  /// `scale * values[i]` and the largest absolute value maps to 127
+  Int8 { scale: f32, values: Vec<i8> },
+}
+
+impl QuantizedVector {
+  pub fn bfloat16(vector: &[f32]) -> Self {
+    Self::BFloat16(vector.iter().map(|v| f32_to_bf16(*v)).collect())
+  }
+
+  pub fn int8(vector: &[f32]) -> Self {
+    let max = vector.iter().fold(0.0f32, |max, v| max.max(v.abs()));
+    if max == 0.0 || !max.is_finite() {
+      return Self::Int8 {
+        scale: 0.0,
+        values: vec![0; vector.len()],
+      };
+    }
+    let scale = max / i8::MAX as f32;
+    Self::Int8 {
+      scale,
+      values: vector
+        .iter()
+        .map(|v| (v / scale).round().clamp(-127.0, 127.0) as i8)
+        .collect(),
+    }
+  }
+
+  /// Quantizes the vector to the same format as this vector
+  pub fn quantize_like(&self, vector: &[f32]) -> Self {
+    match self {
+      Self::BFloat16(_) => Self::bfloat16(vector),
+      Self::Int8 { .. } => Self::int8(vector),
+    }
+  }
+
+  /// Returns the f32 vector that this vector approximates
+  pub fn to_f32(&self) -> Vec<f32> {
+    match self {
+      Self::BFloat16(values) => {
+        values.iter().map(|v| bf16_to_f32(*v)).collect()
+      }
+      Self::Int8 { scale, values } => {
+        values.iter().map(|v| *v as f32 * scale).collect()
+      }
+    }
+  }
+
+  /// Calculates the similarity score of two quantized vectors without
+  /// converting them to f32 vectors first. Int8 vectors use integer
+  /// arithmetic and only apply the scales to the sums.
+  ///
+  /// The scores are comparable to the scores of [super::SimilarityScorer]
+  pub fn similarity_score(&self, t: &SimilarityType, query: &Self) -> Score {
+    match (self, query) {
+      (Self::BFloat16(vector), Self::BFloat16(query)) => similarity_score(
+        t,
+        vector
+          .iter()
+          .zip(query.iter())
+          .map(|(v, q)| (bf16_to_f32(*v), bf16_to_f32(*q))),
+      ),
+      (
+        Self::Int8 {
+          scale: vector_scale,
+          values: vector,
+        },
+        Self::Int8 {
+          scale: query_scale,
+          values: query,
+        },
+      ) => {
+        let (dot, vector_norm, query_norm) = vector.iter().zip(query).fold(
+          (0i32, 0i32, 0i32),
+          |(dot, vector_norm, query_norm), (v, q)| {
+            let (v, q) = (*v as i32, *q as i32);
+            (dot + v * q, vector_norm + v * v, query_norm + q * q)
+          },
+        );
+        let (dot, vector_norm, query_norm) =
+          (dot as f32, vector_norm as f32, query_norm as f32);
+        let scales = vector_scale * query_scale;
+        match t {
+          SimilarityType::Dot => OrderedFloat(scales * dot),
+          SimilarityType::L2 => OrderedFloat(
+            2.0 * scales * dot
+              - vector_scale * vector_scale * vector_norm
+              - query_scale * query_scale * query_norm,
+          ),
+          // Scales don't change the angle between the vectors
+          SimilarityType::Cosine => {
+            cosine_similarity(dot, vector_norm, query_norm)
+          }
+        }
+      }
+      // Vectors of a column are always quantized the same way but
+      // handle this case anyways
+      (vector, query) => {
+        similarity_score(t, vector.to_f32().into_iter().zip(query.to_f32()))
+      }
+    }
+  }
+}
+
+/// Calculates the similarity score of (vector, query) value pairs
+#[inline]
+fn similarity_score(
+  t: &SimilarityType,
+  values: impl Iterator<Item = (f32, f32)>,
+) -> Score {
+  match t {
+    SimilarityType::Dot => OrderedFloat(values.map(|(v, q)| v * q).sum()),
+    SimilarityType::L2 => {
+      OrderedFloat(-values.map(|(v, q)| (v - q) * (v - q)).sum::<f32>())
+    }
+    SimilarityType::Cosine => {
+      let (dot, vector_norm, query_norm) = values.fold(
+        (0.0, 0.0, 0.0),
+        |(dot, vector_norm, query_norm), (v, q)| {
+          (dot + v * q, vector_norm + v * v, query_norm + q * q)
+        },
+      );
+      cosine_similarity(dot, vector_norm, query_norm)
+    }
+  }
+}
+
+/// Rounds f32 to the nearest bfloat16 (ties to even)
+#[inline]
+fn f32_to_bf16(value: f32) -> u16 {
+  let bits = value.to_bits();
+  if value.is_nan() {
+    // Keep NaN a NaN even if the mantissa bits are truncated
+    return ((bits >> 16) | 0x0040) as u16;
+  }
+  let rounding_bias = 0x7FFF + ((bits >> 16) & 1);
+  (bits.wrapping_add(rounding_bias) >> 16) as u16
+}
+
+#[inline]
+fn bf16_to_f32(value: u16) -> f32 {
+  f32::from_bits((value as u32) << 16)
+}
+
+#[cfg(test)]
+mod tests {
+  use super::QuantizedVector;
+  use crate::vectors::{SimilarityScorerFactory, SimilarityType};
+
+  #[test]
+  fn test_quantized_vectors() {
+    let vector = vec![0.1, -0.25, 3.0, 1.5, 0.0, -2.75, 0.333, 1.0];
+    let query = vec![1.0, 0.5, -1.0, 2.0, 0.0, 1.5, 0.25, -0.5];
+
+    for (quantized, tolerance) in [
+      (QuantizedVector::bfloat16(&vector), 0.01),
+      (QuantizedVector::int8(&vector), 0.03),
+    ] {
+      assert_eq!(quantized.to_f32().len(), vector.len());
+      for (v, dequantized) in vector.iter().zip(quantized.to_f32()) {
+        assert!((v - dequantized).abs() <= tolerance);
+      }
+
+      let quantized_query = quantized.quantize_like(&query);
+      for t in [
+        SimilarityType::Dot,
+        SimilarityType::L2,
+        SimilarityType::Cosine,
+      ] {
+        let expected = SimilarityScorerFactory::get_default(t.clone())
+          .similarity_score(&vector, &query);
+        let score = quantized.similarity_score(&t, &quantized_query);
+        assert!(
+          (score.0 - expected.0).abs() < 0.1,
+          "Expected {:?} score to be close to {} but got {}",
+          t,
+          expected.0,
+          score.0
+        );
+      }
+    }
+  }
+
+  #[test]
+  fn test_int8_zero_vector() {
+    let quantized = QuantizedVector::int8(&[0.0, 0.0, 0.0, 0.0]);
+    assert_eq!(quantized.to_f32(), vec![0.0, 0.0, 0.0, 0.0]);
+  }
+}
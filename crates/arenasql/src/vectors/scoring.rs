@@ -5,23 +5,19 @@ use datafusion::arrow::datatypes::ArrowNativeTypeOp;
 use derivative::Derivative;
 use ordered_float::OrderedFloat;
 
-use super::glam::GlamDotSimilarity;
-use super::ultraviolet::UltravioletDotSimilarity;
+use super::glam::{GlamCosineSimilarity, GlamDotSimilarity, GlamL2Similarity};
+use super::ultraviolet::{
+  UltravioletCosineSimilarity, UltravioletDotSimilarity,
+  UltravioletL2Similarity,
+};
+use crate::schema::VectorMetric;
 
 pub type Score = OrderedFloat<f32>;
 
+/// Note: bfloat16 and int8 vectors are scored in the compact form
+/// by [super::QuantizedVector] instead of the scorers here
 pub type VectorElement = f32;
 
-/// When using `bfloat16` feature, the stored vectors are
-/// represented as u16 and converted to f32 during similarity
-/// scoring.
-/// Converting u16 to f32 with unchecked transmutate is really
-/// fast and creating `ultraviolet::f32x8` from u16 seems to be
-/// 30% faster than using f32;
-/// TODO: IDK why that's the case benchmark properly to verify
-#[cfg(feature = "bfloat16")]
-pub type VectorElement = u16;
-
 #[allow(dead_code)]
 #[derive(Debug, Clone)]
 pub enum SimilarityType {
@@ -35,12 +31,36 @@ pub enum SimilarityType {
   L2,
 }
 
+impl From<&VectorMetric> for SimilarityType {
+  fn from(metric: &VectorMetric) -> Self {
+    match metric {
+      VectorMetric::Dot => Self::Dot,
+      VectorMetric::L2 => Self::L2,
+      VectorMetric::Cos => Self::Cosine,
+    }
+  }
+}
+
 pub trait SimilarityScorer {
   /// Calculate the similarity score of two vectors
   /// The length of the vectors must be multiple of 4
   fn similarity_score(&self, vector: &[VectorElement], query: &[f32]) -> Score;
 }
 
+/// Returns the cosine similarity from the dot product and the squared
+/// norms of the vectors. Returns 0 if any of the vector is a zero vector
+#[inline]
+pub(super) fn cosine_similarity(
+  dot: f32,
+  vector_norm: f32,
+  query_norm: f32,
+) -> Score {
+  if vector_norm == 0.0 || query_norm == 0.0 {
+    return OrderedFloat(0.0);
+  }
+  OrderedFloat(dot / (vector_norm.sqrt() * query_norm.sqrt()))
+}
+
 pub struct SimilarityScorerFactory;
 
 impl SimilarityScorerFactory {
@@ -52,7 +72,8 @@ impl SimilarityScorerFactory {
   pub fn glam<'a>(t: SimilarityType) -> &'a dyn SimilarityScorer {
     match t {
       SimilarityType::Dot => &GlamDotSimilarity {},
-      _ => unimplemented!(),
+      SimilarityType::Cosine => &GlamCosineSimilarity {},
+      SimilarityType::L2 => &GlamL2Similarity {},
     }
   }
 
@@ -61,7 +82,8 @@ impl SimilarityScorerFactory {
   pub fn untraviolet<'a>(t: SimilarityType) -> &'a dyn SimilarityScorer {
     match t {
       SimilarityType::Dot => &UltravioletDotSimilarity {},
-      _ => unimplemented!(),
+      SimilarityType::Cosine => &UltravioletCosineSimilarity {},
+      SimilarityType::L2 => &UltravioletL2Similarity {},
     }
   }
 }
@@ -119,3 +141,55 @@ impl<T> SortedSimilarityScores<T> {
     self.heap.into_sorted_vec()
   }
 }
+
+#[cfg(test)]
+mod tests {
+  use super::{SimilarityScorerFactory, SimilarityType};
+
+  fn naive_score(t: &SimilarityType, vector: &[f32], query: &[f32]) -> f32 {
+    let dot: f32 = vector.iter().zip(query).map(|(a, b)| a * b).sum();
+    match t {
+      SimilarityType::Dot => dot,
+      SimilarityType::L2 => -vector
+        .iter()
+        .zip(query)
+        .map(|(a, b)| (a - b) * (a - b))
+        .sum::<f32>(),
+      SimilarityType::Cosine => {
+        let vector_norm: f32 = vector.iter().map(|a| a * a).sum();
+        let query_norm: f32 = query.iter().map(|a| a * a).sum();
+        dot / (vector_norm.sqrt() * query_norm.sqrt())
+      }
+    }
+  }
+
+  #[test]
+  fn test_similarity_scorers() {
+    let vector = vec![0.1, 0.2, 0.3, 0.4, 0.5, 0.6, 0.7, 0.8];
+    let query = vec![1.0, 0.5, -1.0, 2.0, 0.0, 1.5, 0.25, -0.5];
+
+    for t in [
+      SimilarityType::Dot,
+      SimilarityType::L2,
+      SimilarityType::Cosine,
+    ] {
+      for scorer in [
+        SimilarityScorerFactory::glam(t.clone()),
+        SimilarityScorerFactory::untraviolet(t.clone()),
+      ] {
+        // Check both 8 and 4 length vectors
+        for len in [8, 4] {
+          let expected = naive_score(&t, &vector[..len], &query[..len]);
+          let score = scorer.similarity_score(&vector[..len], &query[..len]);
+          assert!(
+            (score.0 - expected).abs() < 1e-5,
+            "Expected {:?} score to be {} but got {}",
+            t,
+            expected,
+            score.0
+          );
+        }
+      }
+    }
+  }
+}
@@ -3,7 +3,7 @@ use std::ops::AddAssign;
 use ordered_float::OrderedFloat;
 use ultraviolet::{f32x4, f32x8};
 
-use super::scoring::{Score, SimilarityScorer};
+use super::scoring::{cosine_similarity, Score, SimilarityScorer};
 
 #[derive(Clone)]
 pub struct UltravioletDotSimilarity;
@@ -62,3 +62,132 @@ impl SimilarityScorer for UltravioletDotSimilarity {
     }
   }
 }
+
+#[derive(Clone)]
+pub struct UltravioletCosineSimilarity;
+
+impl UltravioletCosineSimilarity {
+  fn similarity_score_x4(&self, vector: &[f32], query: &[f32]) -> Score {
+    let zero = f32x4::new([0.0, 0.0, 0.0, 0.0]);
+    let (dot, vector_norm, query_norm) = query
+      .chunks_exact(4)
+      .map(|v| f32x4::new(v.try_into().unwrap()))
+      .zip(
+        vector
+          .chunks_exact(4)
+          .map(|v| f32x4::new(v.try_into().unwrap())),
+      )
+      .fold(
+        (zero, zero, zero),
+        |(mut dot, mut vector_norm, mut query_norm), (a, b)| {
+          dot.add_assign(a * b);
+          vector_norm.add_assign(b * b);
+          query_norm.add_assign(a * a);
+          (dot, vector_norm, query_norm)
+        },
+      );
+    cosine_similarity(
+      dot.reduce_add(),
+      vector_norm.reduce_add(),
+      query_norm.reduce_add(),
+    )
+  }
+
+  fn similarity_score_x8(&self, vector: &[f32], query: &[f32]) -> Score {
+    let zero = f32x8::new([0.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0]);
+    let (dot, vector_norm, query_norm) = query
+      .chunks_exact(8)
+      .map(|v| f32x8::new(v.try_into().unwrap()))
+      .zip(
+        vector
+          .chunks_exact(8)
+          .map(|v| f32x8::new(v.try_into().unwrap())),
+      )
+      .fold(
+        (zero, zero, zero),
+        |(mut dot, mut vector_norm, mut query_norm), (a, b)| {
+          dot.add_assign(a * b);
+          vector_norm.add_assign(b * b);
+          query_norm.add_assign(a * a);
+          (dot, vector_norm, query_norm)
+        },
+      );
+    cosine_similarity(
+      dot.reduce_add(),
+      vector_norm.reduce_add(),
+      query_norm.reduce_add(),
+    )
+  }
+}
+
+impl SimilarityScorer for UltravioletCosineSimilarity {
+  fn similarity_score(&self, vector: &[f32], query: &[f32]) -> Score {
+    if query.len() % 8 == 0 {
+      self.similarity_score_x8(vector, query)
+    } else if query.len() % 4 == 0 {
+      self.similarity_score_x4(vector, query)
+    } else {
+      panic!("Vectors must be multiple of 4")
+    }
+  }
+}
+
+/// Negative of the squared euclidean distance is used as the similarity
+/// score so that the closer vectors have higher score
+#[derive(Clone)]
+pub struct UltravioletL2Similarity;
+
+impl UltravioletL2Similarity {
+  fn similarity_score_x4(&self, vector: &[f32], query: &[f32]) -> Score {
+    OrderedFloat(
+      -query
+        .chunks_exact(4)
+        .map(|v| f32x4::new(v.try_into().unwrap()))
+        .zip(
+          vector
+            .chunks_exact(4)
+            .map(|v| f32x4::new(v.try_into().unwrap())),
+        )
+        .fold(f32x4::new([0.0, 0.0, 0.0, 0.0]), |mut agg, (a, b)| {
+          let diff = a - b;
+          agg.add_assign(diff * diff);
+          agg
+        })
+        .reduce_add(),
+    )
+  }
+
+  fn similarity_score_x8(&self, vector: &[f32], query: &[f32]) -> Score {
+    OrderedFloat(
+      -query
+        .chunks_exact(8)
+        .map(|v| f32x8::new(v.try_into().unwrap()))
+        .zip(
+          vector
+            .chunks_exact(8)
+            .map(|v| f32x8::new(v.try_into().unwrap())),
+        )
+        .fold(
+          f32x8::new([0.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0]),
+          |mut agg, (a, b)| {
+            let diff = a - b;
+            agg.add_assign(diff * diff);
+            agg
+          },
+        )
+        .reduce_add(),
+    )
+  }
+}
+
+impl SimilarityScorer for UltravioletL2Similarity {
+  fn similarity_score(&self, vector: &[f32], query: &[f32]) -> Score {
+    if query.len() % 8 == 0 {
+      self.similarity_score_x8(vector, query)
+    } else if query.len() % 4 == 0 {
+      self.similarity_score_x4(vector, query)
+    } else {
+      panic!("Vectors must be multiple of 4")
+    }
+  }
+}
@@ -10,6 +10,7 @@ use chrono::NaiveDateTime;
 use deno_core::serde_json::{json, Value};
 use futures::TryStreamExt;
 use heck::ToLowerCamelCase;
+use postgres::types::FromSql;
 use postgres::types::ToSql;
 use postgres::types::Type;
 use serde::{Deserialize, Serialize};
@@ -186,6 +187,9 @@ fn get_json_value(
       convert_to_json_value!(row, col_index, Vec<f32>, |v| { Value::from(v) })
     }
     &Type::VOID => Ok(Value::Null),
+    t if <Vector as FromSql>::accepts(t) => {
+      convert_to_json_value!(row, col_index, Vector, |v| Value::from(v.0))
+    }
     t => Err(anyhow!("UnsupportedDataTypeError: {}", t)),
   }
 }
@@ -211,6 +215,14 @@ impl ToSql for Param {
         }
         _ => {}
       },
+      _ if <Vector as ToSql>::accepts(ty) => {
+        let vector = match &self.0 {
+          Value::String(value) => serde_json::from_str::<Vec<f32>>(value),
+          value => serde_json::from_str::<Vec<f32>>(&value.to_string()),
+        }
+        .map_err(|err| anyhow!("Error deserializing vector: {:?}", err))?;
+        return Vector(vector).to_sql(ty, out);
+      }
       _ => {}
     };
 
@@ -295,6 +307,7 @@ impl ToSql for Param {
       | Type::FLOAT4_ARRAY
       | Type::JSONB
       | Type::JSON_ARRAY => true,
+      _ if <Vector as ToSql>::accepts(ty) => true,
       _ => {
         error!("Unsupported type: {}", ty);
         false
@@ -311,6 +324,58 @@ impl ToSql for Param {
   }
 }
 
+/// pgvector's `vector` in binary format; dimension (u16), unused (u16)
+/// followed by the values (f32)
+#[derive(Debug)]
+struct Vector(Vec<f32>);
+
+impl<'a> FromSql<'a> for Vector {
+  fn from_sql(
+    _ty: &Type,
+    mut raw: &'a [u8],
+  ) -> Result<Self, Box<dyn std::error::Error + Send + Sync + 'static>> {
+    use bytes::Buf;
+    if raw.len() < 4 {
+      return Err(anyhow!("Invalid vector").into());
+    }
+    let dim = raw.get_u16() as usize;
+    raw.get_u16();
+    if raw.len() != dim * 4 {
+      return Err(anyhow!("Invalid vector").into());
+    }
+    Ok(Self((0..dim).map(|_| raw.get_f32()).collect()))
+  }
+
+  fn accepts(ty: &Type) -> bool {
+    ty.name() == "vector"
+  }
+}
+
+impl ToSql for Vector {
+  fn to_sql(
+    &self,
+    _ty: &Type,
+    out: &mut BytesMut,
+  ) -> Result<IsNull, Box<dyn std::error::Error + Send + Sync + 'static>> {
+    out.put_u16(self.0.len().try_into()?);
+    out.put_u16(0);
+    self.0.iter().for_each(|v| out.put_f32(*v));
+    Ok(IsNull::No)
+  }
+
+  fn accepts(ty: &Type) -> bool {
+    <Self as FromSql>::accepts(ty)
+  }
+
+  fn to_sql_checked(
+    &self,
+    ty: &Type,
+    out: &mut BytesMut,
+  ) -> Result<IsNull, Box<dyn std::error::Error + Send + Sync + 'static>> {
+    self.to_sql(ty, out)
+  }
+}
+
 #[cfg(test)]
 mod tests {
   // #[tokio::test]